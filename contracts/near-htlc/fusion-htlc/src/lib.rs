@@ -0,0 +1,9 @@
+// FusionHTLC contract built on its own near-sdk 5 crate
+//
+// The near-htlc crate only ships SimpleHTLC, so this crate is what compiles,
+// unit-tests and produces fusion_htlc.wasm for the workspaces tests.
+
+#[path = "../../src/fusion_htlc.rs"]
+mod fusion_htlc;
+
+pub use fusion_htlc::*;
@@ -0,0 +1,115 @@
+//! NEP-297 event schema shared by the FusionHTLC contract and fusion-core
+//!
+//! The contract serializes these types into `EVENT_JSON:` logs and
+//! fusion-core deserializes the same types, so field names and types are
+//! defined in this file only. Depends on plain `serde` so it compiles in both
+//! the contract and the off-chain crates.
+
+use serde::{Deserialize, Serialize};
+
+/// NEP-297 log prefix
+pub const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+/// `standard` field of every FusionHTLC event
+pub const EVENT_STANDARD: &str = "fusion-htlc";
+/// Schema version, bump the major version on incompatible changes
+pub const EVENT_VERSION: &str = "1.0.0";
+
+/// Emitted when an escrow is created
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EscrowCreated {
+    pub escrow_id: String,
+    pub resolver: String,
+    pub beneficiary: String,
+    /// Decimal string in JSON, like NEAR's `U128`
+    #[serde(with = "u128_string")]
+    pub amount: u128,
+    pub secret_hash: String,
+    /// Nanoseconds since the Unix epoch
+    pub finality_time: u64,
+    pub cancel_time: u64,
+    pub public_cancel_time: u64,
+}
+
+/// Emitted when an escrow is claimed and its secret revealed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EscrowClaimed {
+    pub escrow_id: String,
+    pub claimer: String,
+    /// Hex-encoded preimage
+    pub secret: String,
+    pub timestamp: u64,
+}
+
+/// Emitted when an escrow is cancelled
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EscrowCancelled {
+    pub escrow_id: String,
+    pub canceller: String,
+    pub timestamp: u64,
+}
+
+/// `event` and `data` fields of a NEP-297 log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum HtlcEvent {
+    EscrowCreated(Vec<EscrowCreated>),
+    EscrowClaimed(Vec<EscrowClaimed>),
+    EscrowCancelled(Vec<EscrowCancelled>),
+}
+
+/// Full NEP-297 envelope
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HtlcEventLog {
+    pub standard: String,
+    pub version: String,
+    #[serde(flatten)]
+    pub event: HtlcEvent,
+}
+
+impl HtlcEventLog {
+    pub fn new(event: HtlcEvent) -> Self {
+        Self {
+            standard: EVENT_STANDARD.to_string(),
+            version: EVENT_VERSION.to_string(),
+            event,
+        }
+    }
+
+    /// Render as an `EVENT_JSON:` log line
+    pub fn to_log_string(&self) -> String {
+        format!(
+            "{}{}",
+            EVENT_LOG_PREFIX,
+            serde_json::to_string(self).expect("Event serialization failed")
+        )
+    }
+
+    /// Parse an `EVENT_JSON:` log line, `None` if the log is not an event
+    pub fn from_log_str(log: &str) -> Option<Result<Self, serde_json::Error>> {
+        log.strip_prefix(EVENT_LOG_PREFIX)
+            .map(|json| serde_json::from_str(json.trim_start()))
+    }
+
+    /// Whether `version` shares the major version of this schema
+    pub fn is_compatible(&self) -> bool {
+        major_version(&self.version) == major_version(EVENT_VERSION)
+    }
+}
+
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+mod u128_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -3,27 +3,38 @@ use near_sdk::collections::UnorderedMap;
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseResult,
+    env, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseOrValue,
+    PromiseResult,
 };
 use sha2::{Digest, Sha256};
 
+#[path = "event_schema.rs"]
+pub mod event_schema;
+use event_schema::{EscrowCancelled, EscrowClaimed, EscrowCreated, HtlcEvent, HtlcEventLog};
+
 type Balance = u128;
 type Timestamp = u64;
 
 // Gas constants - Made configurable for future NEAR upgrades
 const BASE_GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(20);
 const BASE_GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
-// Removed unused constants - GAS_PER_BATCH_ITEM and NO_DEPOSIT
+const BASE_GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_tgas(50);
+const GAS_FOR_ROUTED_CLAIM_CALLBACK: Gas = Gas::from_tgas(60); // May forward the unused amount and pay the safety deposit
+                                                               // Removed unused constants - GAS_PER_BATCH_ITEM and NO_DEPOSIT
 const ONE_YOCTO: Balance = 1;
 
 // Time constants for overflow protection
 const MAX_TIME_PERIOD_SECONDS: u64 = 10 * 365 * 24 * 60 * 60; // 10 years
 const NANOSECONDS_PER_SECOND: u64 = 1_000_000_000;
 
-// Storage limits to prevent DoS attacks
-const MAX_TOTAL_ESCROWS: u64 = 10_000; // Maximum number of total escrows
-const MAX_ESCROWS_PER_ACCOUNT: u64 = 100; // Maximum number of active escrows per account
-const MAX_ESCROW_AMOUNT: Balance = 1_000_000 * 10u128.pow(24); // 1M NEAR max per escrow
+// Default storage limits to prevent DoS attacks (adjustable by the owner)
+const DEFAULT_MAX_TOTAL_ESCROWS: u64 = 10_000; // Maximum number of total escrows
+const DEFAULT_MAX_ESCROWS_PER_ACCOUNT: u64 = 100; // Maximum number of active escrows per account
+const DEFAULT_MAX_ESCROW_AMOUNT: Balance = 1_000_000 * 10u128.pow(24); // 1M NEAR max per escrow
+const DEFAULT_MIN_TIME_PERIOD_SECONDS: u64 = 60; // Well above NEAR finality (~2s) so claims can land
+
+// Failed transfers before an escrow is parked in the Failed state
+const MAX_TRANSFER_ATTEMPTS: u32 = 3;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -32,6 +43,58 @@ pub struct FusionHTLC {
     pub escrow_counter: u64,
     pub owner: AccountId,
     pub active_escrows_per_account: UnorderedMap<AccountId, u64>, // Track active escrows per account
+    pub limits: EscrowLimits,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowLimits {
+    pub max_total_escrows: u64,
+    pub max_escrows_per_account: u64,
+    pub max_escrow_amount: U128, // Applies to both the amount and the safety deposit
+    // Shortest allowed finality period; only lower this for tests
+    #[serde(default = "default_min_time_period_seconds")]
+    pub min_time_period_seconds: u64,
+}
+
+fn default_min_time_period_seconds() -> u64 {
+    DEFAULT_MIN_TIME_PERIOD_SECONDS
+}
+
+impl Default for EscrowLimits {
+    fn default() -> Self {
+        Self {
+            max_total_escrows: DEFAULT_MAX_TOTAL_ESCROWS,
+            max_escrows_per_account: DEFAULT_MAX_ESCROWS_PER_ACCOUNT,
+            max_escrow_amount: U128(DEFAULT_MAX_ESCROW_AMOUNT),
+            min_time_period_seconds: DEFAULT_MIN_TIME_PERIOD_SECONDS,
+        }
+    }
+}
+
+impl EscrowLimits {
+    fn assert_valid(&self) {
+        assert!(
+            self.max_total_escrows > 0,
+            "Total escrow limit must be positive"
+        );
+        assert!(
+            self.max_escrows_per_account > 0,
+            "Per-account escrow limit must be positive"
+        );
+        assert!(
+            self.max_escrows_per_account <= self.max_total_escrows,
+            "Per-account escrow limit cannot exceed the total limit"
+        );
+        assert!(
+            self.max_escrow_amount.0 > 0,
+            "Escrow amount limit must be positive"
+        );
+        assert!(
+            self.min_time_period_seconds <= MAX_TIME_PERIOD_SECONDS,
+            "Minimum time period too large"
+        );
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -62,6 +125,12 @@ pub struct FusionEscrow {
     pub state: EscrowState,
     pub resolved_by: Option<AccountId>, // Who claimed/cancelled
     pub resolution_time: Option<Timestamp>, // When it was resolved
+    pub failed_transfer_attempts: u32,  // Transfers that failed in a callback
+    pub prior_state: Option<EscrowState>, // State before the in-flight transfer
+    pub cumulative_filled: Option<Balance>, // Amount filled so far, None unless partial fills are allowed
+    pub beneficiary_msg: Option<String>, // Passed to ft_transfer_call on claim so a vault can route the funds
+    pub refund_fallback: Option<AccountId>, // Receives the cancel refund if the resolver's transfer fails
+    pub failed_operation: Option<String>,   // Operation whose transfers marked the escrow Failed
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -71,6 +140,7 @@ pub enum EscrowState {
     Claimed,
     Cancelled,
     Refunded,
+    Failed, // Transfers kept failing, needs owner or resolver intervention
 }
 
 #[derive(Serialize, Deserialize)]
@@ -85,38 +155,386 @@ pub struct CreateEscrowParams {
     pub finality_period: u64,      // Seconds until finality lock
     pub cancel_period: u64,        // Seconds until resolver can cancel
     pub public_cancel_period: u64, // Seconds until anyone can cancel
+    #[serde(default)]
+    pub allow_partial_fills: bool, // Track fills so the amount can be claimed in parts
+    #[serde(default)]
+    pub beneficiary_msg: Option<String>, // Token escrows only: claim with ft_transfer_call and this msg
+    #[serde(default)]
+    pub refund_fallback: Option<AccountId>, // Cancel refund goes here if the resolver account is gone
 }
 
 #[near_bindgen]
 impl FusionHTLC {
+    /// Initialize with the default storage limits unless `limits` is given
     #[init]
-    pub fn new(owner: AccountId) -> Self {
+    pub fn new(owner: AccountId, limits: Option<EscrowLimits>) -> Self {
         assert!(!env::state_exists(), "Already initialized");
+        let limits = limits.unwrap_or_default();
+        limits.assert_valid();
         Self {
             escrows: UnorderedMap::new(b"e"),
             escrow_counter: 0,
             owner,
             active_escrows_per_account: UnorderedMap::new(b"a"),
+            limits,
         }
     }
 
-    /// Create a new 1inch Fusion+ compatible escrow
+    /// Adjust the storage limits as the deployment scales (owner only)
+    pub fn set_escrow_limits(&mut self, limits: EscrowLimits) {
+        assert!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner can update limits"
+        );
+        limits.assert_valid();
+        self.limits = limits;
+    }
+
+    pub fn get_escrow_limits(&self) -> EscrowLimits {
+        self.limits.clone()
+    }
+
+    /// Create a new 1inch Fusion+ compatible escrow backed by attached NEAR
     #[payable]
     pub fn create_escrow(&mut self, params: CreateEscrowParams) -> String {
+        assert!(
+            params.token_id.is_none(),
+            "Token escrows must be funded with ft_transfer_call"
+        );
+
         let resolver = env::predecessor_account_id();
-        let deposit = env::attached_deposit();
+        let deposit = env::attached_deposit().as_yoctonear();
+        self.internal_create_escrow(resolver, params, deposit)
+    }
+
+    /// NEP-141 receiver: create a token escrow from the transferred tokens
+    ///
+    /// `msg` carries the JSON encoded `CreateEscrowParams`. Any tokens beyond
+    /// the escrow total are returned to the sender.
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token_id = env::predecessor_account_id();
+        let params: CreateEscrowParams =
+            near_sdk::serde_json::from_str(&msg).expect("Invalid escrow params in msg");
+        assert_eq!(
+            params.token_id.as_ref(),
+            Some(&token_id),
+            "Token does not match escrow params"
+        );
+
+        let total_amount = params.amount.0 + params.safety_deposit.0;
+        self.internal_create_escrow(sender_id, params, amount.0);
+
+        PromiseOrValue::Value(U128(amount.0 - total_amount))
+    }
+
+    /// Claim escrow with secret (only beneficiary before finality)
+    /// Secret should be provided as hex-encoded string
+    ///
+    /// On a partial-fill escrow only the unfilled remainder is paid out.
+    pub fn claim(&mut self, escrow_id: String, secret: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let claimer = env::predecessor_account_id();
+        let now = env::block_timestamp();
+
+        self.assert_claimable(&escrow, &claimer, now, &secret);
+
+        self.internal_claim(escrow_id, escrow, claimer, secret, now)
+    }
+
+    /// Fill part of a partial-fill escrow with the secret (only beneficiary before finality)
+    ///
+    /// Pays `fill_amount` to the beneficiary and keeps the escrow active for the
+    /// remaining parts. The fill that completes the amount claims the escrow,
+    /// which also releases the safety deposit.
+    pub fn claim_partial(
+        &mut self,
+        escrow_id: String,
+        secret: String,
+        fill_amount: U128,
+    ) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let claimer = env::predecessor_account_id();
+        let now = env::block_timestamp();
+
+        self.assert_claimable(&escrow, &claimer, now, &secret);
+        let filled = escrow
+            .cumulative_filled
+            .expect("Escrow does not allow partial fills");
+        assert!(
+            escrow.beneficiary_msg.is_none(),
+            "Routed escrows cannot be filled in parts"
+        );
+        let fill_amount = fill_amount.0;
+        assert!(fill_amount > 0, "Fill amount must be positive");
+        let unfilled = escrow.amount - filled;
+        assert!(fill_amount <= unfilled, "Fill exceeds the unfilled amount");
+
+        if fill_amount == unfilled {
+            return self.internal_claim(escrow_id, escrow, claimer, secret, now);
+        }
+
+        // Update state before external calls; prior_state marks the fill in flight
+        escrow.cumulative_filled = Some(filled + fill_amount);
+        escrow.prior_state = Some(escrow.state.clone());
+        self.escrows.insert(&escrow_id, &escrow);
+
+        env::log_str(&format!("Secret revealed: {}", secret));
+        env::log_str(&format!(
+            "Filled {} of {} on escrow {}, {} filled so far",
+            fill_amount,
+            escrow.amount,
+            escrow_id,
+            filled + fill_amount
+        ));
+
+        let memo = format!("{} fill", escrow_id);
+        let promise = match escrow.token_id {
+            Some(token_id) => ft_transfer(token_id, &escrow.beneficiary, fill_amount, Some(&memo)),
+            None => Promise::new(escrow.beneficiary.clone())
+                .transfer(NearToken::from_yoctonear(fill_amount)),
+        };
+        promise.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(BASE_GAS_FOR_CALLBACK)
+                .on_fill_complete(escrow_id, U128(fill_amount)),
+        )
+    }
+
+    fn internal_claim(
+        &mut self,
+        escrow_id: String,
+        mut escrow: FusionEscrow,
+        claimer: AccountId,
+        secret: String,
+        now: Timestamp,
+    ) -> Promise {
+        // Update state before external calls
+        escrow.prior_state = Some(escrow.state.clone());
+        escrow.state = EscrowState::Claimed;
+        escrow.resolved_by = Some(claimer.clone());
+        escrow.resolution_time = Some(now);
+        self.escrows.insert(&escrow_id, &escrow);
+
+        // Release the resolver's slot
+        self.release_active_slot(&escrow.resolver);
+
+        // Store secret for cross-chain verification
+        env::log_str(&format!("Secret revealed: {}", secret));
+        emit_event(HtlcEvent::EscrowClaimed(vec![EscrowClaimed {
+            escrow_id: escrow_id.clone(),
+            claimer: claimer.to_string(),
+            secret,
+            timestamp: now,
+        }]));
+
+        // Execute transfers
+        self.execute_claim_transfers(escrow_id, escrow)
+    }
+
+    /// Cancel escrow (resolver after cancel_time, anyone after public_cancel_time)
+    pub fn cancel(&mut self, escrow_id: String) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let canceller = env::predecessor_account_id();
+        let now = env::block_timestamp();
+
+        // Validate state
+        assert_eq!(escrow.state, EscrowState::Active, "Escrow not active");
+        assert!(escrow.prior_state.is_none(), "Fill transfer in flight");
+
+        // Validate timing and permissions
+        if now >= escrow.public_cancel_time {
+            // Anyone can cancel
+        } else if now >= escrow.cancel_time {
+            // Only resolver can cancel
+            assert_eq!(canceller, escrow.resolver, "Only resolver can cancel now");
+        } else {
+            panic!("Too early to cancel");
+        }
+
+        self.internal_cancel(escrow_id, escrow, canceller, now)
+    }
+
+    /// Cancel the unfilled remainder of a partial-fill escrow
+    ///
+    /// Filled parts stay with the beneficiary; only the unfilled amount and the
+    /// safety deposit go back to the resolver. Before finality the beneficiary
+    /// can agree to release the remainder early, otherwise the cancel windows apply.
+    pub fn cancel_unfilled(&mut self, escrow_id: String) -> Promise {
+        let escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let canceller = env::predecessor_account_id();
+        let now = env::block_timestamp();
+
+        // Validate state
+        assert_eq!(escrow.state, EscrowState::Active, "Escrow not active");
+        let filled = escrow
+            .cumulative_filled
+            .expect("Escrow does not allow partial fills");
+        assert!(filled < escrow.amount, "Escrow is already fully filled");
+        assert!(escrow.prior_state.is_none(), "Fill transfer in flight");
+
+        // Validate timing and permissions
+        if canceller == escrow.beneficiary && now < escrow.finality_time {
+            // The beneficiary gives up the parts it has not filled
+        } else if now >= escrow.public_cancel_time {
+            // Anyone can cancel
+        } else if now >= escrow.cancel_time {
+            // Only resolver can cancel
+            assert_eq!(canceller, escrow.resolver, "Only resolver can cancel now");
+        } else {
+            panic!("Too early to cancel");
+        }
+
+        self.internal_cancel(escrow_id, escrow, canceller, now)
+    }
+
+    /// Send the funds of a Failed escrow again
+    ///
+    /// A failed claim already belongs to the beneficiary, so it can only be paid
+    /// to the beneficiary, on the beneficiary's or owner's request. A failed
+    /// refund can be sent to any recipient by the owner or resolver.
+    pub fn retarget_failed_escrow(&mut self, escrow_id: String, recipient: AccountId) -> Promise {
+        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let caller = env::predecessor_account_id();
+
+        assert_eq!(escrow.state, EscrowState::Failed, "Escrow not failed");
+        let claim_failed = escrow.failed_operation.as_deref() == Some("claim");
+        if claim_failed {
+            assert!(
+                caller == self.owner || caller == escrow.beneficiary,
+                "Only owner or beneficiary can retarget a failed claim"
+            );
+            assert_eq!(
+                recipient, escrow.beneficiary,
+                "A failed claim can only be paid to the beneficiary"
+            );
+        } else {
+            assert!(
+                caller == self.owner || caller == escrow.resolver,
+                "Only owner or resolver can retarget"
+            );
+        }
+
+        // Update state before external calls
+        escrow.prior_state = Some(escrow.state.clone());
+        escrow.state = if claim_failed {
+            EscrowState::Claimed
+        } else {
+            EscrowState::Refunded
+        };
+        escrow.failed_operation = None;
+        escrow.resolved_by = Some(caller);
+        escrow.resolution_time = Some(env::block_timestamp());
+        self.escrows.insert(&escrow_id, &escrow);
+
+        env::log_str(&format!(
+            "Failed escrow {} retargeted to {}",
+            escrow_id, recipient
+        ));
+
+        if claim_failed {
+            self.execute_claim_transfers(escrow_id, escrow)
+        } else {
+            self.execute_unfilled_refund(escrow_id, escrow, recipient, "retarget")
+        }
+    }
+
+    /// Rebuild an account's active escrow count from the escrows it resolves
+    /// (owner or the account itself)
+    ///
+    /// Repairs a count that drifted from the actual Active escrows, e.g. one
+    /// stuck at the per-account limit. Walks every escrow, so gas grows with
+    /// the number of stored escrows.
+    pub fn recompute_active_count(&mut self, account: AccountId) -> u64 {
+        let caller = env::predecessor_account_id();
+        assert!(
+            caller == self.owner || caller == account,
+            "Only owner or the account can recompute its count"
+        );
+
+        let actual = self
+            .escrows
+            .values()
+            .filter(|escrow| escrow.state == EscrowState::Active && escrow.resolver == account)
+            .count() as u64;
+        let recorded = self.active_escrows_per_account.get(&account).unwrap_or(0);
+
+        if actual == 0 {
+            self.active_escrows_per_account.remove(&account);
+        } else {
+            self.active_escrows_per_account.insert(&account, &actual);
+        }
+        if actual != recorded {
+            env::log_str(&format!(
+                "Active escrow count for {} corrected from {} to {}",
+                account, recorded, actual
+            ));
+        }
+
+        actual
+    }
+
+    /// Get escrow details
+    pub fn get_escrow(&self, escrow_id: String) -> Option<FusionEscrow> {
+        self.escrows.get(&escrow_id)
+    }
+
+    /// Amount still claimable on an active escrow that allows partial fills
+    ///
+    /// Returns None for escrows without partial fills and for resolved ones.
+    pub fn get_remaining_claimable(&self, escrow_id: String) -> Option<Balance> {
+        let escrow = self.escrows.get(&escrow_id)?;
+        if escrow.state != EscrowState::Active {
+            return None;
+        }
+        let filled = escrow.cumulative_filled?;
+        Some(escrow.amount.saturating_sub(filled))
+    }
+
+    /// Get all active escrows
+    pub fn get_active_escrows(&self, from_index: u64, limit: u64) -> Vec<(String, FusionEscrow)> {
+        let mut result = Vec::new();
+        let keys: Vec<String> = self.escrows.keys_as_vector().iter().collect();
+
+        let start = from_index as usize;
+        let end = std::cmp::min(start + limit as usize, keys.len());
+
+        for key in keys.iter().skip(start).take(end - start) {
+            if let Some(escrow) = self.escrows.get(key) {
+                if escrow.state == EscrowState::Active {
+                    result.push((key.clone(), escrow));
+                }
+            }
+        }
+
+        result
+    }
+
+    // Private helper methods
+
+    /// Create an escrow once `funded` covers amount plus safety deposit
+    fn internal_create_escrow(
+        &mut self,
+        resolver: AccountId,
+        params: CreateEscrowParams,
+        funded: Balance,
+    ) -> String {
         let now = env::block_timestamp();
 
         // Check storage limits to prevent DoS
         assert!(
-            self.escrow_counter < MAX_TOTAL_ESCROWS,
+            self.escrow_counter < self.limits.max_total_escrows,
             "Maximum total escrows limit reached"
         );
 
         // Check per-account limits
         let active_count = self.active_escrows_per_account.get(&resolver).unwrap_or(0);
         assert!(
-            active_count < MAX_ESCROWS_PER_ACCOUNT,
+            active_count < self.limits.max_escrows_per_account,
             "Maximum escrows per account limit reached"
         );
 
@@ -124,14 +542,20 @@ impl FusionHTLC {
         let amount: Balance = params.amount.into();
         let safety_deposit: Balance = params.safety_deposit.into();
         assert!(
-            amount <= MAX_ESCROW_AMOUNT,
+            amount <= self.limits.max_escrow_amount.0,
             "Escrow amount exceeds maximum limit"
         );
         assert!(
-            safety_deposit <= MAX_ESCROW_AMOUNT,
+            safety_deposit <= self.limits.max_escrow_amount.0,
             "Safety deposit exceeds maximum limit"
         );
 
+        // Reject claim windows too short to outlast NEAR finality
+        assert!(
+            params.finality_period >= self.limits.min_time_period_seconds,
+            "Finality period below minimum"
+        );
+
         // Validate time periods to prevent overflow
         assert!(
             params.finality_period <= MAX_TIME_PERIOD_SECONDS,
@@ -146,6 +570,11 @@ impl FusionHTLC {
             "Public cancel period too large"
         );
 
+        assert!(
+            params.beneficiary_msg.is_none() || params.token_id.is_some(),
+            "Beneficiary msg requires a token escrow"
+        );
+
         // Convert time periods to timestamps with overflow protection
         let finality_time = self.safe_add_time(now, params.finality_period);
         let cancel_time = self.safe_add_time(now, params.cancel_period);
@@ -161,16 +590,12 @@ impl FusionHTLC {
             "Cancel time must be before public cancel"
         );
 
-        let amount: Balance = params.amount.into();
-        let safety_deposit: Balance = params.safety_deposit.into();
+        // The contract must already hold the backing funds
         let total_amount = amount + safety_deposit;
-
-        // For NEAR transfers, ensure sufficient deposit
         if params.token_id.is_none() {
-            assert!(
-                deposit >= NearToken::from_yoctonear(total_amount),
-                "Insufficient NEAR deposit"
-            );
+            assert!(funded >= total_amount, "Insufficient NEAR deposit");
+        } else {
+            assert!(funded >= total_amount, "Insufficient token deposit");
         }
 
         let escrow_id = format!("fusion_{}", self.escrow_counter);
@@ -191,168 +616,128 @@ impl FusionHTLC {
             state: EscrowState::Active,
             resolved_by: None,
             resolution_time: None,
+            failed_transfer_attempts: 0,
+            prior_state: None,
+            failed_operation: None,
+            cumulative_filled: params.allow_partial_fills.then_some(0),
+            beneficiary_msg: params.beneficiary_msg,
+            refund_fallback: params.refund_fallback,
         };
 
         self.escrows.insert(&escrow_id, &escrow);
 
         // Update active escrow count for resolver
-        self.active_escrows_per_account
-            .insert(&resolver, &(active_count + 1));
+        self.take_active_slot(&resolver);
 
         env::log_str(&format!(
             "Fusion escrow created: {} by {} for {}, amount: {}, safety: {}",
             escrow_id, resolver, escrow.beneficiary, amount, safety_deposit
         ));
+        emit_event(HtlcEvent::EscrowCreated(vec![EscrowCreated {
+            escrow_id: escrow_id.clone(),
+            resolver: resolver.to_string(),
+            beneficiary: escrow.beneficiary.to_string(),
+            amount,
+            secret_hash: escrow.secret_hash.clone(),
+            finality_time: escrow.finality_time,
+            cancel_time: escrow.cancel_time,
+            public_cancel_time: escrow.public_cancel_time,
+        }]));
 
         escrow_id
     }
 
-    /// Claim escrow with secret (only beneficiary before finality)
-    /// Secret should be provided as hex-encoded string
-    pub fn claim(&mut self, escrow_id: String, secret: String) -> Promise {
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
-        let claimer = env::predecessor_account_id();
-        let now = env::block_timestamp();
+    /// Count one more active escrow for the resolver
+    fn take_active_slot(&mut self, resolver: &AccountId) {
+        let active_count = self.active_escrows_per_account.get(resolver).unwrap_or(0);
+        self.active_escrows_per_account
+            .insert(resolver, &active_count.saturating_add(1));
+    }
+
+    /// Decrement without underflow, dropping the entry once it reaches zero
+    fn release_active_slot(&mut self, resolver: &AccountId) {
+        match self.active_escrows_per_account.get(resolver) {
+            Some(active_count) if active_count > 1 => {
+                self.active_escrows_per_account
+                    .insert(resolver, &(active_count - 1));
+            }
+            Some(_) => {
+                self.active_escrows_per_account.remove(resolver);
+            }
+            None => env::log_str(&format!(
+                "Active escrow count for {} was already zero",
+                resolver
+            )),
+        }
+    }
 
+    /// Checks shared by `claim` and `claim_partial`
+    fn assert_claimable(
+        &self,
+        escrow: &FusionEscrow,
+        claimer: &AccountId,
+        now: Timestamp,
+        secret: &str,
+    ) {
         // Validate state
         assert_eq!(escrow.state, EscrowState::Active, "Escrow not active");
+        assert!(escrow.prior_state.is_none(), "Fill transfer in flight");
 
         // Validate timing - only beneficiary can claim before finality
         assert!(
             now < escrow.finality_time,
             "Past finality time, cannot claim"
         );
-        assert_eq!(claimer, escrow.beneficiary, "Only beneficiary can claim");
+        assert_eq!(claimer, &escrow.beneficiary, "Only beneficiary can claim");
 
         // Verify secret
-        let secret_hash = self.hash_secret(&secret);
+        let secret_hash = self.hash_secret(secret);
         assert_eq!(secret_hash, escrow.secret_hash, "Invalid secret");
+    }
 
-        // Update state before external calls
-        escrow.state = EscrowState::Claimed;
-        escrow.resolved_by = Some(claimer.clone());
-        escrow.resolution_time = Some(now);
-        self.escrows.insert(&escrow_id, &escrow);
+    /// Safely add seconds to a timestamp, preventing overflow
+    fn safe_add_time(&self, base_time: Timestamp, seconds: u64) -> Timestamp {
+        let nanoseconds = seconds.saturating_mul(NANOSECONDS_PER_SECOND);
+        base_time.saturating_add(nanoseconds)
+    }
 
-        // Decrease active escrow count for resolver
-        let active_count = self
-            .active_escrows_per_account
-            .get(&escrow.resolver)
-            .unwrap_or(1);
-        if active_count > 1 {
-            self.active_escrows_per_account
-                .insert(&escrow.resolver, &(active_count - 1));
-        } else {
-            self.active_escrows_per_account.remove(&escrow.resolver);
-        }
+    // Removed unused calculate_gas method
 
-        // Store secret for cross-chain verification
-        env::log_str(&format!("Secret revealed: {}", secret));
+    fn hash_secret(&self, secret: &str) -> String {
+        // Decode hex string to bytes
+        let secret_bytes = hex::decode(secret).expect("Invalid hex secret");
 
-        // Execute transfers
-        self.execute_claim_transfers(escrow_id, escrow)
+        let mut hasher = Sha256::new();
+        hasher.update(&secret_bytes);
+        let result = hasher.finalize();
+        bs58::encode(result).into_string()
     }
 
-    /// Cancel escrow (resolver after cancel_time, anyone after public_cancel_time)
-    pub fn cancel(&mut self, escrow_id: String) -> Promise {
-        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
-        let canceller = env::predecessor_account_id();
-        let now = env::block_timestamp();
+    fn execute_claim_transfers(&self, escrow_id: String, escrow: FusionEscrow) -> Promise {
+        let mut promise: Promise;
+        // Filled parts were already paid by claim_partial
+        let unfilled = escrow.amount - escrow.cumulative_filled.unwrap_or(0);
 
-        // Validate state
-        assert_eq!(escrow.state, EscrowState::Active, "Escrow not active");
-
-        // Validate timing and permissions
-        if now >= escrow.public_cancel_time {
-            // Anyone can cancel
-        } else if now >= escrow.cancel_time {
-            // Only resolver can cancel
-            assert_eq!(canceller, escrow.resolver, "Only resolver can cancel now");
-        } else {
-            panic!("Too early to cancel");
-        }
-
-        // Update state before external calls
-        escrow.state = EscrowState::Cancelled;
-        escrow.resolved_by = Some(canceller.clone());
-        escrow.resolution_time = Some(now);
-        self.escrows.insert(&escrow_id, &escrow);
-
-        // Decrease active escrow count for resolver
-        let active_count = self
-            .active_escrows_per_account
-            .get(&escrow.resolver)
-            .unwrap_or(1);
-        if active_count > 1 {
-            self.active_escrows_per_account
-                .insert(&escrow.resolver, &(active_count - 1));
-        } else {
-            self.active_escrows_per_account.remove(&escrow.resolver);
-        }
-
-        // Execute refund
-        self.execute_cancel_refund(escrow_id, escrow)
-    }
-
-    /// Get escrow details
-    pub fn get_escrow(&self, escrow_id: String) -> Option<FusionEscrow> {
-        self.escrows.get(&escrow_id)
-    }
-
-    /// Get all active escrows
-    pub fn get_active_escrows(&self, from_index: u64, limit: u64) -> Vec<(String, FusionEscrow)> {
-        let mut result = Vec::new();
-        let keys: Vec<String> = self.escrows.keys_as_vector().iter().collect();
-
-        let start = from_index as usize;
-        let end = std::cmp::min(start + limit as usize, keys.len());
-
-        for key in keys.iter().skip(start).take(end - start) {
-            if let Some(escrow) = self.escrows.get(key) {
-                if escrow.state == EscrowState::Active {
-                    result.push((key.clone(), escrow));
-                }
-            }
-        }
-
-        result
-    }
-
-    // Private helper methods
-
-    /// Safely add seconds to a timestamp, preventing overflow
-    fn safe_add_time(&self, base_time: Timestamp, seconds: u64) -> Timestamp {
-        let nanoseconds = seconds.saturating_mul(NANOSECONDS_PER_SECOND);
-        base_time.saturating_add(nanoseconds)
-    }
-
-    // Removed unused calculate_gas method
-
-    fn hash_secret(&self, secret: &str) -> String {
-        // Decode hex string to bytes
-        let secret_bytes = hex::decode(secret).expect("Invalid hex secret");
-
-        let mut hasher = Sha256::new();
-        hasher.update(&secret_bytes);
-        let result = hasher.finalize();
-        bs58::encode(result).into_string()
-    }
-
-    fn execute_claim_transfers(&self, escrow_id: String, escrow: FusionEscrow) -> Promise {
-        let mut promise: Promise;
-
-        if let Some(token_id) = escrow.token_id {
-            // NEP-141 token transfers
-            promise = Promise::new(token_id.clone()).function_call(
-                "ft_transfer".to_string(),
-                format!(
-                    r#"{{"receiver_id":"{}","amount":"{}"}}"#,
-                    escrow.beneficiary, escrow.amount
-                )
-                .into_bytes(),
-                NearToken::from_yoctonear(ONE_YOCTO),
-                BASE_GAS_FOR_FT_TRANSFER,
-            );
+        if let Some(token_id) = escrow.token_id {
+            // NEP-141 token transfers
+            let memo = format!("{} claim", escrow_id);
+            if let Some(msg) = escrow.beneficiary_msg {
+                // Let the beneficiary contract act on the transfer; the callback
+                // settles the rest, including the safety deposit
+                return ft_transfer_call(
+                    token_id,
+                    &escrow.beneficiary,
+                    unfilled,
+                    Some(&memo),
+                    &msg,
+                )
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_ROUTED_CLAIM_CALLBACK)
+                        .on_routed_claim(escrow_id, U128(unfilled)),
+                );
+            }
+            promise = ft_transfer(token_id.clone(), &escrow.beneficiary, unfilled, Some(&memo));
 
             // Transfer safety deposit if exists
             if escrow.safety_deposit > 0 {
@@ -360,23 +745,17 @@ impl FusionHTLC {
                     .safety_deposit_beneficiary
                     .unwrap_or(escrow.resolver.clone());
 
-                promise = promise.then(
-                    Promise::new(token_id).function_call(
-                        "ft_transfer".to_string(),
-                        format!(
-                            r#"{{"receiver_id":"{}","amount":"{}"}}"#,
-                            safety_recipient, escrow.safety_deposit
-                        )
-                        .into_bytes(),
-                        NearToken::from_yoctonear(ONE_YOCTO),
-                        BASE_GAS_FOR_FT_TRANSFER,
-                    ),
-                );
+                promise = promise.then(ft_transfer(
+                    token_id,
+                    &safety_recipient,
+                    escrow.safety_deposit,
+                    Some(&format!("{} safety deposit", escrow_id)),
+                ));
             }
         } else {
             // NEAR transfers
             promise = Promise::new(escrow.beneficiary.clone())
-                .transfer(NearToken::from_yoctonear(escrow.amount));
+                .transfer(NearToken::from_yoctonear(unfilled));
 
             if escrow.safety_deposit > 0 {
                 let safety_recipient = escrow
@@ -398,57 +777,256 @@ impl FusionHTLC {
         )
     }
 
+    fn internal_cancel(
+        &mut self,
+        escrow_id: String,
+        mut escrow: FusionEscrow,
+        canceller: AccountId,
+        now: Timestamp,
+    ) -> Promise {
+        // Update state before external calls
+        escrow.prior_state = Some(escrow.state.clone());
+        escrow.state = EscrowState::Cancelled;
+        escrow.resolved_by = Some(canceller.clone());
+        escrow.resolution_time = Some(now);
+        self.escrows.insert(&escrow_id, &escrow);
+
+        // Release the resolver's slot
+        self.release_active_slot(&escrow.resolver);
+
+        emit_event(HtlcEvent::EscrowCancelled(vec![EscrowCancelled {
+            escrow_id: escrow_id.clone(),
+            canceller: canceller.to_string(),
+            timestamp: now,
+        }]));
+
+        // Execute refund
+        self.execute_cancel_refund(escrow_id, escrow)
+    }
+
     fn execute_cancel_refund(&self, escrow_id: String, escrow: FusionEscrow) -> Promise {
+        let resolver = escrow.resolver.clone();
+        self.execute_unfilled_refund(escrow_id, escrow, resolver, "cancel")
+    }
+
+    fn execute_unfilled_refund(
+        &self,
+        escrow_id: String,
+        mut escrow: FusionEscrow,
+        recipient: AccountId,
+        operation: &str,
+    ) -> Promise {
+        // Filled parts already went to the beneficiary, only refund the rest
+        if let Some(filled) = escrow.cumulative_filled {
+            let unfilled = escrow.amount.saturating_sub(filled);
+            env::log_str(&format!(
+                "Refunding unfilled remainder {} of {} on escrow {}",
+                unfilled, escrow.amount, escrow_id
+            ));
+            escrow.amount = unfilled;
+        }
+
+        self.execute_refund(escrow_id, escrow, recipient, operation)
+    }
+
+    fn execute_refund(
+        &self,
+        escrow_id: String,
+        escrow: FusionEscrow,
+        recipient: AccountId,
+        operation: &str,
+    ) -> Promise {
         let total_amount = escrow.amount + escrow.safety_deposit;
 
         let promise = if let Some(token_id) = escrow.token_id {
             // NEP-141 token refund
-            Promise::new(token_id).function_call(
-                "ft_transfer".to_string(),
-                format!(
-                    r#"{{"receiver_id":"{}","amount":"{}"}}"#,
-                    escrow.resolver, total_amount
-                )
-                .into_bytes(),
-                NearToken::from_yoctonear(ONE_YOCTO),
-                BASE_GAS_FOR_FT_TRANSFER,
+            ft_transfer(
+                token_id,
+                &recipient,
+                total_amount,
+                Some(&format!("{} {}", escrow_id, operation)),
             )
         } else {
             // NEAR refund
-            Promise::new(escrow.resolver.clone()).transfer(NearToken::from_yoctonear(total_amount))
+            Promise::new(recipient).transfer(NearToken::from_yoctonear(total_amount))
         };
 
         // Add callback
         promise.then(
             Self::ext(env::current_account_id())
                 .with_static_gas(BASE_GAS_FOR_CALLBACK)
-                .on_transfer_complete(escrow_id, "cancel".to_string()),
+                .on_transfer_complete(escrow_id, operation.to_string()),
         )
     }
 
+    /// Settle a claim routed with `ft_transfer_call`
+    ///
+    /// The token refunds whatever the beneficiary contract did not use back to
+    /// this contract, so that part is sent to the beneficiary with a plain transfer
+    /// before the safety deposit is paid. A failed transfer call kept the tokens
+    /// here, so it is handled like any failed claim transfer.
     #[private]
-    pub fn on_transfer_complete(&mut self, escrow_id: String, operation: String) {
+    pub fn on_routed_claim(&mut self, escrow_id: String, amount: U128) -> PromiseOrValue<U128> {
+        let used = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value)
+                .map(|used| used.0.min(amount.0))
+                .unwrap_or(amount.0),
+            PromiseResult::Failed => {
+                self.handle_transfer_failure(escrow_id, "claim");
+                return PromiseOrValue::Value(U128(0));
+            }
+        };
+
+        let mut escrow = self.escrows.get(&escrow_id).expect("Escrow not found");
+        let token_id = escrow
+            .token_id
+            .clone()
+            .expect("Routed claims are token escrows");
+        let mut promise: Option<Promise> = None;
+
+        let unused = amount.0 - used;
+        if unused > 0 {
+            env::log_str(&format!(
+                "Beneficiary used {} of {} on escrow {}, forwarding {} directly",
+                used, amount.0, escrow_id, unused
+            ));
+            promise = Some(ft_transfer(
+                token_id.clone(),
+                &escrow.beneficiary,
+                unused,
+                Some(&format!("{} claim remainder", escrow_id)),
+            ));
+        }
+
+        if escrow.safety_deposit > 0 {
+            let safety_recipient = escrow
+                .safety_deposit_beneficiary
+                .clone()
+                .unwrap_or(escrow.resolver.clone());
+            let transfer = ft_transfer(
+                token_id,
+                &safety_recipient,
+                escrow.safety_deposit,
+                Some(&format!("{} safety deposit", escrow_id)),
+            );
+            promise = Some(match promise {
+                Some(promise) => promise.then(transfer),
+                None => transfer,
+            });
+        }
+
+        match promise {
+            Some(promise) => PromiseOrValue::Promise(
+                promise.then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(BASE_GAS_FOR_CALLBACK)
+                        .on_transfer_complete(escrow_id, "claim".to_string()),
+                ),
+            ),
+            None => {
+                // Nothing left to transfer, the claim is settled
+                escrow.prior_state = None;
+                self.escrows.insert(&escrow_id, &escrow);
+                PromiseOrValue::Value(U128(used))
+            }
+        }
+    }
+
+    /// Settle a `claim_partial` transfer, taking the fill back if it failed
+    #[private]
+    pub fn on_fill_complete(&mut self, escrow_id: String, fill_amount: U128) {
+        let Some(mut escrow) = self.escrows.get(&escrow_id) else {
+            return;
+        };
+        escrow.prior_state = None;
+
         match env::promise_result(0) {
             PromiseResult::Successful(_) => {
                 env::log_str(&format!(
-                    "Transfer completed successfully for {} operation on escrow {}",
-                    operation, escrow_id
+                    "Fill of {} completed on escrow {}",
+                    fill_amount.0, escrow_id
                 ));
             }
             PromiseResult::Failed => {
-                // Revert state on failure
+                // The beneficiary did not receive the part, so it is unfilled again
+                escrow.failed_transfer_attempts += 1;
+                escrow.cumulative_filled = escrow
+                    .cumulative_filled
+                    .map(|filled| filled.saturating_sub(fill_amount.0));
+                env::log_str(&format!(
+                    "Fill of {} failed on escrow {}, reverted",
+                    fill_amount.0, escrow_id
+                ));
+            }
+        }
+
+        self.escrows.insert(&escrow_id, &escrow);
+    }
+
+    #[private]
+    pub fn on_transfer_complete(&mut self, escrow_id: String, operation: String) {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
                 if let Some(mut escrow) = self.escrows.get(&escrow_id) {
-                    escrow.state = EscrowState::Active;
-                    escrow.resolved_by = None;
-                    escrow.resolution_time = None;
+                    escrow.prior_state = None;
                     self.escrows.insert(&escrow_id, &escrow);
+                }
+                env::log_str(&format!(
+                    "Transfer completed successfully for {} operation on escrow {}",
+                    operation, escrow_id
+                ));
+            }
+            PromiseResult::Failed => self.handle_transfer_failure(escrow_id, &operation),
+        }
+    }
+
+    /// Count a failed transfer and revert the escrow, or park it as Failed
+    fn handle_transfer_failure(&mut self, escrow_id: String, operation: &str) {
+        if let Some(mut escrow) = self.escrows.get(&escrow_id) {
+            escrow.failed_transfer_attempts += 1;
 
+            // A deleted resolver account can never take the refund, so send
+            // it to the fallback and keep the escrow cancelled
+            if operation == "cancel" {
+                if let Some(fallback) = escrow.refund_fallback.clone() {
                     env::log_str(&format!(
-                        "Transfer failed for {} operation on escrow {}, reverted to active",
-                        operation, escrow_id
+                        "Refund for escrow {} failed, re-routing to fallback {}",
+                        escrow_id, fallback
                     ));
+                    self.escrows.insert(&escrow_id, &escrow);
+                    self.execute_unfilled_refund(escrow_id, escrow, fallback, "cancel_fallback");
+                    return;
+                }
+            }
+
+            escrow.resolved_by = None;
+            escrow.resolution_time = None;
+
+            // Only go back to the state the operation started from, never a
+            // blanket Active that could reopen a path the operation had closed
+            let prior_state = escrow.prior_state.take().unwrap_or(EscrowState::Active);
+
+            // A token contract that is gone would otherwise fail forever
+            if escrow.failed_transfer_attempts >= MAX_TRANSFER_ATTEMPTS {
+                escrow.state = EscrowState::Failed;
+                escrow.failed_operation = Some(operation.to_string());
+                env::log_str(&format!(
+                    "Transfer failed for {} operation on escrow {} after {} attempts, marked as failed",
+                    operation, escrow_id, escrow.failed_transfer_attempts
+                ));
+            } else {
+                if prior_state == EscrowState::Active && escrow.state != EscrowState::Active {
+                    // claim/cancel released the resolver's slot
+                    self.take_active_slot(&escrow.resolver);
                 }
+                escrow.state = prior_state;
+                env::log_str(&format!(
+                    "Transfer failed for {} operation on escrow {}, reverted to {:?}",
+                    operation, escrow_id, escrow.state
+                ));
             }
+
+            self.escrows.insert(&escrow_id, &escrow);
         }
     }
 }
@@ -470,6 +1048,7 @@ impl FusionHTLC {
 
             if let Some(escrow) = self.escrows.get(&escrow_id) {
                 if escrow.state == EscrowState::Active
+                    && escrow.prior_state.is_none()
                     && env::block_timestamp() >= escrow.public_cancel_time
                 {
                     // Store state before external call
@@ -527,6 +1106,72 @@ impl FusionHTLC {
     }
 }
 
+/// Arguments of NEP-141 `ft_transfer`
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct FtTransferArgs<'a> {
+    receiver_id: &'a AccountId,
+    amount: U128,
+    memo: Option<&'a str>,
+}
+
+/// Arguments of NEP-141 `ft_transfer_call`
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct FtTransferCallArgs<'a> {
+    receiver_id: &'a AccountId,
+    amount: U128,
+    memo: Option<&'a str>,
+    msg: &'a str,
+}
+
+/// NEP-141 `ft_transfer` with the 1 yoctoNEAR the standard requires
+fn ft_transfer(
+    token_id: AccountId,
+    receiver_id: &AccountId,
+    amount: Balance,
+    memo: Option<&str>,
+) -> Promise {
+    let args = FtTransferArgs {
+        receiver_id,
+        amount: U128(amount),
+        memo,
+    };
+    Promise::new(token_id).function_call(
+        "ft_transfer".to_string(),
+        near_sdk::serde_json::to_vec(&args).expect("Failed to serialize ft_transfer args"),
+        NearToken::from_yoctonear(ONE_YOCTO),
+        BASE_GAS_FOR_FT_TRANSFER,
+    )
+}
+
+/// NEP-141 `ft_transfer_call`, letting `receiver_id` act on the transfer with `msg`
+fn ft_transfer_call(
+    token_id: AccountId,
+    receiver_id: &AccountId,
+    amount: Balance,
+    memo: Option<&str>,
+    msg: &str,
+) -> Promise {
+    let args = FtTransferCallArgs {
+        receiver_id,
+        amount: U128(amount),
+        memo,
+        msg,
+    };
+    Promise::new(token_id).function_call(
+        "ft_transfer_call".to_string(),
+        near_sdk::serde_json::to_vec(&args).expect("Failed to serialize ft_transfer_call args"),
+        NearToken::from_yoctonear(ONE_YOCTO),
+        BASE_GAS_FOR_FT_TRANSFER_CALL,
+    )
+}
+
+/// Log a NEP-297 event in the shared schema
+fn emit_event(event: HtlcEvent) {
+    env::log_str(&HtlcEventLog::new(event).to_log_string());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -541,6 +1186,26 @@ mod tests {
             .build()
     }
 
+    /// Fund a token escrow the way a NEP-141 contract would via ft_transfer_call
+    fn fund_token_escrow(
+        contract: &mut FusionHTLC,
+        sender: AccountId,
+        transferred: Balance,
+        params: CreateEscrowParams,
+    ) -> (String, U128) {
+        let token_id = params.token_id.clone().unwrap();
+        testing_env!(get_context(token_id, 0, 0));
+
+        let msg = near_sdk::serde_json::to_string(&params).unwrap();
+        let unused = match contract.ft_on_transfer(sender, U128(transferred), msg) {
+            PromiseOrValue::Value(unused) => unused,
+            PromiseOrValue::Promise(_) => panic!("Expected unused amount"),
+        };
+
+        let escrow_id = format!("fusion_{}", contract.escrow_counter - 1);
+        (escrow_id, unused)
+    }
+
     fn create_valid_secret_hash() -> String {
         // Create a proper base58 encoded SHA256 hash
         let secret = "my_secret_12345";
@@ -555,7 +1220,7 @@ mod tests {
         let context = get_context(accounts(0), 2_000_000_000_000_000_000_000_000, 0); // 2 NEAR
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         let params = CreateEscrowParams {
             beneficiary: accounts(1),
@@ -567,6 +1232,9 @@ mod tests {
             finality_period: 3600,       // 1 hour
             cancel_period: 7200,         // 2 hours
             public_cancel_period: 10800, // 3 hours
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -578,13 +1246,245 @@ mod tests {
         assert_eq!(escrow.state, EscrowState::Active);
     }
 
+    #[test]
+    fn test_remaining_claimable_after_half_fill() {
+        let context = get_context(accounts(0), 2_000_000_000_000_000_000_000_000, 0);
+        testing_env!(context);
+
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let amount = 1_000_000_000_000_000_000_000_000; // 1 NEAR
+
+        let partial_params = |allow_partial_fills| CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: None,
+            amount: U128(amount),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+
+        let partial_id = contract.create_escrow(partial_params(true));
+        let whole_id = contract.create_escrow(partial_params(false));
+        assert_eq!(
+            contract.get_remaining_claimable(partial_id.clone()),
+            Some(amount)
+        );
+        assert_eq!(contract.get_remaining_claimable(whole_id), None);
+
+        // Half of the escrow has been filled
+        fill_native(
+            &mut contract,
+            &partial_id,
+            amount / 2,
+            PromiseResult::Successful(vec![]),
+        );
+        assert_eq!(
+            contract.get_remaining_claimable(partial_id.clone()),
+            Some(amount / 2)
+        );
+
+        // Nothing is claimable once the escrow is resolved
+        claim_native(&mut contract, &partial_id);
+        assert_eq!(contract.get_remaining_claimable(partial_id), None);
+        assert_eq!(
+            contract.get_remaining_claimable("missing".to_string()),
+            None
+        );
+    }
+
+    /// Fill part of a native escrow as the beneficiary and settle the transfer
+    fn fill_native(
+        contract: &mut FusionHTLC,
+        escrow_id: &str,
+        fill_amount: Balance,
+        result: PromiseResult,
+    ) {
+        testing_env!(get_context(accounts(1), 0, 0));
+        let _ = contract.claim_partial(
+            escrow_id.to_string(),
+            hex::encode("my_secret_12345"),
+            U128(fill_amount),
+        );
+
+        testing_env!(
+            VMContextBuilder::new()
+                .predecessor_account_id(accounts(0))
+                .current_account_id(accounts(0))
+                .build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+        contract.on_fill_complete(escrow_id.to_string(), U128(fill_amount));
+    }
+
+    fn partial_escrow_params(amount: Balance) -> CreateEscrowParams {
+        CreateEscrowParams {
+            allow_partial_fills: true,
+            amount: U128(amount),
+            ..native_escrow_params()
+        }
+    }
+
+    #[test]
+    fn test_cancel_unfilled_refunds_remaining_parts() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(partial_escrow_params(1_000_000));
+
+        // Two of four equal parts have been filled and paid to the beneficiary
+        for _ in 0..2 {
+            fill_native(
+                &mut contract,
+                &escrow_id,
+                250_000,
+                PromiseResult::Successful(vec![]),
+            );
+        }
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log == "Fill of 250000 completed on escrow fusion_0"));
+        assert_eq!(
+            contract
+                .get_escrow(escrow_id.clone())
+                .unwrap()
+                .cumulative_filled,
+            Some(500_000)
+        );
+
+        // The beneficiary releases the other two parts before finality
+        testing_env!(get_context(accounts(1), 0, 1000 * 1_000_000_000));
+        let _ = contract.cancel_unfilled(escrow_id.clone());
+
+        let escrow = contract.get_escrow(escrow_id.clone()).unwrap();
+        assert_eq!(escrow.state, EscrowState::Cancelled);
+        assert_eq!(escrow.resolved_by, Some(accounts(1)));
+        assert_eq!(escrow.cumulative_filled, Some(500_000));
+        assert_eq!(contract.get_remaining_claimable(escrow_id), None);
+        assert_eq!(contract.active_escrows_per_account.get(&accounts(0)), None);
+        // The resolver only gets back the unfilled half
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log == "Refunding unfilled remainder 500000 of 1000000 on escrow fusion_0"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Too early to cancel")]
+    fn test_cancel_unfilled_respects_cancel_window() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(partial_escrow_params(1_000_000));
+
+        // The resolver has to wait for the cancel time like a full cancel
+        testing_env!(get_context(accounts(0), 0, 1000 * 1_000_000_000));
+        let _ = contract.cancel_unfilled(escrow_id);
+    }
+
+    #[test]
+    fn test_failed_fill_is_unfilled_again() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(partial_escrow_params(1_000_000));
+
+        fill_native(&mut contract, &escrow_id, 250_000, PromiseResult::Failed);
+
+        let escrow = contract.get_escrow(escrow_id.clone()).unwrap();
+        assert_eq!(escrow.cumulative_filled, Some(0));
+        assert_eq!(escrow.failed_transfer_attempts, 1);
+        assert_eq!(escrow.prior_state, None);
+        assert_eq!(contract.get_remaining_claimable(escrow_id), Some(1_000_000));
+    }
+
+    #[test]
+    fn test_claim_after_fills_completes_escrow() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(partial_escrow_params(1_000_000));
+
+        fill_native(
+            &mut contract,
+            &escrow_id,
+            250_000,
+            PromiseResult::Successful(vec![]),
+        );
+        // The last part completes the escrow like a claim
+        testing_env!(get_context(accounts(1), 0, 0));
+        let _ = contract.claim_partial(
+            escrow_id.clone(),
+            hex::encode("my_secret_12345"),
+            U128(750_000),
+        );
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Claimed);
+        assert_eq!(escrow.cumulative_filled, Some(250_000));
+        assert_eq!(contract.active_escrows_per_account.get(&accounts(0)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fill exceeds the unfilled amount")]
+    fn test_fill_cannot_exceed_unfilled_amount() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(partial_escrow_params(1_000_000));
+
+        fill_native(
+            &mut contract,
+            &escrow_id,
+            500_000,
+            PromiseResult::Successful(vec![]),
+        );
+        fill_native(
+            &mut contract,
+            &escrow_id,
+            500_001,
+            PromiseResult::Successful(vec![]),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Fill transfer in flight")]
+    fn test_cancel_waits_for_fill_transfer() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(partial_escrow_params(1_000_000));
+
+        testing_env!(get_context(accounts(1), 0, 0));
+        let _ = contract.claim_partial(
+            escrow_id.clone(),
+            hex::encode("my_secret_12345"),
+            U128(250_000),
+        );
+
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Escrow does not allow partial fills")]
+    fn test_cancel_unfilled_requires_partial_escrow() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel_unfilled(escrow_id);
+    }
+
     // Test 1: Binary Data Hash Verification
     #[test]
     fn test_hash_verification_with_binary_data() {
         let context = get_context(accounts(0), 2_000_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         // Test with actual binary data secret
         let secret_bytes = vec![0xde, 0xad, 0xbe, 0xef, 0x01, 0x23, 0x45, 0x67];
@@ -606,6 +1506,9 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -626,7 +1529,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         let params = CreateEscrowParams {
             beneficiary: accounts(1),
@@ -638,6 +1541,9 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -655,7 +1561,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, start_time);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         // Test with large time periods that could cause overflow
         let params = CreateEscrowParams {
@@ -668,6 +1574,9 @@ mod tests {
             finality_period: 31_536_000,      // 1 year in seconds
             cancel_period: 63_072_000,        // 2 years in seconds
             public_cancel_period: 94_608_000, // 3 years in seconds
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -695,7 +1604,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, max_time);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         // This should cause overflow
         let params = CreateEscrowParams {
@@ -708,6 +1617,9 @@ mod tests {
             finality_period: u64::MAX / 1_000_000_000, // This will overflow
             cancel_period: u64::MAX / 1_000_000_000,
             public_cancel_period: u64::MAX / 1_000_000_000,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         contract.create_escrow(params);
@@ -719,7 +1631,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
         let secret = "test_secret_123";
         let secret_hash = contract.hash_secret(&hex::encode(secret.as_bytes()));
 
@@ -733,6 +1645,9 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -753,7 +1668,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
         let secret = "test_secret_123";
         let secret_hash = contract.hash_secret(&hex::encode(secret.as_bytes()));
 
@@ -767,6 +1682,9 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -784,7 +1702,7 @@ mod tests {
         let context = get_context(accounts(0), 5_000_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
         let mut escrow_ids = Vec::new();
 
         // Create multiple escrows
@@ -799,6 +1717,9 @@ mod tests {
                 finality_period: 3600,
                 cancel_period: 7200,
                 public_cancel_period: 10800,
+                allow_partial_fills: false,
+                beneficiary_msg: None,
+                refund_fallback: None,
             };
             escrow_ids.push(contract.create_escrow(params));
         }
@@ -822,7 +1743,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
         let secret = "test_secret_123";
         let secret_hash = contract.hash_secret(&hex::encode(secret.as_bytes()));
 
@@ -836,6 +1757,9 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -864,7 +1788,7 @@ mod tests {
         let context = get_context(accounts(0), 1, 0); // Only 1 yocto for token transfers
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
         let token_id: AccountId = "token.testnet".parse().unwrap();
 
         let params = CreateEscrowParams {
@@ -877,43 +1801,156 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
-        // Should accept token escrow with minimal NEAR deposit
-        let escrow_id = contract.create_escrow(params);
+        // Tokens arrive through ft_transfer_call, the surplus is returned
+        let (escrow_id, unused) = fund_token_escrow(&mut contract, accounts(0), 1_200_000, params);
         let escrow = contract.get_escrow(escrow_id).unwrap();
 
         assert_eq!(escrow.token_id, Some(token_id));
+        assert_eq!(escrow.resolver, accounts(0));
         assert_eq!(escrow.amount, 1_000_000);
+        assert_eq!(unused, U128(100_000));
     }
 
-    // Test 7: Authorization and Access Control
     #[test]
-    #[should_panic(expected = "Only beneficiary can claim")]
-    fn test_unauthorized_claim() {
-        let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
-        testing_env!(context);
-
-        let mut contract = FusionHTLC::new(accounts(0));
-        let secret = "test_secret_123";
-        let secret_hash = contract.hash_secret(&hex::encode(secret.as_bytes()));
+    #[should_panic(expected = "Insufficient token deposit")]
+    fn test_underfunded_token_escrow_rejected() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         let params = CreateEscrowParams {
-            beneficiary: accounts(1), // Beneficiary is account 1
-            secret_hash,
-            token_id: None,
-            amount: U128(1_000_000_000_000_000_000_000_000),
-            safety_deposit: U128(0),
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(100_000),
             safety_deposit_beneficiary: None,
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
-        let escrow_id = contract.create_escrow(params);
+        // Only the amount is transferred, not the safety deposit
+        fund_token_escrow(&mut contract, accounts(0), 1_000_000, params);
+    }
 
-        // Try to claim as wrong account (account 2)
-        testing_env!(get_context(accounts(2), 0, 1800 * 1_000_000_000));
+    #[test]
+    #[should_panic(expected = "Token escrows must be funded with ft_transfer_call")]
+    fn test_token_escrow_without_transfer_rejected() {
+        testing_env!(get_context(accounts(0), 1, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+
+        contract.create_escrow(params);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token does not match escrow params")]
+    fn test_token_escrow_from_wrong_token_rejected() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+
+        // A different contract calling ft_on_transfer must not create the escrow
+        testing_env!(get_context("fake-token.testnet".parse().unwrap(), 0, 0));
+        let msg = near_sdk::serde_json::to_string(&params).unwrap();
+        let _ = contract.ft_on_transfer(accounts(0), U128(1_000_000), msg);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient NEAR deposit")]
+    fn test_underfunded_native_escrow_with_safety_deposit() {
+        // Enough for the amount but not for the safety deposit on top
+        testing_env!(get_context(
+            accounts(0),
+            1_000_000_000_000_000_000_000_000,
+            0
+        ));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: None,
+            amount: U128(1_000_000_000_000_000_000_000_000),
+            safety_deposit: U128(1),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+
+        contract.create_escrow(params);
+    }
+
+    // Test 7: Authorization and Access Control
+    #[test]
+    #[should_panic(expected = "Only beneficiary can claim")]
+    fn test_unauthorized_claim() {
+        let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
+        testing_env!(context);
+
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let secret = "test_secret_123";
+        let secret_hash = contract.hash_secret(&hex::encode(secret.as_bytes()));
+
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1), // Beneficiary is account 1
+            secret_hash,
+            token_id: None,
+            amount: U128(1_000_000_000_000_000_000_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+
+        let escrow_id = contract.create_escrow(params);
+
+        // Try to claim as wrong account (account 2)
+        testing_env!(get_context(accounts(2), 0, 1800 * 1_000_000_000));
         contract.claim(escrow_id, hex::encode(secret.as_bytes()));
     }
 
@@ -923,7 +1960,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         let params = CreateEscrowParams {
             beneficiary: accounts(1),
@@ -935,6 +1972,9 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         let escrow_id = contract.create_escrow(params);
@@ -950,7 +1990,7 @@ mod tests {
         let context = get_context(accounts(0), 0, 0);
         testing_env!(context);
 
-        let contract = FusionHTLC::new(accounts(0));
+        let contract = FusionHTLC::new(accounts(0), None);
 
         // Test various binary patterns
         let test_cases = vec![
@@ -981,7 +2021,7 @@ mod tests {
         let context = get_context(accounts(0), 1_100_000_000_000_000_000_000_000, 0);
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         let params = CreateEscrowParams {
             beneficiary: accounts(1),
@@ -993,6 +2033,9 @@ mod tests {
             finality_period: 7200,       // 2 hours
             cancel_period: 3600,         // 1 hour (invalid - before finality)
             public_cancel_period: 10800, // 3 hours
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         contract.create_escrow(params);
@@ -1004,7 +2047,7 @@ mod tests {
         let context = get_context(accounts(0), 1_000_000_000_000_000_000_000_000, 0); // 1 NEAR
         testing_env!(context);
 
-        let mut contract = FusionHTLC::new(accounts(0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
 
         let params = CreateEscrowParams {
             beneficiary: accounts(1),
@@ -1016,8 +2059,742 @@ mod tests {
             finality_period: 3600,
             cancel_period: 7200,
             public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
         };
 
         contract.create_escrow(params);
     }
+
+    // Test 10: Repeated transfer failure parks the escrow
+    fn fail_transfer(contract: &mut FusionHTLC, escrow_id: &str) {
+        testing_env!(
+            VMContextBuilder::new()
+                .predecessor_account_id(accounts(0))
+                .current_account_id(accounts(0))
+                .build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        contract.on_transfer_complete(escrow_id.to_string(), "cancel".to_string());
+    }
+
+    /// Claim with the test secret and fail the transfer until the escrow is marked Failed
+    fn fail_claim_until_failed(contract: &mut FusionHTLC, escrow_id: &str) {
+        for _ in 0..MAX_TRANSFER_ATTEMPTS {
+            testing_env!(get_context(accounts(1), 0, 1800 * 1_000_000_000));
+            let _ = contract.claim(
+                escrow_id.to_string(),
+                hex::encode("my_secret_12345".as_bytes()),
+            );
+            testing_env!(
+                VMContextBuilder::new()
+                    .predecessor_account_id(accounts(0))
+                    .current_account_id(accounts(0))
+                    .build(),
+                near_sdk::test_vm_config(),
+                near_sdk::RuntimeFeesConfig::test(),
+                Default::default(),
+                vec![PromiseResult::Failed],
+            );
+            contract.on_transfer_complete(escrow_id.to_string(), "claim".to_string());
+        }
+        let escrow = contract.get_escrow(escrow_id.to_string()).unwrap();
+        assert_eq!(escrow.state, EscrowState::Failed);
+        assert_eq!(escrow.failed_operation, Some("claim".to_string()));
+    }
+
+    #[test]
+    fn test_repeated_transfer_failure_marks_escrow_failed() {
+        let context = get_context(accounts(0), 1, 0);
+        testing_env!(context);
+
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("gone-token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+        let (escrow_id, _) = fund_token_escrow(&mut contract, accounts(0), 1_000_000, params);
+
+        for attempt in 1..=MAX_TRANSFER_ATTEMPTS {
+            testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+            let _ = contract.cancel(escrow_id.clone());
+            fail_transfer(&mut contract, &escrow_id);
+
+            let escrow = contract.get_escrow(escrow_id.clone()).unwrap();
+            assert_eq!(escrow.failed_transfer_attempts, attempt);
+            if attempt < MAX_TRANSFER_ATTEMPTS {
+                assert_eq!(escrow.state, EscrowState::Active);
+            } else {
+                assert_eq!(escrow.state, EscrowState::Failed);
+            }
+        }
+
+        // The resolver can send the funds somewhere else
+        testing_env!(get_context(accounts(0), 0, 8000 * 1_000_000_000));
+        let _ = contract.retarget_failed_escrow(escrow_id.clone(), accounts(3));
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Refunded);
+        assert_eq!(escrow.resolved_by, Some(accounts(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Escrow not active")]
+    fn test_failed_escrow_cannot_be_cancelled() {
+        let context = get_context(accounts(0), 1, 0);
+        testing_env!(context);
+
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("gone-token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+        let (escrow_id, _) = fund_token_escrow(&mut contract, accounts(0), 1_000_000, params);
+
+        for _ in 0..MAX_TRANSFER_ATTEMPTS {
+            testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+            let _ = contract.cancel(escrow_id.clone());
+            fail_transfer(&mut contract, &escrow_id);
+        }
+
+        testing_env!(get_context(accounts(0), 0, 11000 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or resolver can retarget")]
+    fn test_retarget_requires_owner_or_resolver() {
+        let context = get_context(accounts(0), 1, 0);
+        testing_env!(context);
+
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let params = CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("gone-token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        };
+        let (escrow_id, _) = fund_token_escrow(&mut contract, accounts(0), 1_000_000, params);
+
+        for _ in 0..MAX_TRANSFER_ATTEMPTS {
+            testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+            let _ = contract.cancel(escrow_id.clone());
+            fail_transfer(&mut contract, &escrow_id);
+        }
+
+        testing_env!(get_context(accounts(2), 0, 8000 * 1_000_000_000));
+        let _ = contract.retarget_failed_escrow(escrow_id, accounts(2));
+    }
+
+    // Test 11: A failed transfer only restores the state the operation started from
+    fn native_escrow_params() -> CreateEscrowParams {
+        CreateEscrowParams {
+            beneficiary: accounts(1),
+            secret_hash: create_valid_secret_hash(),
+            token_id: None,
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: None,
+            refund_fallback: None,
+        }
+    }
+
+    #[test]
+    fn test_failed_cancel_restores_prior_state() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id.clone());
+        let escrow = contract.get_escrow(escrow_id.clone()).unwrap();
+        assert_eq!(escrow.state, EscrowState::Cancelled);
+        assert_eq!(escrow.prior_state, Some(EscrowState::Active));
+        assert_eq!(contract.active_escrows_per_account.get(&accounts(0)), None);
+
+        fail_transfer(&mut contract, &escrow_id);
+
+        let escrow = contract.get_escrow(escrow_id.clone()).unwrap();
+        assert_eq!(escrow.state, EscrowState::Active);
+        assert_eq!(escrow.prior_state, None);
+        assert_eq!(escrow.resolved_by, None);
+        // The resolver slot released by cancel is taken again
+        assert_eq!(
+            contract.active_escrows_per_account.get(&accounts(0)),
+            Some(1)
+        );
+
+        // The resolver can retry the refund
+        testing_env!(get_context(accounts(0), 0, 7600 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id.clone());
+        assert_eq!(
+            contract.get_escrow(escrow_id).unwrap().state,
+            EscrowState::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_failed_cancel_refund_goes_to_fallback() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(CreateEscrowParams {
+            refund_fallback: Some(accounts(3)),
+            ..native_escrow_params()
+        });
+
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id.clone());
+        fail_transfer(&mut contract, &escrow_id);
+
+        // The escrow stays cancelled while the fallback refund is in flight
+        let escrow = contract.get_escrow(escrow_id.clone()).unwrap();
+        assert_eq!(escrow.state, EscrowState::Cancelled);
+        assert_eq!(escrow.failed_transfer_attempts, 1);
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log
+            == &format!(
+                "Refund for escrow {} failed, re-routing to fallback {}",
+                escrow_id,
+                accounts(3)
+            )));
+
+        // If the fallback also fails the escrow goes back to Active as usual
+        testing_env!(
+            VMContextBuilder::new()
+                .predecessor_account_id(accounts(0))
+                .current_account_id(accounts(0))
+                .build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        contract.on_transfer_complete(escrow_id.clone(), "cancel_fallback".to_string());
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Active);
+        assert_eq!(escrow.failed_transfer_attempts, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Past finality time, cannot claim")]
+    fn test_failed_cancel_does_not_reopen_claim() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id.clone());
+        fail_transfer(&mut contract, &escrow_id);
+
+        // The beneficiary must not be able to race the pending refund
+        testing_env!(get_context(accounts(1), 0, 7500 * 1_000_000_000));
+        let _ = contract.claim(escrow_id, "00".repeat(32));
+    }
+
+    #[test]
+    fn test_failed_retarget_stays_failed() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+
+        for _ in 0..MAX_TRANSFER_ATTEMPTS {
+            testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+            let _ = contract.cancel(escrow_id.clone());
+            fail_transfer(&mut contract, &escrow_id);
+        }
+
+        testing_env!(get_context(accounts(0), 0, 8000 * 1_000_000_000));
+        let _ = contract.retarget_failed_escrow(escrow_id.clone(), accounts(3));
+        fail_transfer(&mut contract, &escrow_id);
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Failed);
+        assert_eq!(escrow.prior_state, None);
+        assert_eq!(escrow.failed_operation, Some("retarget".to_string()));
+    }
+
+    #[test]
+    fn test_failed_claim_retarget_pays_beneficiary() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+        fail_claim_until_failed(&mut contract, &escrow_id);
+
+        testing_env!(get_context(accounts(1), 0, 8000 * 1_000_000_000));
+        let _ = contract.retarget_failed_escrow(escrow_id.clone(), accounts(1));
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Claimed);
+        assert_eq!(escrow.failed_operation, None);
+        assert_eq!(escrow.resolved_by, Some(accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or beneficiary can retarget a failed claim")]
+    fn test_resolver_cannot_retarget_failed_claim() {
+        testing_env!(get_context(accounts(2), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+        fail_claim_until_failed(&mut contract, &escrow_id);
+
+        testing_env!(get_context(accounts(2), 0, 8000 * 1_000_000_000));
+        let _ = contract.retarget_failed_escrow(escrow_id, accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "A failed claim can only be paid to the beneficiary")]
+    fn test_failed_claim_cannot_be_redirected() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+        fail_claim_until_failed(&mut contract, &escrow_id);
+
+        // Not even the owner can send the beneficiary's funds elsewhere
+        testing_env!(get_context(accounts(0), 0, 8000 * 1_000_000_000));
+        let _ = contract.retarget_failed_escrow(escrow_id, accounts(3));
+    }
+
+    #[test]
+    fn test_successful_transfer_clears_prior_state() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id.clone());
+
+        testing_env!(
+            VMContextBuilder::new()
+                .predecessor_account_id(accounts(0))
+                .current_account_id(accounts(0))
+                .build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(vec![])],
+        );
+        contract.on_transfer_complete(escrow_id.clone(), "cancel".to_string());
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Cancelled);
+        assert_eq!(escrow.prior_state, None);
+    }
+
+    // Test 12: Storage limits are set at init and adjusted by the owner
+    fn small_limits() -> EscrowLimits {
+        EscrowLimits {
+            max_total_escrows: 3,
+            max_escrows_per_account: 1,
+            max_escrow_amount: U128(10_000_000),
+            min_time_period_seconds: 60,
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Maximum escrows per account limit reached")]
+    fn test_lowered_per_account_limit_enforced() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), Some(small_limits()));
+        contract.create_escrow(native_escrow_params());
+
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        contract.create_escrow(native_escrow_params());
+    }
+
+    #[test]
+    fn test_owner_can_raise_total_cap() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(
+            accounts(0),
+            Some(EscrowLimits {
+                max_total_escrows: 1,
+                ..small_limits()
+            }),
+        );
+
+        testing_env!(get_context(accounts(1), 1_000_000, 0));
+        contract.create_escrow(native_escrow_params());
+
+        testing_env!(get_context(accounts(0), 0, 0));
+        contract.set_escrow_limits(small_limits());
+        assert_eq!(contract.get_escrow_limits(), small_limits());
+
+        testing_env!(get_context(accounts(2), 1_000_000, 0));
+        assert_eq!(contract.create_escrow(native_escrow_params()), "fusion_1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can update limits")]
+    fn test_non_owner_cannot_update_limits() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+
+        testing_env!(get_context(accounts(1), 0, 0));
+        contract.set_escrow_limits(small_limits());
+    }
+
+    #[test]
+    #[should_panic(expected = "Per-account escrow limit cannot exceed the total limit")]
+    fn test_inconsistent_limits_rejected() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        contract.set_escrow_limits(EscrowLimits {
+            max_escrows_per_account: 4,
+            ..small_limits()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Finality period below minimum")]
+    fn test_one_second_timeout_rejected_by_default() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        contract.create_escrow(CreateEscrowParams {
+            finality_period: 1,
+            cancel_period: 2,
+            public_cancel_period: 3,
+            ..native_escrow_params()
+        });
+    }
+
+    #[test]
+    fn test_one_second_timeout_allowed_with_lowered_minimum() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(
+            accounts(0),
+            Some(EscrowLimits {
+                min_time_period_seconds: 1,
+                ..small_limits()
+            }),
+        );
+        let escrow_id = contract.create_escrow(CreateEscrowParams {
+            finality_period: 1,
+            cancel_period: 2,
+            public_cancel_period: 3,
+            ..native_escrow_params()
+        });
+        assert!(contract.get_escrow(escrow_id).is_some());
+    }
+
+    // Test 13: Claims routed into a beneficiary contract
+    fn routed_token_escrow(contract: &mut FusionHTLC) -> String {
+        let params = CreateEscrowParams {
+            beneficiary: "vault.testnet".parse().unwrap(),
+            secret_hash: create_valid_secret_hash(),
+            token_id: Some("token.testnet".parse().unwrap()),
+            amount: U128(1_000_000),
+            safety_deposit: U128(0),
+            safety_deposit_beneficiary: None,
+            finality_period: 3600,
+            cancel_period: 7200,
+            public_cancel_period: 10800,
+            allow_partial_fills: false,
+            beneficiary_msg: Some(r#"{"owner":"alice.testnet"}"#.to_string()),
+            refund_fallback: None,
+        };
+        let (escrow_id, _) = fund_token_escrow(contract, accounts(0), 1_000_000, params);
+        escrow_id
+    }
+
+    fn settle_routed_claim(
+        contract: &mut FusionHTLC,
+        escrow_id: &str,
+        result: PromiseResult,
+    ) -> PromiseOrValue<U128> {
+        testing_env!(
+            VMContextBuilder::new()
+                .predecessor_account_id(accounts(0))
+                .current_account_id(accounts(0))
+                .build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+        contract.on_routed_claim(escrow_id.to_string(), U128(1_000_000))
+    }
+
+    #[test]
+    fn test_routed_claim_keeps_beneficiary_msg() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = routed_token_escrow(&mut contract);
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(
+            escrow.beneficiary_msg.as_deref(),
+            Some(r#"{"owner":"alice.testnet"}"#)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Beneficiary msg requires a token escrow")]
+    fn test_beneficiary_msg_rejected_for_native_escrow() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        contract.create_escrow(CreateEscrowParams {
+            beneficiary_msg: Some("deposit".to_string()),
+            ..native_escrow_params()
+        });
+    }
+
+    #[test]
+    fn test_routed_claim_fully_used() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = routed_token_escrow(&mut contract);
+
+        let used = near_sdk::serde_json::to_vec(&U128(1_000_000)).unwrap();
+        match settle_routed_claim(&mut contract, &escrow_id, PromiseResult::Successful(used)) {
+            PromiseOrValue::Value(used) => assert_eq!(used, U128(1_000_000)),
+            PromiseOrValue::Promise(_) => panic!("Nothing should be forwarded"),
+        }
+    }
+
+    #[test]
+    fn test_routed_claim_forwards_unused_amount() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = routed_token_escrow(&mut contract);
+
+        // The vault only took part, the token refunded the rest to the HTLC
+        let used = near_sdk::serde_json::to_vec(&U128(600_000)).unwrap();
+        let result =
+            settle_routed_claim(&mut contract, &escrow_id, PromiseResult::Successful(used));
+        assert!(matches!(result, PromiseOrValue::Promise(_)));
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("forwarding 400000 directly")));
+    }
+
+    #[test]
+    fn test_failed_routed_claim_reverts_to_active() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = routed_token_escrow(&mut contract);
+
+        testing_env!(get_context("vault.testnet".parse().unwrap(), 0, 0));
+        let _ = contract.claim(escrow_id.clone(), hex::encode("my_secret_12345"));
+        assert_eq!(contract.active_escrows_per_account.get(&accounts(0)), None);
+
+        // The tokens never left the contract, so the claim can be retried
+        let result = settle_routed_claim(&mut contract, &escrow_id, PromiseResult::Failed);
+        assert!(matches!(result, PromiseOrValue::Value(U128(0))));
+
+        let escrow = contract.get_escrow(escrow_id).unwrap();
+        assert_eq!(escrow.state, EscrowState::Active);
+        assert_eq!(escrow.failed_transfer_attempts, 1);
+        assert_eq!(escrow.prior_state, None);
+        assert_eq!(escrow.resolved_by, None);
+        assert_eq!(
+            contract.active_escrows_per_account.get(&accounts(0)),
+            Some(1)
+        );
+    }
+
+    // Test 14: Active escrow count bookkeeping
+    fn active_count(contract: &FusionHTLC) -> Option<u64> {
+        contract.active_escrows_per_account.get(&accounts(0))
+    }
+
+    fn claim_native(contract: &mut FusionHTLC, escrow_id: &str) {
+        testing_env!(get_context(accounts(1), 0, 0));
+        let _ = contract.claim(escrow_id.to_string(), hex::encode("my_secret_12345"));
+    }
+
+    fn cancel_native(contract: &mut FusionHTLC, escrow_id: &str) {
+        testing_env!(get_context(accounts(0), 0, 7500 * 1_000_000_000));
+        let _ = contract.cancel(escrow_id.to_string());
+    }
+
+    #[test]
+    fn test_active_count_follows_create_claim_cancel_interleavings() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let first = contract.create_escrow(native_escrow_params());
+        let second = contract.create_escrow(native_escrow_params());
+        assert_eq!(active_count(&contract), Some(2));
+
+        claim_native(&mut contract, &first);
+        assert_eq!(active_count(&contract), Some(1));
+
+        // A new escrow takes the slot the claim released
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let third = contract.create_escrow(native_escrow_params());
+        assert_eq!(active_count(&contract), Some(2));
+
+        cancel_native(&mut contract, &second);
+        assert_eq!(active_count(&contract), Some(1));
+
+        // The failed refund reopens the escrow and its slot
+        fail_transfer(&mut contract, &second);
+        assert_eq!(active_count(&contract), Some(2));
+
+        cancel_native(&mut contract, &second);
+        cancel_native(&mut contract, &third);
+        assert_eq!(active_count(&contract), None);
+
+        testing_env!(get_context(accounts(0), 0, 0));
+        assert_eq!(contract.recompute_active_count(accounts(0)), 0);
+        assert_eq!(active_count(&contract), None);
+    }
+
+    #[test]
+    fn test_release_never_underflows_missing_count() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let first = contract.create_escrow(native_escrow_params());
+        let second = contract.create_escrow(native_escrow_params());
+
+        // Simulate a count that drifted away from the stored escrows
+        contract.active_escrows_per_account.remove(&accounts(0));
+        claim_native(&mut contract, &first);
+        assert_eq!(active_count(&contract), None);
+
+        testing_env!(get_context(accounts(0), 0, 0));
+        assert_eq!(contract.recompute_active_count(accounts(0)), 1);
+        assert_eq!(active_count(&contract), Some(1));
+
+        cancel_native(&mut contract, &second);
+        assert_eq!(active_count(&contract), None);
+    }
+
+    #[test]
+    fn test_recompute_frees_slots_held_by_stale_count() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        contract.create_escrow(native_escrow_params());
+        contract
+            .active_escrows_per_account
+            .insert(&accounts(0), &DEFAULT_MAX_ESCROWS_PER_ACCOUNT);
+
+        // The account itself may repair its own count
+        assert_eq!(contract.recompute_active_count(accounts(0)), 1);
+        contract.create_escrow(native_escrow_params());
+        assert_eq!(active_count(&contract), Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or the account can recompute its count")]
+    fn test_recompute_restricted_to_owner_or_account() {
+        testing_env!(get_context(accounts(0), 0, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+
+        testing_env!(get_context(accounts(2), 0, 0));
+        contract.recompute_active_count(accounts(1));
+    }
+
+    // Test 15: NEP-297 events follow the shared schema
+    fn logged_events() -> Vec<HtlcEventLog> {
+        near_sdk::test_utils::get_logs()
+            .iter()
+            .filter_map(|log| HtlcEventLog::from_log_str(log))
+            .map(|event| event.expect("Event log must match the schema"))
+            .collect()
+    }
+
+    #[test]
+    fn test_create_and_claim_emit_schema_events() {
+        testing_env!(get_context(accounts(0), 1_000_000, 0));
+        let mut contract = FusionHTLC::new(accounts(0), None);
+        let escrow_id = contract.create_escrow(native_escrow_params());
+
+        let created = logged_events();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].standard, event_schema::EVENT_STANDARD);
+        assert_eq!(created[0].version, event_schema::EVENT_VERSION);
+        match &created[0].event {
+            HtlcEvent::EscrowCreated(data) => {
+                assert_eq!(data[0].escrow_id, escrow_id);
+                assert_eq!(data[0].beneficiary, accounts(1).to_string());
+                assert_eq!(data[0].secret_hash, create_valid_secret_hash());
+            }
+            other => panic!("Expected escrow_created, got {:?}", other),
+        }
+
+        claim_native(&mut contract, &escrow_id);
+        assert_eq!(
+            logged_events(),
+            vec![HtlcEventLog::new(HtlcEvent::EscrowClaimed(vec![
+                EscrowClaimed {
+                    escrow_id,
+                    claimer: accounts(1).to_string(),
+                    secret: hex::encode("my_secret_12345"),
+                    timestamp: 0,
+                }
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_nep141_args_are_serialized_with_memo() {
+        let receiver = accounts(1);
+
+        let args = FtTransferArgs {
+            receiver_id: &receiver,
+            amount: U128(50_000_000),
+            memo: Some("fusion_0 claim"),
+        };
+        assert_eq!(
+            near_sdk::serde_json::to_value(&args).unwrap(),
+            near_sdk::serde_json::json!({
+                "receiver_id": receiver.to_string(),
+                "amount": "50000000",
+                "memo": "fusion_0 claim",
+            })
+        );
+
+        // Standard tokens take an explicit null memo
+        let args = FtTransferCallArgs {
+            receiver_id: &receiver,
+            amount: U128(1),
+            memo: None,
+            msg: r#"{"owner":"alice.near"}"#,
+        };
+        assert_eq!(
+            near_sdk::serde_json::to_value(&args).unwrap(),
+            near_sdk::serde_json::json!({
+                "receiver_id": receiver.to_string(),
+                "amount": "1",
+                "memo": null,
+                "msg": r#"{"owner":"alice.near"}"#,
+            })
+        );
+    }
 }
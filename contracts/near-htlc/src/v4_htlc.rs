@@ -5,12 +5,27 @@ use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{env, log, near_bindgen, AccountId, Balance, PanicOnDefault, Promise};
 use sha2::{Digest, Sha256};
 
+/// Default shortest escrow timeout, well above NEAR finality (~2s) so claims can land
+pub const DEFAULT_MIN_TIMEOUT_SECONDS: u64 = 60;
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct SimpleHTLC {
     pub owner: AccountId,
     pub escrows: UnorderedMap<String, Escrow>,
     pub escrow_counter: u64,
+    /// secret_hash -> ids of the active escrows with that hash, oldest first
+    pub escrows_by_hash: UnorderedMap<String, Vec<String>>,
+    /// Shortest `timeout_seconds` accepted by `create_escrow`; only lower this for tests
+    pub min_timeout_seconds: u64,
+}
+
+/// Layout deployed before the hash index existed, read by `migrate`
+#[derive(BorshDeserialize)]
+struct SimpleHTLCV1 {
+    owner: AccountId,
+    escrows: UnorderedMap<String, Escrow>,
+    escrow_counter: u64,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -27,13 +42,39 @@ pub struct Escrow {
 #[near_bindgen]
 impl SimpleHTLC {
     #[init]
-    pub fn new(owner: AccountId) -> Self {
+    pub fn new(owner: AccountId, min_timeout_seconds: Option<u64>) -> Self {
         assert!(!env::state_exists(), "Already initialized");
         Self {
             owner,
             escrows: UnorderedMap::new(b"e"),
             escrow_counter: 0,
+            escrows_by_hash: UnorderedMap::new(b"h"),
+            min_timeout_seconds: min_timeout_seconds.unwrap_or(DEFAULT_MIN_TIMEOUT_SECONDS),
+        }
+    }
+
+    /// Rebuild state written by the pre-index layout and index its active escrows
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: SimpleHTLCV1 = env::state_read().expect("No state to migrate");
+        let mut contract = Self {
+            owner: old.owner,
+            escrows: old.escrows,
+            escrow_counter: old.escrow_counter,
+            escrows_by_hash: UnorderedMap::new(b"h"),
+            min_timeout_seconds: DEFAULT_MIN_TIMEOUT_SECONDS,
+        };
+        // Walk ids in creation order so the oldest escrow stays first per hash
+        for n in 0..contract.escrow_counter {
+            let escrow_id = format!("escrow_{}", n);
+            if let Some(escrow) = contract.escrows.get(&escrow_id) {
+                if escrow.is_active {
+                    contract.add_hash_index(&escrow_id, &escrow.secret_hash);
+                }
+            }
         }
+        contract
     }
 
     pub fn get_owner(&self) -> AccountId {
@@ -44,6 +85,10 @@ impl SimpleHTLC {
         self.escrow_counter
     }
 
+    pub fn get_min_timeout_seconds(&self) -> u64 {
+        self.min_timeout_seconds
+    }
+
     #[payable]
     pub fn create_escrow(
         &mut self,
@@ -53,6 +98,10 @@ impl SimpleHTLC {
     ) -> String {
         let amount = env::attached_deposit();
         assert!(amount > 0, "Must attach deposit");
+        assert!(
+            timeout_seconds >= self.min_timeout_seconds,
+            "Timeout below minimum"
+        );
 
         let sender = env::predecessor_account_id();
         let timeout = env::block_timestamp() + (timeout_seconds * 1_000_000_000);
@@ -70,6 +119,8 @@ impl SimpleHTLC {
         self.escrows.insert(&escrow_id, &escrow);
         self.escrow_counter += 1;
 
+        self.add_hash_index(&escrow_id, &secret_hash);
+
         log!(
             "Created escrow {} from {} to {} with amount {}",
             escrow_id,
@@ -87,12 +138,16 @@ impl SimpleHTLC {
 
         // Verify secret
         let hash = Self::hash_secret(&secret);
-        assert_eq!(hash, escrow.secret_hash, "Invalid secret");
+        assert!(
+            Self::constant_time_eq(hash.as_bytes(), escrow.secret_hash.as_bytes()),
+            "Invalid secret"
+        );
 
         // Update escrow
         let mut updated_escrow = escrow.clone();
         updated_escrow.is_active = false;
         self.escrows.insert(&escrow_id, &updated_escrow);
+        self.remove_hash_index(&escrow_id, &escrow.secret_hash);
 
         // Transfer funds
         Promise::new(escrow.recipient.clone()).transfer(escrow.amount);
@@ -113,6 +168,7 @@ impl SimpleHTLC {
         let mut updated_escrow = escrow.clone();
         updated_escrow.is_active = false;
         self.escrows.insert(&escrow_id, &updated_escrow);
+        self.remove_hash_index(&escrow_id, &escrow.secret_hash);
 
         // Refund to sender
         Promise::new(escrow.sender.clone()).transfer(escrow.amount);
@@ -125,8 +181,108 @@ impl SimpleHTLC {
         self.escrows.get(&escrow_id)
     }
 
+    /// First active escrow locked with `secret_hash`, for resolvers that only know the hash
+    pub fn get_escrow_by_hash(&self, secret_hash: String) -> Option<(String, Escrow)> {
+        let escrow_ids = self.escrows_by_hash.get(&secret_hash)?;
+        escrow_ids.into_iter().find_map(|escrow_id| {
+            let escrow = self.escrows.get(&escrow_id)?;
+            if escrow.is_active {
+                Some((escrow_id, escrow))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn add_hash_index(&mut self, escrow_id: &String, secret_hash: &String) {
+        let mut escrow_ids = self.escrows_by_hash.get(secret_hash).unwrap_or_default();
+        escrow_ids.push(escrow_id.clone());
+        self.escrows_by_hash.insert(secret_hash, &escrow_ids);
+    }
+
+    /// Drop a closed escrow from the index, and the entry once no escrow is left
+    fn remove_hash_index(&mut self, escrow_id: &String, secret_hash: &String) {
+        let mut escrow_ids = match self.escrows_by_hash.get(secret_hash) {
+            Some(escrow_ids) => escrow_ids,
+            None => return,
+        };
+        escrow_ids.retain(|id| id != escrow_id);
+        if escrow_ids.is_empty() {
+            self.escrows_by_hash.remove(secret_hash);
+        } else {
+            self.escrows_by_hash.insert(secret_hash, &escrow_ids);
+        }
+    }
+
     fn hash_secret(secret: &str) -> String {
         let hash = Sha256::digest(secret.as_bytes());
         bs58::encode(hash).into_string()
     }
+
+    /// Compare without short-circuiting so timing does not reveal the matching prefix
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn set_context(predecessor: usize, deposit: Balance, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(predecessor))
+            .attached_deposit(deposit)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    #[test]
+    fn test_hash_index_moves_to_next_active_escrow() {
+        set_context(0, 0, 0);
+        let mut contract = SimpleHTLC::new(accounts(0).into(), None);
+        let secret = "shared_secret";
+        let secret_hash = SimpleHTLC::hash_secret(secret);
+
+        set_context(1, 1_000, 0);
+        let first = contract.create_escrow(accounts(2).into(), secret_hash.clone(), 3600);
+        set_context(1, 2_000, 0);
+        let second = contract.create_escrow(accounts(2).into(), secret_hash.clone(), 3600);
+
+        let (escrow_id, _) = contract.get_escrow_by_hash(secret_hash.clone()).unwrap();
+        assert_eq!(escrow_id, first);
+
+        set_context(2, 0, 0);
+        contract.claim(first, secret.to_string());
+        let (escrow_id, escrow) = contract.get_escrow_by_hash(secret_hash.clone()).unwrap();
+        assert_eq!(escrow_id, second);
+        assert_eq!(escrow.amount, 2_000);
+
+        set_context(1, 0, 3601 * 1_000_000_000);
+        contract.refund(second);
+        assert!(contract.get_escrow_by_hash(secret_hash.clone()).is_none());
+        assert!(contract.escrows_by_hash.get(&secret_hash).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Timeout below minimum")]
+    fn test_one_second_timeout_rejected_by_default() {
+        set_context(0, 0, 0);
+        let mut contract = SimpleHTLC::new(accounts(0).into(), None);
+
+        set_context(1, 1_000, 0);
+        contract.create_escrow(accounts(2).into(), SimpleHTLC::hash_secret("s"), 1);
+    }
+
+    #[test]
+    fn test_one_second_timeout_allowed_with_lowered_minimum() {
+        set_context(0, 0, 0);
+        let mut contract = SimpleHTLC::new(accounts(0).into(), Some(1));
+
+        set_context(1, 1_000, 0);
+        let escrow_id = contract.create_escrow(accounts(2).into(), SimpleHTLC::hash_secret("s"), 1);
+        assert!(contract.get_escrow(escrow_id).unwrap().is_active);
+    }
 }
@@ -5,9 +5,15 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::LookupMap;
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault, PromiseOrValue};
+use near_sdk::{
+    env, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseOrValue,
+    PromiseResult,
+};
 type Balance = u128;
 
+const GAS_FOR_FT_ON_TRANSFER: Gas = Gas::from_tgas(25);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(10);
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct TestToken {
@@ -70,12 +76,64 @@ impl TestToken {
         receiver_id: AccountId,
         amount: U128,
         memo: Option<String>,
-        _msg: String,
+        msg: String,
     ) -> PromiseOrValue<U128> {
+        let sender = env::predecessor_account_id();
         self.ft_transfer(receiver_id.clone(), amount, memo);
 
-        // For testing, just return the amount
-        PromiseOrValue::Value(amount)
+        // Let the receiver act on the transfer, then refund what it did not use
+        Promise::new(receiver_id.clone())
+            .function_call(
+                "ft_on_transfer".to_string(),
+                near_sdk::serde_json::json!({
+                    "sender_id": sender,
+                    "amount": amount,
+                    "msg": msg,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_FT_ON_TRANSFER,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender, receiver_id, amount),
+            )
+            .into()
+    }
+
+    #[private]
+    pub fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        let amount = amount.0;
+        let unused = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value)
+                .map(|unused| unused.0.min(amount))
+                .unwrap_or(amount),
+            PromiseResult::Failed => amount,
+        };
+
+        // Only refund what the receiver still holds
+        let receiver_balance = self.balances.get(&receiver_id).unwrap_or(0);
+        let refund = unused.min(receiver_balance);
+        if refund > 0 {
+            self.balances
+                .insert(&receiver_id, &(receiver_balance - refund));
+            let sender_balance = self.balances.get(&sender_id).unwrap_or(0);
+            self.balances.insert(&sender_id, &(sender_balance + refund));
+            env::log_str(&format!(
+                "Refund {} from {} to {}",
+                refund, receiver_id, sender_id
+            ));
+        }
+
+        // The amount the receiver ended up keeping
+        U128(amount - refund)
     }
 
     pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
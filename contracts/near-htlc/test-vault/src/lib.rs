@@ -0,0 +1,65 @@
+// Minimal NEP-141 receiver vault for testing routed HTLC claims
+// Credits deposits to the owner named in the transfer msg
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::Deserialize;
+use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault, PromiseOrValue};
+type Balance = u128;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct TestVault {
+    pub token_id: AccountId,
+    pub positions: LookupMap<AccountId, Balance>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositMsg {
+    pub owner: AccountId,
+    pub max: Option<U128>, // Accept at most this much, the rest goes back to the sender
+}
+
+#[near_bindgen]
+impl TestVault {
+    #[init]
+    pub fn new(token_id: AccountId) -> Self {
+        Self {
+            token_id,
+            positions: LookupMap::new(b"p"),
+        }
+    }
+
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.token_id,
+            "Unsupported token"
+        );
+        let deposit: DepositMsg =
+            near_sdk::serde_json::from_str(&msg).expect("Invalid deposit msg");
+
+        let accepted = deposit.max.map_or(amount.0, |max| max.0.min(amount.0));
+        let position = self.positions.get(&deposit.owner).unwrap_or(0);
+        self.positions
+            .insert(&deposit.owner, &(position + accepted));
+
+        env::log_str(&format!(
+            "Deposit {} from {} for {}",
+            accepted, sender_id, deposit.owner
+        ));
+
+        PromiseOrValue::Value(U128(amount.0 - accepted))
+    }
+
+    pub fn get_position(&self, owner: AccountId) -> U128 {
+        U128(self.positions.get(&owner).unwrap_or(0))
+    }
+}
@@ -17,6 +17,19 @@ use serde_json::json;
 use sha2::{Digest, Sha256};
 
 const WASM_FILEPATH: &str = "../../target/wasm32-unknown-unknown/release/near_htlc.wasm";
+// FusionHTLC is built by its own crate, see fusion-htlc/
+const FUSION_WASM_FILEPATH: &str =
+    "fusion-htlc/target/wasm32-unknown-unknown/release/fusion_htlc.wasm";
+
+// Default escrow limits with the minimum time period lowered for second-scale tests
+fn short_period_limits() -> serde_json::Value {
+    json!({
+        "max_total_escrows": 10_000,
+        "max_escrows_per_account": 100,
+        "max_escrow_amount": U128::from(1_000_000 * 10u128.pow(24)),
+        "min_time_period_seconds": 0
+    })
+}
 
 #[tokio::test]
 #[ignore = "WASM deserialization error - needs investigation"]
@@ -155,16 +168,16 @@ async fn test_security_timestamp_overflow() -> Result<(), Box<dyn std::error::Er
 }
 
 #[tokio::test]
-#[ignore = "WASM deserialization error - needs investigation"]
+#[ignore = "FusionHTLC WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc to enable"]
 async fn test_reentrancy_protection_batch_cancel() -> Result<(), Box<dyn std::error::Error>> {
     let worker = near_workspaces::sandbox().await?;
-    let wasm = std::fs::read(WASM_FILEPATH)?;
+    let wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
     let contract = worker.dev_deploy(&wasm).await?;
 
     let owner = worker.dev_create_account().await?;
     contract
         .call("new")
-        .args_json(json!({ "owner": owner.id() }))
+        .args_json(json!({ "owner": owner.id(), "limits": short_period_limits() }))
         .transact()
         .await?
         .into_result()?;
@@ -501,3 +514,365 @@ async fn test_hash_collision_resistance() -> Result<(), Box<dyn std::error::Erro
 
     Ok(())
 }
+
+#[tokio::test]
+#[ignore = "FusionHTLC/test token/vault WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc, test-token and test-vault to enable"]
+async fn test_claim_routed_into_vault() -> Result<(), Box<dyn std::error::Error>> {
+    let worker = near_workspaces::sandbox().await?;
+
+    let token_wasm = std::fs::read("../../target/wasm32-unknown-unknown/release/test_token.wasm")?;
+    let token_contract = worker.dev_deploy(&token_wasm).await?;
+    let vault_wasm = std::fs::read("../../target/wasm32-unknown-unknown/release/test_vault.wasm")?;
+    let vault_contract = worker.dev_deploy(&vault_wasm).await?;
+    let htlc_wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
+    let htlc_contract = worker.dev_deploy(&htlc_wasm).await?;
+
+    let owner = worker.dev_create_account().await?;
+    htlc_contract
+        .call("new")
+        .args_json(json!({ "owner": owner.id(), "limits": null }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    token_contract
+        .call("new")
+        .args_json(json!({
+            "total_supply": U128::from(1_000_000_000_000),
+            "metadata": {
+                "spec": "ft-1.0.0",
+                "name": "Test Token",
+                "symbol": "TEST",
+                "decimals": 6
+            }
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    vault_contract
+        .call("new")
+        .args_json(json!({ "token_id": token_contract.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let resolver = worker.dev_create_account().await?;
+    let vault_owner = worker.dev_create_account().await?;
+
+    for account_id in [resolver.id(), htlc_contract.id(), vault_contract.id()] {
+        token_contract
+            .call("storage_deposit")
+            .args_json(json!({ "account_id": account_id }))
+            .deposit(NearToken::from_millinear(8))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    token_contract
+        .call("ft_transfer")
+        .args_json(json!({
+            "receiver_id": resolver.id(),
+            "amount": U128::from(100_000_000),
+            "memo": null
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let secret = "vault_routing_secret";
+    let secret_hash = bs58::encode(Sha256::digest(secret.as_bytes())).into_string();
+
+    // The vault only accepts 30 of the 50 tokens, the rest must still reach it directly
+    let params = json!({
+        "beneficiary": vault_contract.id(),
+        "secret_hash": secret_hash,
+        "token_id": token_contract.id(),
+        "amount": U128::from(50_000_000),
+        "safety_deposit": U128::from(0),
+        "safety_deposit_beneficiary": null,
+        // The beneficiary can claim until finality
+        "finality_period": 600,
+        "cancel_period": 7200,
+        "public_cancel_period": 10800,
+        "allow_partial_fills": false,
+        "beneficiary_msg": json!({
+            "owner": vault_owner.id(),
+            "max": U128::from(30_000_000)
+        })
+        .to_string()
+    });
+
+    resolver
+        .call(token_contract.id(), "ft_transfer_call")
+        .args_json(json!({
+            "receiver_id": htlc_contract.id(),
+            "amount": U128::from(50_000_000),
+            "memo": null,
+            "msg": params.to_string()
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Only the beneficiary can claim, here the vault contract itself
+    vault_contract
+        .as_account()
+        .call(htlc_contract.id(), "claim")
+        .args_json(json!({
+            "escrow_id": "fusion_0",
+            "secret": hex::encode(secret.as_bytes())
+        }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let position: U128 = vault_contract
+        .view("get_position")
+        .args_json(json!({ "owner": vault_owner.id() }))
+        .await?
+        .json()?;
+    assert_eq!(position, U128::from(30_000_000));
+
+    // The vault holds the routed deposit plus the forwarded remainder
+    let vault_balance: U128 = token_contract
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": vault_contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(vault_balance, U128::from(50_000_000));
+
+    let htlc_balance: U128 = token_contract
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": htlc_contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(htlc_balance, U128::from(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "FusionHTLC/test token WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc and test-token to enable"]
+async fn test_nep141_claim_and_cancel_refund() -> Result<(), Box<dyn std::error::Error>> {
+    let worker = near_workspaces::sandbox().await?;
+
+    let token_wasm = std::fs::read("../../target/wasm32-unknown-unknown/release/test_token.wasm")?;
+    let token_contract = worker.dev_deploy(&token_wasm).await?;
+    let htlc_wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
+    let htlc_contract = worker.dev_deploy(&htlc_wasm).await?;
+
+    let owner = worker.dev_create_account().await?;
+    htlc_contract
+        .call("new")
+        .args_json(json!({ "owner": owner.id(), "limits": null }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    token_contract
+        .call("new")
+        .args_json(json!({
+            "total_supply": U128::from(1_000_000_000_000),
+            "metadata": {
+                "spec": "ft-1.0.0",
+                "name": "Test Token",
+                "symbol": "TEST",
+                "decimals": 6
+            }
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let resolver = worker.dev_create_account().await?;
+    let beneficiary = worker.dev_create_account().await?;
+
+    for account_id in [resolver.id(), beneficiary.id(), htlc_contract.id()] {
+        token_contract
+            .call("storage_deposit")
+            .args_json(json!({ "account_id": account_id }))
+            .deposit(NearToken::from_millinear(8))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    token_contract
+        .call("ft_transfer")
+        .args_json(json!({
+            "receiver_id": resolver.id(),
+            "amount": U128::from(100_000_000),
+            "memo": null
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let secret = "nep141_claim_secret";
+    let secret_hash = bs58::encode(Sha256::digest(secret.as_bytes())).into_string();
+
+    // fusion_0 is claimed, fusion_1 is left to expire and cancelled
+    for hash in [secret_hash.clone(), "unrevealed_hash".to_string()] {
+        let params = json!({
+            "beneficiary": beneficiary.id(),
+            "secret_hash": hash,
+            "token_id": token_contract.id(),
+            "amount": U128::from(40_000_000),
+            "safety_deposit": U128::from(5_000_000),
+            "safety_deposit_beneficiary": null,
+            "finality_period": 60,
+            "cancel_period": 120,
+            "public_cancel_period": 3600
+        });
+        resolver
+            .call(token_contract.id(), "ft_transfer_call")
+            .args_json(json!({
+                "receiver_id": htlc_contract.id(),
+                "amount": U128::from(45_000_000),
+                "memo": null,
+                "msg": params.to_string()
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    // Within the claim window, only the beneficiary can claim
+    let claim = beneficiary
+        .call(htlc_contract.id(), "claim")
+        .args_json(json!({
+            "escrow_id": "fusion_0",
+            "secret": hex::encode(secret.as_bytes())
+        }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    // The token received well-formed args, including the memo
+    assert!(claim
+        .logs()
+        .iter()
+        .any(|log| log.contains("memo: Some(\"fusion_0 claim\")")));
+
+    // Wait out the resolver's cancel period on fusion_1
+    worker.fast_forward(250).await?;
+    resolver
+        .call(htlc_contract.id(), "cancel")
+        .args_json(json!({ "escrow_id": "fusion_1" }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let balance_of = |account_id: near_workspaces::AccountId| {
+        let token_contract = token_contract.clone();
+        async move {
+            token_contract
+                .view("ft_balance_of")
+                .args_json(json!({ "account_id": account_id }))
+                .await?
+                .json::<U128>()
+        }
+    };
+
+    // Claim paid the beneficiary and returned the safety deposit to the resolver;
+    // the cancel refunded amount and deposit of the second escrow
+    assert_eq!(
+        balance_of(beneficiary.id().clone()).await?,
+        U128::from(40_000_000)
+    );
+    assert_eq!(
+        balance_of(resolver.id().clone()).await?,
+        U128::from(100_000_000 - 40_000_000)
+    );
+    assert_eq!(balance_of(htlc_contract.id().clone()).await?, U128::from(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "FusionHTLC WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc to enable"]
+async fn test_cancel_refund_falls_back_when_resolver_deleted(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
+    let contract = worker.dev_deploy(&wasm).await?;
+
+    let owner = worker.dev_create_account().await?;
+    contract
+        .call("new")
+        .args_json(json!({ "owner": owner.id(), "limits": null }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let resolver = worker.dev_create_account().await?;
+    let beneficiary = worker.dev_create_account().await?;
+    let fallback = worker.dev_create_account().await?;
+
+    let amount = NearToken::from_near(1);
+    let escrow_id: String = resolver
+        .call(contract.id(), "create_escrow")
+        .args_json(json!({
+            "params": {
+                "beneficiary": beneficiary.id(),
+                "secret_hash": "fallback_hash",
+                "token_id": null,
+                "amount": U128::from(amount.as_yoctonear()),
+                "safety_deposit": U128::from(0),
+                "safety_deposit_beneficiary": null,
+                "finality_period": 60,
+                "cancel_period": 120,
+                "public_cancel_period": 180,
+                "refund_fallback": fallback.id()
+            }
+        }))
+        .deposit(amount)
+        .transact()
+        .await?
+        .json()?;
+
+    // The resolver's account is closed, so the refund transfer to it fails
+    resolver
+        .clone()
+        .delete_account(owner.id())
+        .await?
+        .into_result()?;
+
+    // Past the public cancel period, anyone can cancel
+    worker.fast_forward(300).await?;
+
+    let fallback_before = fallback.view_account().await?.balance;
+    let anyone = worker.dev_create_account().await?;
+    anyone
+        .call(contract.id(), "cancel")
+        .args_json(json!({ "escrow_id": escrow_id }))
+        .gas(near_workspaces::types::Gas::from_tgas(300))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let fallback_after = fallback.view_account().await?.balance;
+    assert_eq!(
+        fallback_after.as_yoctonear() - fallback_before.as_yoctonear(),
+        amount.as_yoctonear()
+    );
+
+    let escrow: serde_json::Value = contract
+        .view("get_escrow")
+        .args_json(json!({ "escrow_id": escrow_id }))
+        .await?
+        .json()?;
+    assert_eq!(escrow["state"], "Cancelled");
+
+    Ok(())
+}
@@ -6,6 +6,19 @@ use sha2::{Digest, Sha256};
 // Removed unused time imports
 
 const WASM_FILEPATH: &str = "../../target/wasm32-unknown-unknown/release/near_htlc.wasm";
+// FusionHTLC is built by its own crate, see fusion-htlc/
+const FUSION_WASM_FILEPATH: &str =
+    "fusion-htlc/target/wasm32-unknown-unknown/release/fusion_htlc.wasm";
+
+// Default escrow limits with the minimum time period lowered for second-scale tests
+fn short_period_limits() -> serde_json::Value {
+    json!({
+        "max_total_escrows": 10_000,
+        "max_escrows_per_account": 100,
+        "max_escrow_amount": U128::from(1_000_000 * 10u128.pow(24)),
+        "min_time_period_seconds": 0
+    })
+}
 
 // Helper function to safely log sensitive information
 fn log_secret_info(_secret_bytes: &[u8], _hash: &str, _context: &str) {
@@ -279,16 +292,16 @@ async fn test_timestamp_overflow_edge_cases() -> Result<(), Box<dyn std::error::
 
 // Test 4: Gas limit stress test with dynamic calculation
 #[tokio::test]
-#[ignore = "WASM deserialization error - needs investigation"]
+#[ignore = "FusionHTLC WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc to enable"]
 async fn test_dynamic_gas_limits() -> Result<(), Box<dyn std::error::Error>> {
     let worker = near_workspaces::sandbox().await?;
-    let wasm = std::fs::read(WASM_FILEPATH)?;
+    let wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
     let contract = worker.dev_deploy(&wasm).await?;
 
     let owner = worker.dev_create_account().await?;
     contract
         .call("new")
-        .args_json(json!({ "owner": owner.id() }))
+        .args_json(json!({ "owner": owner.id(), "limits": short_period_limits() }))
         .transact()
         .await?
         .into_result()?;
@@ -362,16 +375,16 @@ async fn test_dynamic_gas_limits() -> Result<(), Box<dyn std::error::Error>> {
 
 // Test 5: Reentrancy protection in batch operations
 #[tokio::test]
-#[ignore = "WASM deserialization error - needs investigation"]
+#[ignore = "FusionHTLC WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc to enable"]
 async fn test_batch_cancel_reentrancy_protection() -> Result<(), Box<dyn std::error::Error>> {
     let worker = near_workspaces::sandbox().await?;
-    let wasm = std::fs::read(WASM_FILEPATH)?;
+    let wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
     let contract = worker.dev_deploy(&wasm).await?;
 
     let owner = worker.dev_create_account().await?;
     contract
         .call("new")
-        .args_json(json!({ "owner": owner.id() }))
+        .args_json(json!({ "owner": owner.id(), "limits": short_period_limits() }))
         .transact()
         .await?
         .into_result()?;
@@ -524,16 +537,16 @@ async fn test_cross_contract_failure_recovery() -> Result<(), Box<dyn std::error
 
 // Test 7: Timing attack prevention
 #[tokio::test]
-#[ignore = "WASM deserialization error - needs investigation"]
+#[ignore = "FusionHTLC WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc to enable"]
 async fn test_timing_boundaries() -> Result<(), Box<dyn std::error::Error>> {
     let worker = near_workspaces::sandbox().await?;
-    let wasm = std::fs::read(WASM_FILEPATH)?;
+    let wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
     let contract = worker.dev_deploy(&wasm).await?;
 
     let owner = worker.dev_create_account().await?;
     contract
         .call("new")
-        .args_json(json!({ "owner": owner.id() }))
+        .args_json(json!({ "owner": owner.id(), "limits": short_period_limits() }))
         .transact()
         .await?
         .into_result()?;
@@ -795,16 +808,16 @@ async fn test_storage_limits() -> Result<(), Box<dyn std::error::Error>> {
 
 // Test 10: Comprehensive integration test with all security features
 #[tokio::test]
-#[ignore = "WASM deserialization error - needs investigation"]
+#[ignore = "FusionHTLC WASM not available - run 'cargo build --target wasm32-unknown-unknown --release' in fusion-htlc to enable"]
 async fn test_comprehensive_security_integration() -> Result<(), Box<dyn std::error::Error>> {
     let worker = near_workspaces::sandbox().await?;
-    let wasm = std::fs::read(WASM_FILEPATH)?;
+    let wasm = std::fs::read(FUSION_WASM_FILEPATH)?;
     let contract = worker.dev_deploy(&wasm).await?;
 
     let owner = worker.dev_create_account().await?;
     contract
         .call("new")
-        .args_json(json!({ "owner": owner.id() }))
+        .args_json(json!({ "owner": owner.id(), "limits": short_period_limits() }))
         .transact()
         .await?
         .into_result()?;
@@ -0,0 +1,480 @@
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use fusion_core::config::Config;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Validate a configuration file before running swaps
+    Validate(ValidateConfigArgs),
+}
+
+#[derive(Args)]
+pub struct ValidateConfigArgs {
+    /// Path to the JSON configuration file
+    #[arg(long)]
+    pub file: String,
+
+    /// Check that every configured RPC endpoint responds
+    #[arg(long)]
+    pub ping_rpc: bool,
+}
+
+/// Configuration file layout
+///
+/// Fields are optional so that every missing value can be reported at once
+/// instead of failing on the first one.
+#[derive(Debug, Default, Deserialize)]
+pub struct FileConfig {
+    #[serde(default)]
+    pub chains: HashMap<String, FileChainConfig>,
+    /// Token symbol -> chain name -> token address
+    #[serde(default)]
+    pub tokens: HashMap<String, HashMap<String, String>>,
+    /// Token symbol -> amount limits of one swap; tokens without an entry are unrestricted
+    #[serde(default)]
+    pub token_limits: HashMap<String, TokenLimit>,
+}
+
+/// Smallest and largest amount of a token one swap may move, in whole tokens
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub struct TokenLimit {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FileChainConfig {
+    pub rpc_url: Option<String>,
+    pub chain_id: Option<u64>,
+    pub escrow_factory: Option<String>,
+    pub limit_order_protocol: Option<String>,
+    /// Extra HTTP headers sent to the RPC endpoint, e.g. `Authorization`
+    #[serde(default)]
+    pub rpc_headers: HashMap<String, String>,
+}
+
+pub async fn handle_config_command(command: ConfigCommands) -> Result<()> {
+    match command {
+        ConfigCommands::Validate(args) => handle_validate_config(args).await,
+    }
+}
+
+/// Read and parse a configuration file without validating it
+pub fn load_config(path: &str) -> Result<FileConfig> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| anyhow!("Failed to read config file: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse config file: {}", e))
+}
+
+pub async fn handle_validate_config(args: ValidateConfigArgs) -> Result<()> {
+    let config = load_config(&args.file)?;
+
+    let mut errors = validate_config(&config);
+    if args.ping_rpc {
+        errors.extend(ping_rpcs(&config).await);
+    }
+
+    let output = json!({
+        "file": args.file,
+        "valid": errors.is_empty(),
+        "chains": config.chains.len(),
+        "tokens": config.tokens.len(),
+        "errors": errors,
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Config validation failed with {} error(s)",
+            errors.len()
+        ))
+    }
+}
+
+/// Check the configuration and collect every problem found
+pub fn validate_config(config: &FileConfig) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if config.chains.is_empty() {
+        errors.push("No chains configured".to_string());
+    }
+
+    // Sort for stable output
+    let mut chain_names: Vec<&String> = config.chains.keys().collect();
+    chain_names.sort();
+
+    for name in chain_names {
+        let chain = &config.chains[name];
+
+        match chain.rpc_url.as_deref() {
+            None | Some("") => errors.push(format!("chains.{}.rpc_url is missing", name)),
+            Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => errors
+                .push(format!(
+                    "chains.{}.rpc_url must be an http(s) URL: {}",
+                    name, url
+                )),
+            _ => {}
+        }
+
+        let mut header_names: Vec<&String> = chain.rpc_headers.keys().collect();
+        header_names.sort();
+        for header in header_names {
+            if reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err()
+                || reqwest::header::HeaderValue::from_str(&chain.rpc_headers[header]).is_err()
+            {
+                errors.push(format!(
+                    "chains.{}.rpc_headers.{} is not a valid HTTP header",
+                    name, header
+                ));
+            }
+        }
+
+        if chain.escrow_factory.is_none() && chain.limit_order_protocol.is_none() {
+            errors.push(format!(
+                "chains.{} needs an escrow_factory or limit_order_protocol address",
+                name
+            ));
+        }
+
+        for (field, address) in [
+            ("escrow_factory", &chain.escrow_factory),
+            ("limit_order_protocol", &chain.limit_order_protocol),
+        ] {
+            if let Some(address) = address {
+                if let Err(e) = validate_chain_address(name, address) {
+                    errors.push(format!("chains.{}.{}: {}", name, field, e));
+                }
+            }
+        }
+    }
+
+    let mut symbols: Vec<&String> = config.tokens.keys().collect();
+    symbols.sort();
+
+    for symbol in symbols {
+        let mut deployments: Vec<(&String, &String)> = config.tokens[symbol].iter().collect();
+        deployments.sort();
+
+        for (chain_name, address) in deployments {
+            if !config.chains.contains_key(chain_name) {
+                errors.push(format!(
+                    "tokens.{}.{} refers to an unconfigured chain",
+                    symbol, chain_name
+                ));
+            }
+            if let Err(e) = validate_chain_address(chain_name, address) {
+                errors.push(format!("tokens.{}.{}: {}", symbol, chain_name, e));
+            }
+        }
+    }
+
+    let mut limited: Vec<&String> = config.token_limits.keys().collect();
+    limited.sort();
+
+    for symbol in limited {
+        let limit = config.token_limits[symbol];
+        for (field, value) in [("min", limit.min), ("max", limit.max)] {
+            if value.is_some_and(|value| !value.is_finite() || value < 0.0) {
+                errors.push(format!(
+                    "token_limits.{}.{} must be zero or positive",
+                    symbol, field
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (limit.min, limit.max) {
+            if min > max {
+                errors.push(format!(
+                    "token_limits.{}.min {} is greater than max {}",
+                    symbol, min, max
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Reject a swap of `amount` whole `token`s outside its configured limits
+pub fn check_token_limit(
+    limits: &HashMap<String, TokenLimit>,
+    token: &str,
+    amount: f64,
+) -> Result<()> {
+    let Some(limit) = limits.get(token) else {
+        return Ok(());
+    };
+    if let Some(min) = limit.min.filter(|min| amount < *min) {
+        return Err(anyhow!(
+            "Amount {} {} is below the configured minimum of {} {}",
+            amount,
+            token,
+            min,
+            token
+        ));
+    }
+    if let Some(max) = limit.max.filter(|max| amount > *max) {
+        return Err(anyhow!(
+            "Amount {} {} is above the configured maximum of {} {}",
+            amount,
+            token,
+            max,
+            token
+        ));
+    }
+    Ok(())
+}
+
+/// An explicit `--limit-order-protocol`, or the one configured for `chain_id`
+pub fn resolve_limit_order_protocol(explicit: Option<&str>, chain_id: u64) -> Result<String> {
+    match explicit {
+        Some(address) => Ok(address.to_string()),
+        None => Config::from_env()
+            .limit_order_protocol(chain_id)
+            .map(str::to_string)
+            .map_err(|e| anyhow!("{}; pass --limit-order-protocol", e)),
+    }
+}
+
+fn validate_chain_address(chain_name: &str, address: &str) -> Result<()> {
+    if is_near_chain(chain_name) {
+        if !address.ends_with(".near") && !address.ends_with(".testnet") {
+            return Err(anyhow!("Invalid NEAR account ID: {}", address));
+        }
+        return Ok(());
+    }
+
+    let addr = address
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow!("Address must start with 0x: {}", address))?;
+    if addr.len() != 40 || hex::decode(addr).is_err() {
+        return Err(anyhow!("Invalid EVM address: {}", address));
+    }
+    Ok(())
+}
+
+/// Send a JSON-RPC request to a configured chain and return its `result`
+pub async fn rpc_call(
+    client: &reqwest::Client,
+    name: &str,
+    chain: &FileChainConfig,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<serde_json::Value> {
+    let url = chain
+        .rpc_url
+        .as_deref()
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| anyhow!("is missing"))?;
+
+    let request = if is_near_chain(name) {
+        json!({"jsonrpc": "2.0", "id": "fusion-cli", "method": method, "params": params})
+    } else {
+        json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})
+    };
+
+    let mut post = client.post(url).json(&request);
+    for (header, value) in &chain.rpc_headers {
+        post = post.header(header, value);
+    }
+
+    let response = post
+        .send()
+        .await
+        .map_err(|e| anyhow!("is unreachable: {}", e))?;
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow!("returned invalid JSON: {}", e))?;
+
+    if body.get("error").is_some() {
+        return Err(anyhow!("returned an error: {}", body["error"]));
+    }
+    Ok(body["result"].clone())
+}
+
+pub fn is_near_chain(name: &str) -> bool {
+    name.to_lowercase().starts_with("near")
+}
+
+async fn ping_rpcs(config: &FileConfig) -> Vec<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+
+    let mut chain_names: Vec<&String> = config.chains.keys().collect();
+    chain_names.sort();
+
+    let mut errors = Vec::new();
+    for name in chain_names {
+        let chain = &config.chains[name];
+        if chain.rpc_url.as_deref().is_none_or(str::is_empty) {
+            continue;
+        }
+
+        let method = if is_near_chain(name) {
+            "status"
+        } else {
+            "eth_chainId"
+        };
+        let result = match rpc_call(&client, name, chain, method, json!([])).await {
+            Ok(result) => result,
+            Err(e) => {
+                errors.push(format!("chains.{}.rpc_url {}", name, e));
+                continue;
+            }
+        };
+
+        // Compare the node's chain ID with the configured one for EVM chains
+        if let (false, Some(expected)) = (is_near_chain(name), chain.chain_id) {
+            let reported = result
+                .as_str()
+                .and_then(|id| u64::from_str_radix(id.trim_start_matches("0x"), 16).ok());
+            if reported != Some(expected) {
+                errors.push(format!(
+                    "chains.{}.chain_id is {} but the RPC reports {:?}",
+                    name, expected, reported
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> FileConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let config = parse(
+            r#"{
+                "chains": {
+                    "base-sepolia": {
+                        "rpc_url": "https://sepolia.base.org",
+                        "chain_id": 84532,
+                        "limit_order_protocol": "0x171C87724E720F2806fc29a010a62897B30fdb62"
+                    },
+                    "near": {
+                        "rpc_url": "https://rpc.testnet.near.org",
+                        "escrow_factory": "htlc-v2.testnet"
+                    }
+                },
+                "tokens": {
+                    "USDC": {"base-sepolia": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"}
+                }
+            }"#,
+        );
+
+        assert!(validate_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_missing_rpc_url() {
+        let config = parse(
+            r#"{
+                "chains": {
+                    "ethereum": {
+                        "escrow_factory": "0x171C87724E720F2806fc29a010a62897B30fdb62"
+                    }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            validate_config(&config),
+            vec!["chains.ethereum.rpc_url is missing".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_reports_all_problems() {
+        let config = parse(
+            r#"{
+                "chains": {
+                    "ethereum": {"rpc_url": "wss://example.org"},
+                    "near": {"rpc_url": "https://rpc.testnet.near.org", "escrow_factory": "0x1234"}
+                },
+                "tokens": {
+                    "USDC": {"polygon": "0xnothex"}
+                }
+            }"#,
+        );
+
+        let errors = validate_config(&config);
+        assert_eq!(errors.len(), 5);
+        assert!(errors[0].contains("chains.ethereum.rpc_url must be an http(s) URL"));
+        assert!(errors[1].contains("chains.ethereum needs an escrow_factory"));
+        assert!(errors[2].contains("chains.near.escrow_factory: Invalid NEAR account ID"));
+        assert!(errors[3].contains("tokens.USDC.polygon refers to an unconfigured chain"));
+        assert!(errors[4].contains("tokens.USDC.polygon: Invalid EVM address"));
+    }
+
+    #[test]
+    fn test_invalid_rpc_header() {
+        let config = parse(
+            r#"{
+                "chains": {
+                    "ethereum": {
+                        "rpc_url": "https://eth.example.org",
+                        "escrow_factory": "0x171C87724E720F2806fc29a010a62897B30fdb62",
+                        "rpc_headers": {"Authorization": "Bearer key", "Bad Header": "x"}
+                    }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            validate_config(&config),
+            vec!["chains.ethereum.rpc_headers.Bad Header is not a valid HTTP header".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_token_limits() {
+        let config = parse(
+            r#"{
+                "token_limits": {
+                    "ETH": {"min": 0.01, "max": 10.0},
+                    "USDC": {"min": 5.0, "max": 1.0}
+                }
+            }"#,
+        );
+
+        let err = check_token_limit(&config.token_limits, "ETH", 0.001).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Amount 0.001 ETH is below the configured minimum of 0.01 ETH"
+        );
+        let err = check_token_limit(&config.token_limits, "ETH", 25.0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Amount 25 ETH is above the configured maximum of 10 ETH"
+        );
+        assert!(check_token_limit(&config.token_limits, "ETH", 1.5).is_ok());
+        // Tokens without an entry are unrestricted
+        assert!(check_token_limit(&config.token_limits, "NEAR", 1e9).is_ok());
+
+        let errors = validate_config(&config);
+        assert!(errors.contains(&"token_limits.USDC.min 5 is greater than max 1".to_string()));
+    }
+
+    #[test]
+    fn test_empty_config() {
+        assert_eq!(
+            validate_config(&FileConfig::default()),
+            vec!["No chains configured".to_string()]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use crate::config_handler::{
+    is_near_chain, rpc_call, validate_config, FileChainConfig, FileConfig,
+};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde::Serialize;
+use serde_json::json;
+use std::process::Command;
+use std::time::Duration;
+
+/// Environment variables the signing commands read
+const REQUIRED_ENV_VARS: [(&str, &str); 3] = [
+    (
+        "PRIVATE_KEY",
+        "export the EVM signer's private key (0x-prefixed hex)",
+    ),
+    (
+        "NEAR_ACCOUNT_ID",
+        "export the NEAR account used to create and claim escrows",
+    ),
+    (
+        "NEAR_PRIVATE_KEY",
+        "export the NEAR account's ed25519 private key",
+    ),
+];
+
+const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Path to the JSON configuration file
+    #[arg(long)]
+    pub config: String,
+
+    /// Only check the configuration file and environment, without contacting RPCs
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+}
+
+/// One line of the readiness checklist
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a failed check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub async fn handle_doctor(args: DoctorArgs) -> Result<()> {
+    let (mut checks, config) = config_checks(&args.config);
+
+    if let (Some(config), false) = (&config, args.offline) {
+        checks.extend(chain_checks(config).await);
+    }
+    checks.push(near_cli_check());
+    checks.extend(env_checks(|name| std::env::var(name).ok()));
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    let output = json!({
+        "config": args.config,
+        "ready": failed == 0,
+        "checks": checks,
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(anyhow!("Doctor found {} failing check(s)", failed))
+    }
+}
+
+/// Load the configuration file and report every validation problem
+pub fn config_checks(path: &str) -> (Vec<Check>, Option<FileConfig>) {
+    let config = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read config file: {}", e))
+        .and_then(|content| {
+            serde_json::from_str::<FileConfig>(&content)
+                .map_err(|e| anyhow!("Failed to parse config file: {}", e))
+        });
+
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            let hint = "create a JSON config as described in `fusion-cli config validate --help`";
+            return (vec![Check::fail("config", e.to_string(), hint)], None);
+        }
+    };
+
+    let errors = validate_config(&config);
+    let checks = if errors.is_empty() {
+        vec![Check::pass("config", format!("{} loaded", path))]
+    } else {
+        errors
+            .into_iter()
+            .map(|e| Check::fail("config", e, format!("edit {} and fill in the field", path)))
+            .collect()
+    };
+    (checks, Some(config))
+}
+
+/// Contact each chain's RPC and look up the configured contracts
+pub async fn chain_checks(config: &FileConfig) -> Vec<Check> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+
+    let mut chain_names: Vec<&String> = config.chains.keys().collect();
+    chain_names.sort();
+
+    let mut checks = Vec::new();
+    for name in chain_names {
+        let chain = &config.chains[name];
+        // Already reported by the config check
+        if chain.rpc_url.as_deref().is_none_or(str::is_empty) {
+            continue;
+        }
+        let check_name = format!("chains.{}.rpc_url", name);
+
+        if is_near_chain(name) {
+            if let Err(e) = rpc_call(&client, name, chain, "status", json!([])).await {
+                checks.push(rpc_failure(check_name, e));
+                continue;
+            }
+            checks.push(Check::pass(check_name, "RPC responds"));
+            if let Some(contract) = &chain.escrow_factory {
+                checks.push(near_contract_check(&client, name, chain, contract).await);
+            }
+            continue;
+        }
+
+        let reported = match rpc_call(&client, name, chain, "eth_chainId", json!([])).await {
+            Ok(result) => result
+                .as_str()
+                .and_then(|id| u64::from_str_radix(id.trim_start_matches("0x"), 16).ok()),
+            Err(e) => {
+                checks.push(rpc_failure(check_name, e));
+                continue;
+            }
+        };
+        match (chain.chain_id, reported) {
+            (Some(expected), reported) if reported != Some(expected) => {
+                checks.push(Check::fail(
+                    check_name,
+                    format!(
+                        "expected chain ID {} but the RPC reports {:?}",
+                        expected, reported
+                    ),
+                    format!(
+                        "point chains.{}.rpc_url at a node for chain {}",
+                        name, expected
+                    ),
+                ));
+                continue;
+            }
+            _ => checks.push(Check::pass(
+                check_name,
+                format!("RPC responds with chain ID {:?}", reported),
+            )),
+        }
+
+        for (field, address) in [
+            ("escrow_factory", &chain.escrow_factory),
+            ("limit_order_protocol", &chain.limit_order_protocol),
+        ] {
+            if let Some(address) = address {
+                checks.push(evm_contract_check(&client, name, chain, field, address).await);
+            }
+        }
+    }
+
+    checks
+}
+
+fn rpc_failure(check_name: String, error: anyhow::Error) -> Check {
+    Check::fail(
+        check_name,
+        format!("RPC {}", error),
+        "start the node (e.g. `anvil`) or fix the RPC URL",
+    )
+}
+
+async fn evm_contract_check(
+    client: &reqwest::Client,
+    name: &str,
+    chain: &FileChainConfig,
+    field: &str,
+    address: &str,
+) -> Check {
+    let check_name = format!("chains.{}.{}", name, field);
+    let hint = format!("deploy the contract and set chains.{}.{}", name, field);
+
+    if address.eq_ignore_ascii_case(ZERO_ADDRESS) {
+        return Check::fail(check_name, "address is the zero placeholder", hint);
+    }
+
+    match rpc_call(
+        client,
+        name,
+        chain,
+        "eth_getCode",
+        json!([address, "latest"]),
+    )
+    .await
+    {
+        Ok(code) if code.as_str().is_some_and(|code| code != "0x") => {
+            Check::pass(check_name, format!("contract deployed at {}", address))
+        }
+        Ok(_) => Check::fail(check_name, format!("no contract code at {}", address), hint),
+        Err(e) => Check::fail(check_name, format!("RPC {}", e), hint),
+    }
+}
+
+async fn near_contract_check(
+    client: &reqwest::Client,
+    name: &str,
+    chain: &FileChainConfig,
+    contract: &str,
+) -> Check {
+    let check_name = format!("chains.{}.escrow_factory", name);
+    let params =
+        json!({"request_type": "view_account", "finality": "final", "account_id": contract});
+
+    match rpc_call(client, name, chain, "query", params).await {
+        Ok(account) if account["code_hash"] != "11111111111111111111111111111111" => {
+            Check::pass(check_name, format!("contract deployed at {}", contract))
+        }
+        Ok(_) => Check::fail(
+            check_name,
+            format!("{} has no contract deployed", contract),
+            "deploy the HTLC contract with `cargo near deploy`",
+        ),
+        Err(e) => Check::fail(
+            check_name,
+            format!("{} not found: {}", contract, e),
+            format!("create the account or fix chains.{}.escrow_factory", name),
+        ),
+    }
+}
+
+fn near_cli_check() -> Check {
+    match Command::new("near").arg("--version").output() {
+        Ok(output) if output.status.success() => Check::pass(
+            "near-cli",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => Check::fail(
+            "near-cli",
+            "`near` is not installed or not on PATH",
+            "install it with `npm install -g near-cli-rs`",
+        ),
+    }
+}
+
+/// Report each required environment variable, looked up through `lookup`
+pub fn env_checks<F: Fn(&str) -> Option<String>>(lookup: F) -> Vec<Check> {
+    REQUIRED_ENV_VARS
+        .iter()
+        .map(|(name, hint)| match lookup(name) {
+            Some(value) if !value.is_empty() => Check::pass(*name, "set"),
+            _ => Check::fail(*name, "not set", *hint),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreachable_rpc_fails() {
+        let config: FileConfig = serde_json::from_str(
+            r#"{
+                "chains": {
+                    "ethereum": {
+                        "rpc_url": "http://127.0.0.1:1",
+                        "chain_id": 31337,
+                        "escrow_factory": "0x171C87724E720F2806fc29a010a62897B30fdb62"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let checks = chain_checks(&config).await;
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "chains.ethereum.rpc_url");
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(checks[0].detail.contains("unreachable"));
+        assert!(checks[0].hint.is_some());
+    }
+
+    #[test]
+    fn test_missing_config_field_fails() {
+        let path = std::env::temp_dir().join("fusion_cli_doctor_missing_rpc.json");
+        std::fs::write(
+            &path,
+            r#"{"chains": {"ethereum": {"escrow_factory": "0x171C87724E720F2806fc29a010a62897B30fdb62"}}}"#,
+        )
+        .unwrap();
+
+        let (checks, config) = config_checks(path.to_str().unwrap());
+        assert!(config.is_some());
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert_eq!(checks[0].detail, "chains.ethereum.rpc_url is missing");
+    }
+
+    #[test]
+    fn test_env_checks() {
+        let checks = env_checks(|name| (name == "PRIVATE_KEY").then(|| "0xabc".to_string()));
+        let statuses: Vec<_> = checks.iter().map(|c| (c.name.as_str(), c.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("PRIVATE_KEY", CheckStatus::Pass),
+                ("NEAR_ACCOUNT_ID", CheckStatus::Fail),
+                ("NEAR_PRIVATE_KEY", CheckStatus::Fail),
+            ]
+        );
+    }
+}
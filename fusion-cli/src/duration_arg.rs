@@ -0,0 +1,69 @@
+/// Parse a timeout or period argument into seconds
+///
+/// Accepts durations such as `90s`, `30m`, `3h`, `1d` or combinations like
+/// `1h30m`. A bare integer is taken as seconds, as it was before units existed.
+pub fn parse_duration_secs(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("duration is empty".to_string());
+    }
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(secs);
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+
+        let unit_secs = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return Err(format!("unknown unit '{}' in duration '{}'", c, value)),
+        };
+        let amount: u64 = digits
+            .parse()
+            .map_err(|_| format!("missing number before '{}' in duration '{}'", c, value))?;
+        total = amount
+            .checked_mul(unit_secs)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| format!("duration '{}' is too large", value))?;
+        digits.clear();
+    }
+
+    if !digits.is_empty() {
+        return Err(format!(
+            "duration '{}' needs a unit (s, m, h or d) after {}",
+            value, digits
+        ));
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_units_and_bare_seconds() {
+        assert_eq!(parse_duration_secs("2h"), Ok(7200));
+        assert_eq!(parse_duration_secs("90m"), Ok(5400));
+        assert_eq!(parse_duration_secs("3600"), Ok(3600));
+        assert_eq!(parse_duration_secs("90s"), Ok(90));
+        assert_eq!(parse_duration_secs("1h30m"), Ok(5400));
+    }
+
+    #[test]
+    fn test_rejects_malformed_durations() {
+        assert!(parse_duration_secs("").is_err());
+        assert!(parse_duration_secs("3w").is_err());
+        assert!(parse_duration_secs("h").is_err());
+        assert!(parse_duration_secs("1h30").is_err());
+        assert!(parse_duration_secs("-5").is_err());
+    }
+}
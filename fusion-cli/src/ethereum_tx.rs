@@ -1,4 +1,6 @@
+use crate::storage::StoredOrder;
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use ethers::prelude::*;
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::types::Bytes;
@@ -165,6 +167,180 @@ pub async fn submit_limit_order(
     }
 }
 
+/// Result of handing a signed order to the limit order protocol
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderSubmission {
+    pub order_hash: String,
+    pub transaction_hash: Option<String>,
+    pub block_number: Option<u64>,
+    pub gas_used: Option<String>,
+}
+
+/// Destination for signed orders created with `--submit`
+#[async_trait]
+pub trait OrderSubmitter: Send + Sync {
+    async fn submit(&self, signed_order: &serde_json::Value) -> Result<OrderSubmission>;
+}
+
+/// Submits orders directly to the deployed limit order protocol contract
+pub struct OnChainSubmitter {
+    rpc_url: String,
+    chain_id: u64,
+    private_key: Option<String>,
+}
+
+impl OnChainSubmitter {
+    pub fn new(rpc_url: String, chain_id: u64, private_key: Option<String>) -> Self {
+        Self {
+            rpc_url,
+            chain_id,
+            private_key,
+        }
+    }
+}
+
+#[async_trait]
+impl OrderSubmitter for OnChainSubmitter {
+    async fn submit(&self, signed_order: &serde_json::Value) -> Result<OrderSubmission> {
+        let receipt = submit_limit_order(
+            signed_order,
+            &self.rpc_url,
+            self.chain_id,
+            self.private_key.clone(),
+        )
+        .await?;
+
+        Ok(OrderSubmission {
+            order_hash: signed_order["eip712_hash"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            transaction_hash: Some(format!("{:?}", receipt.transaction_hash)),
+            block_number: receipt.block_number.map(|n| n.as_u64()),
+            gas_used: receipt.gas_used.map(|g| g.to_string()),
+        })
+    }
+}
+
+/// Posts signed orders to a relayer endpoint which registers them on our behalf
+pub struct RelayerSubmitter {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl RelayerSubmitter {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl OrderSubmitter for RelayerSubmitter {
+    async fn submit(&self, signed_order: &serde_json::Value) -> Result<OrderSubmission> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(signed_order)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| anyhow!("Relayer rejected order: {}", e))?;
+
+        // Relayers are not required to answer with a body
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+
+        let order_hash = body["orderHash"]
+            .as_str()
+            .or_else(|| signed_order["eip712_hash"].as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(OrderSubmission {
+            order_hash,
+            transaction_hash: body["txHash"].as_str().map(str::to_string),
+            block_number: None,
+            gas_used: None,
+        })
+    }
+}
+
+/// Invalidates orders that were already submitted on-chain
+#[async_trait]
+pub trait OrderCanceller: Send + Sync {
+    /// Cancel the order and return the transaction hash
+    async fn cancel(&self, order: &StoredOrder) -> Result<String>;
+}
+
+/// Calls `cancelOrder` on the limit order protocol the order was submitted to
+pub struct OnChainCanceller {
+    rpc_url: String,
+    private_key: Option<String>,
+}
+
+impl OnChainCanceller {
+    pub fn new(rpc_url: String, private_key: Option<String>) -> Self {
+        Self {
+            rpc_url,
+            private_key,
+        }
+    }
+}
+
+#[async_trait]
+impl OrderCanceller for OnChainCanceller {
+    async fn cancel(&self, order: &StoredOrder) -> Result<String> {
+        let submission = order
+            .submission
+            .as_ref()
+            .ok_or_else(|| anyhow!("Order {} was never submitted", order.id))?;
+
+        let wallet: LocalWallet = self
+            .private_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("Private key required to cancel a submitted order"))?
+            .parse()
+            .map_err(|_| anyhow!("Invalid private key format"))?;
+        let provider = Provider::<Http>::try_from(self.rpc_url.as_str())?;
+        let client = SignerMiddleware::new(provider, wallet.with_chain_id(submission.chain_id));
+
+        abigen!(
+            LimitOrderCancel,
+            r#"[
+                {
+                    "inputs": [
+                        {"internalType": "MakerTraits", "name": "makerTraits", "type": "uint256"},
+                        {"internalType": "bytes32", "name": "orderHash", "type": "bytes32"}
+                    ],
+                    "name": "cancelOrder",
+                    "outputs": [],
+                    "stateMutability": "nonpayable",
+                    "type": "function"
+                }
+            ]"#
+        );
+
+        let order_hash = hex::decode(order.order_hash.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| anyhow!("Order hash must be 32 bytes: {}", order.order_hash))?;
+
+        let contract = LimitOrderCancel::new(
+            Address::from_str(&submission.verifying_contract)?,
+            Arc::new(client),
+        );
+        let call = contract.cancel_order(U256::zero(), order_hash);
+        let tx = call.send().await?;
+        let receipt = tx
+            .await?
+            .ok_or_else(|| anyhow!("Failed to get transaction receipt"))?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+}
+
 /// Sign an order using EIP-712 hash
 pub async fn sign_order_hash(eip712_hash: &[u8; 32], private_key: &str) -> Result<Signature> {
     let wallet: LocalWallet = private_key
@@ -0,0 +1,107 @@
+use serde_json::Value;
+use std::fmt;
+
+/// Process exit codes, so scripts can branch on why a command failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ExitCode {
+    Success = 0,
+    /// Invalid arguments or a request the current state does not allow
+    UserError = 1,
+    /// The HTLC, order or swap does not exist
+    NotFound = 2,
+    /// The chain or its RPC failed or returned an error
+    Chain = 3,
+    /// Waiting for a chain event timed out
+    Timeout = 4,
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        std::process::ExitCode::from(code as u8)
+    }
+}
+
+/// An error whose JSON output is printed to stdout before exiting with `code`
+#[derive(Debug)]
+pub struct CliError {
+    pub code: ExitCode,
+    pub output: Value,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.output.get("error").and_then(Value::as_str) {
+            Some(error) => write!(f, "{}", error),
+            None => write!(f, "{}", self.output),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Fail the command with `code`, keeping `output` as its JSON error
+pub fn cli_error(code: ExitCode, output: Value) -> anyhow::Error {
+    CliError { code, output }.into()
+}
+
+/// Pick the exit code for an error returned by a command handler
+///
+/// Errors raised with [`cli_error`] carry their code. Anything else is
+/// classified from its cause chain, falling back to a user error.
+pub fn exit_code_for(error: &anyhow::Error) -> ExitCode {
+    if let Some(error) = error.downcast_ref::<CliError>() {
+        return error.code;
+    }
+
+    for cause in error.chain() {
+        if cause.is::<tokio::time::error::Elapsed>() {
+            return ExitCode::Timeout;
+        }
+        if cause.is::<reqwest::Error>() || cause.is::<ethers::providers::ProviderError>() {
+            return ExitCode::Chain;
+        }
+    }
+
+    let message = error.to_string().to_lowercase();
+    if message.contains("not found") {
+        ExitCode::NotFound
+    } else if message.contains("timed out") {
+        ExitCode::Timeout
+    } else if message.contains("rpc") {
+        ExitCode::Chain
+    } else {
+        ExitCode::UserError
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    #[test]
+    fn test_exit_code_for_errors() {
+        let not_found = cli_error(ExitCode::NotFound, json!({"error": "HTLC not found"}));
+        assert_eq!(exit_code_for(&not_found), ExitCode::NotFound);
+        assert_eq!(not_found.to_string(), "HTLC not found");
+
+        assert_eq!(
+            exit_code_for(&anyhow!("Swap not found: swap_1")),
+            ExitCode::NotFound
+        );
+        assert_eq!(
+            exit_code_for(&anyhow!("HTLC monitoring timed out after 3 attempts")),
+            ExitCode::Timeout
+        );
+        assert_eq!(
+            exit_code_for(&anyhow!("Failed to query NEAR RPC")),
+            ExitCode::Chain
+        );
+        assert_eq!(
+            exit_code_for(&anyhow!("Amount must be positive")),
+            ExitCode::UserError
+        );
+    }
+}
@@ -1,9 +1,14 @@
+use crate::storage::HtlcStorage;
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use ethers::prelude::*;
+use fusion_core::execution_path_optimizer::ExecutionPathOptimizer;
+use fusion_core::htlc::HtlcState;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 use tokio::time::sleep;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +22,402 @@ pub struct HTLCStatus {
     pub amount: String,
 }
 
+/// Shortest delay between two polls of the same leg
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often each leg of a swap is polled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PollIntervals {
+    pub source: Duration,
+    pub target: Duration,
+}
+
+impl PollIntervals {
+    /// Scale `base_secs` by each chain's block time
+    ///
+    /// The slower chain is polled every `base_secs` and the faster one
+    /// proportionally more often. Chains without a known block time use
+    /// `base_secs` as is.
+    pub fn from_block_times(
+        source_chain: &str,
+        target_chain: &str,
+        base_secs: u64,
+        optimizer: &ExecutionPathOptimizer,
+    ) -> Self {
+        let base = Duration::from_secs(base_secs);
+        let (Some(source_block), Some(target_block)) = (
+            optimizer.block_time(source_chain),
+            optimizer.block_time(target_chain),
+        ) else {
+            return Self {
+                source: base,
+                target: base,
+            };
+        };
+
+        let slowest = source_block.max(target_block).max(1) as f64;
+        let scale = |block_time: u64| {
+            base.mul_f64(block_time as f64 / slowest)
+                .max(MIN_POLL_INTERVAL)
+        };
+
+        Self {
+            source: scale(source_block),
+            target: scale(target_block),
+        }
+    }
+}
+
+/// Tracks when each leg is next due for a poll
+#[derive(Debug, Clone)]
+pub struct PollSchedule {
+    intervals: PollIntervals,
+    next_source: Duration,
+    next_target: Duration,
+}
+
+impl PollSchedule {
+    /// Both legs are due immediately
+    pub fn new(intervals: PollIntervals) -> Self {
+        Self {
+            intervals,
+            next_source: Duration::ZERO,
+            next_target: Duration::ZERO,
+        }
+    }
+
+    /// Return which legs (source, target) are due at `elapsed` and reschedule them
+    pub fn due(&mut self, elapsed: Duration) -> (bool, bool) {
+        let source_due = elapsed >= self.next_source;
+        if source_due {
+            self.next_source = elapsed + self.intervals.source;
+        }
+        let target_due = elapsed >= self.next_target;
+        if target_due {
+            self.next_target = elapsed + self.intervals.target;
+        }
+        (source_due, target_due)
+    }
+
+    /// Elapsed time at which the next leg becomes due
+    pub fn next_wake(&self) -> Duration {
+        self.next_source.min(self.next_target)
+    }
+}
+
+/// Alert raised while a swap is being monitored
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "alert")]
+pub enum SwapAlert {
+    /// Neither leg changed state within the expected-duration budget
+    Stuck {
+        stage: String,
+        stalled_secs: u64,
+        budget_secs: u64,
+        recommendation: String,
+    },
+    /// The source leg will be refunded once its timelock passes
+    RefundPending {
+        htlc_id: String,
+        chain: String,
+        refund_at: u64,
+        secs_until_refund: u64,
+    },
+}
+
+/// Receives alerts from the swap monitor
+pub trait SwapObserver: Send + Sync {
+    fn on_alert(&self, alert: &SwapAlert);
+}
+
+/// Prints alerts as JSON next to the rest of the monitor output
+pub struct StdoutObserver;
+
+impl SwapObserver for StdoutObserver {
+    fn on_alert(&self, alert: &SwapAlert) {
+        if let Ok(json) = serde_json::to_string(alert) {
+            println!("{}", json);
+        }
+    }
+}
+
+/// Upper bound of a plan estimate such as "10-30 seconds" or "< 1 second"
+pub fn parse_estimated_time(estimate: &str) -> Option<Duration> {
+    let mut parts = estimate.split_whitespace().filter(|part| *part != "<");
+    let range = parts.next()?;
+    let unit = parts.next()?;
+
+    let upper: u64 = range.rsplit('-').next()?.parse().ok()?;
+    let unit_secs = match unit.trim_end_matches('s') {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 3600,
+        _ => return None,
+    };
+    Some(Duration::from_secs(upper * unit_secs))
+}
+
+/// Fires once when a swap stays in the same stage longer than its budget
+#[derive(Debug, Clone)]
+pub struct StuckWatchdog {
+    budget: Duration,
+    stage: Option<String>,
+    stage_since: Duration,
+    fired: bool,
+}
+
+impl StuckWatchdog {
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            stage: None,
+            stage_since: Duration::ZERO,
+            fired: false,
+        }
+    }
+
+    /// Record the stage seen at `elapsed`, returning an alert if it has stalled
+    pub fn observe(&mut self, stage: &str, elapsed: Duration) -> Option<SwapAlert> {
+        if self.stage.as_deref() != Some(stage) {
+            self.stage = Some(stage.to_string());
+            self.stage_since = elapsed;
+            self.fired = false;
+            return None;
+        }
+
+        let stalled = elapsed.saturating_sub(self.stage_since);
+        if self.fired || stalled < self.budget {
+            return None;
+        }
+
+        self.fired = true;
+        Some(SwapAlert::Stuck {
+            stage: stage.to_string(),
+            stalled_secs: stalled.as_secs(),
+            budget_secs: self.budget.as_secs(),
+            recommendation: "Counterparty may be gone; refund once the timelock expires"
+                .to_string(),
+        })
+    }
+}
+
+/// Reads the current status of an HTLC on either chain
+#[async_trait]
+pub trait HtlcStatusSource: Send + Sync {
+    async fn check_htlc(&self, htlc_id: &str, chain: &str) -> Result<HTLCStatus>;
+
+    /// Read several HTLCs on one chain, returned in the order of `htlc_ids`
+    ///
+    /// Sources that can batch reads (a multicall or a JSON-RPC batch) override
+    /// this so a whole chain is read in one round trip.
+    async fn check_htlcs(&self, htlc_ids: &[String], chain: &str) -> Result<Vec<HTLCStatus>> {
+        let mut statuses = Vec::with_capacity(htlc_ids.len());
+        for htlc_id in htlc_ids {
+            statuses.push(self.check_htlc(htlc_id, chain).await?);
+        }
+        Ok(statuses)
+    }
+}
+
+#[async_trait]
+impl HtlcStatusSource for HTLCMonitor {
+    async fn check_htlc(&self, htlc_id: &str, chain: &str) -> Result<HTLCStatus> {
+        HTLCMonitor::check_htlc(self, htlc_id, chain).await
+    }
+}
+
+/// Holds an automatic refund back until the timelock, announcing it `lead` ahead
+///
+/// The `RefundPending` alert gives external systems a window to intervene
+/// before funds move. A refund that is already due is announced and allowed
+/// in the same poll.
+#[derive(Debug, Clone)]
+pub struct RefundGate {
+    htlc_id: String,
+    chain: String,
+    /// Unix time in seconds at which the refund becomes possible
+    refund_at: u64,
+    lead: Duration,
+    announced: bool,
+}
+
+impl RefundGate {
+    pub fn new(htlc_id: &str, chain: &str, refund_at: u64, lead: Duration) -> Self {
+        Self {
+            htlc_id: htlc_id.to_string(),
+            chain: chain.to_string(),
+            refund_at,
+            lead,
+            announced: false,
+        }
+    }
+
+    /// Whether the refund may proceed at `now`, announcing it once the lead window opens
+    pub fn ready(&mut self, now: u64, observer: &dyn SwapObserver) -> bool {
+        if !self.announced && now.saturating_add(self.lead.as_secs()) >= self.refund_at {
+            self.announced = true;
+            observer.on_alert(&SwapAlert::RefundPending {
+                htlc_id: self.htlc_id.clone(),
+                chain: self.chain.clone(),
+                refund_at: self.refund_at,
+                secs_until_refund: self.refund_at.saturating_sub(now),
+            });
+        }
+        now >= self.refund_at
+    }
+}
+
+/// How `execute_bidirectional_swap` ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapRunOutcome {
+    /// The target HTLC was claimed, or both legs already were
+    Completed,
+    /// The target HTLC holds less than the minimum and was left unclaimed
+    BelowMinimum { observed: u128, min_received: u128 },
+}
+
+/// Claim the target HTLC unless it holds less than `min_received`
+///
+/// A short fill is reported and left for manual handling, since claiming it
+/// would accept a worse rate than the slippage tolerance allows.
+pub async fn claim_unless_below_minimum<C, Fut>(
+    target: &HTLCStatus,
+    min_received: Option<u128>,
+    claim: C,
+) -> Result<SwapRunOutcome>
+where
+    C: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let observed = target.amount.parse::<u128>().ok();
+    if let (Some(observed), Some(min_received)) = (observed, min_received) {
+        if observed < min_received {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "warning": "Target HTLC holds less than the minimum; skipping auto-claim",
+                    "htlc_id": target.htlc_id,
+                    "chain": target.chain,
+                    "observed_amount": observed.to_string(),
+                    "min_received": min_received.to_string(),
+                    "manual_action_required": true,
+                })
+            );
+            return Ok(SwapRunOutcome::BelowMinimum {
+                observed,
+                min_received,
+            });
+        }
+    }
+
+    claim().await?;
+    Ok(SwapRunOutcome::Completed)
+}
+
+/// How `monitor_for_refund` stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefundWatchOutcome {
+    /// The refund went through, or the HTLC was already refunded
+    Refunded,
+    /// The recipient claimed the HTLC, so there is nothing to refund
+    Claimed,
+}
+
+/// Poll a stored HTLC and refund it once its timeout has passed
+///
+/// `refund` runs only while the HTLC is still `Pending` after
+/// `created_at + timeout`; a failed refund is reported and retried on the next
+/// poll. `now` supplies the current time so tests can drive the clock.
+pub async fn monitor_for_refund<N, R, Fut>(
+    storage: &HtlcStorage,
+    htlc_id: &str,
+    poll_interval: Duration,
+    now: N,
+    mut refund: R,
+) -> Result<RefundWatchOutcome>
+where
+    N: Fn() -> SystemTime,
+    R: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    loop {
+        let stored = storage.get(htlc_id)?;
+        match stored.state {
+            HtlcState::Claimed => {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "htlc_id": htlc_id,
+                        "status": "Claimed",
+                        "message": "HTLC was claimed; nothing to refund",
+                    })
+                );
+                return Ok(RefundWatchOutcome::Claimed);
+            }
+            HtlcState::Refunded => return Ok(RefundWatchOutcome::Refunded),
+            HtlcState::Pending => {}
+        }
+
+        if now() >= stored.created_at + stored.timeout {
+            match refund().await {
+                Ok(()) => {
+                    storage.update_state(htlc_id, HtlcState::Refunded)?;
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "htlc_id": htlc_id,
+                            "status": "Refunded",
+                            "refunded_at": chrono::Utc::now().to_rfc3339(),
+                        })
+                    );
+                    return Ok(RefundWatchOutcome::Refunded);
+                }
+                Err(e) => println!(
+                    "{}",
+                    serde_json::json!({
+                        "htlc_id": htlc_id,
+                        "error": format!("Refund failed, retrying: {}", e),
+                    })
+                ),
+            }
+        }
+
+        sleep(poll_interval).await;
+    }
+}
+
+/// Read-through cache of escrow states for a single poll cycle
+///
+/// Swaps that share an escrow see the same state and the chain is queried
+/// once per escrow. Create a new cache for every cycle so no state outlives it.
+pub struct PollCycleCache<'a, S: HtlcStatusSource + ?Sized> {
+    source: &'a S,
+    states: Mutex<HashMap<(String, String), HTLCStatus>>,
+}
+
+impl<'a, S: HtlcStatusSource + ?Sized> PollCycleCache<'a, S> {
+    pub fn new(source: &'a S) -> Self {
+        Self {
+            source,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: HtlcStatusSource + ?Sized> HtlcStatusSource for PollCycleCache<'_, S> {
+    async fn check_htlc(&self, htlc_id: &str, chain: &str) -> Result<HTLCStatus> {
+        let key = (chain.to_string(), htlc_id.to_string());
+        if let Some(status) = self.states.lock().unwrap().get(&key) {
+            return Ok(status.clone());
+        }
+
+        let status = self.source.check_htlc(htlc_id, chain).await?;
+        self.states.lock().unwrap().insert(key, status.clone());
+        Ok(status)
+    }
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct HTLCMonitor {
@@ -73,6 +474,15 @@ impl HTLCMonitor {
         ))
     }
 
+    /// Read the current status of an HTLC once
+    pub async fn check_htlc(&self, htlc_id: &str, chain: &str) -> Result<HTLCStatus> {
+        match chain {
+            "ethereum" => self.check_ethereum_htlc(htlc_id).await,
+            "near" => self.check_near_htlc(htlc_id).await,
+            _ => Err(anyhow!("Unsupported chain: {}", chain)),
+        }
+    }
+
     /// Check HTLC status on Ethereum
     async fn check_ethereum_htlc(&self, htlc_id: &str) -> Result<HTLCStatus> {
         // In a real implementation, this would query the Ethereum HTLC contract
@@ -272,7 +682,108 @@ impl HTLCMonitor {
         Ok("claim_successful".to_string())
     }
 
+    /// Refund an expired HTLC back to its sender
+    pub async fn refund_htlc(&self, htlc_id: &str, chain: &str) -> Result<String> {
+        match chain {
+            "ethereum" => {
+                let private_key = std::env::var("PRIVATE_KEY")
+                    .map_err(|_| anyhow!("Private key required for Ethereum HTLC refund"))?;
+                self.refund_ethereum_htlc(htlc_id, &private_key).await
+            }
+            "near" => {
+                let account_id =
+                    std::env::var("NEAR_ACCOUNT_ID").unwrap_or_else(|_| "user.testnet".to_string());
+                self.refund_near_htlc(htlc_id, &account_id).await
+            }
+            _ => Err(anyhow!("Unsupported chain: {}", chain)),
+        }
+    }
+
+    async fn refund_ethereum_htlc(&self, htlc_id: &str, private_key: &str) -> Result<String> {
+        println!("Refunding Ethereum HTLC {}", htlc_id);
+
+        let provider = Provider::<Http>::try_from(&self.ethereum_rpc)?;
+        let wallet: LocalWallet = private_key
+            .parse()
+            .map_err(|_| anyhow!("Invalid private key format"))?;
+        let chain_id = provider.get_chainid().await?;
+        let client = Arc::new(SignerMiddleware::new(
+            provider,
+            wallet.with_chain_id(chain_id.as_u64()),
+        ));
+
+        abigen!(
+            HTLCRefundContract,
+            r#"[
+                {
+                    "inputs": [
+                        {"internalType": "bytes32", "name": "htlcId", "type": "bytes32"}
+                    ],
+                    "name": "refund",
+                    "outputs": [],
+                    "stateMutability": "nonpayable",
+                    "type": "function"
+                }
+            ]"#
+        );
+
+        let contract_address = std::env::var("ETH_HTLC_CONTRACT")
+            .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
+        let contract = HTLCRefundContract::new(Address::from_str(&contract_address)?, client);
+
+        let htlc_id_bytes32: [u8; 32] = hex::decode(htlc_id.trim_start_matches("0x"))?
+            .try_into()
+            .map_err(|_| anyhow!("HTLC ID must be 32 bytes"))?;
+
+        let tx_call = contract.refund(htlc_id_bytes32).gas(100000u64);
+        let tx = tx_call.send().await?;
+        let tx_hash = format!("0x{:x}", tx.tx_hash());
+        println!("Refund transaction submitted: {}", tx_hash);
+        tx.await?;
+
+        Ok(tx_hash)
+    }
+
+    async fn refund_near_htlc(&self, htlc_id: &str, account_id: &str) -> Result<String> {
+        use std::process::Command;
+
+        println!("Refunding NEAR HTLC {}", htlc_id);
+
+        let output = Command::new("near")
+            .args([
+                "call",
+                "htlc-v2.testnet",
+                "refund",
+                &format!(r#"{{"escrow_id": "{}"}}"#, htlc_id),
+                "--use-account",
+                account_id,
+            ])
+            .output()
+            .map_err(|e| anyhow!("Failed to execute NEAR refund: {}", e))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "NEAR HTLC refund failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        Ok(output_str
+            .split("Transaction Id: ")
+            .nth(1)
+            .and_then(|rest| rest.lines().next())
+            .map(|tx_id| tx_id.trim().to_string())
+            .unwrap_or_else(|| "refund_successful".to_string()))
+    }
+
     /// Automated bidirectional swap flow
+    ///
+    /// `watchdog` alerts `observer` when neither leg moves for too long. With a
+    /// `refund_gate` the source leg is refunded once its timelock passes while
+    /// neither leg has been claimed. The target leg is only claimed when it
+    /// holds at least `min_received`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute_bidirectional_swap(
         &self,
         source_chain: &str,
@@ -280,54 +791,86 @@ impl HTLCMonitor {
         source_htlc_id: &str,
         target_htlc_id: &str,
         secret: &str,
-        interval_secs: u64,
-    ) -> Result<()> {
+        intervals: PollIntervals,
+        mut watchdog: StuckWatchdog,
+        mut refund_gate: Option<RefundGate>,
+        min_received: Option<u128>,
+        observer: &dyn SwapObserver,
+    ) -> Result<SwapRunOutcome> {
         println!("Starting bidirectional swap monitoring...");
-        println!("Source: {} ({})", source_chain, source_htlc_id);
-        println!("Target: {} ({})", target_chain, target_htlc_id);
+        println!(
+            "Source: {} ({}), polled every {:?}",
+            source_chain, source_htlc_id, intervals.source
+        );
+        println!(
+            "Target: {} ({}), polled every {:?}",
+            target_chain, target_htlc_id, intervals.target
+        );
 
-        // Monitor source chain HTLC
-        let _max_attempts = 60; // 30 minutes with 30 second intervals
+        let started = tokio::time::Instant::now();
+        let mut schedule = PollSchedule::new(intervals);
+        let mut source_status = None;
+        let mut target_status = None;
 
         loop {
-            // Check source chain HTLC status
-            let source_status = self
-                .monitor_htlc(source_htlc_id, source_chain, 1, 0)
-                .await?;
+            let (source_due, target_due) = schedule.due(started.elapsed());
 
-            println!("{} HTLC status: {}", source_chain, source_status.status);
+            // Only poll the legs whose interval has passed, keeping the last known status
+            if source_due {
+                let status = self
+                    .monitor_htlc(source_htlc_id, source_chain, 1, 0)
+                    .await?;
+                println!("{} HTLC status: {}", source_chain, status.status);
+                source_status = Some(status);
+            }
+            if target_due {
+                let status = self
+                    .monitor_htlc(target_htlc_id, target_chain, 1, 0)
+                    .await?;
+                println!("{} HTLC status: {}", target_chain, status.status);
+                target_status = Some(status);
+            }
 
-            // Check target chain HTLC status
-            let target_status = self
-                .monitor_htlc(target_htlc_id, target_chain, 1, 0)
-                .await?;
+            let (Some(source_status), Some(target_status)) = (&source_status, &target_status)
+            else {
+                continue;
+            };
 
-            println!("{} HTLC status: {}", target_chain, target_status.status);
+            let stage = format!(
+                "{} {} / {} {}",
+                source_chain, source_status.status, target_chain, target_status.status
+            );
+            if let Some(alert) = watchdog.observe(&stage, started.elapsed()) {
+                observer.on_alert(&alert);
+            }
 
             // If source is claimed, we need to claim target
             if source_status.status == "claimed" && target_status.status == "active" {
-                println!("Source HTLC claimed! Claiming target HTLC...");
-
-                match target_chain {
-                    "ethereum" => {
-                        let private_key = std::env::var("PRIVATE_KEY").ok();
-                        let tx_hash = self
-                            .claim_ethereum_htlc(target_htlc_id, secret, private_key)
-                            .await?;
-                        println!("Ethereum HTLC claimed! Transaction: {}", tx_hash);
-                        break;
-                    }
-                    "near" => {
-                        let account_id = std::env::var("NEAR_ACCOUNT_ID")
-                            .unwrap_or_else(|_| "user.testnet".to_string());
-                        let tx_id = self
-                            .claim_near_htlc(target_htlc_id, secret, &account_id)
-                            .await?;
-                        println!("NEAR HTLC claimed! Transaction: {}", tx_id);
-                        break;
+                return claim_unless_below_minimum(target_status, min_received, || async {
+                    println!("Source HTLC claimed! Claiming target HTLC...");
+
+                    match target_chain {
+                        "ethereum" => {
+                            let private_key = std::env::var("PRIVATE_KEY").ok();
+                            let tx_hash = self
+                                .claim_ethereum_htlc(target_htlc_id, secret, private_key)
+                                .await?;
+                            println!("Ethereum HTLC claimed! Transaction: {}", tx_hash);
+                            Ok(())
+                        }
+                        "near" => {
+                            let account_id = std::env::var("NEAR_ACCOUNT_ID")
+                                .unwrap_or_else(|_| "user.testnet".to_string());
+                            let tx_id = self
+                                .claim_near_htlc(target_htlc_id, secret, &account_id)
+                                .await?;
+                            println!("NEAR HTLC claimed! Transaction: {}", tx_id);
+                            Ok(())
+                        }
+                        _ => Err(anyhow!("Unsupported target chain")),
                     }
-                    _ => return Err(anyhow!("Unsupported target chain")),
-                }
+                })
+                .await;
             }
 
             // Check for timeout or refund conditions
@@ -340,16 +883,253 @@ impl HTLCMonitor {
                 break;
             }
 
-            sleep(Duration::from_secs(interval_secs)).await;
+            if let Some(gate) = refund_gate.as_mut() {
+                let unclaimed =
+                    source_status.status != "claimed" && target_status.status != "claimed";
+                if unclaimed && gate.ready(chrono::Utc::now().timestamp() as u64, observer) {
+                    let tx = self.refund_htlc(source_htlc_id, source_chain).await?;
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "status": "Source HTLC refunded",
+                            "htlc_id": source_htlc_id,
+                            "chain": source_chain,
+                            "transaction": tx,
+                        })
+                    );
+                    return Err(anyhow!(
+                        "Swap expired: source HTLC {} was refunded",
+                        source_htlc_id
+                    ));
+                }
+            }
+
+            sleep(schedule.next_wake().saturating_sub(started.elapsed())).await;
         }
 
-        Ok(())
+        Ok(SwapRunOutcome::Completed)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::StoredHtlc;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    fn stored_htlc(created_at: SystemTime, timeout: Duration) -> StoredHtlc {
+        StoredHtlc {
+            sender: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: 100,
+            secret_hash: [0u8; 32],
+            timeout,
+            created_at,
+            state: HtlcState::Pending,
+            secret: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refund_fires_once_after_timeout() {
+        let created_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let storage = HtlcStorage::new();
+        storage
+            .store(
+                "htlc_1".to_string(),
+                stored_htlc(created_at, Duration::from_secs(3)),
+            )
+            .unwrap();
+
+        // Each poll advances the mock clock by one second
+        let clock = AtomicU64::new(1_000);
+        let now =
+            || SystemTime::UNIX_EPOCH + Duration::from_secs(clock.fetch_add(1, Ordering::SeqCst));
+        let refunds = AtomicUsize::new(0);
+
+        let outcome = monitor_for_refund(&storage, "htlc_1", Duration::from_millis(1), now, || {
+            refunds.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, RefundWatchOutcome::Refunded);
+        assert_eq!(refunds.load(Ordering::SeqCst), 1);
+        // Polled at t+0..t+3 and refunded on the poll at the deadline
+        assert_eq!(clock.load(Ordering::SeqCst), 1_004);
+        assert_eq!(storage.get("htlc_1").unwrap().state, HtlcState::Refunded);
+
+        // Watching an already refunded HTLC does not refund it again
+        let outcome = monitor_for_refund(&storage, "htlc_1", Duration::from_millis(1), now, || {
+            refunds.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(outcome, RefundWatchOutcome::Refunded);
+        assert_eq!(refunds.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_claimed_htlc_stops_without_refund() {
+        let storage = HtlcStorage::new();
+        storage
+            .store(
+                "htlc_2".to_string(),
+                stored_htlc(SystemTime::UNIX_EPOCH, Duration::from_secs(3)),
+            )
+            .unwrap();
+        storage.update_state("htlc_2", HtlcState::Claimed).unwrap();
+
+        let refunds = AtomicUsize::new(0);
+        let outcome = monitor_for_refund(
+            &storage,
+            "htlc_2",
+            Duration::from_millis(1),
+            SystemTime::now,
+            || {
+                refunds.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, RefundWatchOutcome::Claimed);
+        assert_eq!(refunds.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_below_minimum_fill_is_not_claimed() {
+        let target = HTLCStatus {
+            htlc_id: "near_htlc".to_string(),
+            chain: "near".to_string(),
+            status: "active".to_string(),
+            secret: None,
+            timeout: 3600,
+            recipient: "alice.testnet".to_string(),
+            amount: "95".to_string(),
+        };
+        let claims = AtomicUsize::new(0);
+        let claim = || {
+            claims.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        };
+
+        let outcome = claim_unless_below_minimum(&target, Some(99), claim)
+            .await
+            .unwrap();
+        assert_eq!(
+            outcome,
+            SwapRunOutcome::BelowMinimum {
+                observed: 95,
+                min_received: 99
+            }
+        );
+        assert_eq!(claims.load(Ordering::SeqCst), 0);
+
+        let outcome = claim_unless_below_minimum(&target, Some(95), claim)
+            .await
+            .unwrap();
+        assert_eq!(outcome, SwapRunOutcome::Completed);
+        assert_eq!(claims.load(Ordering::SeqCst), 1);
+    }
+
+    /// Counts queries per escrow and reports every escrow as active
+    #[derive(Default)]
+    struct CountingSource {
+        queries: Mutex<HashMap<String, usize>>,
+        total: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HtlcStatusSource for CountingSource {
+        async fn check_htlc(&self, htlc_id: &str, chain: &str) -> Result<HTLCStatus> {
+            self.total.fetch_add(1, Ordering::SeqCst);
+            *self
+                .queries
+                .lock()
+                .unwrap()
+                .entry(htlc_id.to_string())
+                .or_default() += 1;
+            Ok(HTLCStatus {
+                htlc_id: htlc_id.to_string(),
+                chain: chain.to_string(),
+                status: "active".to_string(),
+                secret: None,
+                timeout: 3600,
+                recipient: "alice.testnet".to_string(),
+                amount: "1".to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_refund_pending_fires_ahead_of_refund() {
+        let observer = RecordingObserver::default();
+        let mut gate = RefundGate::new("fusion_1", "near", 1_000, Duration::from_secs(300));
+
+        assert!(!gate.ready(600, &observer));
+        assert!(observer.alerts.lock().unwrap().is_empty());
+
+        // Lead window opens: announce, but hold the refund back
+        assert!(!gate.ready(750, &observer));
+        assert_eq!(
+            *observer.alerts.lock().unwrap(),
+            vec![SwapAlert::RefundPending {
+                htlc_id: "fusion_1".to_string(),
+                chain: "near".to_string(),
+                refund_at: 1_000,
+                secs_until_refund: 250,
+            }]
+        );
+
+        assert!(!gate.ready(900, &observer));
+        assert!(gate.ready(1_000, &observer));
+        assert_eq!(observer.alerts.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_overdue_refund_is_announced_then_allowed() {
+        let observer = RecordingObserver::default();
+        let mut gate = RefundGate::new("0xabc", "ethereum", 1_000, Duration::from_secs(300));
+
+        assert!(gate.ready(5_000, &observer));
+        let alerts = observer.alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(
+            alerts[0],
+            SwapAlert::RefundPending {
+                secs_until_refund: 0,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_poll_cycle_queries_shared_escrow_once() {
+        let source = CountingSource::default();
+        // Both swaps lock their source funds in their own escrow but share the NEAR one
+        let swaps = [
+            [("ethereum", "0xaaa"), ("near", "fusion_7")],
+            [("ethereum", "0xbbb"), ("near", "fusion_7")],
+        ];
+
+        for cycle in 1..=2 {
+            let cache = PollCycleCache::new(&source);
+            for legs in &swaps {
+                for (chain, htlc_id) in legs {
+                    let status = cache.check_htlc(htlc_id, chain).await.unwrap();
+                    assert_eq!(status.htlc_id, *htlc_id);
+                }
+            }
+
+            // One query per unique escrow in each cycle
+            assert_eq!(source.total.load(Ordering::SeqCst), 3 * cycle);
+            assert_eq!(source.queries.lock().unwrap()["fusion_7"], cycle);
+        }
+    }
 
     #[test]
     fn test_htlc_status_serialization() {
@@ -370,4 +1150,98 @@ mod tests {
         assert_eq!(parsed.chain, status.chain);
         assert_eq!(parsed.status, status.status);
     }
+
+    #[test]
+    fn test_near_leg_polled_more_often_than_ethereum() {
+        let optimizer = ExecutionPathOptimizer::new();
+        let intervals = PollIntervals::from_block_times("ethereum", "near", 30, &optimizer);
+
+        // Ethereum (12s blocks) keeps the base interval, NEAR (1s blocks) is scaled down
+        assert_eq!(intervals.source, Duration::from_secs(30));
+        assert_eq!(intervals.target, Duration::from_millis(2500));
+
+        // Simulate ten minutes of monitoring
+        let mut schedule = PollSchedule::new(intervals);
+        let (mut ethereum_polls, mut near_polls) = (0, 0);
+        let mut elapsed = Duration::ZERO;
+        while elapsed < Duration::from_secs(600) {
+            let (source_due, target_due) = schedule.due(elapsed);
+            ethereum_polls += source_due as u32;
+            near_polls += target_due as u32;
+            elapsed = schedule.next_wake();
+        }
+
+        assert_eq!(ethereum_polls, 20);
+        assert_eq!(near_polls, 240);
+    }
+
+    #[test]
+    fn test_parse_estimated_time() {
+        assert_eq!(
+            parse_estimated_time("< 1 second"),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            parse_estimated_time("10-30 seconds"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_estimated_time("2-15 minutes"),
+            Some(Duration::from_secs(900))
+        );
+        assert_eq!(parse_estimated_time("soon"), None);
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        alerts: Mutex<Vec<SwapAlert>>,
+    }
+
+    impl SwapObserver for RecordingObserver {
+        fn on_alert(&self, alert: &SwapAlert) {
+            self.alerts.lock().unwrap().push(alert.clone());
+        }
+    }
+
+    #[test]
+    fn test_watchdog_fires_when_swap_stalls() {
+        let observer = RecordingObserver::default();
+        let mut watchdog = StuckWatchdog::new(Duration::from_secs(120));
+        let mut fired_at = Vec::new();
+        let mut poll = |stage: &str, secs: u64| {
+            if let Some(alert) = watchdog.observe(stage, Duration::from_secs(secs)) {
+                observer.on_alert(&alert);
+                fired_at.push(secs);
+            }
+        };
+
+        // The source leg progresses, then the counterparty disappears
+        poll("ethereum pending / near active", 0);
+        poll("ethereum pending / near active", 90);
+        poll("ethereum claimed / near active", 100);
+        for secs in (130..=400).step_by(30) {
+            poll("ethereum claimed / near active", secs);
+        }
+
+        assert_eq!(fired_at, vec![220]);
+        assert_eq!(
+            *observer.alerts.lock().unwrap(),
+            vec![SwapAlert::Stuck {
+                stage: "ethereum claimed / near active".to_string(),
+                stalled_secs: 120,
+                budget_secs: 120,
+                recommendation: "Counterparty may be gone; refund once the timelock expires"
+                    .to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unknown_chain_uses_base_interval() {
+        let optimizer = ExecutionPathOptimizer::new();
+        let intervals = PollIntervals::from_block_times("polygon", "near", 30, &optimizer);
+
+        assert_eq!(intervals.source, Duration::from_secs(30));
+        assert_eq!(intervals.target, Duration::from_secs(30));
+    }
 }
@@ -1,5 +1,9 @@
+pub mod config_handler;
+pub mod duration_arg;
 pub mod ethereum_tx;
+pub mod exit_code;
 pub mod htlc_monitor;
+pub mod near_client;
 pub mod near_order_handler;
 // pub mod oneinch_api; // Removed: Not using 1inch API per hackathon requirements
 pub mod order_handler;
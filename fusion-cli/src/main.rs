@@ -1,28 +1,46 @@
 use anyhow::Result;
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use fusion_core::htlc::{generate_secret, hash_secret, Htlc, HtlcState};
 use serde_json::json;
 use std::time::Duration;
 
+mod config_handler;
+mod doctor_handler;
+mod duration_arg;
 mod ethereum_tx;
+mod exit_code;
 mod htlc_monitor;
+mod near_client;
 mod near_order_handler;
 // mod oneinch_api; // Removed: Not using 1inch API per hackathon requirements
 mod order_handler;
 mod order_management;
 mod relay_order_handler;
 mod storage;
+mod swap_benchmark;
 mod swap_handler;
-use once_cell::sync::Lazy;
+mod verify_secret_handler;
+use exit_code::{cli_error, exit_code_for, CliError, ExitCode};
+use once_cell::sync::OnceCell;
+use std::path::PathBuf;
 use storage::{HtlcStorage, StoredHtlc};
 
-static STORAGE: Lazy<HtlcStorage> = Lazy::new(HtlcStorage::new);
+static STORAGE: OnceCell<HtlcStorage> = OnceCell::new();
+
+/// HTLC storage chosen by `--storage-path` or `FUSION_STORAGE_PATH` (in-memory if neither)
+fn storage() -> &'static HtlcStorage {
+    STORAGE.get_or_init(HtlcStorage::from_env)
+}
 
 #[derive(Parser)]
 #[command(name = "fusion-cli")]
 #[command(about = "UniteSwap CLI")]
 #[command(version = "0.1.0")]
 struct Cli {
+    /// SQLite database that keeps HTLCs between invocations (overrides FUSION_STORAGE_PATH)
+    #[arg(long, global = true)]
+    storage_path: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -35,6 +53,10 @@ enum Commands {
     Claim(ClaimArgs),
     /// Refund an HTLC after timeout
     Refund(RefundArgs),
+    /// List stored HTLCs as JSON
+    ListHtlc(ListHtlcArgs),
+    /// Check a secret against a hashlock offline, without claiming
+    VerifySecret(verify_secret_handler::VerifySecretArgs),
     /// Order commands
     Order(Box<OrderCommands>),
     /// Relay an order from EVM to another chain
@@ -44,6 +66,11 @@ enum Commands {
     /// Integrated cross-chain token swap
     #[command(subcommand)]
     Swap(swap_handler::SwapCommands),
+    /// Configuration file utilities
+    #[command(subcommand)]
+    Config(config_handler::ConfigCommands),
+    /// Check that the configuration, RPCs, contracts and environment are ready
+    Doctor(doctor_handler::DoctorArgs),
 }
 
 #[derive(Args)]
@@ -56,12 +83,18 @@ struct OrderCommands {
 enum OrderSubcommands {
     /// Create a new limit order
     Create(order_handler::CreateOrderArgs),
+    /// Create a limit order and its HTLC from a single secret
+    CreateWithHtlc(order_handler::CreateOrderWithHtlcArgs),
     /// Create a NEAR to Ethereum order
     CreateNear(near_order_handler::CreateNearOrderArgs),
+    /// Print the EIP-712 typed data of an order for signing with an external wallet
+    TypedData(order_handler::OrderTypedDataArgs),
     /// Check order status
     Status(order_management::StatusArgs),
     /// Cancel an order
     Cancel(order_management::CancelArgs),
+    /// Match signed orders from a file and print the profitable pairs
+    Match(order_management::MatchArgs),
 }
 
 #[derive(Args)]
@@ -75,8 +108,8 @@ struct CreateHtlcArgs {
     /// Amount to transfer
     #[arg(long)]
     amount: u64,
-    /// Timeout duration in seconds
-    #[arg(long, default_value = "3600")]
+    /// Timeout duration, e.g. 90s, 30m or 3h (bare numbers are seconds)
+    #[arg(long, default_value = "3600", value_parser = crate::duration_arg::parse_duration_secs)]
     timeout: u64,
 }
 
@@ -88,6 +121,31 @@ struct ClaimArgs {
     /// Secret to claim the HTLC
     #[arg(long)]
     secret: String,
+    /// Encoding of --secret (base58 for values copied from NEAR tools)
+    #[arg(long, value_enum, default_value_t = SecretEncoding::Hex)]
+    secret_encoding: SecretEncoding,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SecretEncoding {
+    Hex,
+    Base58,
+}
+
+impl SecretEncoding {
+    fn decode(self, value: &str) -> Result<Vec<u8>> {
+        Ok(match self {
+            SecretEncoding::Hex => hex::decode(value)?,
+            SecretEncoding::Base58 => bs58::decode(value).into_vec()?,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SecretEncoding::Hex => "hex",
+            SecretEncoding::Base58 => "base58",
+        }
+    }
 }
 
 #[derive(Args)]
@@ -95,30 +153,110 @@ struct RefundArgs {
     /// HTLC identifier
     #[arg(long)]
     htlc_id: String,
+    /// Keep polling and refund automatically once the timeout has passed
+    #[arg(long)]
+    wait: bool,
+    /// How often to poll with --wait, e.g. 10s or 1m
+    #[arg(long, default_value = "10", value_parser = crate::duration_arg::parse_duration_secs)]
+    poll_interval: u64,
+}
+
+#[derive(Args)]
+struct ListHtlcArgs {
+    /// Only list HTLCs in this state
+    #[arg(long, value_enum)]
+    state: Option<HtlcStateFilter>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum HtlcStateFilter {
+    Pending,
+    Claimed,
+    Refunded,
+}
+
+impl HtlcStateFilter {
+    fn matches(self, state: &HtlcState) -> bool {
+        matches!(
+            (self, state),
+            (HtlcStateFilter::Pending, HtlcState::Pending)
+                | (HtlcStateFilter::Claimed, HtlcState::Claimed)
+                | (HtlcStateFilter::Refunded, HtlcState::Refunded)
+        )
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> std::process::ExitCode {
     let cli = Cli::parse();
+    if let Some(path) = cli.storage_path {
+        // Set before any command touches the storage
+        let _ = STORAGE.set(HtlcStorage::open(path));
+    }
 
-    match cli.command {
+    match run(cli.command).await {
+        Ok(()) => ExitCode::Success.into(),
+        Err(e) => {
+            // Errors with a JSON body keep printing it to stdout for scripts
+            match e.downcast_ref::<CliError>() {
+                Some(error) => match serde_json::to_string_pretty(&error.output) {
+                    Ok(output) => println!("{}", output),
+                    Err(_) => println!("{}", error.output),
+                },
+                None => eprintln!("Error: {:?}", e),
+            }
+            exit_code_for(&e).into()
+        }
+    }
+}
+
+async fn run(command: Commands) -> Result<()> {
+    match command {
         Commands::CreateHtlc(args) => handle_create_htlc(args).await,
         Commands::Claim(args) => handle_claim(args).await,
         Commands::Refund(args) => handle_refund(args).await,
+        Commands::ListHtlc(args) => handle_list_htlc(args).await,
+        Commands::VerifySecret(args) => verify_secret_handler::handle_verify_secret(args).await,
         Commands::Order(order_cmd) => match order_cmd.command {
             OrderSubcommands::Create(args) => order_handler::handle_create_order(args).await,
+            OrderSubcommands::CreateWithHtlc(args) => {
+                order_handler::handle_create_order_with_htlc(args).await
+            }
             OrderSubcommands::CreateNear(args) => {
                 near_order_handler::handle_create_near_order(args).await
             }
+            OrderSubcommands::TypedData(args) => order_handler::handle_order_typed_data(args).await,
             OrderSubcommands::Status(args) => order_management::handle_order_status(args).await,
             OrderSubcommands::Cancel(args) => order_management::handle_order_cancel(args).await,
+            OrderSubcommands::Match(args) => order_management::handle_order_match(args).await,
         },
         Commands::RelayOrder(args) => relay_order_handler::handle_relay_order(args).await,
         Commands::Orderbook(args) => order_management::handle_orderbook(args).await,
         Commands::Swap(swap_cmd) => match swap_cmd {
             swap_handler::SwapCommands::Execute(args) => swap_handler::handle_swap(args).await,
             swap_handler::SwapCommands::Batch(args) => swap_handler::handle_batch_swap(args).await,
+            swap_handler::SwapCommands::Immutables(args) => {
+                swap_handler::handle_immutables(args).await
+            }
+            swap_handler::SwapCommands::List(args) => swap_handler::handle_list_swaps(args).await,
+            swap_handler::SwapCommands::Verify(args) => {
+                swap_handler::handle_verify_swap(args).await
+            }
+            swap_handler::SwapCommands::Resume(args) => {
+                swap_handler::handle_resume_swap(args).await
+            }
+            swap_handler::SwapCommands::Status(args) => {
+                swap_handler::handle_swap_status(args).await
+            }
+            swap_handler::SwapCommands::StatusAll(args) => {
+                swap_handler::handle_status_all(args).await
+            }
+            swap_handler::SwapCommands::Benchmark(args) => {
+                swap_benchmark::handle_swap_benchmark(args).await
+            }
         },
+        Commands::Config(config_cmd) => config_handler::handle_config_command(config_cmd).await,
+        Commands::Doctor(args) => doctor_handler::handle_doctor(args).await,
     }
 }
 
@@ -150,7 +288,7 @@ async fn handle_create_htlc(args: CreateHtlcArgs) -> Result<()> {
         state: HtlcState::Pending,
         secret: Some(secret.to_vec()),
     };
-    STORAGE.store(htlc_id.clone(), stored_htlc)?;
+    storage().store(htlc_id.clone(), stored_htlc)?;
 
     // Output result as JSON
     let output = json!({
@@ -170,50 +308,57 @@ async fn handle_create_htlc(args: CreateHtlcArgs) -> Result<()> {
 
 async fn handle_claim(args: ClaimArgs) -> Result<()> {
     // Get HTLC from storage
-    let stored_htlc = match STORAGE.get(&args.htlc_id) {
+    let stored_htlc = match storage().get(&args.htlc_id) {
         Ok(htlc) => htlc,
         Err(_) => {
-            let output = json!({
-                "error": "HTLC not found",
-                "htlc_id": args.htlc_id
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            return Ok(());
+            return Err(cli_error(
+                ExitCode::NotFound,
+                json!({
+                    "error": "HTLC not found",
+                    "htlc_id": args.htlc_id
+                }),
+            ));
         }
     };
 
     // Check if HTLC is already claimed
     if stored_htlc.state == HtlcState::Claimed {
-        let output = json!({
-            "error": "HTLC already claimed",
-            "htlc_id": args.htlc_id,
-            "status": stored_htlc.state
-        });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return Err(cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": "HTLC already claimed",
+                "htlc_id": args.htlc_id,
+                "status": stored_htlc.state
+            }),
+        ));
     }
 
     // Check if HTLC is refunded
     if stored_htlc.state == HtlcState::Refunded {
-        let output = json!({
-            "error": "HTLC already refunded",
-            "htlc_id": args.htlc_id,
-            "status": stored_htlc.state
-        });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return Err(cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": "HTLC already refunded",
+                "htlc_id": args.htlc_id,
+                "status": stored_htlc.state
+            }),
+        ));
     }
 
-    // Parse the secret from hex string
-    let secret_bytes = match hex::decode(&args.secret) {
+    // Parse the secret in the requested encoding
+    let secret_bytes = match args.secret_encoding.decode(&args.secret) {
         Ok(bytes) => bytes,
         Err(_) => {
-            let output = json!({
-                "error": "Invalid secret format",
-                "message": "Secret must be a valid hex string"
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            return Ok(());
+            return Err(cli_error(
+                ExitCode::UserError,
+                json!({
+                    "error": "Invalid secret format",
+                    "message": format!(
+                        "Secret must be a valid {} string",
+                        args.secret_encoding.name()
+                    )
+                }),
+            ));
         }
     };
 
@@ -221,12 +366,13 @@ async fn handle_claim(args: ClaimArgs) -> Result<()> {
     let secret: fusion_core::htlc::Secret = match secret_bytes.try_into() {
         Ok(arr) => arr,
         Err(_) => {
-            let output = json!({
-                "error": "Invalid secret length",
-                "message": "Secret must be exactly 32 bytes (64 hex characters)"
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            return Ok(());
+            return Err(cli_error(
+                ExitCode::UserError,
+                json!({
+                    "error": "Invalid secret length",
+                    "message": "Secret must be exactly 32 bytes"
+                }),
+            ));
         }
     };
 
@@ -243,7 +389,7 @@ async fn handle_claim(args: ClaimArgs) -> Result<()> {
     match htlc.claim(&secret) {
         Ok(_) => {
             // Update state in storage
-            STORAGE.update_state(&args.htlc_id, HtlcState::Claimed)?;
+            storage().update_state(&args.htlc_id, HtlcState::Claimed)?;
 
             // Output successful claim
             let output = json!({
@@ -254,48 +400,60 @@ async fn handle_claim(args: ClaimArgs) -> Result<()> {
             println!("{}", serde_json::to_string_pretty(&output)?);
             Ok(())
         }
-        Err(fusion_core::htlc::HtlcError::InvalidSecret) => {
-            let output = json!({
+        Err(fusion_core::htlc::HtlcError::InvalidSecret) => Err(cli_error(
+            ExitCode::UserError,
+            json!({
                 "error": "Invalid secret",
                 "htlc_id": args.htlc_id
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
-        Err(e) => {
-            let output = json!({
+            }),
+        )),
+        Err(e) => Err(cli_error(
+            ExitCode::UserError,
+            json!({
                 "error": format!("Claim failed: {}", e),
                 "htlc_id": args.htlc_id
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
+            }),
+        )),
     }
 }
 
 async fn handle_refund(args: RefundArgs) -> Result<()> {
+    if args.wait {
+        htlc_monitor::monitor_for_refund(
+            storage(),
+            &args.htlc_id,
+            Duration::from_secs(args.poll_interval),
+            std::time::SystemTime::now,
+            || async { Ok(()) },
+        )
+        .await?;
+        return Ok(());
+    }
+
     // Get HTLC from storage
-    let stored_htlc = STORAGE.get(&args.htlc_id)?;
+    let stored_htlc = storage().get(&args.htlc_id)?;
 
     // Check if HTLC is already claimed or refunded
     if stored_htlc.state == HtlcState::Claimed {
-        let output = json!({
-            "error": "HTLC already claimed",
-            "htlc_id": args.htlc_id,
-            "status": stored_htlc.state
-        });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return Err(cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": "HTLC already claimed",
+                "htlc_id": args.htlc_id,
+                "status": stored_htlc.state
+            }),
+        ));
     }
 
     if stored_htlc.state == HtlcState::Refunded {
-        let output = json!({
-            "error": "HTLC already refunded",
-            "htlc_id": args.htlc_id,
-            "status": stored_htlc.state
-        });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return Err(cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": "HTLC already refunded",
+                "htlc_id": args.htlc_id,
+                "status": stored_htlc.state
+            }),
+        ));
     }
 
     // Recreate HTLC to validate construction
@@ -313,17 +471,18 @@ async fn handle_refund(args: RefundArgs) -> Result<()> {
         .unwrap_or(Duration::from_secs(0));
 
     if elapsed <= stored_htlc.timeout {
-        let output = json!({
-            "error": "HTLC has not timed out yet",
-            "htlc_id": args.htlc_id,
-            "timeout_remaining_seconds": (stored_htlc.timeout.as_secs() - elapsed.as_secs())
-        });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return Err(cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": "HTLC has not timed out yet",
+                "htlc_id": args.htlc_id,
+                "timeout_remaining_seconds": (stored_htlc.timeout.as_secs() - elapsed.as_secs())
+            }),
+        ));
     }
 
     // Update state to refunded
-    STORAGE.update_state(&args.htlc_id, HtlcState::Refunded)?;
+    storage().update_state(&args.htlc_id, HtlcState::Refunded)?;
 
     // Output successful refund
     let output = json!({
@@ -335,3 +494,152 @@ async fn handle_refund(args: RefundArgs) -> Result<()> {
     println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
+
+/// Stored HTLCs in `state` (all if `None`), with seconds left until each can be refunded
+fn list_htlcs(
+    storage: &HtlcStorage,
+    state: Option<HtlcStateFilter>,
+    now: std::time::SystemTime,
+) -> Result<Vec<serde_json::Value>> {
+    Ok(storage
+        .list()?
+        .into_iter()
+        .filter(|(_, htlc)| state.is_none_or(|state| state.matches(&htlc.state)))
+        .map(|(htlc_id, htlc)| {
+            let elapsed = now
+                .duration_since(htlc.created_at)
+                .unwrap_or(Duration::from_secs(0));
+            json!({
+                "htlc_id": htlc_id,
+                "state": format!("{:?}", htlc.state),
+                "amount": htlc.amount,
+                "timeout_remaining_seconds": htlc.timeout.saturating_sub(elapsed).as_secs(),
+            })
+        })
+        .collect())
+}
+
+async fn handle_list_htlc(args: ListHtlcArgs) -> Result<()> {
+    let htlcs = list_htlcs(storage(), args.state, std::time::SystemTime::now())?;
+    println!("{}", serde_json::to_string_pretty(&htlcs)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_pending_htlc(htlc_id: &str, secret: &fusion_core::htlc::Secret) {
+        storage()
+            .store(
+                htlc_id.to_string(),
+                StoredHtlc {
+                    sender: "Alice".to_string(),
+                    recipient: "Bob".to_string(),
+                    amount: 1000,
+                    secret_hash: hash_secret(secret),
+                    timeout: Duration::from_secs(3600),
+                    created_at: std::time::SystemTime::now(),
+                    state: HtlcState::Pending,
+                    secret: None,
+                },
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_claim_with_base58_secret() {
+        let secret = generate_secret();
+        store_pending_htlc("htlc_base58", &secret);
+
+        handle_claim(ClaimArgs {
+            htlc_id: "htlc_base58".to_string(),
+            secret: bs58::encode(secret).into_string(),
+            secret_encoding: SecretEncoding::Base58,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            storage().get("htlc_base58").unwrap().state,
+            HtlcState::Claimed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claim_rejects_secret_in_wrong_encoding() {
+        let secret = generate_secret();
+        store_pending_htlc("htlc_wrong_encoding", &secret);
+
+        // base58の値をhexとして渡すと形式エラー
+        let err = handle_claim(ClaimArgs {
+            htlc_id: "htlc_wrong_encoding".to_string(),
+            secret: bs58::encode(secret).into_string(),
+            secret_encoding: SecretEncoding::Hex,
+        })
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Invalid secret format"));
+        assert_eq!(
+            storage().get("htlc_wrong_encoding").unwrap().state,
+            HtlcState::Pending
+        );
+    }
+
+    #[test]
+    fn test_list_htlcs_filters_by_state() {
+        let storage = HtlcStorage::new();
+        let created_at = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        for (htlc_id, state, amount) in [
+            ("htlc_a", HtlcState::Pending, 100),
+            ("htlc_b", HtlcState::Claimed, 200),
+            ("htlc_c", HtlcState::Pending, 300),
+            ("htlc_d", HtlcState::Refunded, 400),
+        ] {
+            storage
+                .store(
+                    htlc_id.to_string(),
+                    StoredHtlc {
+                        sender: "Alice".to_string(),
+                        recipient: "Bob".to_string(),
+                        amount,
+                        secret_hash: [0u8; 32],
+                        timeout: Duration::from_secs(3600),
+                        created_at,
+                        state,
+                        secret: None,
+                    },
+                )
+                .unwrap();
+        }
+        let now = created_at + Duration::from_secs(600);
+        let ids = |state| -> Vec<String> {
+            list_htlcs(&storage, state, now)
+                .unwrap()
+                .iter()
+                .map(|htlc| htlc["htlc_id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(ids(None), vec!["htlc_a", "htlc_b", "htlc_c", "htlc_d"]);
+        assert_eq!(
+            ids(Some(HtlcStateFilter::Pending)),
+            vec!["htlc_a", "htlc_c"]
+        );
+        assert_eq!(ids(Some(HtlcStateFilter::Claimed)), vec!["htlc_b"]);
+        assert_eq!(ids(Some(HtlcStateFilter::Refunded)), vec!["htlc_d"]);
+
+        let listed = list_htlcs(&storage, Some(HtlcStateFilter::Claimed), now).unwrap();
+        assert_eq!(listed[0]["state"], "Claimed");
+        assert_eq!(listed[0]["amount"], 200);
+        assert_eq!(listed[0]["timeout_remaining_seconds"], 3000);
+
+        // Expired HTLCs report no time left rather than underflowing
+        let late = created_at + Duration::from_secs(7200);
+        assert_eq!(
+            list_htlcs(&storage, None, late).unwrap()[0]["timeout_remaining_seconds"],
+            0
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::{anyhow, Result};
+use fusion_core::chains::near::NearHtlcConnector;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// NEAR connectors shared by every command run in this process
+pub static NEAR_CLIENTS: Lazy<NearClientPool> = Lazy::new(NearClientPool::new);
+
+/// What a NEAR connector is bound to; connectors are reused per distinct config
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NearClientConfig {
+    pub rpc_url: String,
+    pub contract_id: String,
+    pub account_id: String,
+}
+
+/// Lazily connected clients, each built once and handed out as `Arc`s
+///
+/// The lock is only held while looking up or inserting a client, never across
+/// an `.await`, so clients can be fetched from any task.
+pub struct NearClientPool<C = NearHtlcConnector> {
+    clients: Mutex<HashMap<NearClientConfig, Arc<C>>>,
+}
+
+impl<C> NearClientPool<C> {
+    pub fn new() -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the client for `config`, calling `connect` only the first time
+    pub fn get_or_connect<F>(&self, config: &NearClientConfig, connect: F) -> Result<Arc<C>>
+    where
+        F: FnOnce(&NearClientConfig) -> Result<C>,
+    {
+        let mut clients = self
+            .clients
+            .lock()
+            .map_err(|e| anyhow!("Lock error: {}", e))?;
+        if let Some(client) = clients.get(config) {
+            return Ok(client.clone());
+        }
+
+        let client = Arc::new(connect(config)?);
+        clients.insert(config.clone(), client.clone());
+        Ok(client)
+    }
+}
+
+impl<C> Default for NearClientPool<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NearClientPool {
+    /// Shared HTLC connector signing as `config.account_id`
+    pub fn connector(
+        &self,
+        config: &NearClientConfig,
+        private_key: &str,
+    ) -> Result<Arc<NearHtlcConnector>> {
+        self.get_or_connect(config, |config| {
+            NearHtlcConnector::new(&config.rpc_url)
+                .with_contract(&config.contract_id)
+                .with_account(&config.account_id, private_key)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn testnet_config(account_id: &str) -> NearClientConfig {
+        NearClientConfig {
+            rpc_url: "https://rpc.testnet.near.org".to_string(),
+            contract_id: "fusion-htlc.testnet".to_string(),
+            account_id: account_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_is_constructed_once_and_reused() {
+        let pool = NearClientPool::<usize>::new();
+        let constructed = AtomicUsize::new(0);
+        let connect = |_: &NearClientConfig| Ok(constructed.fetch_add(1, Ordering::SeqCst));
+
+        let config = testnet_config("alice.testnet");
+        let first = pool.get_or_connect(&config, connect).unwrap();
+        let second = pool.get_or_connect(&config, connect).unwrap();
+        assert_eq!(constructed.load(Ordering::SeqCst), 1);
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Another signer gets its own client
+        let other = pool
+            .get_or_connect(&testnet_config("bob.testnet"), connect)
+            .unwrap();
+        assert_eq!(constructed.load(Ordering::SeqCst), 2);
+        assert!(!Arc::ptr_eq(&first, &other));
+    }
+
+    #[tokio::test]
+    async fn test_failed_connect_is_not_cached() {
+        let pool = NearClientPool::<u8>::new();
+        let config = testnet_config("alice.testnet");
+
+        assert!(pool
+            .get_or_connect(&config, |_| Err(anyhow!("bad key")))
+            .is_err());
+        assert_eq!(*pool.get_or_connect(&config, |_| Ok(7)).unwrap(), 7);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config_handler::resolve_limit_order_protocol;
 use anyhow::{anyhow, Result};
 use clap::Args;
 use fusion_core::{
@@ -30,8 +31,8 @@ pub struct CreateNearOrderArgs {
     #[arg(long)]
     pub secret_hash: Option<String>,
 
-    /// HTLC timeout in seconds (default: 3600)
-    #[arg(long, default_value = "3600")]
+    /// HTLC timeout, e.g. 90s, 30m or 3h; bare numbers are seconds (default: 3600)
+    #[arg(long, default_value = "3600", value_parser = crate::duration_arg::parse_duration_secs)]
     pub timeout: u64,
 
     /// Slippage tolerance in basis points (100 = 1%)
@@ -42,9 +43,9 @@ pub struct CreateNearOrderArgs {
     #[arg(long, default_value = "84532")]
     pub chain_id: u64,
 
-    /// Limit Order Protocol address (default: Base Sepolia deployment)
-    #[arg(long, default_value = "0x171C87724E720F2806fc29a010a62897B30fdb62")]
-    pub limit_order_protocol: String,
+    /// Limit Order Protocol address (default: the one configured for --chain-id)
+    #[arg(long)]
+    pub limit_order_protocol: Option<String>,
 }
 
 pub async fn handle_create_near_order(args: CreateNearOrderArgs) -> Result<()> {
@@ -54,6 +55,8 @@ pub async fn handle_create_near_order(args: CreateNearOrderArgs) -> Result<()> {
     }
 
     validate_ethereum_address(&args.ethereum_address)?;
+    let limit_order_protocol =
+        resolve_limit_order_protocol(args.limit_order_protocol.as_deref(), args.chain_id)?;
 
     // Handle secret generation or parsing
     let (secret, secret_hash) = if args.generate_secret {
@@ -101,7 +104,7 @@ pub async fn handle_create_near_order(args: CreateNearOrderArgs) -> Result<()> {
     )?;
 
     // Create EIP-712 typed data
-    let typed_data = order.to_eip712(args.chain_id, &args.limit_order_protocol);
+    let typed_data = order.to_eip712(args.chain_id, &limit_order_protocol);
     let eip712_hash = typed_data.hash();
 
     // Prepare output
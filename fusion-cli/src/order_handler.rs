@@ -1,8 +1,14 @@
+use crate::ethereum_tx::{OnChainSubmitter, OrderSubmitter, RelayerSubmitter};
+use crate::storage::{StoredSwap, SwapStore};
 use anyhow::{anyhow, Result};
 use clap::Args;
-use fusion_core::eip712::OrderEIP712;
+use ethers::providers::{Http, Provider};
+use ethers::types::Address;
+use fusion_core::chains::ethereum::token_check::{check_token, TokenSource};
+use fusion_core::eip712::{to_typed_data_json, OrderEIP712};
+use fusion_core::htlc::{generate_secret, hash_secret, Htlc, Secret};
 use fusion_core::near_limit_order::HTLCData;
-use fusion_core::order::OrderBuilder;
+use fusion_core::order::{Order, OrderBuilder};
 use serde_json::json;
 
 #[derive(Args)]
@@ -31,8 +37,8 @@ pub struct CreateOrderArgs {
     #[arg(long)]
     pub htlc_secret_hash: String,
 
-    /// HTLC timeout in seconds
-    #[arg(long)]
+    /// HTLC timeout, e.g. 90s, 30m or 3h (bare numbers are seconds)
+    #[arg(long, value_parser = crate::duration_arg::parse_duration_secs)]
     pub htlc_timeout: u64,
 
     /// Chain ID
@@ -59,6 +65,10 @@ pub struct CreateOrderArgs {
     #[arg(long)]
     pub recipient_address: Option<String>,
 
+    /// Order salt as hex (up to 32 bytes), random if omitted; fixes the order hash
+    #[arg(long)]
+    pub salt: Option<String>,
+
     /// Sign the order with private key from PRIVATE_KEY env var
     #[arg(long)]
     pub sign: bool,
@@ -66,9 +76,324 @@ pub struct CreateOrderArgs {
     /// Submit the order to blockchain (requires PRIVATE_KEY and ETHEREUM_RPC_URL env vars)
     #[arg(long)]
     pub submit: bool,
+
+    /// Relayer endpoint to post the signed order to instead of the limit order protocol contract
+    #[arg(long)]
+    pub relayer_url: Option<String>,
+
+    /// Skip checking on-chain that the assets are deployed ERC20 tokens (for offline use)
+    #[arg(long)]
+    pub no_token_check: bool,
+}
+
+#[derive(Args)]
+pub struct OrderTypedDataArgs {
+    /// Maker asset address
+    #[arg(long)]
+    pub maker_asset: String,
+
+    /// Taker asset address
+    #[arg(long)]
+    pub taker_asset: String,
+
+    /// Maker address
+    #[arg(long)]
+    pub maker: String,
+
+    /// Making amount
+    #[arg(long)]
+    pub making_amount: u128,
+
+    /// Taking amount
+    #[arg(long)]
+    pub taking_amount: u128,
+
+    /// HTLC secret hash (32 bytes hex)
+    #[arg(long)]
+    pub htlc_secret_hash: String,
+
+    /// HTLC timeout, e.g. 90s, 30m or 3h (bare numbers are seconds)
+    #[arg(long, value_parser = crate::duration_arg::parse_duration_secs)]
+    pub htlc_timeout: u64,
+
+    /// Chain ID
+    #[arg(long)]
+    pub chain_id: u64,
+
+    /// Verifying contract address
+    #[arg(long)]
+    pub verifying_contract: String,
+
+    /// Receiver address (optional)
+    #[arg(long)]
+    pub receiver: Option<String>,
+
+    /// Allowed sender address (optional)
+    #[arg(long)]
+    pub allowed_sender: Option<String>,
+
+    /// Recipient chain for HTLC (e.g., "near", "ethereum")
+    #[arg(long)]
+    pub recipient_chain: Option<String>,
+
+    /// Recipient address on the target chain
+    #[arg(long)]
+    pub recipient_address: Option<String>,
+
+    /// Order salt as hex (up to 32 bytes), random if omitted; fixes the order hash
+    #[arg(long)]
+    pub salt: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CreateOrderWithHtlcArgs {
+    /// Maker asset address
+    #[arg(long)]
+    pub maker_asset: String,
+
+    /// Taker asset address
+    #[arg(long)]
+    pub taker_asset: String,
+
+    /// Maker address
+    #[arg(long)]
+    pub maker: String,
+
+    /// Making amount
+    #[arg(long)]
+    pub making_amount: u128,
+
+    /// Taking amount
+    #[arg(long)]
+    pub taking_amount: u128,
+
+    /// HTLC timeout, e.g. 90s, 30m or 3h (bare numbers are seconds)
+    #[arg(long, default_value = "3600", value_parser = crate::duration_arg::parse_duration_secs)]
+    pub htlc_timeout: u64,
+
+    /// Chain ID
+    #[arg(long)]
+    pub chain_id: u64,
+
+    /// Verifying contract address
+    #[arg(long)]
+    pub verifying_contract: String,
+
+    /// Receiver address (optional)
+    #[arg(long)]
+    pub receiver: Option<String>,
+
+    /// Allowed sender address (optional)
+    #[arg(long)]
+    pub allowed_sender: Option<String>,
+
+    /// Chain the HTLC is created on
+    #[arg(long, default_value = "near")]
+    pub recipient_chain: String,
+
+    /// Account that can claim the HTLC with the secret
+    #[arg(long)]
+    pub recipient_address: String,
+
+    /// Account funding the HTLC
+    #[arg(long)]
+    pub htlc_sender: String,
+
+    /// Amount locked in the HTLC
+    #[arg(long)]
+    pub htlc_amount: u64,
+
+    /// Skip checking on-chain that the assets are deployed ERC20 tokens (for offline use)
+    #[arg(long)]
+    pub no_token_check: bool,
 }
 
 pub async fn handle_create_order(args: CreateOrderArgs) -> Result<()> {
+    let output = create_order(args).await?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Build the order and hand it to the configured submitter, returning the JSON summary
+pub async fn create_order(args: CreateOrderArgs) -> Result<serde_json::Value> {
+    // Get RPC URL and private key from environment
+    let rpc_url = std::env::var("ETHEREUM_RPC_URL")
+        .unwrap_or_else(|_| "https://sepolia.base.org".to_string());
+    let private_key = std::env::var("PRIVATE_KEY").ok();
+
+    if !args.no_token_check {
+        let provider = Provider::<Http>::try_from(rpc_url.as_str())?;
+        check_order_assets(&args, &provider).await?;
+    }
+
+    match args.relayer_url.clone() {
+        Some(endpoint) => {
+            let submitter = RelayerSubmitter::new(endpoint);
+            build_and_submit_order(args, private_key, &submitter).await
+        }
+        None => {
+            let submitter = OnChainSubmitter::new(rpc_url, args.chain_id, private_key.clone());
+            build_and_submit_order(args, private_key, &submitter).await
+        }
+    }
+}
+
+pub async fn handle_order_typed_data(args: OrderTypedDataArgs) -> Result<()> {
+    let output = order_typed_data(args)?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Full EIP-712 typed data for the order, ready for `eth_signTypedData_v4`
+pub fn order_typed_data(args: OrderTypedDataArgs) -> Result<serde_json::Value> {
+    let chain_id = args.chain_id;
+    let verifying_contract = args.verifying_contract.clone();
+    let (order, _) = build_order(&CreateOrderArgs {
+        maker_asset: args.maker_asset,
+        taker_asset: args.taker_asset,
+        maker: args.maker,
+        making_amount: args.making_amount,
+        taking_amount: args.taking_amount,
+        htlc_secret_hash: args.htlc_secret_hash,
+        htlc_timeout: args.htlc_timeout,
+        chain_id,
+        verifying_contract: args.verifying_contract,
+        receiver: args.receiver,
+        allowed_sender: args.allowed_sender,
+        recipient_chain: args.recipient_chain,
+        recipient_address: args.recipient_address,
+        salt: args.salt,
+        sign: false,
+        submit: false,
+        relayer_url: None,
+        no_token_check: true,
+    })?;
+
+    let typed_data = order.to_eip712(chain_id, &verifying_contract);
+    Ok(to_typed_data_json(&order, &typed_data.domain))
+}
+
+pub async fn handle_create_order_with_htlc(args: CreateOrderWithHtlcArgs) -> Result<()> {
+    let output = create_order_with_htlc(args, generate_secret(), &SwapStore::from_env()).await?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Create the limit order and its HTLC from one secret and record the link
+///
+/// The HTLC is validated before the order is built and nothing is stored
+/// unless both succeed, so a failed run never leaves a half-linked pair.
+pub async fn create_order_with_htlc(
+    args: CreateOrderWithHtlcArgs,
+    secret: Secret,
+    store: &SwapStore,
+) -> Result<serde_json::Value> {
+    let secret_hash = hash_secret(&secret);
+
+    let htlc = Htlc::new(
+        args.htlc_sender.clone(),
+        args.recipient_address.clone(),
+        args.htlc_amount,
+        secret_hash,
+        std::time::Duration::from_secs(args.htlc_timeout),
+    )?;
+    let htlc_id = format!("htlc_{}", hex::encode(&secret_hash[..8]));
+
+    let order = create_order(CreateOrderArgs {
+        maker_asset: args.maker_asset.clone(),
+        taker_asset: args.taker_asset.clone(),
+        maker: args.maker.clone(),
+        making_amount: args.making_amount,
+        taking_amount: args.taking_amount,
+        htlc_secret_hash: hex::encode(secret_hash),
+        htlc_timeout: args.htlc_timeout,
+        chain_id: args.chain_id,
+        verifying_contract: args.verifying_contract.clone(),
+        receiver: args.receiver.clone(),
+        allowed_sender: args.allowed_sender.clone(),
+        recipient_chain: Some(args.recipient_chain.clone()),
+        recipient_address: Some(args.recipient_address.clone()),
+        salt: None,
+        sign: false,
+        submit: false,
+        relayer_url: None,
+        no_token_check: args.no_token_check,
+    })
+    .await?;
+
+    let order_hash = order["eip712_hash"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Order output is missing eip712_hash"))?
+        .to_string();
+    let swap_id = format!("swap_{}", hex::encode(&secret_hash[..8]));
+
+    store.save(StoredSwap {
+        swap_id: swap_id.clone(),
+        from_chain: "ethereum".to_string(),
+        to_chain: args.recipient_chain.clone(),
+        from_token: args.maker_asset,
+        to_token: args.taker_asset,
+        from_address: args.maker,
+        to_address: args.recipient_address,
+        src_amount: args.making_amount,
+        dst_amount: Some(args.taking_amount),
+        secret_hash: hex::encode(secret_hash),
+        order_hash: Some(order_hash),
+        htlc_id: Some(htlc_id.clone()),
+        timeout: args.htlc_timeout,
+        status: "pending".to_string(),
+        created_at: chrono::Utc::now().timestamp() as u64,
+        safety_deposit: 0.0,
+        split: None,
+        transactions: Vec::new(),
+    })?;
+
+    Ok(json!({
+        "swap_id": swap_id,
+        "secret": hex::encode(secret),
+        "secret_hash": hex::encode(secret_hash),
+        "order": order,
+        "htlc": {
+            "htlc_id": htlc_id,
+            "chain": args.recipient_chain,
+            "secret_hash": hex::encode(htlc.secret_hash()),
+            "sender": htlc.sender(),
+            "recipient": htlc.recipient(),
+            "amount": htlc.amount(),
+            "timeout_seconds": args.htlc_timeout,
+            "status": "Pending",
+        },
+    }))
+}
+
+/// Make sure both assets are ERC20 tokens so the order can actually be filled
+///
+/// The zero address stands for an asset on the other chain of a cross-chain
+/// order and is not checked.
+async fn check_order_assets<S: TokenSource + ?Sized>(
+    args: &CreateOrderArgs,
+    source: &S,
+) -> Result<()> {
+    for (name, asset) in [("maker", &args.maker_asset), ("taker", &args.taker_asset)] {
+        if asset
+            .parse::<Address>()
+            .is_ok_and(|address| address.is_zero())
+        {
+            continue;
+        }
+        check_token(source, asset).await.map_err(|e| {
+            anyhow!(
+                "{} asset check failed: {} (pass --no-token-check to skip)",
+                name,
+                e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Validate the order fields and build the order with its HTLC data embedded
+fn build_order(args: &CreateOrderArgs) -> Result<(Order, [u8; 32])> {
     // Validate addresses
     validate_address(&args.maker_asset)?;
     validate_address(&args.taker_asset)?;
@@ -119,15 +444,46 @@ pub async fn handle_create_order(args: CreateOrderArgs) -> Result<()> {
         .taking_amount(args.taking_amount)
         .interactions(&interactions_data);
 
-    if let Some(receiver) = args.receiver {
-        builder = builder.receiver(&receiver);
+    if let Some(receiver) = &args.receiver {
+        builder = builder.receiver(receiver);
+    }
+
+    if let Some(allowed_sender) = &args.allowed_sender {
+        builder = builder.allowed_sender(allowed_sender);
     }
 
-    if let Some(allowed_sender) = args.allowed_sender {
-        builder = builder.allowed_sender(&allowed_sender);
+    if let Some(salt) = &args.salt {
+        builder = builder.salt(parse_salt(salt)?);
+    }
+
+    Ok((builder.build()?, secret_hash))
+}
+
+/// Parse a hex salt as a big-endian uint256, left-padding values shorter than 32 bytes
+fn parse_salt(salt: &str) -> Result<[u8; 32]> {
+    let hex_salt = salt.trim_start_matches("0x");
+    let bytes = hex::decode(if hex_salt.len() % 2 == 1 {
+        format!("0{}", hex_salt)
+    } else {
+        hex_salt.to_string()
+    })
+    .map_err(|_| anyhow!("Invalid salt format: {}", salt))?;
+
+    if bytes.is_empty() || bytes.len() > 32 {
+        return Err(anyhow!("Salt must be between 1 and 32 bytes"));
     }
 
-    let order = builder.build()?;
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(padded)
+}
+
+async fn build_and_submit_order<S: OrderSubmitter>(
+    args: CreateOrderArgs,
+    private_key: Option<String>,
+    submitter: &S,
+) -> Result<serde_json::Value> {
+    let (order, secret_hash) = build_order(&args)?;
 
     // Create EIP-712 typed data
     let typed_data = order.to_eip712(args.chain_id, &args.verifying_contract);
@@ -155,7 +511,7 @@ pub async fn handle_create_order(args: CreateOrderArgs) -> Result<()> {
         },
         "eip712_hash": format!("0x{}", hex::encode(eip712_hash)),
         "htlc_info": {
-            "secret_hash": format!("0x{}", hex::encode(secret_hash_bytes)),
+            "secret_hash": format!("0x{}", hex::encode(secret_hash)),
             "timeout_seconds": args.htlc_timeout,
             "recipient_chain": args.recipient_chain,
             "recipient_address": args.recipient_address,
@@ -170,60 +526,44 @@ pub async fn handle_create_order(args: CreateOrderArgs) -> Result<()> {
             println!("Signing order...");
         }
 
-        // Get RPC URL and private key from environment
-        let rpc_url = std::env::var("ETHEREUM_RPC_URL")
-            .unwrap_or_else(|_| "https://sepolia.base.org".to_string());
-        let private_key = std::env::var("PRIVATE_KEY").ok();
-
         // First sign the order
         if let Some(pk) = &private_key {
             match crate::ethereum_tx::sign_order_hash(&eip712_hash, pk).await {
                 Ok(signature) => {
-                    // Add signature to output for API submission
-                    let mut signed_output = output.clone();
-                    signed_output["signature"] =
-                        json!(format!("0x{}", hex::encode(signature.to_vec())));
-
+                    let signature_hex = format!("0x{}", hex::encode(signature.to_vec()));
                     println!("Order signed successfully!");
-                    println!("Signature: 0x{}", hex::encode(signature.to_vec()));
+                    println!("Signature: {}", signature_hex);
 
                     // Update output with signature
-                    output["signature"] = json!(format!("0x{}", hex::encode(signature.to_vec())));
+                    output["signature"] = json!(signature_hex);
 
                     if args.submit {
-                        match crate::ethereum_tx::submit_limit_order(
-                            &signed_output,
-                            &rpc_url,
-                            args.chain_id,
-                            private_key.clone(),
-                        )
-                        .await
-                        {
-                            Ok(receipt) => {
-                                println!("Transaction submitted successfully!");
-                                println!("Transaction hash: {:?}", receipt.transaction_hash);
-                                println!("Block number: {:?}", receipt.block_number);
-                                println!("Gas used: {:?}", receipt.gas_used);
-
-                                // Display explorer URL based on chain ID
-                                let explorer_url = match args.chain_id {
-                                    84532 => format!(
-                                        "https://sepolia.basescan.org/tx/{:?}",
-                                        receipt.transaction_hash
-                                    ),
-                                    _ => format!(
-                                        "https://etherscan.io/tx/{:?}",
-                                        receipt.transaction_hash
-                                    ),
-                                };
-                                println!("View on explorer: {}", explorer_url);
+                        match submitter.submit(&output).await {
+                            Ok(submission) => {
+                                println!("Order submitted successfully!");
+                                if let Some(tx_hash) = &submission.transaction_hash {
+                                    println!("Transaction hash: {}", tx_hash);
+                                    println!(
+                                        "View on explorer: {}",
+                                        explorer_tx_url(args.chain_id, tx_hash)
+                                    );
+                                }
+
+                                output["submission"] = json!({
+                                    "order_hash": submission.order_hash,
+                                    "transaction_hash": submission.transaction_hash,
+                                    "block_number": submission.block_number,
+                                    "gas_used": submission.gas_used,
+                                });
                             }
                             Err(e) => {
-                                println!("Warning: Transaction submission failed: {}", e);
+                                println!("Warning: Order submission failed: {}", e);
                                 println!(
                                     "Order has been signed and prepared for manual submission."
                                 );
-                                println!("You can submit the signed order to 1inch API or another relayer.");
+                                println!(
+                                    "You can post the signed order to a relayer with --relayer-url."
+                                );
                             }
                         }
                     } else {
@@ -241,8 +581,14 @@ pub async fn handle_create_order(args: CreateOrderArgs) -> Result<()> {
         }
     }
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
-    Ok(())
+    Ok(output)
+}
+
+fn explorer_tx_url(chain_id: u64, tx_hash: &str) -> String {
+    match chain_id {
+        84532 => format!("https://sepolia.basescan.org/tx/{}", tx_hash),
+        _ => format!("https://etherscan.io/tx/{}", tx_hash),
+    }
 }
 
 fn validate_address(address: &str) -> Result<()> {
@@ -295,6 +641,132 @@ pub fn extract_htlc_info(maker_asset_data: &str) -> Result<(Vec<u8>, u64)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ethereum_tx::OrderSubmission;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    // Well-known development key, never funded on a real network
+    const TEST_PRIVATE_KEY: &str =
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[derive(Default)]
+    struct MockSubmitter {
+        submitted: Mutex<Vec<serde_json::Value>>,
+    }
+
+    #[async_trait]
+    impl OrderSubmitter for MockSubmitter {
+        async fn submit(&self, signed_order: &serde_json::Value) -> Result<OrderSubmission> {
+            self.submitted.lock().unwrap().push(signed_order.clone());
+            Ok(OrderSubmission {
+                order_hash: signed_order["eip712_hash"].as_str().unwrap().to_string(),
+                transaction_hash: Some("0xabc".to_string()),
+                block_number: Some(1),
+                gas_used: Some("21000".to_string()),
+            })
+        }
+    }
+
+    fn order_args(sign: bool, submit: bool) -> CreateOrderArgs {
+        CreateOrderArgs {
+            maker_asset: "0x4200000000000000000000000000000000000006".to_string(),
+            taker_asset: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
+            maker: "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950".to_string(),
+            making_amount: 1_000_000_000_000_000_000,
+            taking_amount: 2_000_000_000,
+            htlc_secret_hash: "0x".to_string() + &"ab".repeat(32),
+            htlc_timeout: 3600,
+            chain_id: 84532,
+            verifying_contract: "0x171C87724E720F2806fc29a010a62897B30fdb62".to_string(),
+            receiver: None,
+            allowed_sender: None,
+            recipient_chain: None,
+            recipient_address: None,
+            salt: None,
+            sign,
+            submit,
+            relayer_url: None,
+            no_token_check: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_order_not_submitted_without_submit_flag() {
+        let submitter = MockSubmitter::default();
+
+        let output = build_and_submit_order(
+            order_args(true, false),
+            Some(TEST_PRIVATE_KEY.to_string()),
+            &submitter,
+        )
+        .await
+        .unwrap();
+
+        assert!(output["signature"].is_string());
+        assert!(output.get("submission").is_none());
+        assert!(submitter.submitted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_order_submitted_with_submit_flag() {
+        let submitter = MockSubmitter::default();
+
+        let output = build_and_submit_order(
+            order_args(false, true),
+            Some(TEST_PRIVATE_KEY.to_string()),
+            &submitter,
+        )
+        .await
+        .unwrap();
+
+        let submitted = submitter.submitted.lock().unwrap();
+        assert_eq!(submitted.len(), 1);
+        assert!(submitted[0]["signature"].is_string());
+        assert_eq!(output["submission"]["order_hash"], output["eip712_hash"]);
+        assert_eq!(output["submission"]["transaction_hash"], "0xabc");
+    }
+
+    #[tokio::test]
+    async fn test_order_not_submitted_without_private_key() {
+        let submitter = MockSubmitter::default();
+
+        let output = build_and_submit_order(order_args(false, true), None, &submitter)
+            .await
+            .unwrap();
+
+        assert!(output.get("submission").is_none());
+        assert!(submitter.submitted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_same_salt_gives_same_order_hash() {
+        let submitter = MockSubmitter::default();
+        let salted = || CreateOrderArgs {
+            salt: Some("0x2a".to_string()),
+            ..order_args(false, false)
+        };
+
+        let first = build_and_submit_order(salted(), None, &submitter)
+            .await
+            .unwrap();
+        let second = build_and_submit_order(salted(), None, &submitter)
+            .await
+            .unwrap();
+        assert_eq!(first["eip712_hash"], second["eip712_hash"]);
+        assert_eq!(
+            first["order"]["salt"],
+            format!("0x{}{}", "00".repeat(31), "2a")
+        );
+
+        // Without a salt every order gets a fresh random one
+        let random = build_and_submit_order(order_args(false, false), None, &submitter)
+            .await
+            .unwrap();
+        assert_ne!(first["eip712_hash"], random["eip712_hash"]);
+
+        assert!(parse_salt("0xzz").is_err());
+        assert!(parse_salt(&"ab".repeat(33)).is_err());
+    }
 
     #[test]
     fn test_htlc_info_extraction() {
@@ -322,6 +794,71 @@ mod tests {
         assert_eq!(extracted_timeout, timeout);
     }
 
+    fn linked_order_args(htlc_amount: u64) -> CreateOrderWithHtlcArgs {
+        CreateOrderWithHtlcArgs {
+            maker_asset: "0x4200000000000000000000000000000000000006".to_string(),
+            taker_asset: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
+            maker: "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950".to_string(),
+            making_amount: 1_000_000_000_000_000_000,
+            taking_amount: 3_000_000_000,
+            htlc_timeout: 3600,
+            chain_id: 84532,
+            verifying_contract: "0x171C87724E720F2806fc29a010a62897B30fdb62".to_string(),
+            receiver: None,
+            allowed_sender: None,
+            recipient_chain: "near".to_string(),
+            recipient_address: "alice.testnet".to_string(),
+            htlc_sender: "resolver.testnet".to_string(),
+            htlc_amount,
+            no_token_check: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_with_htlc_persists_link() {
+        let path = std::env::temp_dir().join("fusion_cli_order_htlc_link.json");
+        std::fs::write(&path, "[]").unwrap();
+        let store = SwapStore::new(&path);
+        let secret = [7u8; 32];
+
+        let output = create_order_with_htlc(linked_order_args(1000), secret, &store)
+            .await
+            .unwrap();
+
+        let expected_hash = hex::encode(hash_secret(&secret));
+        assert_eq!(output["htlc"]["secret_hash"], expected_hash);
+        assert_eq!(
+            output["order"]["htlc_info"]["secret_hash"],
+            format!("0x{}", expected_hash)
+        );
+
+        let stored = store.get(output["swap_id"].as_str().unwrap()).unwrap();
+        assert_eq!(stored.secret_hash, expected_hash);
+        assert_eq!(
+            stored.order_hash.as_deref(),
+            output["order"]["eip712_hash"].as_str()
+        );
+        assert_eq!(
+            stored.htlc_id.as_deref(),
+            output["htlc"]["htlc_id"].as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_with_htlc_stores_nothing_when_htlc_is_invalid() {
+        let path = std::env::temp_dir().join("fusion_cli_order_htlc_invalid.json");
+        std::fs::write(&path, "[]").unwrap();
+        let store = SwapStore::new(&path);
+
+        // A zero-amount HTLC is rejected before the order is built
+        assert!(
+            create_order_with_htlc(linked_order_args(0), [8u8; 32], &store)
+                .await
+                .is_err()
+        );
+        assert!(store.list().unwrap().is_empty());
+    }
+
     #[test]
     fn test_validate_address() {
         // Valid addresses
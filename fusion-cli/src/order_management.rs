@@ -1,11 +1,17 @@
+use crate::ethereum_tx::{OnChainCanceller, OrderCanceller};
+use crate::exit_code::{cli_error, ExitCode};
 #[cfg(test)]
 use crate::storage::StoredOrder;
 use crate::storage::{OrderStatus, OrderStorage};
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use clap::Args;
+use fusion_core::order_matching_engine::{OrderMatchingEngine, OrderType, PendingOrder};
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 
 pub static ORDER_STORAGE: Lazy<OrderStorage> = Lazy::new(OrderStorage::new);
 
@@ -30,6 +36,44 @@ pub struct OrderbookArgs {
     pub chain: String,
 }
 
+#[derive(Args)]
+pub struct MatchArgs {
+    /// JSON file with an array of signed orders to match
+    #[arg(long)]
+    pub orders_file: PathBuf,
+    /// Only report matches with at least this profit in basis points
+    #[arg(long, default_value = "0")]
+    pub min_profit_bps: u16,
+}
+
+/// A signed order as read from an orders file
+#[derive(Debug, Deserialize)]
+pub struct MatchOrderEntry {
+    pub id: String,
+    pub maker: String,
+    pub chain: String,
+    /// Pair as BASE/QUOTE, e.g. NEAR/USDC
+    pub token_pair: String,
+    /// "buy" or "sell" of the base token
+    pub side: String,
+    /// Quote units per base unit
+    pub price: f64,
+    /// Base token amount in its smallest unit
+    pub amount: String,
+    pub signature: String,
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Unix time after which the order no longer matches
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Decimals of the base token on this chain, 18 when omitted
+    #[serde(default)]
+    pub base_decimals: Option<u8>,
+    /// Decimals of the quote token on this chain, 18 when omitted
+    #[serde(default)]
+    pub quote_decimals: Option<u8>,
+}
+
 pub async fn handle_order_status(args: StatusArgs) -> Result<()> {
     // Get order from storage
     match ORDER_STORAGE.get(&args.order_id) {
@@ -51,73 +95,97 @@ pub async fn handle_order_status(args: StatusArgs) -> Result<()> {
             println!("{}", serde_json::to_string_pretty(&output)?);
             Ok(())
         }
-        Err(_) => {
-            let output = json!({
+        Err(_) => Err(cli_error(
+            ExitCode::NotFound,
+            json!({
                 "error": "Order not found",
                 "order_id": args.order_id
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
+            }),
+        )),
     }
 }
 
 pub async fn handle_order_cancel(args: CancelArgs) -> Result<()> {
-    // Get order from storage
-    let order = match ORDER_STORAGE.get(&args.order_id) {
+    let rpc_url = std::env::var("ETHEREUM_RPC_URL")
+        .unwrap_or_else(|_| "https://sepolia.base.org".to_string());
+    let canceller = OnChainCanceller::new(rpc_url, std::env::var("PRIVATE_KEY").ok());
+
+    let output = cancel_order(&ORDER_STORAGE, &args.order_id, &canceller).await?;
+    if let Some(error) = output["error"].as_str() {
+        let code = if error == "Order not found" {
+            ExitCode::NotFound
+        } else {
+            ExitCode::UserError
+        };
+        return Err(cli_error(code, output));
+    }
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Cancel an order, only touching the chain if it was actually submitted
+///
+/// Orders that were signed but never submitted cannot be filled by anyone
+/// else yet, so discarding them locally is enough.
+pub async fn cancel_order<C: OrderCanceller + ?Sized>(
+    storage: &OrderStorage,
+    order_id: &str,
+    canceller: &C,
+) -> Result<serde_json::Value> {
+    let order = match storage.get(order_id) {
         Ok(order) => order,
         Err(_) => {
-            let output = json!({
+            return Ok(json!({
                 "error": "Order not found",
-                "order_id": args.order_id
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            return Ok(());
+                "order_id": order_id
+            }));
         }
     };
 
     // Check if order can be cancelled
     match order.status {
-        OrderStatus::Active => {
-            // Update status to cancelled
-            ORDER_STORAGE.update_status(&args.order_id, OrderStatus::Cancelled)?;
+        OrderStatus::Active => match &order.submission {
+            None => {
+                storage.remove(order_id)?;
 
-            let output = json!({
-                "order_id": args.order_id,
-                "status": "Cancelled",
-                "message": "Order has been successfully cancelled",
-                "cancelled_at": chrono::Utc::now().to_rfc3339()
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
-        OrderStatus::Filled => {
-            let output = json!({
-                "error": "Cannot cancel filled order",
-                "order_id": args.order_id,
-                "status": "Filled"
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
-        OrderStatus::Cancelled => {
-            let output = json!({
-                "error": "Order already cancelled",
-                "order_id": args.order_id,
-                "status": "Cancelled"
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
-        OrderStatus::Expired => {
-            let output = json!({
-                "error": "Cannot cancel expired order",
-                "order_id": args.order_id,
-                "status": "Expired"
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-            Ok(())
-        }
+                Ok(json!({
+                    "order_id": order_id,
+                    "status": "Cancelled",
+                    "method": "local",
+                    "message": "Order was never submitted and has been discarded locally",
+                    "cancelled_at": chrono::Utc::now().to_rfc3339()
+                }))
+            }
+            Some(submission) => {
+                let transaction_hash = canceller.cancel(&order).await?;
+                storage.update_status(order_id, OrderStatus::Cancelled)?;
+
+                Ok(json!({
+                    "order_id": order_id,
+                    "status": "Cancelled",
+                    "method": "on_chain",
+                    "message": "Order has been cancelled on the limit order protocol",
+                    "verifying_contract": submission.verifying_contract,
+                    "transaction_hash": transaction_hash,
+                    "cancelled_at": chrono::Utc::now().to_rfc3339()
+                }))
+            }
+        },
+        OrderStatus::Filled => Ok(json!({
+            "error": "Cannot cancel filled order",
+            "order_id": order_id,
+            "status": "Filled"
+        })),
+        OrderStatus::Cancelled => Ok(json!({
+            "error": "Order already cancelled",
+            "order_id": order_id,
+            "status": "Cancelled"
+        })),
+        OrderStatus::Expired => Ok(json!({
+            "error": "Cannot cancel expired order",
+            "order_id": order_id,
+            "status": "Expired"
+        })),
     }
 }
 
@@ -166,6 +234,111 @@ pub async fn handle_orderbook(args: OrderbookArgs) -> Result<()> {
     Ok(())
 }
 
+pub async fn handle_order_match(args: MatchArgs) -> Result<()> {
+    let output = match_orders_file(&args.orders_file, args.min_profit_bps)?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Load signed orders from a file and report the profitable cross-chain matches
+pub fn match_orders_file(path: &Path, min_profit_bps: u16) -> Result<serde_json::Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read orders file {}", path.display()))?;
+    let entries: Vec<MatchOrderEntry> = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid orders file {}", path.display()))?;
+
+    let mut engine = OrderMatchingEngine::new(min_profit_bps);
+    let mut makers = HashMap::new();
+    let mut token_pairs = BTreeSet::new();
+
+    for entry in entries.iter() {
+        if entry.signature.trim().is_empty() {
+            return Err(anyhow!("Order {} is not signed", entry.id));
+        }
+        if makers
+            .insert(entry.id.clone(), entry.maker.clone())
+            .is_some()
+        {
+            return Err(anyhow!("Duplicate order id {}", entry.id));
+        }
+
+        let (base, quote) = entry.token_pair.split_once('/').ok_or_else(|| {
+            anyhow!(
+                "Invalid token pair {}, expected BASE/QUOTE",
+                entry.token_pair
+            )
+        })?;
+        if let Some(decimals) = entry.base_decimals {
+            engine.set_token_decimals(&entry.chain, base, decimals);
+        }
+        if let Some(decimals) = entry.quote_decimals {
+            engine.set_token_decimals(&entry.chain, quote, decimals);
+        }
+
+        let order_type = match entry.side.to_lowercase().as_str() {
+            "buy" => OrderType::Buy,
+            "sell" => OrderType::Sell,
+            other => {
+                return Err(anyhow!(
+                    "Invalid side {} for order {}, expected buy or sell",
+                    other,
+                    entry.id
+                ))
+            }
+        };
+        let amount = entry
+            .amount
+            .parse::<u128>()
+            .with_context(|| format!("Invalid amount for order {}", entry.id))?;
+
+        token_pairs.insert(entry.token_pair.clone());
+        engine.add_order(PendingOrder {
+            id: entry.id.clone(),
+            chain_id: entry.chain.clone(),
+            token_pair: entry.token_pair.clone(),
+            order_type,
+            price: entry.price,
+            amount,
+            timestamp: entry.timestamp,
+            expires_at: entry.expires_at,
+        })?;
+    }
+
+    let mut matches: Vec<_> = token_pairs
+        .iter()
+        .flat_map(|pair| {
+            engine
+                .find_matches(pair)
+                .into_iter()
+                .map(move |order_match| (pair, order_match))
+        })
+        .collect();
+    matches.sort_by(|(_, a), (_, b)| b.profit_bps.cmp(&a.profit_bps));
+
+    let matches_json: Vec<serde_json::Value> = matches
+        .iter()
+        .map(|(pair, order_match)| {
+            json!({
+                "token_pair": pair,
+                "buy_order_id": order_match.buy_order_id,
+                "buy_maker": makers[&order_match.buy_order_id],
+                "sell_order_id": order_match.sell_order_id,
+                "sell_maker": makers[&order_match.sell_order_id],
+                "match_price": order_match.match_price,
+                "match_amount": order_match.match_amount.to_string(),
+                "profit_bps": order_match.profit_bps,
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "orders_loaded": entries.len(),
+        "min_profit_bps": min_profit_bps,
+        "matches": matches_json,
+        "total_matches": matches_json.len(),
+    }))
+}
+
 fn calculate_price(making_amount: u128, taking_amount: u128) -> String {
     if taking_amount == 0 {
         return "0".to_string();
@@ -179,6 +352,7 @@ fn calculate_price(making_amount: u128, taking_amount: u128) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::OrderSubmissionRecord;
     use std::time::SystemTime;
 
     #[tokio::test]
@@ -196,6 +370,7 @@ mod tests {
             created_at: SystemTime::now(),
             chain: "ethereum".to_string(),
             order_hash: "0xdeadbeef".to_string(),
+            submission: None,
         };
         ORDER_STORAGE.store(order_id.to_string(), order).unwrap();
 
@@ -209,6 +384,86 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    /// Records which orders were cancelled on-chain
+    #[derive(Default)]
+    struct RecordingCanceller {
+        cancelled: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl OrderCanceller for RecordingCanceller {
+        async fn cancel(&self, order: &StoredOrder) -> Result<String> {
+            self.cancelled
+                .lock()
+                .unwrap()
+                .push(order.order_hash.clone());
+            Ok("0xcancel".to_string())
+        }
+    }
+
+    fn active_order(order_id: &str, submission: Option<OrderSubmissionRecord>) -> StoredOrder {
+        StoredOrder {
+            id: order_id.to_string(),
+            maker: "0x1234567890123456789012345678901234567890".to_string(),
+            maker_asset: "0xA000000000000000000000000000000000000001".to_string(),
+            taker_asset: "0xB000000000000000000000000000000000000002".to_string(),
+            making_amount: 1000000000000000000u128,
+            taking_amount: 3000000000u128,
+            status: OrderStatus::Active,
+            created_at: SystemTime::now(),
+            chain: "ethereum".to_string(),
+            order_hash: format!("0x{}", "ab".repeat(32)),
+            submission,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_unsubmitted_order_is_local() {
+        let storage = OrderStorage::new();
+        let canceller = RecordingCanceller::default();
+        storage
+            .store("local".to_string(), active_order("local", None))
+            .unwrap();
+
+        let output = cancel_order(&storage, "local", &canceller).await.unwrap();
+
+        assert_eq!(output["method"], "local");
+        assert!(storage.get("local").is_err());
+        assert!(canceller.cancelled.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_submitted_order_goes_on_chain() {
+        let storage = OrderStorage::new();
+        let canceller = RecordingCanceller::default();
+        let submission = OrderSubmissionRecord {
+            transaction_hash: "0xsubmit".to_string(),
+            chain_id: 84532,
+            verifying_contract: "0x171C87724E720F2806fc29a010a62897B30fdb62".to_string(),
+        };
+        storage
+            .store(
+                "submitted".to_string(),
+                active_order("submitted", Some(submission)),
+            )
+            .unwrap();
+
+        let output = cancel_order(&storage, "submitted", &canceller)
+            .await
+            .unwrap();
+
+        assert_eq!(output["method"], "on_chain");
+        assert_eq!(output["transaction_hash"], "0xcancel");
+        assert_eq!(
+            storage.get("submitted").unwrap().status,
+            OrderStatus::Cancelled
+        );
+        assert_eq!(
+            *canceller.cancelled.lock().unwrap(),
+            vec![format!("0x{}", "ab".repeat(32))]
+        );
+    }
+
     #[tokio::test]
     async fn test_order_cancel_active_order() {
         // Setup
@@ -224,6 +479,7 @@ mod tests {
             created_at: SystemTime::now(),
             chain: "ethereum".to_string(),
             order_hash: "0xdeadbeef".to_string(),
+            submission: None,
         };
         ORDER_STORAGE.store(order_id.to_string(), order).unwrap();
 
@@ -233,10 +489,9 @@ mod tests {
         };
         let result = handle_order_cancel(args).await;
 
-        // Verify
+        // Verify: the order was never submitted, so it is discarded locally
         assert!(result.is_ok());
-        let updated_order = ORDER_STORAGE.get(order_id).unwrap();
-        assert_eq!(updated_order.status, OrderStatus::Cancelled);
+        assert!(ORDER_STORAGE.get(order_id).is_err());
     }
 
     #[tokio::test]
@@ -254,6 +509,7 @@ mod tests {
                 created_at: SystemTime::now(),
                 chain: "ethereum".to_string(),
                 order_hash: format!("0xdeadbeef{}", i),
+                submission: None,
             };
             ORDER_STORAGE.store(order.id.clone(), order).unwrap();
         }
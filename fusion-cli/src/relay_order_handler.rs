@@ -1,8 +1,15 @@
+use crate::near_client::{NearClientConfig, NEAR_CLIENTS};
+use crate::storage::{StoredSwap, SwapStore};
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use clap::Args;
-use fusion_core::htlc::SecretHash;
+use fusion_core::chains::ethereum::order_extractor::OrderExtractor;
+use fusion_core::chains::near::NearHtlcConnector;
+use fusion_core::htlc::{hash_secret, SecretHash};
 use fusion_core::order::Order;
-use serde_json::json;
+use fusion_core::price_oracle::{MockPriceOracle, PriceConverter, PriceOracle};
+use serde_json::{json, Value};
+use std::sync::Arc;
 
 #[derive(Args)]
 pub struct RelayOrderArgs {
@@ -29,20 +36,78 @@ pub struct RelayOrderArgs {
     /// NEAR network (testnet/mainnet)
     #[arg(long, default_value = "testnet")]
     pub near_network: String,
+
+    /// NEAR account that can claim the HTLC (defaults to --near-account)
+    #[arg(long)]
+    pub recipient: Option<String>,
+
+    /// Destination HTLC timeout, e.g. 90s, 30m or 3h; bare numbers are seconds (default: 3600)
+    #[arg(long, default_value = "3600", value_parser = crate::duration_arg::parse_duration_secs)]
+    pub timeout: u64,
+}
+
+/// Reads the source order being relayed
+#[async_trait]
+pub trait SourceOrderReader: Send + Sync {
+    /// Fails if the order does not exist or can no longer be filled
+    async fn fetch_order(&self, order_hash: &str) -> Result<Order>;
+}
+
+#[async_trait]
+impl SourceOrderReader for OrderExtractor {
+    async fn fetch_order(&self, order_hash: &str) -> Result<Order> {
+        self.extract_order_by_hash(order_hash).await
+    }
+}
+
+/// Creates the HTLC on the destination chain
+#[async_trait]
+pub trait DestinationHtlcCreator: Send + Sync {
+    async fn create_htlc(
+        &self,
+        amount: u128,
+        secret_hash: SecretHash,
+        timeout_seconds: u64,
+        recipient: &str,
+    ) -> Result<String>;
+}
+
+#[async_trait]
+impl DestinationHtlcCreator for NearHtlcConnector {
+    async fn create_htlc(
+        &self,
+        amount: u128,
+        secret_hash: SecretHash,
+        timeout_seconds: u64,
+        recipient: &str,
+    ) -> Result<String> {
+        NearHtlcConnector::create_htlc(self, amount, secret_hash, timeout_seconds, recipient).await
+    }
 }
 
 pub async fn handle_relay_order(args: RelayOrderArgs) -> Result<()> {
     // Validate inputs
     validate_inputs(&args)?;
 
-    // Step 1: Extract order information from EVM
-    let order_info = extract_order_from_evm(&args).await?;
-
-    // Step 2: Create HTLC on NEAR
-    let htlc_result = create_htlc_on_near(&args, &order_info).await?;
+    let rpc_url = args
+        .evm_rpc
+        .as_ref()
+        .ok_or_else(|| anyhow!("EVM RPC URL must be provided via --evm-rpc flag"))?;
+    let limit_order_address = "0x171C87724E720F2806fc29a010a62897B30fdb62"; // Base Sepolia deployment
+    let extractor = OrderExtractor::new(rpc_url, limit_order_address)?;
 
-    // Step 3: Display results
-    display_relay_results(&args, &order_info, &htlc_result)?;
+    let connector = near_connector(&args)?;
+    let converter = PriceConverter::new(MockPriceOracle::new());
+
+    let output = relay_order(
+        &args,
+        &extractor,
+        connector.as_ref(),
+        &converter,
+        &SwapStore::from_env(),
+    )
+    .await?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
 
     Ok(())
 }
@@ -72,71 +137,24 @@ fn validate_inputs(args: &RelayOrderArgs) -> Result<()> {
         return Err(anyhow!("NEAR network must be 'testnet' or 'mainnet'"));
     }
 
+    if args.timeout == 0 {
+        return Err(anyhow!("Timeout must be positive"));
+    }
+
     Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct OrderInfo {
-    order: Order,
+/// Parameters of the destination HTLC derived from the source order
+#[derive(Debug, Clone, PartialEq)]
+struct DestinationParams {
+    recipient: String,
+    amount: u128,
     secret_hash: SecretHash,
     timeout: u64,
-    recipient_chain: String,
-    recipient_address: String,
 }
 
-struct HTLCResult {
-    htlc_id: String,
-    transaction_hash: String,
-    near_explorer_url: String,
-}
-
-async fn extract_order_from_evm(args: &RelayOrderArgs) -> Result<OrderInfo> {
-    use fusion_core::chains::ethereum::order_extractor::OrderExtractor;
-    use sha2::{Digest, Sha256};
-
-    // Get RPC URL and contract address
-    let rpc_url = args
-        .evm_rpc
-        .as_ref()
-        .ok_or_else(|| anyhow!("EVM RPC URL must be provided via --evm-rpc flag"))?;
-    let limit_order_address = "0x171C87724E720F2806fc29a010a62897B30fdb62"; // Base Sepolia deployment
-
-    // Extract the order from EVM
-    let extractor = OrderExtractor::new(rpc_url, limit_order_address)?;
-    let order = extractor.extract_order_by_hash(&args.order_hash).await?;
-
-    // Calculate secret hash from provided secret
-    let secret_bytes = hex::decode(args.htlc_secret.trim_start_matches("0x"))
-        .map_err(|_| anyhow!("Invalid HTLC secret format"))?;
-    let mut hasher = Sha256::new();
-    hasher.update(&secret_bytes);
-    let hash_result = hasher.finalize();
-    let mut secret_hash = [0u8; 32];
-    secret_hash.copy_from_slice(&hash_result);
-
-    Ok(OrderInfo {
-        order,
-        secret_hash,
-        timeout: 3600, // Default 1 hour
-        recipient_chain: "near".to_string(),
-        recipient_address: args
-            .near_account
-            .clone()
-            .unwrap_or_else(|| "relay.testnet".to_string()),
-    })
-}
-
-async fn create_htlc_on_near(args: &RelayOrderArgs, order_info: &OrderInfo) -> Result<HTLCResult> {
-    use fusion_core::chains::near::NearHtlcConnector;
-
-    // Parse the secret for validation
-    let secret_bytes = hex::decode(args.htlc_secret.trim_start_matches("0x"))
-        .map_err(|_| anyhow!("Invalid secret format"))?;
-
-    if secret_bytes.len() != 32 {
-        return Err(anyhow!("Secret must be exactly 32 bytes"));
-    }
-
+/// Shared connector for the configured account, connected on first use
+fn near_connector(args: &RelayOrderArgs) -> Result<Arc<NearHtlcConnector>> {
     // Get NEAR configuration
     let near_rpc = match args.near_network.as_str() {
         "mainnet" => "https://rpc.mainnet.near.org",
@@ -158,84 +176,294 @@ async fn create_htlc_on_near(args: &RelayOrderArgs, order_info: &OrderInfo) -> R
     let private_key = std::env::var("NEAR_PRIVATE_KEY")
         .map_err(|_| anyhow!("NEAR_PRIVATE_KEY environment variable must be set"))?;
 
-    // Create NEAR connector
-    let connector = NearHtlcConnector::new(near_rpc)
-        .with_contract(htlc_contract)
-        .with_account(near_account, &private_key)?;
+    let config = NearClientConfig {
+        rpc_url: near_rpc.to_string(),
+        contract_id: htlc_contract.to_string(),
+        account_id: near_account.clone(),
+    };
+    NEAR_CLIENTS.connector(&config, &private_key)
+}
+
+/// Relay a verified EVM order to NEAR by creating the matching HTLC
+///
+/// The NEAR amount is the order's taking amount priced through `converter`,
+/// and the relayed pair is saved as a swap so later commands can find it.
+pub async fn relay_order<O: PriceOracle>(
+    args: &RelayOrderArgs,
+    source: &dyn SourceOrderReader,
+    destination: &dyn DestinationHtlcCreator,
+    converter: &PriceConverter<O>,
+    store: &SwapStore,
+) -> Result<Value> {
+    validate_inputs(args)?;
+
+    // Step 1: Verify the source order exists and is still fillable
+    let order = source.fetch_order(&args.order_hash).await?;
 
-    // Calculate amount from order (convert to NEAR)
-    // EVM tokens typically have 18 decimals, NEAR has 24 decimals
-    let evm_amount = order_info.order.taking_amount();
-    let amount = convert_evm_to_near_amount(evm_amount);
+    // Step 2: Derive the destination HTLC parameters
+    let params = destination_params(args, &order, converter).await?;
 
-    // Create HTLC on NEAR
-    let htlc_id = connector
+    // Step 3: Create HTLC on NEAR
+    let htlc_id = destination
         .create_htlc(
-            amount,
-            order_info.secret_hash,
-            order_info.timeout,
-            &order_info.recipient_address,
+            params.amount,
+            params.secret_hash,
+            params.timeout,
+            &params.recipient,
         )
         .await?;
 
-    // Generate explorer URL
-    let transaction_hash = format!("0x{}", hex::encode(&order_info.secret_hash[..16]));
-    let near_explorer_url = format!(
-        "https://explorer.{}.near.org/transactions/{}",
-        args.near_network, transaction_hash
-    );
-
-    Ok(HTLCResult {
-        htlc_id,
-        transaction_hash,
-        near_explorer_url,
-    })
-}
-
-fn display_relay_results(
-    args: &RelayOrderArgs,
-    order_info: &OrderInfo,
-    htlc_result: &HTLCResult,
-) -> Result<()> {
-    let output = json!({
+    // Step 4: Link the order and the HTLC
+    let swap_id = format!("swap_{}", hex::encode(&params.secret_hash[..8]));
+    store.save(StoredSwap {
+        swap_id: swap_id.clone(),
+        from_chain: "ethereum".to_string(),
+        to_chain: args.to_chain.clone(),
+        from_token: order.maker_asset().to_string(),
+        to_token: "NEAR".to_string(),
+        from_address: order.maker().to_string(),
+        to_address: params.recipient.clone(),
+        src_amount: order.making_amount(),
+        dst_amount: Some(params.amount),
+        secret_hash: hex::encode(params.secret_hash),
+        order_hash: Some(args.order_hash.clone()),
+        htlc_id: Some(htlc_id.clone()),
+        timeout: params.timeout,
+        status: "pending".to_string(),
+        created_at: chrono::Utc::now().timestamp() as u64,
+        safety_deposit: 0.0,
+        split: None,
+        transactions: Vec::new(),
+    })?;
+
+    Ok(json!({
         "status": "success",
+        "swap_id": swap_id,
         "relay_details": {
             "from_chain": "ethereum",
             "to_chain": args.to_chain,
             "order_hash": args.order_hash,
-            "recipient_chain": order_info.recipient_chain,
         },
         "order": {
-            "maker": order_info.order.maker(),
-            "maker_asset": order_info.order.maker_asset(),
-            "taker_asset": order_info.order.taker_asset(),
-            "making_amount": order_info.order.making_amount(),
-            "taking_amount": order_info.order.taking_amount(),
+            "maker": order.maker(),
+            "maker_asset": order.maker_asset(),
+            "taker_asset": order.taker_asset(),
+            "making_amount": order.making_amount().to_string(),
+            "taking_amount": order.taking_amount().to_string(),
         },
         "htlc_info": {
-            "htlc_id": htlc_result.htlc_id,
-            "secret_hash": format!("0x{}", hex::encode(order_info.secret_hash)),
-            "timeout_seconds": order_info.timeout,
-            "recipient": order_info.recipient_address,
-        },
-        "transactions": {
-            "near_htlc_creation": htlc_result.transaction_hash,
-            "explorer_url": htlc_result.near_explorer_url,
+            "htlc_id": htlc_id,
+            "amount": params.amount.to_string(),
+            "secret_hash": format!("0x{}", hex::encode(params.secret_hash)),
+            "timeout_seconds": params.timeout,
+            "recipient": params.recipient,
         },
         "next_steps": [
             "Monitor the order execution on Ethereum",
             "Once the order is filled, the secret will be revealed",
             "Use the secret to claim funds from the NEAR HTLC",
         ]
-    });
+    }))
+}
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
-    Ok(())
+async fn destination_params<O: PriceOracle>(
+    args: &RelayOrderArgs,
+    order: &Order,
+    converter: &PriceConverter<O>,
+) -> Result<DestinationParams> {
+    let recipient = args
+        .recipient
+        .clone()
+        .or_else(|| args.near_account.clone())
+        .ok_or_else(|| {
+            anyhow!("HTLC recipient must be provided via --recipient or --near-account")
+        })?;
+
+    let (symbol, decimals) = evm_token_symbol(order.taker_asset())
+        .ok_or_else(|| anyhow!("No price feed for taker asset {}", order.taker_asset()))?;
+    let amount = converter
+        .convert_amount(order.taking_amount(), symbol, decimals, "NEAR", 24)
+        .await?;
+    if amount == 0 {
+        return Err(anyhow!("Order taking amount converts to zero NEAR"));
+    }
+
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&hex::decode(args.htlc_secret.trim_start_matches("0x"))?);
+
+    Ok(DestinationParams {
+        recipient,
+        amount,
+        secret_hash: hash_secret(&secret),
+        timeout: args.timeout,
+    })
+}
+
+/// Price feed symbol and decimals of a known EVM token
+fn evm_token_symbol(address: &str) -> Option<(&'static str, u8)> {
+    match address.to_lowercase().as_str() {
+        "0x0000000000000000000000000000000000000000" => Some(("ETH", 18)),
+        "0x4200000000000000000000000000000000000006" => Some(("WETH", 18)),
+        "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913"
+        | "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48" => Some(("USDC", 6)),
+        _ => None,
+    }
 }
 
-/// Convert EVM token amount (18 decimals) to NEAR token amount (24 decimals)
-fn convert_evm_to_near_amount(evm_amount: u128) -> u128 {
-    // EVM: 18 decimals, NEAR: 24 decimals
-    // To convert, multiply by 10^6 (1,000,000)
-    evm_amount.saturating_mul(1_000_000)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fusion_core::order::OrderBuilder;
+    use std::sync::Mutex;
+
+    const ORDER_HASH: &str = "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+    const SECRET: &str = "0x9876543210fedcba9876543210fedcba9876543210fedcba9876543210fedcba";
+
+    struct MockOrderReader {
+        order: Option<Order>,
+    }
+
+    #[async_trait]
+    impl SourceOrderReader for MockOrderReader {
+        async fn fetch_order(&self, _order_hash: &str) -> Result<Order> {
+            self.order
+                .clone()
+                .ok_or_else(|| anyhow!("Order is fully filled or cancelled"))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingHtlcCreator {
+        created: Mutex<Vec<DestinationParams>>,
+    }
+
+    #[async_trait]
+    impl DestinationHtlcCreator for RecordingHtlcCreator {
+        async fn create_htlc(
+            &self,
+            amount: u128,
+            secret_hash: SecretHash,
+            timeout_seconds: u64,
+            recipient: &str,
+        ) -> Result<String> {
+            self.created.lock().unwrap().push(DestinationParams {
+                recipient: recipient.to_string(),
+                amount,
+                secret_hash,
+                timeout: timeout_seconds,
+            });
+            Ok("fusion_7".to_string())
+        }
+    }
+
+    fn relay_args() -> RelayOrderArgs {
+        RelayOrderArgs {
+            order_hash: ORDER_HASH.to_string(),
+            to_chain: "near".to_string(),
+            htlc_secret: SECRET.to_string(),
+            near_account: Some("resolver.testnet".to_string()),
+            evm_rpc: None,
+            near_network: "testnet".to_string(),
+            recipient: Some("alice.testnet".to_string()),
+            timeout: 1800,
+        }
+    }
+
+    fn weth_order() -> Order {
+        OrderBuilder::new()
+            .maker("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+            .maker_asset("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+            .taker_asset("0x4200000000000000000000000000000000000006")
+            .making_amount(2_000_000) // 2 USDC
+            .taking_amount(1_000_000_000_000_000) // 0.001 WETH
+            .build()
+            .unwrap()
+    }
+
+    fn temp_store(name: &str) -> SwapStore {
+        let path = std::env::temp_dir().join(format!("fusion_cli_relay_{}.json", name));
+        std::fs::write(&path, "[]").unwrap();
+        SwapStore::new(&path)
+    }
+
+    #[tokio::test]
+    async fn test_relay_creates_destination_htlc() {
+        let args = relay_args();
+        let reader = MockOrderReader {
+            order: Some(weth_order()),
+        };
+        let creator = RecordingHtlcCreator::default();
+        let store = temp_store("creates");
+
+        let output = relay_order(
+            &args,
+            &reader,
+            &creator,
+            &PriceConverter::new(MockPriceOracle::new()),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        // 0.001 WETH at 2000 / 5 = 0.4 NEAR
+        let mut secret = [0u8; 32];
+        secret.copy_from_slice(&hex::decode(SECRET.trim_start_matches("0x")).unwrap());
+        let created = creator.created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].recipient, "alice.testnet");
+        assert_eq!(created[0].secret_hash, hash_secret(&secret));
+        assert_eq!(created[0].timeout, 1800);
+        let near = created[0].amount as f64 / 1e24;
+        assert!((near - 0.4).abs() < 1e-9);
+
+        let swap = store.get(output["swap_id"].as_str().unwrap()).unwrap();
+        assert_eq!(swap.htlc_id.as_deref(), Some("fusion_7"));
+        assert_eq!(swap.order_hash.as_deref(), Some(ORDER_HASH));
+        assert_eq!(swap.dst_amount, Some(created[0].amount));
+        assert_eq!(swap.secret_hash, hex::encode(created[0].secret_hash));
+    }
+
+    #[tokio::test]
+    async fn test_relay_requires_fillable_source_order() {
+        let creator = RecordingHtlcCreator::default();
+
+        let err = relay_order(
+            &relay_args(),
+            &MockOrderReader { order: None },
+            &creator,
+            &PriceConverter::new(MockPriceOracle::new()),
+            &temp_store("missing"),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("fully filled or cancelled"));
+        assert!(creator.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_relay_rejects_unpriced_taker_asset() {
+        let order = OrderBuilder::new()
+            .maker("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+            .maker_asset("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+            .taker_asset("0x1111111111111111111111111111111111111111")
+            .making_amount(1)
+            .taking_amount(1)
+            .build()
+            .unwrap();
+        let creator = RecordingHtlcCreator::default();
+
+        let err = relay_order(
+            &relay_args(),
+            &MockOrderReader { order: Some(order) },
+            &creator,
+            &PriceConverter::new(MockPriceOracle::new()),
+            &temp_store("unpriced"),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("No price feed"));
+        assert!(creator.created.lock().unwrap().is_empty());
+    }
 }
@@ -1,9 +1,12 @@
 use anyhow::{anyhow, Result};
 use fusion_core::htlc::{HtlcState, SecretHash};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredHtlc {
@@ -17,52 +20,213 @@ pub struct StoredHtlc {
     pub secret: Option<Vec<u8>>,
 }
 
+/// HTLC records in a SQLite database, in memory or in a file shared between CLI invocations
 #[derive(Clone)]
 pub struct HtlcStorage {
-    htlcs: Arc<Mutex<HashMap<String, StoredHtlc>>>,
+    /// Opened on first use so that constructing the storage cannot fail
+    conn: Arc<Mutex<Option<Connection>>>,
+    /// Database file; `None` keeps records for the life of the process only
+    path: Option<PathBuf>,
 }
 
 impl HtlcStorage {
     pub fn new() -> Self {
         Self {
-            htlcs: Arc::new(Mutex::new(HashMap::new())),
+            conn: Arc::new(Mutex::new(None)),
+            path: None,
+        }
+    }
+
+    /// File-backed storage, created with owner-only permissions if missing
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..Self::new()
+        }
+    }
+
+    /// Use `FUSION_STORAGE_PATH` if set, otherwise in-memory storage
+    pub fn from_env() -> Self {
+        match std::env::var_os("FUSION_STORAGE_PATH") {
+            Some(path) => Self::open(path),
+            None => Self::new(),
         }
     }
 
     pub fn store(&self, htlc_id: String, stored_htlc: StoredHtlc) -> Result<()> {
-        let mut storage = self
-            .htlcs
-            .lock()
-            .map_err(|e| anyhow!("Lock error: {}", e))?;
-        storage.insert(htlc_id, stored_htlc);
-        Ok(())
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO htlcs
+                 (htlc_id, sender, recipient, amount, secret_hash, timeout, created_at, state, secret)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    htlc_id,
+                    stored_htlc.sender,
+                    stored_htlc.recipient,
+                    stored_htlc.amount,
+                    &stored_htlc.secret_hash[..],
+                    duration_to_millis(stored_htlc.timeout)?,
+                    duration_to_millis(
+                        stored_htlc
+                            .created_at
+                            .duration_since(UNIX_EPOCH)
+                            .map_err(|e| anyhow!("Invalid HTLC creation time: {}", e))?
+                    )?,
+                    state_to_str(&stored_htlc.state),
+                    stored_htlc.secret,
+                ],
+            )?;
+            Ok(())
+        })
     }
 
     pub fn get(&self, htlc_id: &str) -> Result<StoredHtlc> {
-        let storage = self
-            .htlcs
-            .lock()
-            .map_err(|e| anyhow!("Lock error: {}", e))?;
-        storage
-            .get(htlc_id)
-            .cloned()
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT sender, recipient, amount, secret_hash, timeout, created_at, state, secret
+                 FROM htlcs WHERE htlc_id = ?1",
+                params![htlc_id],
+                row_to_htlc,
+            )
+            .optional()?
             .ok_or_else(|| anyhow!("HTLC not found: {}", htlc_id))
+        })
     }
 
     pub fn update_state(&self, htlc_id: &str, state: HtlcState) -> Result<()> {
-        let mut storage = self
-            .htlcs
-            .lock()
-            .map_err(|e| anyhow!("Lock error: {}", e))?;
-        if let Some(stored) = storage.get_mut(htlc_id) {
-            stored.state = state;
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE htlcs SET state = ?1 WHERE htlc_id = ?2",
+                params![state_to_str(&state), htlc_id],
+            )?;
+            if updated == 0 {
+                return Err(anyhow!("HTLC not found: {}", htlc_id));
+            }
             Ok(())
-        } else {
-            Err(anyhow!("HTLC not found: {}", htlc_id))
+        })
+    }
+
+    /// All stored HTLCs with their IDs, ordered by ID
+    pub fn list(&self) -> Result<Vec<(String, StoredHtlc)>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT sender, recipient, amount, secret_hash, timeout, created_at, state, secret, htlc_id
+                 FROM htlcs ORDER BY htlc_id",
+            )?;
+            let htlcs = stmt
+                .query_map([], |row| Ok((row.get(8)?, row_to_htlc(row)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(htlcs)
+        })
+    }
+
+    /// Run `f` on the database connection, opening it on first use
+    fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        if conn.is_none() {
+            *conn = Some(open_connection(self.path.as_deref())?);
         }
+        f(conn.as_ref().expect("connection opened above"))
     }
 }
 
+fn open_connection(path: Option<&Path>) -> Result<Connection> {
+    let conn = match path {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| anyhow!("Failed to create HTLC storage directory: {}", e))?;
+            }
+            // Stored secrets make the database sensitive; SQLite gives its
+            // journal files the same permissions as the database file
+            create_private_file(path)
+                .map_err(|e| anyhow!("Failed to create HTLC storage: {}", e))?;
+            let conn = Connection::open(path)
+                .map_err(|e| anyhow!("Failed to open HTLC storage: {}", e))?;
+            // Let concurrent CLI invocations wait for each other's writes
+            conn.busy_timeout(Duration::from_secs(5))?;
+            conn
+        }
+        None => Connection::open_in_memory()?,
+    };
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS htlcs (
+            htlc_id TEXT PRIMARY KEY,
+            sender TEXT NOT NULL,
+            recipient TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            secret_hash BLOB NOT NULL,
+            timeout INTEGER NOT NULL, -- milliseconds
+            created_at INTEGER NOT NULL, -- milliseconds since the Unix epoch
+            state TEXT NOT NULL,
+            secret BLOB
+        )",
+    )?;
+    Ok(conn)
+}
+
+#[cfg(unix)]
+fn create_private_file(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .mode(0o600)
+        .open(path)?;
+    // Tighten files left behind by older versions
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn create_private_file(path: &Path) -> std::io::Result<()> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map(|_| ())
+}
+
+fn duration_to_millis(duration: Duration) -> Result<i64> {
+    i64::try_from(duration.as_millis()).map_err(|_| anyhow!("Duration too large to store"))
+}
+
+fn state_to_str(state: &HtlcState) -> &'static str {
+    match state {
+        HtlcState::Pending => "Pending",
+        HtlcState::Claimed => "Claimed",
+        HtlcState::Refunded => "Refunded",
+    }
+}
+
+/// Decode a row selected as `sender, recipient, amount, secret_hash, timeout, created_at, state, secret`
+fn row_to_htlc(row: &Row<'_>) -> rusqlite::Result<StoredHtlc> {
+    let secret_hash: Vec<u8> = row.get(3)?;
+    let state: String = row.get(6)?;
+    Ok(StoredHtlc {
+        sender: row.get(0)?,
+        recipient: row.get(1)?,
+        amount: row.get(2)?,
+        secret_hash: secret_hash
+            .try_into()
+            .map_err(|_| invalid_column(3, Type::Blob, "secret hash is not 32 bytes"))?,
+        timeout: Duration::from_millis(row.get(4)?),
+        created_at: UNIX_EPOCH + Duration::from_millis(row.get(5)?),
+        state: match state.as_str() {
+            "Pending" => HtlcState::Pending,
+            "Claimed" => HtlcState::Claimed,
+            "Refunded" => HtlcState::Refunded,
+            _ => return Err(invalid_column(6, Type::Text, "unknown HTLC state")),
+        },
+        secret: row.get(7)?,
+    })
+}
+
+fn invalid_column(index: usize, ty: Type, message: &str) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(index, ty, message.into())
+}
+
 impl Default for HtlcStorage {
     fn default() -> Self {
         Self::new()
@@ -90,6 +254,16 @@ pub struct StoredOrder {
     pub created_at: SystemTime,
     pub chain: String,
     pub order_hash: String,
+    /// Set once the order has been sent to the limit order protocol
+    pub submission: Option<OrderSubmissionRecord>,
+}
+
+/// Where a submitted order lives on-chain, needed to cancel it later
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OrderSubmissionRecord {
+    pub transaction_hash: String,
+    pub chain_id: u64,
+    pub verifying_contract: String,
 }
 
 #[derive(Clone)]
@@ -138,6 +312,16 @@ impl OrderStorage {
         }
     }
 
+    pub fn remove(&self, order_id: &str) -> Result<StoredOrder> {
+        let mut storage = self
+            .orders
+            .lock()
+            .map_err(|e| anyhow!("Lock error: {}", e))?;
+        storage
+            .remove(order_id)
+            .ok_or_else(|| anyhow!("Order not found: {}", order_id))
+    }
+
     pub fn get_orders_by_chain(&self, chain: &str) -> Result<Vec<StoredOrder>> {
         let storage = self
             .orders
@@ -159,3 +343,189 @@ impl Default for OrderStorage {
         Self::new()
     }
 }
+
+/// Record of a swap started with `fusion-cli swap swap`
+///
+/// Amounts are kept in the smallest unit of each token so that later commands
+/// can rebuild escrow parameters without re-querying prices.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoredSwap {
+    pub swap_id: String,
+    pub from_chain: String,
+    pub to_chain: String,
+    pub from_token: String,
+    pub to_token: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub src_amount: u128,
+    /// Missing when no price was available for the pair
+    pub dst_amount: Option<u128>,
+    pub secret_hash: String,
+    pub order_hash: Option<String>,
+    pub htlc_id: Option<String>,
+    pub timeout: u64,
+    pub status: String,
+    /// Unix timestamp in seconds
+    pub created_at: u64,
+    /// Per-escrow safety deposit in each leg's native token
+    #[serde(default)]
+    pub safety_deposit: f64,
+    /// Set when the swap is one part of a `--split` swap
+    #[serde(default)]
+    pub split: Option<SplitPart>,
+    /// Transactions sent when the swap was started
+    #[serde(default)]
+    pub transactions: Vec<TransactionInfo>,
+}
+
+/// Transaction sent while executing a swap
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionInfo {
+    pub chain: String,
+    pub tx_hash: String,
+    pub explorer_url: String,
+    pub description: String,
+}
+
+/// Position of a swap within a split swap
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SplitPart {
+    pub split_id: String,
+    pub index: u32,
+    /// Number of parts the swap was split into
+    pub parts: u32,
+}
+
+/// File-backed swap records shared between CLI invocations
+#[derive(Debug, Clone)]
+pub struct SwapStore {
+    path: PathBuf,
+}
+
+impl SwapStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Use `FUSION_SWAP_STORE`, falling back to `~/.fusion-cli/swaps.json`
+    pub fn from_env() -> Self {
+        let path = std::env::var("FUSION_SWAP_STORE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join(".fusion-cli").join("swaps.json")
+            });
+        Self::new(path)
+    }
+
+    pub fn list(&self) -> Result<Vec<StoredSwap>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| anyhow!("Failed to read swap store: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse swap store: {}", e))
+    }
+
+    pub fn get(&self, swap_id: &str) -> Result<StoredSwap> {
+        self.list()?
+            .into_iter()
+            .find(|swap| swap.swap_id == swap_id)
+            .ok_or_else(|| anyhow!("Swap not found: {}", swap_id))
+    }
+
+    /// Insert a new record or replace the one with the same swap ID
+    pub fn save(&self, swap: StoredSwap) -> Result<()> {
+        let mut swaps = self.list()?;
+        match swaps.iter_mut().find(|s| s.swap_id == swap.swap_id) {
+            Some(existing) => *existing = swap,
+            None => swaps.push(swap),
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| anyhow!("Failed to create swap store directory: {}", e))?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&swaps)?)
+            .map_err(|e| anyhow!("Failed to write swap store: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_htlc(state: HtlcState) -> StoredHtlc {
+        StoredHtlc {
+            sender: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: 1000,
+            secret_hash: [3u8; 32],
+            timeout: Duration::from_secs(3600),
+            created_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            state,
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn test_file_storage_persists_across_instances() {
+        let path =
+            std::env::temp_dir().join(format!("fusion_htlc_storage_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut htlc = stored_htlc(HtlcState::Pending);
+        htlc.secret = Some(vec![7u8; 32]);
+        HtlcStorage::open(&path)
+            .store("htlc_1".to_string(), htlc)
+            .unwrap();
+        HtlcStorage::open(&path)
+            .update_state("htlc_1", HtlcState::Claimed)
+            .unwrap();
+
+        let reopened = HtlcStorage::open(&path).get("htlc_1").unwrap();
+        assert_eq!(reopened.state, HtlcState::Claimed);
+        assert_eq!(reopened.secret_hash, [3u8; 32]);
+        assert_eq!(
+            reopened.created_at,
+            stored_htlc(HtlcState::Pending).created_at
+        );
+        assert_eq!(reopened.secret, Some(vec![7u8; 32]));
+        assert_eq!(reopened.timeout, Duration::from_secs(3600));
+        assert!(HtlcStorage::open(&path).get("missing").is_err());
+        assert!(HtlcStorage::open(&path)
+            .update_state("missing", HtlcState::Refunded)
+            .is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // In-memory storage does not see the file
+        assert!(HtlcStorage::new().get("htlc_1").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_list_is_ordered_by_id() {
+        let storage = HtlcStorage::new();
+        storage
+            .store("htlc_b".to_string(), stored_htlc(HtlcState::Pending))
+            .unwrap();
+        storage
+            .store("htlc_a".to_string(), stored_htlc(HtlcState::Refunded))
+            .unwrap();
+
+        let ids: Vec<String> = storage
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, vec!["htlc_a".to_string(), "htlc_b".to_string()]);
+        assert_eq!(storage.get("htlc_a").unwrap().state, HtlcState::Refunded);
+    }
+}
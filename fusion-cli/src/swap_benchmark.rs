@@ -0,0 +1,236 @@
+use crate::swap_handler::{
+    convert_wei_to_amount, native_token, DEFAULT_EVM_GAS_PRICE, EVM_CLAIM_GAS, NEAR_GAS_PRICE,
+    NEAR_HTLC_CALL_GAS,
+};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use ethers::providers::{Http, Provider};
+use fusion_core::chains::ethereum::balance::{BalanceSource, ESCROW_CREATION_GAS};
+use fusion_core::chains::SwapDirection;
+use fusion_core::execution_path_optimizer::ExecutionPathOptimizer;
+use rand::Rng;
+use serde::Serialize;
+use serde_json::json;
+
+/// Upper bound for `--samples`, each live sample is one RPC call
+const MAX_BENCHMARK_SAMPLES: u32 = 1000;
+
+/// Block time assumed for chains the optimizer does not know
+const DEFAULT_BLOCK_TIME: u64 = 12;
+
+/// A transaction waits for between 1 and this many blocks before inclusion
+const MAX_INCLUSION_BLOCKS: u64 = 3;
+
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// Source chain (ethereum or near)
+    #[arg(long)]
+    pub from_chain: String,
+
+    /// Destination chain (ethereum or near)
+    #[arg(long)]
+    pub to_chain: String,
+
+    /// Number of simulated swaps to sample
+    #[arg(long, default_value = "10")]
+    pub samples: u32,
+
+    /// EVM RPC endpoint to read the live gas price from on every sample
+    #[arg(long)]
+    pub evm_rpc: Option<String>,
+}
+
+/// Min, median and max of the sampled values
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Distribution {
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    fn from_samples(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        };
+        Self {
+            min: sorted[0],
+            median,
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// One chain's part of the swap: locking the escrow and claiming it
+struct Leg {
+    name: &'static str,
+    chain: String,
+    lock_gas: u64,
+    claim_gas: u64,
+    block_time: u64,
+    costs: Vec<f64>,
+    times: Vec<f64>,
+}
+
+impl Leg {
+    fn new(
+        name: &'static str,
+        chain: &str,
+        is_evm: bool,
+        optimizer: &ExecutionPathOptimizer,
+    ) -> Self {
+        let (lock_gas, claim_gas) = if is_evm {
+            (ESCROW_CREATION_GAS, EVM_CLAIM_GAS)
+        } else {
+            (NEAR_HTLC_CALL_GAS, NEAR_HTLC_CALL_GAS)
+        };
+        Self {
+            name,
+            chain: chain.to_string(),
+            lock_gas,
+            claim_gas,
+            block_time: optimizer.block_time(chain).unwrap_or(DEFAULT_BLOCK_TIME),
+            costs: Vec::new(),
+            times: Vec::new(),
+        }
+    }
+
+    /// Record one swap with the given gas price, waiting a random number of blocks per transaction
+    fn sample<R: Rng>(&mut self, gas_price: u128, rng: &mut R) {
+        let gas = u128::from(self.lock_gas + self.claim_gas);
+        self.costs.push(convert_wei_to_amount(
+            gas * gas_price,
+            native_token(&self.chain),
+        ));
+
+        let blocks =
+            rng.gen_range(1..=MAX_INCLUSION_BLOCKS) + rng.gen_range(1..=MAX_INCLUSION_BLOCKS);
+        self.times.push((blocks * self.block_time) as f64);
+    }
+}
+
+pub async fn handle_swap_benchmark(args: BenchmarkArgs) -> Result<()> {
+    let provider = args
+        .evm_rpc
+        .as_deref()
+        .map(Provider::<Http>::try_from)
+        .transpose()?;
+
+    let output = run_benchmark(&args, &mut rand::thread_rng(), || async {
+        match &provider {
+            Some(provider) => Ok::<_, anyhow::Error>(provider.gas_price().await?.low_u128()),
+            None => Ok(u128::from(DEFAULT_EVM_GAS_PRICE)),
+        }
+    })
+    .await?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Sample `args.samples` swaps and report the cost and time distribution of each leg
+///
+/// `evm_gas_price` is called once per sample. Without a live RPC its price is
+/// scaled by a random factor between 0.5 and 2 to simulate fee swings.
+pub async fn run_benchmark<R, F, Fut>(
+    args: &BenchmarkArgs,
+    rng: &mut R,
+    mut evm_gas_price: F,
+) -> Result<serde_json::Value>
+where
+    R: Rng,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<u128>>,
+{
+    if args.samples == 0 || args.samples > MAX_BENCHMARK_SAMPLES {
+        return Err(anyhow!(
+            "--samples must be between 1 and {}",
+            MAX_BENCHMARK_SAMPLES
+        ));
+    }
+
+    let direction = SwapDirection::parse(&args.from_chain, &args.to_chain)?;
+    let optimizer = ExecutionPathOptimizer::new();
+    let mut legs = [
+        Leg::new(
+            "source",
+            &args.from_chain,
+            direction.from.is_evm(),
+            &optimizer,
+        ),
+        Leg::new(
+            "destination",
+            &args.to_chain,
+            direction.to.is_evm(),
+            &optimizer,
+        ),
+    ];
+
+    let live = args.evm_rpc.is_some();
+    let mut totals = Vec::new();
+    for _ in 0..args.samples {
+        let mut evm_price = evm_gas_price().await?;
+        if !live {
+            evm_price = (evm_price as f64 * rng.gen_range(0.5..=2.0)) as u128;
+        }
+
+        let mut total = 0.0;
+        for (leg, is_evm) in legs
+            .iter_mut()
+            .zip([direction.from.is_evm(), direction.to.is_evm()])
+        {
+            let gas_price = if is_evm { evm_price } else { NEAR_GAS_PRICE };
+            leg.sample(gas_price, rng);
+            total += leg.times[leg.times.len() - 1];
+        }
+        totals.push(total);
+    }
+
+    let legs: Vec<serde_json::Value> = legs
+        .iter()
+        .map(|leg| {
+            json!({
+                "leg": leg.name,
+                "chain": leg.chain,
+                "cost_token": native_token(&leg.chain),
+                "cost": Distribution::from_samples(&leg.costs),
+                "time_seconds": Distribution::from_samples(&leg.times),
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "from_chain": args.from_chain,
+        "to_chain": args.to_chain,
+        "samples": args.samples,
+        "gas_price_source": if live { "live" } else { "simulated" },
+        "legs": legs,
+        "total_time_seconds": Distribution::from_samples(&totals),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_median() {
+        let odd = Distribution::from_samples(&[3.0, 1.0, 2.0]);
+        assert_eq!(
+            odd,
+            Distribution {
+                min: 1.0,
+                median: 2.0,
+                max: 3.0
+            }
+        );
+        assert_eq!(
+            Distribution::from_samples(&[4.0, 1.0, 2.0, 3.0]).median,
+            2.5
+        );
+    }
+}
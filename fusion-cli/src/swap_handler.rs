@@ -1,11 +1,33 @@
+use crate::config_handler::{check_token_limit, load_config, resolve_limit_order_protocol};
+use crate::exit_code::{cli_error, ExitCode};
+use crate::htlc_monitor::{
+    parse_estimated_time, HTLCMonitor, HtlcStatusSource, PollCycleCache, RefundGate,
+    StdoutObserver, StuckWatchdog, SwapRunOutcome,
+};
+use crate::storage::{SplitPart, StoredSwap, SwapStore, TransactionInfo};
 use anyhow::{anyhow, Result};
 use clap::{Args, Subcommand};
+use ethers::providers::{Http, Provider};
+use ethers::types::{Address, H256, U256};
 use fusion_core::{
-    htlc::{generate_secret, hash_secret, SecretHash},
-    price_oracle::{MockPriceOracle, PriceConverter},
+    chains::ethereum::balance::{check_funding, BalanceSource, ESCROW_CREATION_GAS},
+    chains::ethereum::immutables::{
+        address_of_escrow_dst, address_of_escrow_src, EscrowFunding, Immutables,
+        SafetyDepositAsset, Timelocks,
+    },
+    chains::ethereum::{EthereumConnector, UnsignedTransaction},
+    chains::{Chain, SwapDirection},
+    execution_path_optimizer::ExecutionPathOptimizer,
+    htlc::{generate_secret, hash_secret, secret_eq, Secret, SecretHash},
+    price_oracle::{
+        ChainlinkPriceOracle, MockPriceOracle, PriceConverter, PriceError, PriceOracle,
+        DEFAULT_MAX_PRICE_AGE,
+    },
+    secret_manager::SecretManager,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::time::Duration;
 
 #[derive(Subcommand)]
 pub enum SwapCommands {
@@ -14,9 +36,47 @@ pub enum SwapCommands {
     Execute(Box<SwapArgs>),
     /// Execute batch swaps from configuration file
     Batch(BatchSwapArgs),
+    /// Print the escrow Immutables and predicted address for a swap leg
+    Immutables(ImmutablesArgs),
+    /// List stored swaps
+    List(ListSwapsArgs),
+    /// Check that a finished swap settled atomically
+    Verify(VerifySwapArgs),
+    /// Continue a split swap, claiming only the parts not yet filled
+    Resume(ResumeSwapArgs),
+    /// Report both legs of every pending swap, reading each chain in one batch
+    StatusAll(StatusAllArgs),
+    /// Show which steps of a stored swap have completed
+    Status(SwapStatusArgs),
+    /// Sample the estimated cost and time of each leg for a chain pair
+    Benchmark(crate::swap_benchmark::BenchmarkArgs),
 }
 
-#[derive(Args)]
+/// A stage may take this many times the plan's estimate before it counts as stuck
+const STUCK_BUDGET_MULTIPLIER: f64 = 2.0;
+
+/// Stage estimate used when the plan's estimate is unknown
+const DEFAULT_STAGE_ESTIMATE: Duration = Duration::from_secs(15 * 60);
+
+/// Upper bound for `--split` so a typo cannot spawn hundreds of escrows
+const MAX_SPLIT_TRANCHES: u32 = 20;
+
+/// Protocol fee charged on the source amount
+const PROTOCOL_FEE_RATE: f64 = 0.001;
+
+/// EVM gas price assumed when no `--evm-rpc` is given (1 gwei)
+pub(crate) const DEFAULT_EVM_GAS_PRICE: u64 = 1_000_000_000;
+
+/// Gas limit of the EVM HTLC claim sent by the monitor
+pub(crate) const EVM_CLAIM_GAS: u64 = 150_000;
+
+/// Minimum NEAR gas price in yoctoNEAR
+pub(crate) const NEAR_GAS_PRICE: u128 = 100_000_000;
+
+/// Gas attached to HTLC create and claim calls on NEAR (30 Tgas)
+pub(crate) const NEAR_HTLC_CALL_GAS: u64 = 30_000_000_000_000;
+
+#[derive(Args, Clone)]
 pub struct SwapArgs {
     /// Source chain (ethereum, near)
     #[arg(long)]
@@ -35,31 +95,55 @@ pub struct SwapArgs {
     pub to_token: String,
 
     /// Amount to swap (in human-readable format)
-    #[arg(long)]
+    #[arg(long, default_value = "0", hide_default_value = true)]
     pub amount: f64,
 
+    /// Amount to swap in USD, converted to the source token at the oracle price (instead of --amount)
+    #[arg(long)]
+    pub amount_usd: Option<f64>,
+
     /// Source address
     #[arg(long)]
     pub from_address: String,
 
-    /// Target address
-    #[arg(long)]
-    pub to_address: String,
+    /// Target address; repeat as ADDRESS:WEIGHT to split a swap to NEAR across recipients
+    #[arg(long = "to-address", required = true, value_parser = parse_weighted_recipient)]
+    pub to_addresses: Vec<WeightedRecipient>,
 
     /// Slippage tolerance in percentage (default: 1.0%)
     #[arg(long, default_value = "1.0")]
     pub slippage: f64,
 
-    /// HTLC timeout in seconds (default: 3600)
-    #[arg(long, default_value = "3600")]
+    /// Slippage tolerance when pricing the order at creation (default: --slippage)
+    #[arg(long)]
+    pub slippage_create: Option<f64>,
+
+    /// Slippage tolerance for the destination amount at claim time; may not exceed the create tolerance (default: --slippage)
+    #[arg(long)]
+    pub slippage_claim: Option<f64>,
+
+    /// HTLC timeout, e.g. 90s, 30m or 3h; bare numbers are seconds (default: 3600)
+    #[arg(long, default_value = "3600", value_parser = crate::duration_arg::parse_duration_secs)]
     pub timeout: u64,
 
     /// Automatically claim funds when available
     #[arg(long)]
     pub auto_claim: bool,
 
-    /// Monitoring interval in seconds (default: 30)
-    #[arg(long, default_value = "30")]
+    /// Once monitoring concludes, verify both legs settled atomically and fail if not (requires --auto-claim)
+    #[arg(long)]
+    pub verify_after: bool,
+
+    /// Refund the source HTLC when its timelock passes with neither leg claimed (requires --auto-claim)
+    #[arg(long)]
+    pub auto_refund: bool,
+
+    /// How long before an automatic refund a RefundPending notice is emitted, e.g. 5m (default: 300 seconds)
+    #[arg(long, default_value = "300", value_parser = crate::duration_arg::parse_duration_secs)]
+    pub refund_notice: u64,
+
+    /// Monitoring interval for the slower chain, e.g. 30s or 2m; faster chains are polled proportionally more often (default: 30 seconds)
+    #[arg(long, default_value = "30", value_parser = crate::duration_arg::parse_duration_secs)]
     pub monitor_interval: u64,
 
     /// Dry run - simulate the swap without executing
@@ -70,9 +154,9 @@ pub struct SwapArgs {
     #[arg(long, default_value = "84532")]
     pub chain_id: u64,
 
-    /// Limit Order Protocol address
-    #[arg(long, default_value = "0x171C87724E720F2806fc29a010a62897B30fdb62")]
-    pub limit_order_protocol: String,
+    /// Limit Order Protocol address (default: the one configured for --chain-id)
+    #[arg(long)]
+    pub limit_order_protocol: Option<String>,
 
     /// EVM RPC endpoint
     #[arg(long)]
@@ -81,6 +165,69 @@ pub struct SwapArgs {
     /// NEAR network (testnet/mainnet)
     #[arg(long, default_value = "testnet")]
     pub near_network: String,
+
+    /// Pin a conversion rate instead of querying the oracle (FROM:TO=RATE, repeatable)
+    #[arg(long = "fixed-rate")]
+    pub fixed_rates: Vec<String>,
+
+    /// Reject oracle rates that stray too far from this reference (FROM:TO=RATE, repeatable)
+    #[arg(long = "reference-rate")]
+    pub reference_rates: Vec<String>,
+
+    /// Allowed deviation from a `--reference-rate` in percentage (default: 5.0%)
+    #[arg(long, default_value = "5.0")]
+    pub max_rate_deviation: f64,
+
+    /// Where quotes come from (mock, chainlink)
+    #[arg(long, default_value = "mock")]
+    pub price_source: String,
+
+    /// Chainlink USD feed of a token for --price-source chainlink (TOKEN=ADDRESS, repeatable)
+    #[arg(long = "price-feed")]
+    pub price_feeds: Vec<String>,
+
+    /// Reject oracle prices older than this, e.g. 30m or 1h (default: 1 hour)
+    #[arg(long, default_value = "3600", value_parser = crate::duration_arg::parse_duration_secs)]
+    pub max_price_age: u64,
+
+    /// Split the amount into N tranches, each with its own secret and escrow pair
+    #[arg(long, default_value = "1")]
+    pub split: u32,
+
+    /// Opt-in: derive the secret from the BIP39 mnemonic in FUSION_SECRET_MNEMONIC at
+    /// this index instead of generating a random one. Anyone holding the mnemonic can
+    /// recompute every derived secret.
+    #[arg(long)]
+    pub secret_index: Option<u32>,
+
+    /// Safety deposit posted with each escrow, in the native token of that leg's chain (ETH or NEAR)
+    #[arg(long, default_value = "0")]
+    pub safety_deposit: f64,
+
+    /// NEAR account that receives the NEAR escrow's safety deposit (default: whoever resolves it)
+    #[arg(long)]
+    pub safety_deposit_beneficiary: Option<String>,
+
+    /// Print the unsigned transactions for offline signing instead of sending them (requires --secret-index)
+    #[arg(long)]
+    pub output_unsigned: bool,
+
+    /// EVM escrow factory the source escrow is created through, for --output-unsigned from ethereum
+    #[arg(long)]
+    pub escrow_factory: Option<String>,
+
+    /// EVM address that can claim the source escrow, for --output-unsigned from ethereum
+    #[arg(long)]
+    pub escrow_recipient: Option<String>,
+
+    /// JSON configuration file whose token_limits bound the swap amount
+    #[arg(long)]
+    pub config: Option<String>,
+
+    /// Exact source amount in the token's smallest units, overriding `amount`
+    /// where escrows are created (set for split tranches and resumed parts)
+    #[arg(skip)]
+    pub amount_units: Option<u128>,
 }
 
 #[derive(Args)]
@@ -94,6 +241,112 @@ pub struct BatchSwapArgs {
     pub dry_run: bool,
 }
 
+#[derive(Args)]
+pub struct ImmutablesArgs {
+    /// Swap ID printed when the swap was started
+    #[arg(long)]
+    pub swap_id: String,
+
+    /// Escrow leg (src, dst)
+    #[arg(long)]
+    pub leg: String,
+
+    /// Resolver address acting as the escrow taker
+    #[arg(long)]
+    pub taker: String,
+
+    /// Escrow factory that deploys the escrow clone
+    #[arg(long)]
+    pub escrow_factory: String,
+
+    /// Escrow implementation cloned by the factory for this leg
+    #[arg(long)]
+    pub escrow_implementation: String,
+
+    /// Safety deposit in wei (default: the one recorded for the swap)
+    #[arg(long)]
+    pub safety_deposit: Option<u128>,
+
+    /// ERC20 used for the safety deposit (default: native token)
+    #[arg(long)]
+    pub safety_deposit_token: Option<String>,
+
+    /// Deployment timestamp used in the timelocks (default: now)
+    #[arg(long)]
+    pub deployed_at: Option<u32>,
+}
+
+#[derive(Args)]
+pub struct ListSwapsArgs {
+    /// Only show swaps in this state (pending, completed, failed)
+    #[arg(long)]
+    pub state: Option<String>,
+
+    /// Only show swaps from this chain
+    #[arg(long)]
+    pub from_chain: Option<String>,
+
+    /// Only show swaps to this chain
+    #[arg(long)]
+    pub to_chain: Option<String>,
+
+    /// Sort key (created, amount)
+    #[arg(long, default_value = "created")]
+    pub sort: String,
+}
+
+#[derive(Args)]
+pub struct VerifySwapArgs {
+    /// Swap ID printed when the swap was started
+    #[arg(long)]
+    pub swap_id: String,
+
+    /// EVM RPC endpoint
+    #[arg(long)]
+    pub evm_rpc: Option<String>,
+
+    /// NEAR network (testnet/mainnet)
+    #[arg(long, default_value = "testnet")]
+    pub near_network: String,
+}
+
+#[derive(Args)]
+pub struct SwapStatusArgs {
+    /// Swap ID printed when the swap was started
+    #[arg(long)]
+    pub swap_id: String,
+}
+
+#[derive(Args)]
+pub struct StatusAllArgs {
+    /// EVM RPC endpoint
+    #[arg(long)]
+    pub evm_rpc: Option<String>,
+
+    /// NEAR network (testnet/mainnet)
+    #[arg(long, default_value = "testnet")]
+    pub near_network: String,
+}
+
+#[derive(Args)]
+pub struct ResumeSwapArgs {
+    /// Split ID printed when the split swap was started
+    #[arg(long)]
+    pub split_id: String,
+
+    /// EVM RPC endpoint
+    #[arg(long)]
+    pub evm_rpc: Option<String>,
+
+    /// NEAR network (testnet/mainnet)
+    #[arg(long, default_value = "testnet")]
+    pub near_network: String,
+
+    /// Monitoring interval for the slower chain, e.g. 30s or 2m (default: 30 seconds)
+    #[arg(long, default_value = "30", value_parser = crate::duration_arg::parse_duration_secs)]
+    pub monitor_interval: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SwapConfig {
     pub from_chain: String,
@@ -121,16 +374,70 @@ fn default_timeout() -> u64 {
 struct SwapPlan {
     steps: Vec<SwapStep>,
     estimated_time: String,
+    /// Minimum output after slippage, or "unavailable" if the oracle failed in dry-run
+    estimated_output: String,
+    /// Minimum output per source token, after slippage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effective_rate: Option<f64>,
+    /// Oracle mid-price: target tokens per source token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oracle_rate: Option<f64>,
+    /// How far the effective rate falls below the oracle rate, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price_impact_pct: Option<f64>,
+    /// Present only when the amount was given with `--amount-usd`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount_conversion: Option<AmountConversion>,
+    /// Present only when the swap is split with `--split`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tranches: Vec<TranchePlan>,
     fees: SwapFees,
     validation_status: ValidationStatus,
 }
 
+#[derive(Debug, Serialize)]
+struct AmountConversion {
+    amount_usd: f64,
+    /// USD price of one source token used for the conversion
+    token_price_usd: f64,
+    amount: f64,
+    token: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TranchePlan {
+    index: u32,
+    amount: f64,
+    /// Amount in the smallest unit of the source token
+    amount_units: String,
+}
+
 #[derive(Debug, Serialize)]
 struct SwapStep {
     step_number: u8,
     action: String,
     description: String,
     estimated_time: String,
+    /// Absent for off-chain steps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_cost: Option<StepCost>,
+}
+
+/// Estimated cost of one on-chain step
+#[derive(Debug, Serialize)]
+struct StepCost {
+    chain: String,
+    gas: u64,
+    /// Price per unit of gas in wei or yoctoNEAR
+    gas_price: String,
+    /// Gas cost in the chain's native token
+    network_fee: f64,
+    network_fee_usd: Option<f64>,
+    /// Protocol fee charged on this step
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol_fee_usd: Option<f64>,
+    /// Network and protocol fee together, `None` if the native token is unpriced
+    cost_usd: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -138,6 +445,11 @@ struct SwapFees {
     network_fees: String,
     protocol_fees: String,
     estimated_total: String,
+    /// Sum of the steps' `cost_usd`, `None` if any step is unpriced
+    total_usd: Option<f64>,
+    /// Refunded to whoever completes or cancels each escrow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    safety_deposit: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -155,24 +467,68 @@ struct SwapResult {
     secret_hash: String,
     htlc_id: Option<String>,
     order_hash: Option<String>,
+    /// Smallest target amount the auto-claim accepts, from the oracle quote less slippage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_received: Option<u128>,
     transactions: Vec<TransactionInfo>,
     next_steps: Vec<String>,
 }
 
+/// Outcome of a swap executed as several independent tranches
 #[derive(Debug, Serialize)]
-struct TransactionInfo {
-    chain: String,
-    tx_hash: String,
-    explorer_url: String,
-    description: String,
+struct SplitSwapResult {
+    split_id: String,
+    /// pending, completed, partially_completed or failed
+    status: String,
+    tranches: Vec<TrancheResult>,
 }
 
-pub async fn handle_swap(args: Box<SwapArgs>) -> Result<()> {
+#[derive(Debug, Serialize)]
+struct TrancheResult {
+    index: u32,
+    amount: f64,
+    amount_units: String,
+    /// pending, completed or failed
+    status: String,
+    swap: Option<SwapResult>,
+    error: Option<String>,
+}
+
+impl SplitSwapResult {
+    fn refresh_status(&mut self) {
+        let failed = self
+            .tranches
+            .iter()
+            .filter(|t| t.status == "failed")
+            .count();
+        let completed = self
+            .tranches
+            .iter()
+            .filter(|t| t.status == "completed")
+            .count();
+
+        self.status = if failed == self.tranches.len() {
+            "failed"
+        } else if failed > 0 {
+            "partially_completed"
+        } else if completed == self.tranches.len() {
+            "completed"
+        } else {
+            "pending"
+        }
+        .to_string();
+    }
+}
+
+pub async fn handle_swap(mut args: Box<SwapArgs>) -> Result<()> {
+    let converter = price_converter(&args)?;
+    resolve_usd_amount(&mut args, &converter).await?;
+
     // Validate inputs
     validate_swap_inputs(&args)?;
 
     // Create swap plan
-    let plan = create_swap_plan(&args).await?;
+    let plan = create_swap_plan_with(&args, &converter).await?;
 
     if args.dry_run {
         println!(
@@ -185,12 +541,29 @@ pub async fn handle_swap(args: Box<SwapArgs>) -> Result<()> {
         return Ok(());
     }
 
+    if args.output_unsigned {
+        return print_unsigned_swap(&args).await;
+    }
+
+    if args.split > 1 {
+        return execute_split_swap(&args, &plan).await;
+    }
+
     // Execute swap
     let result = execute_swap(&args, &plan).await?;
 
     // Start monitoring if auto-claim is enabled
     if args.auto_claim {
-        monitor_and_claim(&args, &result).await?;
+        monitor_and_claim(&args, &result, stuck_budget(&plan)).await?;
+    }
+
+    if args.verify_after {
+        let swap = SwapStore::from_env().get(&result.swap_id)?;
+        check_swap_atomicity(
+            &swap,
+            &swap_monitor(args.evm_rpc.clone(), &args.near_network),
+        )
+        .await?;
     }
 
     Ok(())
@@ -221,17 +594,38 @@ pub async fn handle_batch_swap(args: BatchSwapArgs) -> Result<()> {
             from_token: swap_config.from_token.clone(),
             to_token: swap_config.to_token.clone(),
             amount: swap_config.amount,
+            amount_usd: None,
             from_address: swap_config.from_address.clone(),
-            to_address: swap_config.to_address.clone(),
+            to_addresses: vec![WeightedRecipient::sole(&swap_config.to_address)],
             slippage: swap_config.slippage,
+            slippage_create: None,
+            slippage_claim: None,
             timeout: swap_config.timeout,
             auto_claim: false,
+            verify_after: false,
+            auto_refund: false,
+            refund_notice: 300,
             monitor_interval: 30,
             dry_run: args.dry_run,
             chain_id: 84532,
-            limit_order_protocol: "0x171C87724E720F2806fc29a010a62897B30fdb62".to_string(),
+            limit_order_protocol: None,
             evm_rpc: None,
             near_network: "testnet".to_string(),
+            fixed_rates: Vec::new(),
+            reference_rates: Vec::new(),
+            max_rate_deviation: 5.0,
+            price_source: "mock".to_string(),
+            price_feeds: Vec::new(),
+            max_price_age: DEFAULT_MAX_PRICE_AGE.as_secs(),
+            split: 1,
+            secret_index: None,
+            safety_deposit: 0.0,
+            safety_deposit_beneficiary: None,
+            output_unsigned: false,
+            escrow_factory: None,
+            escrow_recipient: None,
+            config: None,
+            amount_units: None,
         };
 
         match create_swap_plan(&swap_args).await {
@@ -267,31 +661,80 @@ pub async fn handle_batch_swap(args: BatchSwapArgs) -> Result<()> {
     Err(anyhow!("Batch swap execution not yet implemented"))
 }
 
+/// Replace `--amount-usd` with the source token amount it buys at the current price
+async fn resolve_usd_amount<O: PriceOracle>(
+    args: &mut SwapArgs,
+    converter: &PriceConverter<O>,
+) -> Result<()> {
+    let Some(amount_usd) = args.amount_usd else {
+        return Ok(());
+    };
+    if args.amount != 0.0 {
+        return Err(anyhow!("Provide either --amount or --amount-usd, not both"));
+    }
+    if !amount_usd.is_finite() || amount_usd <= 0.0 {
+        return Err(anyhow!("USD amount must be positive"));
+    }
+
+    let price = converter
+        .usd_price(&args.from_token)
+        .await
+        .map_err(|e| anyhow!("Cannot price {} in USD: {}", args.from_token, e))?;
+    if !price.is_finite() || price <= 0.0 {
+        return Err(anyhow!(
+            "Invalid USD price {} for {}",
+            price,
+            args.from_token
+        ));
+    }
+    args.amount = amount_usd / price;
+    Ok(())
+}
+
+/// Parse the chain pair once so callers branch on chain kinds, not names
+fn swap_direction(args: &SwapArgs) -> Result<SwapDirection> {
+    SwapDirection::parse(&args.from_chain, &args.to_chain)
+}
+
+fn limit_order_protocol(args: &SwapArgs) -> Result<String> {
+    resolve_limit_order_protocol(args.limit_order_protocol.as_deref(), args.chain_id)
+}
+
 fn validate_swap_inputs(args: &SwapArgs) -> Result<()> {
     // Validate chains
-    let valid_chains = ["ethereum", "near"];
+    let valid_chains = ["ethereum", "near", "arbitrum", "optimism"];
     if !valid_chains.contains(&args.from_chain.as_str()) {
-        return Err(anyhow!("Invalid from_chain: must be ethereum or near"));
+        return Err(anyhow!(
+            "Invalid from_chain: must be one of {}",
+            valid_chains.join(", ")
+        ));
     }
     if !valid_chains.contains(&args.to_chain.as_str()) {
-        return Err(anyhow!("Invalid to_chain: must be ethereum or near"));
-    }
-    if args.from_chain == args.to_chain {
-        return Err(anyhow!("from_chain and to_chain cannot be the same"));
+        return Err(anyhow!(
+            "Invalid to_chain: must be one of {}",
+            valid_chains.join(", ")
+        ));
     }
+    let direction = swap_direction(args)?;
 
     // Validate addresses based on chain
-    if args.from_chain == "ethereum" {
+    if direction.from.is_evm() {
         validate_ethereum_address(&args.from_address)?;
     } else {
         validate_near_address(&args.from_address)?;
     }
 
-    if args.to_chain == "ethereum" {
-        validate_ethereum_address(&args.to_address)?;
-    } else {
-        validate_near_address(&args.to_address)?;
+    for recipient in &args.to_addresses {
+        if direction.to.is_evm() {
+            validate_ethereum_address(&recipient.address)?;
+        } else {
+            validate_near_address(&recipient.address)?;
+        }
     }
+    validate_recipient_weights(&args.to_addresses, direction)?;
+
+    // Orders must be signed for the protocol deployed on the selected chain
+    limit_order_protocol(args)?;
 
     // Validate amount
     if args.amount <= 0.0 {
@@ -302,136 +745,754 @@ fn validate_swap_inputs(args: &SwapArgs) -> Result<()> {
     if args.slippage < 0.0 || args.slippage > 50.0 {
         return Err(anyhow!("Slippage must be between 0 and 50 percent"));
     }
+    for (flag, slippage) in [
+        ("--slippage-create", args.slippage_create),
+        ("--slippage-claim", args.slippage_claim),
+    ] {
+        if slippage.is_some_and(|s| !(0.0..=50.0).contains(&s)) {
+            return Err(anyhow!("{} must be between 0 and 50 percent", flag));
+        }
+    }
+    // Tolerance only tightens as the swap progresses
+    if args.claim_slippage() > args.create_slippage() {
+        return Err(anyhow!(
+            "Claim slippage ({}%) cannot exceed create slippage ({}%)",
+            args.claim_slippage(),
+            args.create_slippage()
+        ));
+    }
 
-    Ok(())
-}
+    for fixed_rate in &args.fixed_rates {
+        parse_pair_rate("fixed rate", fixed_rate)?;
+    }
+    for reference_rate in &args.reference_rates {
+        parse_pair_rate("reference rate", reference_rate)?;
+    }
+    if !(args.max_rate_deviation > 0.0 && args.max_rate_deviation <= 100.0) {
+        return Err(anyhow!(
+            "Max rate deviation must be between 0 and 100 percent"
+        ));
+    }
+    match args.price_source.as_str() {
+        "mock" => {}
+        "chainlink" => {
+            if args.price_feeds.is_empty() {
+                return Err(anyhow!(
+                    "--price-source chainlink needs at least one --price-feed"
+                ));
+            }
+            for feed in &args.price_feeds {
+                parse_price_feed(feed)?;
+            }
+        }
+        other => {
+            return Err(anyhow!(
+                "Unknown price source: {} (expected mock or chainlink)",
+                other
+            ))
+        }
+    }
 
-fn validate_ethereum_address(address: &str) -> Result<()> {
-    let addr = address.trim_start_matches("0x");
-    if addr.len() != 40 {
-        return Err(anyhow!("Invalid Ethereum address format"));
+    if args.split == 0 || args.split > MAX_SPLIT_TRANCHES {
+        return Err(anyhow!(
+            "Split must be between 1 and {} tranches",
+            MAX_SPLIT_TRANCHES
+        ));
     }
-    hex::decode(addr).map_err(|_| anyhow!("Invalid hexadecimal in address"))?;
+    if let Some(index) = args.secret_index {
+        if index.checked_add(args.split - 1).is_none() {
+            return Err(anyhow!(
+                "Secret index is too large for {} tranches",
+                args.split
+            ));
+        }
+    }
+    if !args.safety_deposit.is_finite() || args.safety_deposit < 0.0 {
+        return Err(anyhow!("Safety deposit must be zero or positive"));
+    }
+    if let Some(beneficiary) = &args.safety_deposit_beneficiary {
+        if args.safety_deposit == 0.0 {
+            return Err(anyhow!(
+                "--safety-deposit-beneficiary requires a non-zero --safety-deposit"
+            ));
+        }
+        validate_near_address(beneficiary)?;
+    }
+    if args.verify_after && !args.auto_claim {
+        return Err(anyhow!(
+            "--verify-after requires --auto-claim so the swap is monitored to completion"
+        ));
+    }
+    if args.auto_refund && !args.auto_claim {
+        return Err(anyhow!(
+            "--auto-refund requires --auto-claim so the swap is monitored until the timelock"
+        ));
+    }
+    if args.source_amount_units() < args.split as u128 {
+        return Err(anyhow!(
+            "Amount is too small to split into {} tranches",
+            args.split
+        ));
+    }
+    if args.output_unsigned {
+        validate_unsigned_output(args, &direction)?;
+    }
+    if let Some(path) = &args.config {
+        check_token_limit(
+            &load_config(path)?.token_limits,
+            &args.from_token,
+            args.amount,
+        )?;
+    }
+
     Ok(())
 }
 
-fn validate_near_address(address: &str) -> Result<()> {
-    if !address.ends_with(".near") && !address.ends_with(".testnet") {
-        return Err(anyhow!("Invalid NEAR address format"));
+/// Check `--output-unsigned` has everything needed to build each leg offline
+fn validate_unsigned_output(args: &SwapArgs, direction: &SwapDirection) -> Result<()> {
+    if args.secret_index.is_none() {
+        return Err(anyhow!(
+            "--output-unsigned requires --secret-index so the secret can be derived again to claim"
+        ));
+    }
+    if args.split > 1 || args.auto_claim {
+        return Err(anyhow!(
+            "--output-unsigned cannot be combined with --split or --auto-claim"
+        ));
+    }
+    if direction.is_evm_to_near() {
+        if args.evm_rpc.is_none() {
+            return Err(anyhow!(
+                "--output-unsigned needs --evm-rpc to fill in the nonce and gas fees"
+            ));
+        }
+        let factory = args
+            .escrow_factory
+            .as_deref()
+            .ok_or_else(|| anyhow!("--output-unsigned from ethereum requires --escrow-factory"))?;
+        parse_evm_address("escrow factory", factory)?;
+        let recipient = args.escrow_recipient.as_deref().ok_or_else(|| {
+            anyhow!("--output-unsigned from ethereum requires --escrow-recipient")
+        })?;
+        parse_evm_address("escrow recipient", recipient)?;
     }
     Ok(())
 }
 
-async fn create_swap_plan(args: &SwapArgs) -> Result<SwapPlan> {
-    let mut steps = Vec::new();
-    let mut warnings = Vec::new();
-
-    // Determine swap direction and create steps
-    match (args.from_chain.as_str(), args.to_chain.as_str()) {
-        ("ethereum", "near") => {
-            steps.push(SwapStep {
-                step_number: 1,
-                action: "Generate Secret".to_string(),
-                description: "Generate cryptographic secret for HTLC".to_string(),
-                estimated_time: "< 1 second".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 2,
-                action: "Create EVM Order".to_string(),
-                description: format!("Create limit order on {} blockchain", args.from_chain),
-                estimated_time: "10-30 seconds".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 3,
-                action: "Create NEAR HTLC".to_string(),
-                description: "Create Hash Time-Locked Contract on NEAR".to_string(),
-                estimated_time: "5-10 seconds".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 4,
-                action: "Monitor Execution".to_string(),
-                description: "Wait for order fulfillment on Ethereum".to_string(),
-                estimated_time: "1-10 minutes".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 5,
-                action: "Claim Funds".to_string(),
-                description: "Claim tokens from NEAR HTLC using secret".to_string(),
-                estimated_time: "5-10 seconds".to_string(),
-            });
-        }
-        ("near", "ethereum") => {
-            steps.push(SwapStep {
-                step_number: 1,
-                action: "Generate Secret".to_string(),
-                description: "Generate cryptographic secret for HTLC".to_string(),
-                estimated_time: "< 1 second".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 2,
-                action: "Create NEAR HTLC".to_string(),
-                description: "Lock NEAR tokens in HTLC contract".to_string(),
-                estimated_time: "5-10 seconds".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 3,
-                action: "Create Order".to_string(),
-                description: "Create cross-chain order for Ethereum".to_string(),
-                estimated_time: "5-10 seconds".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 4,
-                action: "Monitor Execution".to_string(),
-                description: "Wait for order fulfillment and HTLC creation on Ethereum".to_string(),
-                estimated_time: "1-10 minutes".to_string(),
-            });
-            steps.push(SwapStep {
-                step_number: 5,
-                action: "Claim Funds".to_string(),
-                description: "Claim tokens from Ethereum HTLC using secret".to_string(),
-                estimated_time: "30-60 seconds".to_string(),
-            });
-        }
-        _ => return Err(anyhow!("Unsupported swap direction")),
+/// Divide `total` into `count` parts, giving the remainder to the last part
+fn tranche_amounts(total: u128, count: u32) -> Vec<u128> {
+    let count = count.max(1) as u128;
+    let base = total / count;
+    let mut amounts = vec![base; count as usize];
+    if let Some(last) = amounts.last_mut() {
+        *last += total - base * count;
     }
+    amounts
+}
 
-    // Calculate fees
-    let fees = SwapFees {
-        network_fees: "~0.05 USD".to_string(),
-        protocol_fees: "0.1%".to_string(),
-        estimated_total: format!("~{} USD", 0.05 + (args.amount * 0.001)),
-    };
+/// Build one set of swap arguments per tranche, paired with its exact amount in units
+fn split_into_tranches(args: &SwapArgs) -> Vec<(u128, SwapArgs)> {
+    let total = args.source_amount_units();
+    tranche_amounts(total, args.split)
+        .into_iter()
+        .enumerate()
+        .map(|(index, units)| {
+            let tranche = SwapArgs {
+                amount: convert_wei_to_amount(units, &args.from_token),
+                // Escrows lock the exact units; `amount` is only for display and pricing
+                amount_units: Some(units),
+                // USD amounts were already converted for the whole swap
+                amount_usd: None,
+                split: 1,
+                // Each tranche needs its own secret, so derived ones take consecutive indices
+                secret_index: args.secret_index.map(|base| base + index as u32),
+                ..args.clone()
+            };
+            (units, tranche)
+        })
+        .collect()
+}
 
-    // Add warnings if needed
-    if args.slippage > 5.0 {
-        warnings.push(format!("High slippage tolerance of {}%", args.slippage));
+/// Parse a `FROM:TO=RATE` value; `kind` names the flag in error messages
+fn parse_pair_rate(kind: &str, value: &str) -> Result<(String, String, f64)> {
+    let (pair, rate) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Invalid {} {}: expected FROM:TO=RATE", kind, value))?;
+    let (from, to) = pair
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Invalid {} {}: expected FROM:TO=RATE", kind, value))?;
+    if from.is_empty() || to.is_empty() {
+        return Err(anyhow!("Invalid {} {}: missing token symbol", kind, value));
     }
-    if args.timeout < 1800 {
-        warnings.push("Short timeout period may increase failure risk".to_string());
+
+    let rate: f64 = rate
+        .parse()
+        .map_err(|_| anyhow!("Invalid {} {}: rate is not a number", kind, value))?;
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(anyhow!("Invalid {} {}: rate must be positive", kind, value));
     }
 
-    let validation_status = ValidationStatus {
-        is_valid: true,
-        warnings,
-        errors: vec![],
-    };
+    Ok((from.to_string(), to.to_string(), rate))
+}
 
-    Ok(SwapPlan {
-        steps,
-        estimated_time: "2-15 minutes".to_string(),
-        fees,
-        validation_status,
-    })
+/// A `--to-address` recipient and its percentage of the swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedRecipient {
+    pub address: String,
+    /// Share of the swap in percent
+    pub weight: u8,
 }
 
-async fn execute_swap(args: &SwapArgs, _plan: &SwapPlan) -> Result<SwapResult> {
-    // Generate secret and hash
-    let secret = generate_secret();
-    let secret_hash = hash_secret(&secret);
+impl WeightedRecipient {
+    /// The only recipient, receiving the whole swap
+    pub fn sole(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            weight: 100,
+        }
+    }
+}
+
+impl SwapArgs {
+    /// First `--to-address` recipient, which also signs the NEAR escrow calls
+    pub fn primary_to_address(&self) -> &str {
+        &self.to_addresses[0].address
+    }
+
+    /// Source amount in the token's smallest units, exact for split tranches
+    pub fn source_amount_units(&self) -> u128 {
+        self.amount_units
+            .unwrap_or_else(|| convert_amount_to_wei(self.amount, &self.from_token))
+    }
+
+    /// Slippage percentage used to price the order when the swap is created
+    pub fn create_slippage(&self) -> f64 {
+        self.slippage_create.unwrap_or(self.slippage)
+    }
+
+    /// Slippage percentage the destination amount must meet before auto-claim
+    pub fn claim_slippage(&self) -> f64 {
+        self.slippage_claim.unwrap_or(self.slippage)
+    }
+}
+
+/// Parse an `ADDRESS` or `ADDRESS:WEIGHT` recipient; a bare address gets the whole swap
+fn parse_weighted_recipient(value: &str) -> Result<WeightedRecipient, String> {
+    let Some((address, weight)) = value.rsplit_once(':') else {
+        return Ok(WeightedRecipient::sole(value));
+    };
+    let weight: u8 = weight
+        .parse()
+        .map_err(|_| format!("invalid weight '{}' in '{}'", weight, value))?;
+    if address.is_empty() || weight == 0 || weight > 100 {
+        return Err(format!(
+            "expected ADDRESS:WEIGHT with weight 1-100, got '{}'",
+            value
+        ));
+    }
+    Ok(WeightedRecipient {
+        address: address.to_string(),
+        weight,
+    })
+}
+
+/// Weights must add up to 100, and only NEAR escrows can be split across recipients
+fn validate_recipient_weights(
+    recipients: &[WeightedRecipient],
+    direction: SwapDirection,
+) -> Result<()> {
+    if recipients.len() > 1 && direction.to != Chain::NEAR {
+        return Err(anyhow!(
+            "Multiple --to-address recipients are only supported when swapping to NEAR"
+        ));
+    }
+    let total: u32 = recipients.iter().map(|r| u32::from(r.weight)).sum();
+    if total != 100 {
+        return Err(anyhow!(
+            "--to-address weights must sum to 100, got {}",
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// Parse a `TOKEN=ADDRESS` Chainlink feed argument
+fn parse_price_feed(value: &str) -> Result<(String, Address)> {
+    let (token, address) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Invalid price feed {}: expected TOKEN=ADDRESS", value))?;
+    if token.is_empty() {
+        return Err(anyhow!(
+            "Invalid price feed {}: missing token symbol",
+            value
+        ));
+    }
+    let address = address
+        .parse()
+        .map_err(|_| anyhow!("Invalid price feed {}: bad feed address", value))?;
+    Ok((token.to_string(), address))
+}
+
+/// Oracle selected with `--price-source`
+fn price_oracle(args: &SwapArgs) -> Result<Box<dyn PriceOracle>> {
+    match args.price_source.as_str() {
+        "mock" => Ok(Box::new(MockPriceOracle::new())),
+        "chainlink" => {
+            let feeds = args
+                .price_feeds
+                .iter()
+                .map(|feed| parse_price_feed(feed))
+                .collect::<Result<std::collections::HashMap<_, _>>>()?;
+            if feeds.is_empty() {
+                return Err(anyhow!(
+                    "--price-source chainlink needs at least one --price-feed"
+                ));
+            }
+            let rpc_url = args
+                .evm_rpc
+                .clone()
+                .or_else(|| std::env::var("ETHEREUM_RPC_URL").ok())
+                .ok_or_else(|| {
+                    anyhow!("--price-source chainlink needs --evm-rpc or ETHEREUM_RPC_URL")
+                })?;
+            let provider = Provider::<Http>::try_from(rpc_url.as_str())?;
+            Ok(Box::new(
+                ChainlinkPriceOracle::new(provider, feeds)
+                    .with_max_age(Duration::from_secs(args.max_price_age)),
+            ))
+        }
+        other => Err(anyhow!(
+            "Unknown price source: {} (expected mock or chainlink)",
+            other
+        )),
+    }
+}
+
+/// Build the price converter, applying any `--fixed-rate` overrides and
+/// `--reference-rate` sanity bounds
+fn price_converter(args: &SwapArgs) -> Result<PriceConverter<Box<dyn PriceOracle>>> {
+    price_converter_with(args, price_oracle(args)?)
+}
+
+fn price_converter_with<O: PriceOracle>(args: &SwapArgs, oracle: O) -> Result<PriceConverter<O>> {
+    let mut converter =
+        PriceConverter::new(oracle).with_max_age(Duration::from_secs(args.max_price_age));
+    for fixed_rate in &args.fixed_rates {
+        let (from, to, rate) = parse_pair_rate("fixed rate", fixed_rate)?;
+        converter = converter.with_fixed_rate(&from, &to, rate);
+    }
+    let max_deviation_bps = (args.max_rate_deviation * 100.0).round() as u32;
+    for reference_rate in &args.reference_rates {
+        let (from, to, rate) = parse_pair_rate("reference rate", reference_rate)?;
+        converter = converter.with_rate_bounds(&from, &to, rate, max_deviation_bps);
+    }
+    Ok(converter)
+}
+
+fn validate_ethereum_address(address: &str) -> Result<()> {
+    let addr = address.trim_start_matches("0x");
+    if addr.len() != 40 {
+        return Err(anyhow!("Invalid Ethereum address format"));
+    }
+    hex::decode(addr).map_err(|_| anyhow!("Invalid hexadecimal in address"))?;
+    Ok(())
+}
+
+fn validate_near_address(address: &str) -> Result<()> {
+    if !address.ends_with(".near") && !address.ends_with(".testnet") {
+        return Err(anyhow!("Invalid NEAR address format"));
+    }
+    Ok(())
+}
+
+async fn create_swap_plan(args: &SwapArgs) -> Result<SwapPlan> {
+    create_swap_plan_with(args, &price_converter(args)?).await
+}
+
+/// Build the swap plan, pricing the output with the given converter
+///
+/// In dry-run an oracle failure only adds a warning so the step plan can
+/// still be shown; real execution needs a working oracle and fails instead.
+async fn create_swap_plan_with<O: PriceOracle>(
+    args: &SwapArgs,
+    converter: &PriceConverter<O>,
+) -> Result<SwapPlan> {
+    let mut steps = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Determine swap direction and create steps
+    let direction = swap_direction(args)?;
+    let evm_chain = if direction.is_evm_to_near() {
+        &args.from_chain
+    } else {
+        &args.to_chain
+    };
+    let evm_gas_price = match evm_gas_price(args).await {
+        Ok(price) => price,
+        Err(e) => {
+            warnings.push(format!("EVM gas price unavailable, assuming 1 gwei: {}", e));
+            DEFAULT_EVM_GAS_PRICE.into()
+        }
+    };
+    let protocol_fee_usd = converter
+        .usd_price(&args.from_token)
+        .await
+        .ok()
+        .map(|price| args.amount * PROTOCOL_FEE_RATE * price);
+
+    // The resolver deploys the EVM escrow when it fills the order
+    let order_cost = step_cost(converter, evm_chain, ESCROW_CREATION_GAS, evm_gas_price)
+        .await
+        .with_protocol_fee(protocol_fee_usd);
+    let near_htlc_cost = step_cost(converter, "near", NEAR_HTLC_CALL_GAS, NEAR_GAS_PRICE).await;
+
+    if direction.is_evm_to_near() {
+        let claim_cost = step_cost(converter, "near", NEAR_HTLC_CALL_GAS, NEAR_GAS_PRICE).await;
+        steps.push(SwapStep {
+            step_number: 1,
+            action: "Generate Secret".to_string(),
+            description: "Generate cryptographic secret for HTLC".to_string(),
+            estimated_time: "< 1 second".to_string(),
+            estimated_cost: None,
+        });
+        steps.push(SwapStep {
+            step_number: 2,
+            action: "Create EVM Order".to_string(),
+            description: format!("Create limit order on {} blockchain", args.from_chain),
+            estimated_time: "10-30 seconds".to_string(),
+            estimated_cost: Some(order_cost),
+        });
+        steps.push(SwapStep {
+            step_number: 3,
+            action: "Create NEAR HTLC".to_string(),
+            description: "Create Hash Time-Locked Contract on NEAR".to_string(),
+            estimated_time: "5-10 seconds".to_string(),
+            estimated_cost: Some(near_htlc_cost),
+        });
+        steps.push(SwapStep {
+            step_number: 4,
+            action: "Monitor Execution".to_string(),
+            description: "Wait for order fulfillment on Ethereum".to_string(),
+            estimated_time: "1-10 minutes".to_string(),
+            estimated_cost: None,
+        });
+        steps.push(SwapStep {
+            step_number: 5,
+            action: "Claim Funds".to_string(),
+            description: "Claim tokens from NEAR HTLC using secret".to_string(),
+            estimated_time: "5-10 seconds".to_string(),
+            estimated_cost: Some(claim_cost),
+        });
+    } else if direction.is_near_to_evm() {
+        let claim_cost = step_cost(converter, evm_chain, EVM_CLAIM_GAS, evm_gas_price).await;
+        steps.push(SwapStep {
+            step_number: 1,
+            action: "Generate Secret".to_string(),
+            description: "Generate cryptographic secret for HTLC".to_string(),
+            estimated_time: "< 1 second".to_string(),
+            estimated_cost: None,
+        });
+        steps.push(SwapStep {
+            step_number: 2,
+            action: "Create NEAR HTLC".to_string(),
+            description: "Lock NEAR tokens in HTLC contract".to_string(),
+            estimated_time: "5-10 seconds".to_string(),
+            estimated_cost: Some(near_htlc_cost),
+        });
+        steps.push(SwapStep {
+            step_number: 3,
+            action: "Create Order".to_string(),
+            description: "Create cross-chain order for Ethereum".to_string(),
+            estimated_time: "5-10 seconds".to_string(),
+            estimated_cost: Some(order_cost),
+        });
+        steps.push(SwapStep {
+            step_number: 4,
+            action: "Monitor Execution".to_string(),
+            description: "Wait for order fulfillment and HTLC creation on Ethereum".to_string(),
+            estimated_time: "1-10 minutes".to_string(),
+            estimated_cost: None,
+        });
+        steps.push(SwapStep {
+            step_number: 5,
+            action: "Claim Funds".to_string(),
+            description: "Claim tokens from Ethereum HTLC using secret".to_string(),
+            estimated_time: "30-60 seconds".to_string(),
+            estimated_cost: Some(claim_cost),
+        });
+    } else {
+        return Err(anyhow!("Unsupported swap direction"));
+    }
+
+    // Calculate fees
+    let step_costs: Vec<&StepCost> = steps
+        .iter()
+        .filter_map(|s| s.estimated_cost.as_ref())
+        .collect();
+    let network_usd: Option<f64> = step_costs.iter().map(|c| c.network_fee_usd).sum();
+    let total_usd: Option<f64> = step_costs.iter().map(|c| c.cost_usd).sum();
+    let fees = SwapFees {
+        network_fees: usd_estimate(network_usd),
+        protocol_fees: format!("{}%", PROTOCOL_FEE_RATE * 100.0),
+        estimated_total: usd_estimate(total_usd),
+        total_usd,
+        safety_deposit: (args.safety_deposit > 0.0).then(|| {
+            format!(
+                "{} {} + {} {} (refundable)",
+                args.safety_deposit,
+                native_token(&args.from_chain),
+                args.safety_deposit,
+                native_token(&args.to_chain)
+            )
+        }),
+    };
+
+    let slippage_bps = (args.create_slippage() * 100.0) as u16;
+    let min_output = match taking_amount_with(converter, args, slippage_bps).await {
+        Ok(amount) => Some(convert_wei_to_amount(amount, &args.to_token)),
+        // A rate outside the sanity bounds is a bad reading, not a missing one
+        Err(e) if e.downcast_ref::<PriceError>().is_some() => return Err(e),
+        Err(e) if args.dry_run => {
+            warnings.push(format!(
+                "Price oracle unavailable, output amount not estimated: {}",
+                e
+            ));
+            None
+        }
+        Err(e) => return Err(anyhow!("Price oracle unavailable: {}", e)),
+    };
+    let estimated_output = min_output.map_or_else(
+        || "unavailable".to_string(),
+        |amount| format!("{} {}", amount, args.to_token),
+    );
+
+    // Compare the quote with the oracle mid-price so a bad quote stands out
+    let effective_rate = min_output.map(|amount| amount / args.amount);
+    let oracle_rate = match min_output {
+        Some(_) => converter
+            .get_conversion_rate(&args.from_token, &args.to_token)
+            .await
+            .ok(),
+        None => None,
+    };
+    let price_impact_pct = effective_rate
+        .zip(oracle_rate)
+        .filter(|(_, oracle)| *oracle > 0.0)
+        .map(|(effective, oracle)| (1.0 - effective / oracle) * 100.0);
+
+    // Add warnings if needed
+    if args.create_slippage() > 5.0 {
+        warnings.push(format!(
+            "High slippage tolerance of {}%",
+            args.create_slippage()
+        ));
+    }
+    if args.timeout < 1800 {
+        warnings.push("Short timeout period may increase failure risk".to_string());
+    }
+
+    let tranches = if args.split > 1 {
+        let total = args.source_amount_units();
+        tranche_amounts(total, args.split)
+            .into_iter()
+            .enumerate()
+            .map(|(index, units)| TranchePlan {
+                index: index as u32,
+                amount: convert_wei_to_amount(units, &args.from_token),
+                amount_units: units.to_string(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let validation_status = ValidationStatus {
+        is_valid: true,
+        warnings,
+        errors: vec![],
+    };
+
+    let amount_conversion = args.amount_usd.map(|amount_usd| AmountConversion {
+        amount_usd,
+        token_price_usd: amount_usd / args.amount,
+        amount: args.amount,
+        token: args.from_token.clone(),
+    });
+
+    Ok(SwapPlan {
+        steps,
+        estimated_time: "2-15 minutes".to_string(),
+        estimated_output,
+        effective_rate,
+        oracle_rate,
+        price_impact_pct,
+        amount_conversion,
+        tranches,
+        fees,
+        validation_status,
+    })
+}
+
+/// Execute each tranche as its own swap and monitor them collectively
+///
+/// A failing tranche does not stop the others, so the swap can settle
+/// partially. Only fails outright when no tranche could be started.
+async fn execute_split_swap(args: &SwapArgs, plan: &SwapPlan) -> Result<()> {
+    let tranches = split_into_tranches(args);
+    let mut result = run_tranches(&tranches, |tranche| execute_swap(tranche, plan)).await;
+    tag_split_parts(&SwapStore::from_env(), &result)?;
+
+    if args.auto_claim {
+        for (tranche, (_, tranche_args)) in result.tranches.iter_mut().zip(&tranches) {
+            let Some(swap) = tranche.swap.as_ref() else {
+                continue;
+            };
+            match monitor_and_claim(tranche_args, swap, stuck_budget(plan)).await {
+                Ok(()) => tranche.status = "completed".to_string(),
+                Err(e) => {
+                    tranche.status = "failed".to_string();
+                    tranche.error = Some(e.to_string());
+                }
+            }
+        }
+        result.refresh_status();
+    }
+
+    // Every tranche is its own swap, so each one has to settle atomically
+    let mut non_atomic = Vec::new();
+    if args.verify_after {
+        let monitor = swap_monitor(args.evm_rpc.clone(), &args.near_network);
+        // All tranches are checked in one cycle, so escrows they share are read once
+        let cycle = PollCycleCache::new(&monitor);
+        for tranche in &result.tranches {
+            let Some(swap) = tranche.swap.as_ref() else {
+                continue;
+            };
+            let stored = SwapStore::from_env().get(&swap.swap_id)?;
+            if let Err(e) = check_swap_atomicity(&stored, &cycle).await {
+                non_atomic.push(e.to_string());
+            }
+        }
+    }
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({ "split_swap": &result }))?
+    );
+
+    if result.status == "failed" {
+        return Err(anyhow!("All {} tranches failed", result.tranches.len()));
+    }
+    if !non_atomic.is_empty() {
+        return Err(anyhow!(non_atomic.join("; ")));
+    }
+    Ok(())
+}
+
+async fn run_tranches<'a, F, Fut>(tranches: &'a [(u128, SwapArgs)], mut start: F) -> SplitSwapResult
+where
+    F: FnMut(&'a SwapArgs) -> Fut,
+    Fut: std::future::Future<Output = Result<SwapResult>>,
+{
+    let mut results = Vec::new();
+    for (index, (units, tranche)) in tranches.iter().enumerate() {
+        let (status, swap, error) = match start(tranche).await {
+            Ok(swap) => ("pending", Some(swap), None),
+            Err(e) => ("failed", None, Some(e.to_string())),
+        };
+        results.push(TrancheResult {
+            index: index as u32,
+            amount: tranche.amount,
+            amount_units: units.to_string(),
+            status: status.to_string(),
+            swap,
+            error,
+        });
+    }
+
+    let mut result = SplitSwapResult {
+        split_id: format!("split_{}", hex::encode(&generate_secret()[..8])),
+        status: String::new(),
+        tranches: results,
+    };
+    result.refresh_status();
+    result
+}
+
+/// Record which split each started tranche belongs to, so it can be resumed
+fn tag_split_parts(store: &SwapStore, result: &SplitSwapResult) -> Result<()> {
+    for tranche in &result.tranches {
+        let Some(swap) = tranche.swap.as_ref() else {
+            continue;
+        };
+        let mut stored = store.get(&swap.swap_id)?;
+        stored.split = Some(SplitPart {
+            split_id: result.split_id.clone(),
+            index: tranche.index,
+            parts: result.tranches.len() as u32,
+        });
+        store.save(stored)?;
+    }
+    Ok(())
+}
+
+async fn execute_swap(args: &SwapArgs, plan: &SwapPlan) -> Result<SwapResult> {
+    let result = start_swap(args, plan).await?;
+    save_swap_record(args, &result).await?;
+    Ok(result)
+}
+
+/// Persist the swap so later commands can look it up by ID
+async fn save_swap_record(args: &SwapArgs, result: &SwapResult) -> Result<()> {
+    let record = StoredSwap {
+        swap_id: result.swap_id.clone(),
+        from_chain: args.from_chain.clone(),
+        to_chain: args.to_chain.clone(),
+        from_token: args.from_token.clone(),
+        to_token: args.to_token.clone(),
+        from_address: args.from_address.clone(),
+        to_address: args.primary_to_address().to_string(),
+        src_amount: args.source_amount_units(),
+        dst_amount: result.min_received,
+        secret_hash: result.secret_hash.clone(),
+        order_hash: result.order_hash.clone(),
+        htlc_id: result.htlc_id.clone(),
+        timeout: args.timeout,
+        status: result.status.clone(),
+        created_at: chrono::Utc::now().timestamp() as u64,
+        safety_deposit: args.safety_deposit,
+        split: None,
+        transactions: result.transactions.clone(),
+    };
+
+    SwapStore::from_env().save(record)
+}
+
+/// Random secret, or the one derived from the user's mnemonic with `--secret-index`
+fn swap_secret(args: &SwapArgs) -> Result<Secret> {
+    let Some(index) = args.secret_index else {
+        return Ok(generate_secret());
+    };
+
+    let mnemonic = std::env::var("FUSION_SECRET_MNEMONIC")
+        .map_err(|_| anyhow!("--secret-index requires FUSION_SECRET_MNEMONIC to be set"))?;
+    let passphrase = std::env::var("FUSION_SECRET_PASSPHRASE").unwrap_or_default();
+    let seed = SecretManager::seed_from_mnemonic(&mnemonic, &passphrase)?;
+    Ok(SecretManager::derive_secret(&seed, index))
+}
+
+async fn start_swap(args: &SwapArgs, _plan: &SwapPlan) -> Result<SwapResult> {
+    // Generate secret and hash
+    let secret = swap_secret(args)?;
+    let secret_hash = hash_secret(&secret);
 
     let swap_id = format!("swap_{}", hex::encode(&secret_hash[..8]));
+    ensure_hashlock_unused(&SwapStore::from_env(), &secret_hash)?;
     let mut transactions = Vec::new();
     let mut next_steps = Vec::new();
+    // The destination amount is checked against the (tighter) claim tolerance
+    let slippage_bps = (args.claim_slippage() * 100.0) as u16;
+    let min_received = calculate_taking_amount(args, slippage_bps).await.ok();
 
     println!(
         "{}",
@@ -444,90 +1505,134 @@ async fn execute_swap(args: &SwapArgs, _plan: &SwapPlan) -> Result<SwapResult> {
         })
     );
 
-    match (args.from_chain.as_str(), args.to_chain.as_str()) {
-        ("ethereum", "near") => {
-            // Step 1: Create order on Ethereum
-            let order_result = create_ethereum_order(args, &secret_hash).await?;
-
-            // Note: The actual transaction hash will be displayed by order_handler
-            // when submit is true. For now, we use a placeholder.
-            transactions.push(TransactionInfo {
-                chain: "ethereum".to_string(),
-                tx_hash: order_result.order_hash.clone(),
-                explorer_url: format!(
-                    "https://sepolia.basescan.org/tx/{}",
-                    order_result.order_hash
-                ),
-                description: "Limit order submitted to blockchain".to_string(),
-            });
-
-            // Step 2: Create HTLC on NEAR
-            let htlc_result = create_near_htlc(args, &secret_hash).await?;
-            transactions.push(TransactionInfo {
-                chain: "near".to_string(),
-                tx_hash: htlc_result.htlc_id.clone(),
-                explorer_url: format!(
-                    "https://explorer.testnet.near.org/transactions/{}",
-                    htlc_result.htlc_id
-                ),
-                description: "HTLC created".to_string(),
-            });
-
-            next_steps.push("Monitor order execution on Ethereum".to_string());
-            next_steps.push("Once filled, use the secret to claim from NEAR HTLC".to_string());
-
-            Ok(SwapResult {
-                swap_id,
-                status: "pending".to_string(),
-                secret_hash: hex::encode(secret_hash),
-                htlc_id: Some(htlc_result.htlc_id),
-                order_hash: Some(order_result.order_hash),
-                transactions,
-                next_steps,
-            })
-        }
-        ("near", "ethereum") => {
-            // Step 1: Create HTLC on NEAR
-            let htlc_result = create_near_htlc(args, &secret_hash).await?;
-            transactions.push(TransactionInfo {
-                chain: "near".to_string(),
-                tx_hash: htlc_result.htlc_id.clone(),
-                explorer_url: format!(
-                    "https://explorer.testnet.near.org/transactions/{}",
-                    htlc_result.htlc_id
-                ),
-                description: "HTLC created".to_string(),
-            });
-
-            // Step 2: Create order pointing to NEAR HTLC
-            let order_result = create_near_to_ethereum_order(args, &secret_hash).await?;
-            transactions.push(TransactionInfo {
-                chain: "ethereum".to_string(),
-                tx_hash: order_result.order_hash.clone(),
-                explorer_url: format!(
-                    "https://sepolia.basescan.org/tx/{}",
-                    order_result.order_hash
-                ),
-                description: "Cross-chain order created".to_string(),
-            });
-
-            next_steps.push("Monitor order execution and HTLC creation on Ethereum".to_string());
-            next_steps.push("Once Ethereum HTLC is created, claim using the secret".to_string());
-
-            Ok(SwapResult {
-                swap_id,
-                status: "pending".to_string(),
-                secret_hash: hex::encode(secret_hash),
-                htlc_id: Some(htlc_result.htlc_id),
-                order_hash: Some(order_result.order_hash),
-                transactions,
-                next_steps,
-            })
+    let direction = swap_direction(args)?;
+    if direction.is_evm_to_near() {
+        if let Some(rpc_url) = &args.evm_rpc {
+            let provider = Provider::<Http>::try_from(rpc_url.as_str())?;
+            preflight_source_balance(args, &provider).await?;
         }
-        _ => Err(anyhow!("Unsupported swap direction")),
+
+        // Step 1: Create order on Ethereum
+        let order_result = create_ethereum_order(args, &secret_hash).await?;
+
+        // Note: The actual transaction hash will be displayed by order_handler
+        // when submit is true. For now, we use a placeholder.
+        transactions.push(TransactionInfo {
+            chain: "ethereum".to_string(),
+            tx_hash: order_result.order_hash.clone(),
+            explorer_url: format!(
+                "https://sepolia.basescan.org/tx/{}",
+                order_result.order_hash
+            ),
+            description: "Limit order submitted to blockchain".to_string(),
+        });
+
+        // Step 2: Create HTLC on NEAR
+        let htlc_result = create_near_htlc(args, &secret_hash).await?;
+        transactions.push(TransactionInfo {
+            chain: "near".to_string(),
+            tx_hash: htlc_result.htlc_id.clone(),
+            explorer_url: format!(
+                "https://explorer.testnet.near.org/transactions/{}",
+                htlc_result.htlc_id
+            ),
+            description: "HTLC created".to_string(),
+        });
+
+        next_steps.push("Monitor order execution on Ethereum".to_string());
+        next_steps.push("Once filled, use the secret to claim from NEAR HTLC".to_string());
+
+        Ok(SwapResult {
+            swap_id,
+            status: "pending".to_string(),
+            secret_hash: hex::encode(secret_hash),
+            htlc_id: Some(htlc_result.htlc_id),
+            order_hash: Some(order_result.order_hash),
+            min_received,
+            transactions,
+            next_steps,
+        })
+    } else if direction.is_near_to_evm() {
+        // Step 1: Create HTLC on NEAR
+        let htlc_result = create_near_htlc(args, &secret_hash).await?;
+        transactions.push(TransactionInfo {
+            chain: "near".to_string(),
+            tx_hash: htlc_result.htlc_id.clone(),
+            explorer_url: format!(
+                "https://explorer.testnet.near.org/transactions/{}",
+                htlc_result.htlc_id
+            ),
+            description: "HTLC created".to_string(),
+        });
+
+        // Step 2: Create order pointing to NEAR HTLC
+        let order_result = create_near_to_ethereum_order(args, &secret_hash).await?;
+        transactions.push(TransactionInfo {
+            chain: "ethereum".to_string(),
+            tx_hash: order_result.order_hash.clone(),
+            explorer_url: format!(
+                "https://sepolia.basescan.org/tx/{}",
+                order_result.order_hash
+            ),
+            description: "Cross-chain order created".to_string(),
+        });
+
+        next_steps.push("Monitor order execution and HTLC creation on Ethereum".to_string());
+        next_steps.push("Once Ethereum HTLC is created, claim using the secret".to_string());
+
+        Ok(SwapResult {
+            swap_id,
+            status: "pending".to_string(),
+            secret_hash: hex::encode(secret_hash),
+            htlc_id: Some(htlc_result.htlc_id),
+            order_hash: Some(order_result.order_hash),
+            min_received,
+            transactions,
+            next_steps,
+        })
+    } else {
+        Err(anyhow!("Unsupported swap direction"))
     }
 }
 
+/// Refuse to start a swap whose hashlock is shared with another in-flight swap
+///
+/// Revealing the secret to settle one swap would let anyone claim the other,
+/// e.g. when the same `--secret-index` is used twice.
+fn ensure_hashlock_unused(store: &SwapStore, secret_hash: &SecretHash) -> Result<()> {
+    let secret_hash = hex::encode(secret_hash);
+    if let Some(existing) = store
+        .list()?
+        .into_iter()
+        .find(|swap| swap.status == "pending" && swap.secret_hash == secret_hash)
+    {
+        return Err(anyhow!(
+            "Hashlock {} is already used by in-flight swap {}",
+            secret_hash,
+            existing.swap_id
+        ));
+    }
+    Ok(())
+}
+
+/// Fail fast if the maker cannot cover the source amount and safety deposit
+///
+/// The resolver pays gas when it fills the order, so only the maker's own
+/// balances are checked here.
+async fn preflight_source_balance<S: BalanceSource + ?Sized>(
+    args: &SwapArgs,
+    source: &S,
+) -> Result<()> {
+    let maker = parse_evm_address("source address", &args.from_address)?;
+    let token = parse_evm_address("source token", &evm_token_address(&args.from_token)?)?;
+    let amount = U256::from(args.source_amount_units());
+
+    let mut funding = EscrowFunding::for_transfer(token, amount);
+    funding.value += U256::from(convert_amount_to_wei(args.safety_deposit, "ETH"));
+    check_funding(source, maker, &funding, U256::zero()).await?;
+    Ok(())
+}
+
 #[derive(Debug)]
 struct OrderResult {
     order_hash: String,
@@ -540,24 +1645,14 @@ struct HtlcResult {
 
 async fn create_ethereum_order(args: &SwapArgs, secret_hash: &SecretHash) -> Result<OrderResult> {
     // Convert slippage to basis points
-    let slippage_bps = (args.slippage * 100.0) as u16;
+    let slippage_bps = (args.create_slippage() * 100.0) as u16;
 
     // Convert token symbols to addresses
-    let maker_asset = match args.from_token.as_str() {
-        "ETH" => "0x0000000000000000000000000000000000000000".to_string(), // Native ETH
-        "WETH" => "0x4200000000000000000000000000000000000006".to_string(), // WETH on Base Sepolia
-        "USDC" => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(), // USDC on Base Sepolia
-        addr if addr.starts_with("0x") => addr.to_string(),                // Already an address
-        _ => return Err(anyhow!("Token {} not supported", args.from_token)),
-    };
+    let maker_asset = evm_token_address(&args.from_token)?;
 
     let taker_asset = match args.to_token.as_str() {
         "NEAR" => "0x0000000000000000000000000000000000000000".to_string(), // Placeholder for cross-chain
-        "ETH" => "0x0000000000000000000000000000000000000000".to_string(),  // Native ETH
-        "WETH" => "0x4200000000000000000000000000000000000006".to_string(), // WETH on Base Sepolia
-        "USDC" => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(), // USDC on Base Sepolia
-        addr if addr.starts_with("0x") => addr.to_string(),                 // Already an address
-        _ => return Err(anyhow!("Token {} not supported", args.to_token)),
+        token => evm_token_address(token)?,
     };
 
     // Use the existing order creation logic
@@ -565,45 +1660,41 @@ async fn create_ethereum_order(args: &SwapArgs, secret_hash: &SecretHash) -> Res
         maker_asset,
         taker_asset,
         maker: args.from_address.clone(),
-        making_amount: convert_amount_to_wei(args.amount, &args.from_token),
-        taking_amount: calculate_taking_amount(
-            args.amount,
-            &args.from_token,
-            &args.to_token,
-            slippage_bps,
-        )
-        .await?,
+        making_amount: args.source_amount_units(),
+        taking_amount: calculate_taking_amount(args, slippage_bps).await?,
         htlc_secret_hash: hex::encode(secret_hash),
         htlc_timeout: args.timeout,
         chain_id: args.chain_id,
-        verifying_contract: args.limit_order_protocol.clone(),
+        verifying_contract: limit_order_protocol(args)?,
         receiver: None, // Receiver is on NEAR, not Ethereum
         allowed_sender: None,
         recipient_chain: Some("near".to_string()),
-        recipient_address: Some(args.to_address.clone()),
+        recipient_address: Some(args.primary_to_address().to_string()),
+        salt: None,
         sign: true,   // Sign the order for immediate submission
         submit: true, // Submit the order to the blockchain
+        relayer_url: None,
+        no_token_check: false,
     };
 
     // Actually call the order creation
     println!("Creating Ethereum order...");
-    crate::order_handler::handle_create_order(order_args).await?;
+    let output = crate::order_handler::create_order(order_args).await?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
 
-    // Use a placeholder hash for now - in a real implementation,
-    // this would be returned from handle_create_order
-    let order_hash = format!("0x{}", hex::encode(&secret_hash[..16]));
+    let order_hash = output["submission"]["order_hash"]
+        .as_str()
+        .or_else(|| output["eip712_hash"].as_str())
+        .unwrap_or_default()
+        .to_string();
     println!("Ethereum order created and submitted to blockchain");
-    println!("Check transaction status in Base Sepolia explorer:");
-    println!("https://sepolia.basescan.org/tx/pending");
 
     Ok(OrderResult { order_hash })
 }
 
 async fn create_near_htlc(args: &SwapArgs, secret_hash: &SecretHash) -> Result<HtlcResult> {
-    use std::process::Command;
-
     // Validate NEAR address to prevent injection
-    validate_near_address(&args.to_address)?;
+    validate_near_address(args.primary_to_address())?;
 
     // Validate timeout is reasonable
     if args.timeout == 0 || args.timeout > 86400 * 7 {
@@ -619,48 +1710,36 @@ async fn create_near_htlc(args: &SwapArgs, secret_hash: &SecretHash) -> Result<H
 
     // Convert hex hash to Base58 for NEAR
     let hash_b58 = bs58::encode(secret_hash).into_string();
-
-    // Convert amount to NEAR units properly
-    // If from_chain is ethereum and to_chain is near, the amount is in source token units
-    let near_amount = if args.from_chain == "near" {
-        args.amount // Already in NEAR
-    } else {
-        // Convert from source token to NEAR using price oracle
-        let oracle = MockPriceOracle::new();
-        let converter = PriceConverter::new(oracle);
-        let source_amount_wei = convert_amount_to_wei(args.amount, &args.from_token);
-        let near_amount_yocto = converter
-            .convert_amount(
-                source_amount_wei,
-                &args.from_token,
-                get_token_decimals(&args.from_token),
-                "NEAR",
-                24,
-            )
-            .await?;
-        convert_wei_to_amount(near_amount_yocto, "NEAR")
-    };
+    let near_amount = near_leg_amount(args).await?;
 
     println!("Creating NEAR HTLC with hash: {}", hash_b58);
 
-    // Create JSON payload using serde_json to prevent injection
-    let escrow_args = json!({
-        "recipient": args.to_address,
-        "secret_hash": hash_b58,
-        "timeout_seconds": args.timeout
-    });
+    // One escrow per recipient, all locked with the same hash
+    let mut escrow_ids = Vec::new();
+    for call in near_escrow_calls(args, &hash_b58, near_amount) {
+        escrow_ids.push(submit_near_escrow(args, &call)?);
+    }
+
+    Ok(HtlcResult {
+        htlc_id: escrow_ids.join(","),
+    })
+}
+
+/// Send one `create_escrow` call through the `near` CLI and return the escrow ID
+fn submit_near_escrow(args: &SwapArgs, call: &NearEscrowCall) -> Result<String> {
+    use std::process::Command;
 
     // Create HTLC on NEAR with proper JSON serialization
     let output = Command::new("near")
         .args([
             "call",
-            "htlc-v2.testnet",
+            &call.contract,
             "create_escrow",
-            &escrow_args.to_string(),
+            &call.args.to_string(),
             "--use-account",
-            &args.to_address,
-            "--deposit",
-            &near_amount.to_string(),
+            args.primary_to_address(),
+            "--depositYocto",
+            &call.deposit.to_string(),
         ])
         .output()
         .map_err(|e| anyhow!("Failed to execute NEAR command: {}", e))?;
@@ -694,52 +1773,1058 @@ async fn create_near_htlc(args: &SwapArgs, secret_hash: &SecretHash) -> Result<H
 
     println!("Created NEAR HTLC: {}", escrow_id);
 
-    Ok(HtlcResult { htlc_id: escrow_id })
+    Ok(escrow_id)
 }
 
-async fn create_near_to_ethereum_order(
-    args: &SwapArgs,
-    secret_hash: &SecretHash,
-) -> Result<OrderResult> {
-    // Convert slippage to basis points
-    let slippage_bps = (args.slippage * 100.0) as u16;
-
-    // Use the existing NEAR order creation logic
-    let _order_args = crate::near_order_handler::CreateNearOrderArgs {
-        near_account: args.from_address.clone(),
-        ethereum_address: args.to_address.clone(),
-        near_amount: args.amount,
-        generate_secret: false,
-        secret_hash: Some(hex::encode(secret_hash)),
-        timeout: args.timeout,
-        slippage_bps,
-        chain_id: args.chain_id,
-        limit_order_protocol: args.limit_order_protocol.clone(),
-    };
+/// Amount locked in the NEAR HTLC, in yoctoNEAR
+async fn near_leg_amount(args: &SwapArgs) -> Result<u128> {
+    // If from_chain is ethereum and to_chain is near, the amount is in source token units
+    if swap_direction(args)?.from == Chain::NEAR {
+        return Ok(args.source_amount_units()); // Already in yoctoNEAR
+    }
 
-    // In a real implementation, this would call the NEAR order creation logic
-    // For now, return a mock result
-    Ok(OrderResult {
-        order_hash: format!("0x{}", hex::encode(&secret_hash[..16])),
-    })
+    // Convert from source token to NEAR using price oracle
+    let converter = price_converter(args)?;
+    let source_amount_wei = args.source_amount_units();
+    converter
+        .convert_amount(
+            source_amount_wei,
+            &args.from_token,
+            get_token_decimals(&args.from_token),
+            "NEAR",
+            24,
+        )
+        .await
 }
 
-/// Get the decimal places for a token
-fn get_token_decimals(token: &str) -> u8 {
-    match token {
-        "NEAR" => 24,
-        "USDC" => 6,
-        "USDT" => 6,
-        "DAI" => 18,
-        "ETH" | "WETH" => 18,
-        addr if addr.starts_with("0x") => 18, // Default for unknown ERC20 tokens
-        _ => 18,                              // Default
+/// Print the swap's transactions for an offline signer instead of sending them
+///
+/// The EVM leg is the source escrow's `createEscrow` call, only present when
+/// swapping from ethereum, and the NEAR leg the `create_escrow` function call.
+async fn print_unsigned_swap(args: &SwapArgs) -> Result<()> {
+    let secret_hash = hash_secret(&swap_secret(args)?);
+    let hash_b58 = bs58::encode(secret_hash).into_string();
+
+    let mut ethereum = Vec::new();
+    if swap_direction(args)?.is_evm_to_near() {
+        ethereum.push(unsigned_source_escrow(args, secret_hash).await?);
     }
-}
 
-/// Convert human-readable amount to smallest unit (wei/yocto)
-fn convert_amount_to_wei(amount: f64, token: &str) -> u128 {
-    let decimals = get_token_decimals(token);
+    let near_signer = if args.from_chain == "near" {
+        &args.from_address
+    } else {
+        args.primary_to_address()
+    };
+    validate_near_address(near_signer)?;
+    let calls = near_escrow_calls(args, &hash_b58, near_leg_amount(args).await?);
+    let actions: Vec<_> = calls
+        .iter()
+        .map(|call| {
+            json!({
+                "FunctionCall": {
+                    "method_name": "create_escrow",
+                    "args": call.args,
+                    "gas": NEAR_HTLC_CALL_GAS.to_string(),
+                    "deposit": call.deposit.to_string(),
+                }
+            })
+        })
+        .collect();
+    let near = json!({
+        "signer_id": near_signer,
+        "receiver_id": calls[0].contract,
+        "actions": actions,
+    });
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({
+            "mode": "unsigned",
+            "swap_id": format!("swap_{}", hex::encode(&secret_hash[..8])),
+            "secret_hash": hex::encode(secret_hash),
+            "secret_index": args.secret_index,
+            "transactions": {
+                "ethereum": ethereum,
+                "near": [near],
+            },
+        }))?
+    );
+    Ok(())
+}
+
+/// Unsigned `createEscrow` locking the source amount, with nonce and fees read from `--evm-rpc`
+async fn unsigned_source_escrow(
+    args: &SwapArgs,
+    secret_hash: SecretHash,
+) -> Result<UnsignedTransaction> {
+    let rpc_url = args
+        .evm_rpc
+        .as_deref()
+        .ok_or_else(|| anyhow!("--output-unsigned needs --evm-rpc"))?;
+    let factory = args
+        .escrow_factory
+        .as_deref()
+        .ok_or_else(|| anyhow!("--output-unsigned from ethereum requires --escrow-factory"))?;
+    let recipient = args
+        .escrow_recipient
+        .as_deref()
+        .ok_or_else(|| anyhow!("--output-unsigned from ethereum requires --escrow-recipient"))?;
+
+    let connector = EthereumConnector::new(rpc_url, factory)
+        .map_err(|e| anyhow!("Failed to connect to {}: {}", rpc_url, e))?;
+    connector
+        .unsigned_create_escrow(
+            parse_evm_address("source", &args.from_address)?,
+            parse_evm_address("source token", &evm_token_address(&args.from_token)?)?,
+            U256::from(args.source_amount_units()),
+            secret_hash,
+            U256::from(args.timeout),
+            parse_evm_address("escrow recipient", recipient)?,
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to build the escrow transaction: {}", e))
+}
+
+/// Contract, arguments and attached deposit (in yoctoNEAR) of the `create_escrow` call
+#[derive(Debug)]
+struct NearEscrowCall {
+    contract: String,
+    args: serde_json::Value,
+    deposit: u128,
+}
+
+/// Build one NEAR `create_escrow` call per `--to-address` recipient
+///
+/// `near_amount` (yoctoNEAR) and the safety deposit are split by the recipients'
+/// weights; the last recipient takes the rounding remainder so the shares add up.
+fn near_escrow_calls(args: &SwapArgs, hash_b58: &str, near_amount: u128) -> Vec<NearEscrowCall> {
+    let amounts = weighted_shares(near_amount, &args.to_addresses);
+    let deposits = weighted_shares(
+        convert_amount_to_wei(args.safety_deposit, "NEAR"),
+        &args.to_addresses,
+    );
+    args.to_addresses
+        .iter()
+        .zip(amounts.into_iter().zip(deposits))
+        .map(|(recipient, (amount, deposit))| {
+            near_escrow_call(args, &recipient.address, hash_b58, amount, deposit)
+        })
+        .collect()
+}
+
+/// Split `total` by the recipients' weights without losing units to rounding
+fn weighted_shares(total: u128, recipients: &[WeightedRecipient]) -> Vec<u128> {
+    let mut shares: Vec<u128> = recipients
+        .iter()
+        .map(|recipient| total * u128::from(recipient.weight) / 100)
+        .collect();
+    let remainder = total - shares.iter().sum::<u128>();
+    if let Some(last) = shares.last_mut() {
+        *last += remainder;
+    }
+    shares
+}
+
+/// Build the NEAR `create_escrow` call locking `near_amount` yoctoNEAR for `recipient`
+///
+/// A safety deposit needs FusionHTLC, which takes it on top of the amount
+/// and pays it to the beneficiary (or whoever resolves the escrow).
+fn near_escrow_call(
+    args: &SwapArgs,
+    recipient: &str,
+    hash_b58: &str,
+    near_amount: u128,
+    safety_deposit: u128,
+) -> NearEscrowCall {
+    if safety_deposit == 0 {
+        // Create JSON payload using serde_json to prevent injection
+        return NearEscrowCall {
+            contract: "htlc-v2.testnet".to_string(),
+            args: json!({
+                "recipient": recipient,
+                "secret_hash": hash_b58,
+                "timeout_seconds": args.timeout
+            }),
+            deposit: near_amount,
+        };
+    }
+
+    // Same stage offsets as the EVM escrow on this leg
+    let stages = Timelocks::from_timeout(u32::try_from(args.timeout).unwrap_or(u32::MAX));
+    let (finality, cancel, public_cancel) = if args.from_chain == "near" {
+        (
+            stages.src_public_withdrawal,
+            stages.src_cancellation,
+            stages.src_public_cancellation,
+        )
+    } else {
+        (
+            stages.dst_public_withdrawal,
+            stages.dst_cancellation,
+            stages.src_cancellation,
+        )
+    };
+
+    let contract = match args.near_network.as_str() {
+        "mainnet" => "fusion-htlc.near",
+        _ => "fusion-htlc.testnet",
+    };
+
+    NearEscrowCall {
+        contract: contract.to_string(),
+        args: json!({
+            "params": {
+                "beneficiary": recipient,
+                "secret_hash": hash_b58,
+                "token_id": null,
+                "amount": near_amount.to_string(),
+                "safety_deposit": safety_deposit.to_string(),
+                "safety_deposit_beneficiary": args.safety_deposit_beneficiary,
+                "finality_period": finality,
+                "cancel_period": cancel,
+                "public_cancel_period": public_cancel,
+            }
+        }),
+        deposit: near_amount + safety_deposit,
+    }
+}
+
+/// Price per gas unit on the EVM chain, live if `--evm-rpc` is given
+async fn evm_gas_price(args: &SwapArgs) -> Result<u128> {
+    match &args.evm_rpc {
+        Some(rpc_url) => {
+            let provider = Provider::<Http>::try_from(rpc_url.as_str())?;
+            Ok(provider.gas_price().await?.low_u128())
+        }
+        None => Ok(DEFAULT_EVM_GAS_PRICE.into()),
+    }
+}
+
+/// Gas cost of a step on `chain`, priced in USD when the oracle knows the native token
+async fn step_cost<O: PriceOracle>(
+    converter: &PriceConverter<O>,
+    chain: &str,
+    gas: u64,
+    gas_price: u128,
+) -> StepCost {
+    let token = native_token(chain);
+    let network_fee = convert_wei_to_amount(u128::from(gas) * gas_price, token);
+    let network_fee_usd = converter
+        .usd_price(token)
+        .await
+        .ok()
+        .map(|price| network_fee * price);
+    StepCost {
+        chain: chain.to_string(),
+        gas,
+        gas_price: gas_price.to_string(),
+        network_fee,
+        network_fee_usd,
+        protocol_fee_usd: None,
+        cost_usd: network_fee_usd,
+    }
+}
+
+impl StepCost {
+    /// Charge the protocol fee on this step; an unpriced fee leaves the step unpriced
+    fn with_protocol_fee(mut self, fee_usd: Option<f64>) -> Self {
+        self.cost_usd = self
+            .network_fee_usd
+            .zip(fee_usd)
+            .map(|(gas, fee)| gas + fee);
+        self.protocol_fee_usd = fee_usd;
+        self
+    }
+}
+
+fn usd_estimate(usd: Option<f64>) -> String {
+    usd.map_or_else(
+        || "unavailable".to_string(),
+        |usd| format!("~{:.4} USD", usd),
+    )
+}
+
+/// Native token used for safety deposits on `chain`
+pub(crate) fn native_token(chain: &str) -> &'static str {
+    match chain {
+        "near" => "NEAR",
+        _ => "ETH",
+    }
+}
+
+async fn create_near_to_ethereum_order(
+    args: &SwapArgs,
+    secret_hash: &SecretHash,
+) -> Result<OrderResult> {
+    // Convert slippage to basis points
+    let slippage_bps = (args.create_slippage() * 100.0) as u16;
+
+    // Use the existing NEAR order creation logic
+    let _order_args = crate::near_order_handler::CreateNearOrderArgs {
+        near_account: args.from_address.clone(),
+        ethereum_address: args.primary_to_address().to_string(),
+        near_amount: args.amount,
+        generate_secret: false,
+        secret_hash: Some(hex::encode(secret_hash)),
+        timeout: args.timeout,
+        slippage_bps,
+        chain_id: args.chain_id,
+        limit_order_protocol: Some(limit_order_protocol(args)?),
+    };
+
+    // In a real implementation, this would call the NEAR order creation logic
+    // For now, return a mock result
+    Ok(OrderResult {
+        order_hash: format!("0x{}", hex::encode(&secret_hash[..16])),
+    })
+}
+
+/// Resolve a token symbol or address to its EVM address on Base Sepolia
+fn evm_token_address(token: &str) -> Result<String> {
+    match token {
+        "ETH" => Ok("0x0000000000000000000000000000000000000000".to_string()), // Native ETH
+        "WETH" => Ok("0x4200000000000000000000000000000000000006".to_string()), // WETH on Base Sepolia
+        "USDC" => Ok("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string()), // USDC on Base Sepolia
+        addr if addr.starts_with("0x") => Ok(addr.to_string()), // Already an address
+        _ => Err(anyhow!("Token {} not supported", token)),
+    }
+}
+
+pub async fn handle_immutables(args: ImmutablesArgs) -> Result<()> {
+    let swap = SwapStore::from_env().get(&args.swap_id)?;
+
+    let taker = parse_evm_address("taker", &args.taker)?;
+    let factory = parse_evm_address("escrow factory", &args.escrow_factory)?;
+    let implementation = parse_evm_address("escrow implementation", &args.escrow_implementation)?;
+    let deployed_at = args
+        .deployed_at
+        .unwrap_or_else(|| chrono::Utc::now().timestamp() as u32);
+
+    let immutables = leg_immutables(
+        &swap,
+        &args.leg,
+        taker,
+        leg_safety_deposit(&swap, args.safety_deposit),
+        deployed_at,
+    )?;
+
+    let deposit_asset = match &args.safety_deposit_token {
+        Some(token) => SafetyDepositAsset::Token(parse_evm_address("safety deposit token", token)?),
+        None => SafetyDepositAsset::Native,
+    };
+    let funding = EscrowFunding::for_immutables(&immutables, deposit_asset);
+
+    let escrow_address = if args.leg == "src" {
+        address_of_escrow_src(factory, implementation, &immutables)
+    } else {
+        address_of_escrow_dst(factory, implementation, &immutables)
+    };
+
+    let timelocks = &immutables.timelocks;
+    let output = json!({
+        "swap_id": swap.swap_id,
+        "leg": args.leg,
+        "immutables": {
+            "order_hash": format!("{:?}", immutables.order_hash),
+            "hashlock": format!("{:?}", immutables.hashlock),
+            "maker": format!("{:?}", immutables.maker),
+            "taker": format!("{:?}", immutables.taker),
+            "token": format!("{:?}", immutables.token),
+            "amount": immutables.amount.to_string(),
+            "safety_deposit": immutables.safety_deposit.to_string(),
+            "timelocks": format!("0x{:064x}", timelocks.pack()),
+        },
+        "timelocks": timelocks,
+        "immutables_hash": format!("{:?}", immutables.hash()),
+        "funding": {
+            "value": funding.value.to_string(),
+            "approvals": funding
+                .approvals
+                .iter()
+                .map(|(token, amount)| json!({
+                    "token": format!("{:?}", token),
+                    "amount": amount.to_string(),
+                }))
+                .collect::<Vec<_>>(),
+        },
+        "escrow_factory": format!("{:?}", factory),
+        "predicted_escrow_address": format!("{:?}", escrow_address),
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+pub async fn handle_list_swaps(args: ListSwapsArgs) -> Result<()> {
+    let swaps = filter_and_sort_swaps(SwapStore::from_env().list()?, &args)?;
+
+    let output = json!({
+        "total": swaps.len(),
+        "swaps": swaps
+            .iter()
+            .map(|swap| json!({
+                "swap_id": swap.swap_id,
+                "state": swap.status,
+                "from": format!("{} on {}", swap.from_token, swap.from_chain),
+                "to": format!("{} on {}", swap.to_token, swap.to_chain),
+                "amount": swap.src_amount.to_string(),
+                "created_at": chrono::DateTime::from_timestamp(swap.created_at as i64, 0)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+            }))
+            .collect::<Vec<_>>(),
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+pub async fn handle_verify_swap(args: VerifySwapArgs) -> Result<()> {
+    let swap = SwapStore::from_env().get(&args.swap_id)?;
+    check_swap_atomicity(&swap, &swap_monitor(args.evm_rpc, &args.near_network)).await
+}
+
+pub async fn handle_swap_status(args: SwapStatusArgs) -> Result<()> {
+    let store = SwapStore::from_env();
+    let Some(swap) = store
+        .list()?
+        .into_iter()
+        .find(|swap| swap.swap_id == args.swap_id)
+    else {
+        return Err(cli_error(
+            ExitCode::NotFound,
+            json!({
+                "error": "swap not found",
+                "swap_id": args.swap_id,
+            }),
+        ));
+    };
+
+    let steps = swap_progress(&swap);
+    let output = json!({
+        "swap_id": swap.swap_id,
+        "state": swap.status,
+        "from": format!("{} on {}", swap.from_token, swap.from_chain),
+        "to": format!("{} on {}", swap.to_token, swap.to_chain),
+        "steps": steps,
+        "transactions": swap.transactions,
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+/// One step of a swap and whether it has happened yet
+#[derive(Debug, Serialize, PartialEq)]
+struct StepProgress {
+    step: &'static str,
+    completed: bool,
+    /// Transaction that completed the step, when one was recorded
+    tx_hash: Option<String>,
+}
+
+/// Rebuild a swap's progress from its stored transactions and state
+///
+/// Records written before transactions were stored fall back to the order
+/// hash and HTLC ID. A completed swap has had its secret revealed by the claim.
+fn swap_progress(swap: &StoredSwap) -> Vec<StepProgress> {
+    let recorded = |keyword: &str| {
+        swap.transactions
+            .iter()
+            .find(|tx| tx.description.to_lowercase().contains(keyword))
+            .map(|tx| tx.tx_hash.clone())
+    };
+    let completed = swap.status == "completed";
+
+    let order = recorded("order").or_else(|| swap.order_hash.clone());
+    let htlc = recorded("htlc").or_else(|| swap.htlc_id.clone());
+    let claim = recorded("claim");
+    vec![
+        StepProgress {
+            step: "order_created",
+            completed: order.is_some(),
+            tx_hash: order,
+        },
+        StepProgress {
+            step: "htlc_created",
+            completed: htlc.is_some(),
+            tx_hash: htlc,
+        },
+        StepProgress {
+            step: "secret_revealed",
+            completed: completed || claim.is_some(),
+            tx_hash: claim.clone(),
+        },
+        StepProgress {
+            step: "funds_claimed",
+            completed,
+            tx_hash: claim,
+        },
+    ]
+}
+
+pub async fn handle_status_all(args: StatusAllArgs) -> Result<()> {
+    let swaps: Vec<StoredSwap> = SwapStore::from_env()
+        .list()?
+        .into_iter()
+        .filter(|swap| swap.status == "pending")
+        .collect();
+
+    let monitor = swap_monitor(args.evm_rpc, &args.near_network);
+    let statuses = status_all(&swaps, &monitor).await?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({
+            "total": statuses.len(),
+            "swaps": statuses,
+        }))?
+    );
+
+    Ok(())
+}
+
+/// On-chain state of both legs of a stored swap
+#[derive(Debug, Serialize)]
+struct SwapStatus {
+    swap_id: String,
+    /// State recorded in the swap store
+    state: String,
+    source: crate::htlc_monitor::HTLCStatus,
+    target: crate::htlc_monitor::HTLCStatus,
+}
+
+/// Read both legs of every swap, issuing one batched query per chain
+///
+/// Escrows shared by several swaps are read once.
+async fn status_all<S: HtlcStatusSource + ?Sized>(
+    swaps: &[StoredSwap],
+    statuses: &S,
+) -> Result<Vec<SwapStatus>> {
+    let mut ids_by_chain: std::collections::BTreeMap<&str, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for swap in swaps {
+        for chain in [&swap.from_chain, &swap.to_chain] {
+            let ids = ids_by_chain.entry(chain).or_default();
+            let htlc_id = leg_htlc_id(swap, chain);
+            if !ids.iter().any(|id| id == htlc_id) {
+                ids.push(htlc_id.to_string());
+            }
+        }
+    }
+
+    let mut states = std::collections::HashMap::new();
+    for (chain, ids) in ids_by_chain {
+        let results = statuses.check_htlcs(&ids, chain).await?;
+        if results.len() != ids.len() {
+            return Err(anyhow!(
+                "Expected {} HTLC states from {} but got {}",
+                ids.len(),
+                chain,
+                results.len()
+            ));
+        }
+        for (id, status) in ids.into_iter().zip(results) {
+            states.insert((chain.to_string(), id), status);
+        }
+    }
+
+    let leg = |swap: &StoredSwap, chain: &str| {
+        states
+            .get(&(chain.to_string(), leg_htlc_id(swap, chain).to_string()))
+            .cloned()
+            .ok_or_else(|| anyhow!("No {} state for swap {}", chain, swap.swap_id))
+    };
+    swaps
+        .iter()
+        .map(|swap| {
+            Ok(SwapStatus {
+                swap_id: swap.swap_id.clone(),
+                state: swap.status.clone(),
+                source: leg(swap, &swap.from_chain)?,
+                target: leg(swap, &swap.to_chain)?,
+            })
+        })
+        .collect()
+}
+
+pub async fn handle_resume_swap(args: ResumeSwapArgs) -> Result<()> {
+    let store = SwapStore::from_env();
+    let parts = split_parts(store.list()?, &args.split_id)?;
+
+    let monitor = swap_monitor(args.evm_rpc.clone(), &args.near_network);
+    let budget = DEFAULT_STAGE_ESTIMATE.mul_f64(STUCK_BUDGET_MULTIPLIER);
+    let args = &args;
+    let report = resume_split_parts(&parts, &PollCycleCache::new(&monitor), |part| async move {
+        monitor_and_claim(
+            &resumed_part_args(part, args),
+            &resumed_part_result(part),
+            budget,
+        )
+        .await
+    })
+    .await?;
+
+    for part in &parts {
+        let index = part.split.as_ref().map(|split| split.index);
+        let status = if index.is_some_and(|i| report.is_completed(i)) {
+            "completed"
+        } else if index.is_some_and(|i| report.is_failed(i)) {
+            "failed"
+        } else {
+            continue;
+        };
+        store.save(StoredSwap {
+            status: status.to_string(),
+            ..part.clone()
+        })?;
+    }
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({ "resumed_split_swap": &report }))?
+    );
+
+    if !report.failed.is_empty() {
+        return Err(anyhow!(
+            "{} of {} parts failed to complete",
+            report.failed.len(),
+            report.parts
+        ));
+    }
+    Ok(())
+}
+
+/// Stored parts of a split swap, ordered by their index
+fn split_parts(swaps: Vec<StoredSwap>, split_id: &str) -> Result<Vec<StoredSwap>> {
+    let mut parts: Vec<StoredSwap> = swaps
+        .into_iter()
+        .filter(|swap| {
+            swap.split
+                .as_ref()
+                .is_some_and(|split| split.split_id == split_id)
+        })
+        .collect();
+    if parts.is_empty() {
+        return Err(anyhow!("Split swap not found: {}", split_id));
+    }
+    parts.sort_by_key(|swap| swap.split.as_ref().map(|split| split.index));
+    Ok(parts)
+}
+
+/// Where each part of a resumed split swap ended up
+#[derive(Debug, Serialize)]
+struct ResumeReport {
+    split_id: String,
+    parts: u32,
+    /// Destination escrow was already claimed, so its secret was not revealed again
+    already_filled: Vec<u32>,
+    /// Filled by this resume
+    filled: Vec<u32>,
+    /// Destination escrow was refunded and can no longer be filled
+    refunded: Vec<u32>,
+    /// Never got an escrow pair when the swap was started
+    missing: Vec<u32>,
+    failed: Vec<TrancheFailure>,
+}
+
+#[derive(Debug, Serialize)]
+struct TrancheFailure {
+    index: u32,
+    error: String,
+}
+
+impl ResumeReport {
+    fn is_completed(&self, index: u32) -> bool {
+        self.already_filled.contains(&index) || self.filled.contains(&index)
+    }
+
+    fn is_failed(&self, index: u32) -> bool {
+        self.refunded.contains(&index) || self.failed.iter().any(|f| f.index == index)
+    }
+}
+
+/// Fill the parts whose destination escrow is still open
+///
+/// Each part's destination escrow is read first; parts already claimed on-chain
+/// are skipped so `fill` never reveals the secret of a filled part again.
+async fn resume_split_parts<'a, S, F, Fut>(
+    parts: &'a [StoredSwap],
+    statuses: &S,
+    mut fill: F,
+) -> Result<ResumeReport>
+where
+    S: HtlcStatusSource + ?Sized,
+    F: FnMut(&'a StoredSwap) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let split = parts
+        .first()
+        .and_then(|part| part.split.clone())
+        .ok_or_else(|| anyhow!("Swap is not part of a split swap"))?;
+    let mut report = ResumeReport {
+        split_id: split.split_id,
+        parts: split.parts,
+        already_filled: Vec::new(),
+        filled: Vec::new(),
+        refunded: Vec::new(),
+        missing: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    let mut seen = Vec::new();
+    for part in parts {
+        let Some(index) = part.split.as_ref().map(|split| split.index) else {
+            continue;
+        };
+        seen.push(index);
+
+        let destination = statuses
+            .check_htlc(leg_htlc_id(part, &part.to_chain), &part.to_chain)
+            .await?;
+        match destination.status.as_str() {
+            "claimed" => report.already_filled.push(index),
+            "refunded" => report.refunded.push(index),
+            _ => match fill(part).await {
+                Ok(()) => report.filled.push(index),
+                Err(e) => report.failed.push(TrancheFailure {
+                    index,
+                    error: e.to_string(),
+                }),
+            },
+        }
+    }
+    report.missing = (0..report.parts).filter(|i| !seen.contains(i)).collect();
+
+    Ok(report)
+}
+
+/// Swap arguments to keep monitoring one stored part of a split swap
+fn resumed_part_args(part: &StoredSwap, args: &ResumeSwapArgs) -> SwapArgs {
+    SwapArgs {
+        from_chain: part.from_chain.clone(),
+        to_chain: part.to_chain.clone(),
+        from_token: part.from_token.clone(),
+        to_token: part.to_token.clone(),
+        amount: convert_wei_to_amount(part.src_amount, &part.from_token),
+        amount_usd: None,
+        from_address: part.from_address.clone(),
+        to_addresses: vec![WeightedRecipient::sole(&part.to_address)],
+        slippage: 1.0,
+        slippage_create: None,
+        slippage_claim: None,
+        timeout: part.timeout,
+        auto_claim: true,
+        verify_after: false,
+        auto_refund: false,
+        refund_notice: 300,
+        monitor_interval: args.monitor_interval,
+        dry_run: false,
+        chain_id: 84532,
+        limit_order_protocol: None,
+        evm_rpc: args.evm_rpc.clone(),
+        near_network: args.near_network.clone(),
+        fixed_rates: Vec::new(),
+        reference_rates: Vec::new(),
+        max_rate_deviation: 5.0,
+        price_source: "mock".to_string(),
+        price_feeds: Vec::new(),
+        max_price_age: DEFAULT_MAX_PRICE_AGE.as_secs(),
+        split: 1,
+        secret_index: None,
+        safety_deposit: part.safety_deposit,
+        safety_deposit_beneficiary: None,
+        output_unsigned: false,
+        escrow_factory: None,
+        escrow_recipient: None,
+        config: None,
+        amount_units: Some(part.src_amount),
+    }
+}
+
+fn resumed_part_result(part: &StoredSwap) -> SwapResult {
+    SwapResult {
+        swap_id: part.swap_id.clone(),
+        status: part.status.clone(),
+        secret_hash: part.secret_hash.clone(),
+        htlc_id: part.htlc_id.clone(),
+        order_hash: part.order_hash.clone(),
+        min_received: part.dst_amount,
+        transactions: Vec::new(),
+        next_steps: part
+            .split
+            .iter()
+            .map(|split| {
+                format!(
+                    "Resume with: fusion-cli swap resume --split-id {}",
+                    split.split_id
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Monitor for both legs, falling back to ETHEREUM_RPC_URL and then Base Sepolia
+fn swap_monitor(evm_rpc: Option<String>, near_network: &str) -> HTLCMonitor {
+    let rpc_url = evm_rpc
+        .or_else(|| std::env::var("ETHEREUM_RPC_URL").ok())
+        .unwrap_or_else(|| "https://sepolia.base.org".to_string());
+    HTLCMonitor::new(rpc_url, near_network.to_string())
+}
+
+/// Print the atomicity report of a stored swap, failing if the legs settled differently
+async fn check_swap_atomicity<S: HtlcStatusSource + ?Sized>(
+    swap: &StoredSwap,
+    statuses: &S,
+) -> Result<()> {
+    let source = statuses
+        .check_htlc(leg_htlc_id(swap, &swap.from_chain), &swap.from_chain)
+        .await?;
+    let target = statuses
+        .check_htlc(leg_htlc_id(swap, &swap.to_chain), &swap.to_chain)
+        .await?;
+
+    let report = verify_atomicity(&swap.secret_hash, &source, &target);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({
+            "swap_id": swap.swap_id,
+            "result": report.verdict,
+            "source": {"chain": source.chain, "htlc_id": source.htlc_id, "status": source.status},
+            "target": {"chain": target.chain, "htlc_id": target.htlc_id, "status": target.status},
+            "details": report.details,
+        }))?
+    );
+
+    if report.verdict == AtomicityVerdict::Fail {
+        return Err(anyhow!("Swap {} is not atomic", swap.swap_id));
+    }
+    Ok(())
+}
+
+/// On-chain identifier of the swap's escrow on `chain`
+fn leg_htlc_id<'a>(swap: &'a StoredSwap, chain: &str) -> &'a str {
+    let id = if chain == "near" {
+        swap.htlc_id.as_deref()
+    } else {
+        swap.order_hash.as_deref()
+    };
+    id.unwrap_or(&swap.swap_id)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum AtomicityVerdict {
+    Pass,
+    Fail,
+    /// At least one leg has not reached a final state yet
+    Incomplete,
+}
+
+#[derive(Debug)]
+struct AtomicityReport {
+    verdict: AtomicityVerdict,
+    details: Vec<String>,
+}
+
+/// 32 bytes of hex with an optional 0x prefix
+fn decode_bytes32(value: &str) -> Option<[u8; 32]> {
+    hex::decode(value.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+}
+
+/// Both legs must be claimed with the swap's secret, or both refunded
+fn verify_atomicity(
+    secret_hash: &str,
+    source: &crate::htlc_monitor::HTLCStatus,
+    target: &crate::htlc_monitor::HTLCStatus,
+) -> AtomicityReport {
+    let mut details = Vec::new();
+
+    // Any revealed secret must open the swap's hashlock
+    for leg in [source, target] {
+        let Some(secret) = &leg.secret else {
+            continue;
+        };
+        let matches = match (decode_bytes32(secret), decode_bytes32(secret_hash)) {
+            (Some(secret), Some(hash)) => secret_eq(&hash_secret(&secret), &hash),
+            _ => false,
+        };
+        if !matches {
+            details.push(format!(
+                "{} leg was claimed with a secret that does not match hash {}",
+                leg.chain, secret_hash
+            ));
+        }
+    }
+    if let (Some(a), Some(b)) = (&source.secret, &target.secret) {
+        let same_secret = match (decode_bytes32(a), decode_bytes32(b)) {
+            (Some(a), Some(b)) => secret_eq(&a, &b),
+            _ => a.trim_start_matches("0x") == b.trim_start_matches("0x"),
+        };
+        if !same_secret {
+            details.push("Legs were claimed with different secrets".to_string());
+        }
+    }
+
+    let verdict = match (source.status.as_str(), target.status.as_str()) {
+        _ if !details.is_empty() => AtomicityVerdict::Fail,
+        ("claimed", "claimed") => {
+            details.push("Both legs were claimed".to_string());
+            AtomicityVerdict::Pass
+        }
+        ("refunded", "refunded") => {
+            details.push("Both legs were refunded".to_string());
+            AtomicityVerdict::Pass
+        }
+        ("claimed", "refunded") | ("refunded", "claimed") => {
+            let (claimed, refunded) = if source.status == "claimed" {
+                (source, target)
+            } else {
+                (target, source)
+            };
+            details.push(format!(
+                "{} leg was claimed but {} leg was refunded",
+                claimed.chain, refunded.chain
+            ));
+            AtomicityVerdict::Fail
+        }
+        (source_status, target_status) => {
+            details.push(format!(
+                "Swap has not settled: {} leg is {}, {} leg is {}",
+                source.chain, source_status, target.chain, target_status
+            ));
+            AtomicityVerdict::Incomplete
+        }
+    };
+
+    AtomicityReport { verdict, details }
+}
+
+fn filter_and_sort_swaps(swaps: Vec<StoredSwap>, args: &ListSwapsArgs) -> Result<Vec<StoredSwap>> {
+    if let Some(state) = &args.state {
+        if !["pending", "completed", "failed"].contains(&state.as_str()) {
+            return Err(anyhow!(
+                "Invalid state: must be pending, completed or failed"
+            ));
+        }
+    }
+
+    let mut swaps: Vec<StoredSwap> = swaps
+        .into_iter()
+        .filter(|swap| args.state.as_ref().is_none_or(|s| &swap.status == s))
+        .filter(|swap| {
+            args.from_chain
+                .as_ref()
+                .is_none_or(|c| &swap.from_chain == c)
+        })
+        .filter(|swap| args.to_chain.as_ref().is_none_or(|c| &swap.to_chain == c))
+        .collect();
+
+    // Oldest first; ties keep the order they were stored in
+    match args.sort.as_str() {
+        "created" => swaps.sort_by_key(|swap| swap.created_at),
+        "amount" => swaps.sort_by_key(|swap| swap.src_amount),
+        _ => return Err(anyhow!("Invalid sort: must be created or amount")),
+    }
+
+    Ok(swaps)
+}
+
+fn parse_evm_address(name: &str, address: &str) -> Result<Address> {
+    validate_ethereum_address(address)?;
+    address
+        .parse::<Address>()
+        .map_err(|e| anyhow!("Invalid {} address {}: {}", name, address, e))
+}
+
+fn parse_bytes32(name: &str, value: &str) -> Result<H256> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))
+        .map_err(|_| anyhow!("Invalid hexadecimal in {}", name))?;
+    if bytes.len() != 32 {
+        return Err(anyhow!("{} must be 32 bytes", name));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Safety deposit in wei for an EVM leg, defaulting to the one chosen at swap time
+fn leg_safety_deposit(swap: &StoredSwap, override_wei: Option<u128>) -> U256 {
+    U256::from(override_wei.unwrap_or_else(|| convert_amount_to_wei(swap.safety_deposit, "ETH")))
+}
+
+/// Build the escrow Immutables for one leg of a stored swap
+///
+/// The same values must be passed to `createDstEscrow` (or used by the source
+/// escrow deployment) for the predicted address to match.
+fn leg_immutables(
+    swap: &StoredSwap,
+    leg: &str,
+    taker: Address,
+    safety_deposit: U256,
+    deployed_at: u32,
+) -> Result<Immutables> {
+    let (chain, maker, token, amount) = match leg {
+        "src" => (
+            &swap.from_chain,
+            &swap.from_address,
+            &swap.from_token,
+            Some(swap.src_amount),
+        ),
+        "dst" => (
+            &swap.to_chain,
+            &swap.to_address,
+            &swap.to_token,
+            swap.dst_amount,
+        ),
+        _ => return Err(anyhow!("Invalid leg: must be src or dst")),
+    };
+
+    if chain != "ethereum" {
+        return Err(anyhow!(
+            "The {} leg of swap {} is on {}; Immutables only apply to EVM escrows",
+            leg,
+            swap.swap_id,
+            chain
+        ));
+    }
+
+    let order_hash = swap
+        .order_hash
+        .as_deref()
+        .ok_or_else(|| anyhow!("Swap {} has no order hash", swap.swap_id))?;
+    let amount =
+        amount.ok_or_else(|| anyhow!("Swap {} has no {} amount recorded", swap.swap_id, leg))?;
+    let timeout = u32::try_from(swap.timeout)
+        .map_err(|_| anyhow!("Swap timeout {} does not fit in a timelock", swap.timeout))?;
+
+    Ok(Immutables {
+        order_hash: parse_bytes32("order hash", order_hash)?,
+        hashlock: parse_bytes32("secret hash", &swap.secret_hash)?,
+        maker: parse_evm_address("maker", maker)?,
+        taker,
+        token: parse_evm_address("token", &evm_token_address(token)?)?,
+        amount: U256::from(amount),
+        safety_deposit,
+        timelocks: Timelocks::from_timeout(timeout).with_deployed_at(deployed_at),
+    })
+}
+
+/// Get the decimal places for a token
+fn get_token_decimals(token: &str) -> u8 {
+    match token {
+        "NEAR" => 24,
+        "USDC" => 6,
+        "USDT" => 6,
+        "DAI" => 18,
+        "ETH" | "WETH" => 18,
+        addr if addr.starts_with("0x") => 18, // Default for unknown ERC20 tokens
+        _ => 18,                              // Default
+    }
+}
+
+/// Convert human-readable amount to smallest unit (wei/yocto)
+fn convert_amount_to_wei(amount: f64, token: &str) -> u128 {
+    let decimals = get_token_decimals(token);
 
     // Handle edge case for very large decimals (like NEAR's 24)
     // Split calculation to avoid floating point overflow
@@ -748,212 +2833,1472 @@ fn convert_amount_to_wei(amount: f64, token: &str) -> u128 {
         let base_multiplier = 10u128.pow(18);
         let extra_multiplier = 10u128.pow(extra_decimals as u32);
 
-        // First scale by 10^18, then by the remaining decimals
-        let base_scaled = (amount * (base_multiplier as f64)).round() as u128;
-        base_scaled.saturating_mul(extra_multiplier)
-    } else {
-        let multiplier = 10u128.pow(decimals as u32);
-        let scaled = amount * (multiplier as f64);
+        // First scale by 10^18, then by the remaining decimals
+        let base_scaled = (amount * (base_multiplier as f64)).round() as u128;
+        base_scaled.saturating_mul(extra_multiplier)
+    } else {
+        let multiplier = 10u128.pow(decimals as u32);
+        let scaled = amount * (multiplier as f64);
+
+        // Round to nearest integer to handle floating point precision
+        scaled.round() as u128
+    }
+}
+
+/// Convert smallest unit (wei/yocto) to human-readable amount
+pub(crate) fn convert_wei_to_amount(wei: u128, token: &str) -> f64 {
+    let decimals = get_token_decimals(token);
+    let divisor = 10u128.pow(decimals as u32);
+
+    (wei as f64) / (divisor as f64)
+}
+
+async fn calculate_taking_amount(args: &SwapArgs, slippage_bps: u16) -> Result<u128> {
+    // Use price oracle (or pinned rates) to calculate expected output
+    taking_amount_with(&price_converter(args)?, args, slippage_bps).await
+}
+
+async fn taking_amount_with<O: PriceOracle>(
+    converter: &PriceConverter<O>,
+    args: &SwapArgs,
+    slippage_bps: u16,
+) -> Result<u128> {
+    let amount = args.amount;
+    let from_token = args.from_token.as_str();
+    let to_token = args.to_token.as_str();
+
+    let from_decimals = get_token_decimals(from_token);
+    let to_decimals = get_token_decimals(to_token);
+
+    let from_amount = convert_amount_to_wei(amount, from_token);
+    let expected_amount = converter
+        .convert_amount(
+            from_amount,
+            from_token,
+            from_decimals,
+            to_token,
+            to_decimals,
+        )
+        .await?;
+
+    // Apply slippage
+    let slippage_factor = 1.0 - (slippage_bps as f64 / 10000.0);
+    Ok((expected_amount as f64 * slippage_factor) as u128)
+}
+
+/// Choice offered when Ctrl-C interrupts swap monitoring
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InterruptAction {
+    Continue,
+    /// Refund the source HTLC now; only offered once its timelock has passed
+    Refund,
+    Exit,
+}
+
+/// Asks what to do after monitoring was interrupted
+trait InterruptPrompt {
+    fn choose(&self, refund_eligible: bool) -> InterruptAction;
+}
+
+/// Reads the choice from the terminal, exiting when stdin is not interactive
+struct TerminalPrompt;
+
+impl InterruptPrompt for TerminalPrompt {
+    fn choose(&self, refund_eligible: bool) -> InterruptAction {
+        use std::io::{BufRead, IsTerminal};
+
+        let stdin = std::io::stdin();
+        if !stdin.is_terminal() {
+            return InterruptAction::Exit;
+        }
+
+        loop {
+            eprintln!("Monitoring paused. [c]ontinue monitoring, [e]xit and resume later");
+            if refund_eligible {
+                eprintln!("The timelock has passed: [r]efund the source HTLC now");
+            }
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                return InterruptAction::Exit;
+            }
+            match line.trim() {
+                "c" | "continue" => return InterruptAction::Continue,
+                "r" | "refund" if refund_eligible => return InterruptAction::Refund,
+                "e" | "exit" => return InterruptAction::Exit,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// How monitoring ended
+#[derive(Debug)]
+enum MonitorOutcome {
+    Finished(Result<SwapRunOutcome>),
+    Refund,
+    Exit,
+}
+
+/// Run the monitor until it finishes or the user stops it after an interrupt
+///
+/// Every interrupt persists the swap record before prompting, so exiting
+/// leaves enough state for `swap verify`, `swap list` and a later refund.
+async fn monitor_with_interrupts<M, MF, I, IF>(
+    mut monitoring: M,
+    mut interrupt: I,
+    store: &SwapStore,
+    swap_id: &str,
+    prompt: &dyn InterruptPrompt,
+) -> Result<MonitorOutcome>
+where
+    M: FnMut() -> MF,
+    MF: std::future::Future<Output = Result<SwapRunOutcome>>,
+    I: FnMut() -> IF,
+    IF: std::future::Future<Output = ()>,
+{
+    loop {
+        tokio::select! {
+            outcome = monitoring() => return Ok(MonitorOutcome::Finished(outcome)),
+            _ = interrupt() => {}
+        }
+
+        let swap = store.get(swap_id)?;
+        store.save(swap.clone())?;
+
+        let refund_at = swap.created_at + swap.timeout;
+        let refund_eligible = chrono::Utc::now().timestamp() as u64 >= refund_at;
+        match prompt.choose(refund_eligible) {
+            InterruptAction::Continue => continue,
+            InterruptAction::Refund => return Ok(MonitorOutcome::Refund),
+            InterruptAction::Exit => {
+                println!(
+                    "{}",
+                    json!({
+                        "status": "Monitoring stopped",
+                        "swap_id": swap_id,
+                        "swap_status": swap.status,
+                        "refund_available_at": refund_at,
+                        "instructions": [
+                            format!("Check both legs with `fusion-cli swap verify --swap-id {}`", swap_id),
+                            "Refund the source HTLC once refund_available_at has passed".to_string(),
+                        ],
+                    })
+                );
+                return Ok(MonitorOutcome::Exit);
+            }
+        }
+    }
+}
+
+/// How long a swap may sit in one stage before the watchdog calls it stuck
+fn stuck_budget(plan: &SwapPlan) -> Duration {
+    parse_estimated_time(&plan.estimated_time)
+        .unwrap_or(DEFAULT_STAGE_ESTIMATE)
+        .mul_f64(STUCK_BUDGET_MULTIPLIER)
+}
+
+async fn monitor_and_claim(
+    args: &SwapArgs,
+    result: &SwapResult,
+    stuck_budget: Duration,
+) -> Result<()> {
+    println!(
+        "{}",
+        json!({
+            "status": "Monitoring swap execution",
+            "swap_id": &result.swap_id,
+            "monitoring_interval": args.monitor_interval
+        })
+    );
+
+    let monitor = swap_monitor(args.evm_rpc.clone(), &args.near_network);
+
+    // Determine monitoring parameters based on swap direction
+    let direction = swap_direction(args)?;
+    let order_hash = result.order_hash.as_ref().unwrap_or(&result.swap_id);
+    let htlc_id = result.htlc_id.as_ref().unwrap_or(&result.swap_id);
+    let (source_htlc, target_htlc) = if direction.is_evm_to_near() {
+        // For ETH->NEAR, we monitor Ethereum order and NEAR HTLC
+        (order_hash, htlc_id)
+    } else if direction.is_near_to_evm() {
+        // For NEAR->ETH, we monitor NEAR HTLC and Ethereum order
+        (htlc_id, order_hash)
+    } else {
+        return Err(anyhow!("Unsupported swap direction"));
+    };
+    let (source_chain, target_chain) = (args.from_chain.as_str(), args.to_chain.as_str());
+
+    let refund_gate = args.auto_refund.then(|| {
+        // The stored record has the creation time; a missing one means the swap just started
+        let refund_at = SwapStore::from_env()
+            .get(&result.swap_id)
+            .map(|swap| swap.created_at + swap.timeout)
+            .unwrap_or_else(|_| chrono::Utc::now().timestamp() as u64 + args.timeout);
+        RefundGate::new(
+            source_htlc,
+            source_chain,
+            refund_at,
+            Duration::from_secs(args.refund_notice),
+        )
+    });
+
+    // Get the secret for claiming (this would be securely stored in production)
+    let secret = result.secret_hash.clone(); // In production, this would be the actual secret
+
+    let watchdog = StuckWatchdog::new(stuck_budget);
+    let intervals = crate::htlc_monitor::PollIntervals::from_block_times(
+        source_chain,
+        target_chain,
+        args.monitor_interval,
+        &ExecutionPathOptimizer::new(),
+    );
+
+    // Execute bidirectional monitoring, pausing on Ctrl-C; "continue" starts a fresh poll
+    let outcome = monitor_with_interrupts(
+        || {
+            monitor.execute_bidirectional_swap(
+                source_chain,
+                target_chain,
+                source_htlc,
+                target_htlc,
+                &secret,
+                intervals,
+                watchdog.clone(),
+                refund_gate.clone(),
+                result.min_received,
+                &StdoutObserver,
+            )
+        },
+        || async {
+            // Without a signal handler, never interrupt instead of spinning
+            if tokio::signal::ctrl_c().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        },
+        &SwapStore::from_env(),
+        &result.swap_id,
+        &TerminalPrompt,
+    )
+    .await?;
+
+    let outcome = match outcome {
+        MonitorOutcome::Finished(outcome) => outcome,
+        MonitorOutcome::Exit => return Ok(()),
+        MonitorOutcome::Refund => {
+            let tx = monitor.refund_htlc(source_htlc, source_chain).await?;
+            println!(
+                "{}",
+                json!({
+                    "status": "Source HTLC refunded",
+                    "swap_id": &result.swap_id,
+                    "htlc_id": source_htlc,
+                    "chain": source_chain,
+                    "transaction": tx,
+                })
+            );
+            return Err(anyhow!(
+                "Swap cancelled: source HTLC {} was refunded",
+                source_htlc
+            ));
+        }
+    };
+
+    match outcome {
+        // Reported by the monitor; the swap stays pending for a manual claim or refund
+        Ok(SwapRunOutcome::BelowMinimum { .. }) => {}
+        Ok(SwapRunOutcome::Completed) => {
+            // `swap status` reports the claim steps from the stored state
+            let store = SwapStore::from_env();
+            if let Ok(swap) = store.get(&result.swap_id) {
+                store.save(StoredSwap {
+                    status: "completed".to_string(),
+                    ..swap
+                })?;
+            }
+            println!(
+                "{}",
+                json!({
+                    "status": "Swap completed successfully",
+                    "swap_id": &result.swap_id,
+                    "source_chain": source_chain,
+                    "target_chain": target_chain
+                })
+            );
+        }
+        Err(e) => {
+            println!(
+                "{}",
+                json!({
+                    "status": "Swap monitoring failed",
+                    "error": e.to_string(),
+                    "manual_action_required": true,
+                    "instructions": result.next_steps
+                })
+            );
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htlc_monitor::HTLCStatus;
+    use fusion_core::chains::ethereum::balance::BalanceError;
+
+    #[test]
+    fn test_token_decimals() {
+        assert_eq!(get_token_decimals("NEAR"), 24);
+        assert_eq!(get_token_decimals("ETH"), 18);
+        assert_eq!(get_token_decimals("WETH"), 18);
+        assert_eq!(get_token_decimals("USDC"), 6);
+        assert_eq!(get_token_decimals("USDT"), 6);
+        assert_eq!(get_token_decimals("DAI"), 18);
+        assert_eq!(get_token_decimals("0x1234567890abcdef"), 18); // Unknown token
+    }
+
+    #[test]
+    fn test_amount_conversion() {
+        // Test ETH conversion (18 decimals)
+        assert_eq!(convert_amount_to_wei(1.0, "ETH"), 1_000_000_000_000_000_000);
+        assert_eq!(convert_amount_to_wei(0.001, "ETH"), 1_000_000_000_000_000);
+
+        // Test NEAR conversion (24 decimals)
+        assert_eq!(
+            convert_amount_to_wei(1.0, "NEAR"),
+            1_000_000_000_000_000_000_000_000
+        );
+        assert_eq!(
+            convert_amount_to_wei(0.001, "NEAR"),
+            1_000_000_000_000_000_000_000
+        );
+
+        // Test USDC conversion (6 decimals)
+        assert_eq!(convert_amount_to_wei(1.0, "USDC"), 1_000_000);
+        assert_eq!(convert_amount_to_wei(0.001, "USDC"), 1_000);
+        assert_eq!(convert_amount_to_wei(1000.0, "USDC"), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_wei_to_amount_conversion() {
+        // Test ETH conversion
+        assert_eq!(convert_wei_to_amount(1_000_000_000_000_000_000, "ETH"), 1.0);
+        assert_eq!(convert_wei_to_amount(1_000_000_000_000_000, "ETH"), 0.001);
+
+        // Test NEAR conversion
+        assert_eq!(
+            convert_wei_to_amount(1_000_000_000_000_000_000_000_000, "NEAR"),
+            1.0
+        );
+
+        // Test USDC conversion
+        assert_eq!(convert_wei_to_amount(1_000_000, "USDC"), 1.0);
+        assert_eq!(convert_wei_to_amount(1_000, "USDC"), 0.001);
+    }
+
+    #[test]
+    fn test_precision_handling() {
+        // Test that we handle floating point precision correctly
+        let amount = 0.123456789;
+        let wei = convert_amount_to_wei(amount, "ETH");
+        let back = convert_wei_to_amount(wei, "ETH");
+
+        // Should be close within floating point precision
+        assert!((amount - back).abs() < 0.000000001);
+
+        // Test with USDC (fewer decimals)
+        let usdc_amount = 1234.56;
+        let usdc_wei = convert_amount_to_wei(usdc_amount, "USDC");
+        assert_eq!(usdc_wei, 1_234_560_000); // 1234.56 * 10^6
+
+        let usdc_back = convert_wei_to_amount(usdc_wei, "USDC");
+        assert!((usdc_amount - usdc_back).abs() < 0.01);
+    }
+
+    fn stored_near_to_ethereum_swap() -> StoredSwap {
+        StoredSwap {
+            swap_id: "swap_0011223344556677".to_string(),
+            from_chain: "near".to_string(),
+            to_chain: "ethereum".to_string(),
+            from_token: "NEAR".to_string(),
+            to_token: "USDC".to_string(),
+            from_address: "alice.near".to_string(),
+            to_address: "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950".to_string(),
+            src_amount: 10_000_000_000_000_000_000_000_000,
+            dst_amount: Some(49_500_000),
+            secret_hash: "11".repeat(32),
+            order_hash: Some(format!("0x{}", "22".repeat(32))),
+            htlc_id: Some("escrow_1".to_string()),
+            timeout: 3600,
+            status: "pending".to_string(),
+            created_at: 1_700_000_000,
+            safety_deposit: 0.0,
+            split: None,
+            transactions: Vec::new(),
+        }
+    }
+
+    fn list_args(state: Option<&str>, sort: &str) -> ListSwapsArgs {
+        ListSwapsArgs {
+            state: state.map(str::to_string),
+            from_chain: None,
+            to_chain: None,
+            sort: sort.to_string(),
+        }
+    }
+
+    fn swap_at(swap_id: &str, status: &str, created_at: u64, src_amount: u128) -> StoredSwap {
+        StoredSwap {
+            swap_id: swap_id.to_string(),
+            status: status.to_string(),
+            created_at,
+            src_amount,
+            ..stored_near_to_ethereum_swap()
+        }
+    }
+
+    #[test]
+    fn test_list_filters_by_state_and_sorts_by_created() {
+        let swaps = vec![
+            swap_at("swap_c", "pending", 300, 1),
+            swap_at("swap_a", "pending", 100, 3),
+            swap_at("swap_done", "completed", 50, 2),
+            swap_at("swap_b", "pending", 200, 2),
+        ];
+
+        let listed =
+            filter_and_sort_swaps(swaps.clone(), &list_args(Some("pending"), "created")).unwrap();
+        let ids: Vec<&str> = listed.iter().map(|s| s.swap_id.as_str()).collect();
+        assert_eq!(ids, vec!["swap_a", "swap_b", "swap_c"]);
+
+        let listed = filter_and_sort_swaps(swaps, &list_args(None, "amount")).unwrap();
+        let ids: Vec<&str> = listed.iter().map(|s| s.swap_id.as_str()).collect();
+        assert_eq!(ids, vec!["swap_c", "swap_done", "swap_b", "swap_a"]);
+    }
+
+    #[test]
+    fn test_list_filters_by_chain_and_rejects_unknown_options() {
+        let mut ethereum_swap = swap_at("swap_eth", "pending", 1, 1);
+        ethereum_swap.from_chain = "ethereum".to_string();
+        ethereum_swap.to_chain = "near".to_string();
+        let swaps = vec![ethereum_swap, swap_at("swap_near", "pending", 2, 1)];
+
+        let mut args = list_args(None, "created");
+        args.from_chain = Some("near".to_string());
+        let listed = filter_and_sort_swaps(swaps.clone(), &args).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].swap_id, "swap_near");
+
+        assert!(
+            filter_and_sort_swaps(swaps.clone(), &list_args(Some("stuck"), "created")).is_err()
+        );
+        assert!(filter_and_sort_swaps(swaps, &list_args(None, "size")).is_err());
+    }
+
+    #[test]
+    fn test_dst_leg_immutables() {
+        let swap = stored_near_to_ethereum_swap();
+        let taker = Address::repeat_byte(0x44);
+
+        let immutables =
+            leg_immutables(&swap, "dst", taker, U256::from(1_000), 1_700_000_100).unwrap();
+
+        assert_eq!(
+            immutables.maker,
+            swap.to_address.parse::<Address>().unwrap()
+        );
+        assert_eq!(immutables.taker, taker);
+        assert_eq!(
+            immutables.token,
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(immutables.amount, U256::from(49_500_000u64));
+        assert_eq!(immutables.hashlock, H256::repeat_byte(0x11));
+        assert_eq!(immutables.order_hash, H256::repeat_byte(0x22));
+        assert_eq!(immutables.timelocks.deployed_at, 1_700_000_100);
+        assert_eq!(immutables.timelocks.src_cancellation, 3600);
+    }
+
+    #[test]
+    fn test_weighted_recipients_split_near_escrows() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.to_addresses = vec![
+            parse_weighted_recipient("alice.near:60").unwrap(),
+            parse_weighted_recipient("bob.near:40").unwrap(),
+        ];
+        assert!(validate_swap_inputs(&args).is_ok());
+
+        let calls = near_escrow_calls(&args, "hash", 2 * 10u128.pow(24));
+        let split: Vec<(&str, u128)> = calls
+            .iter()
+            .map(|call| (call.args["recipient"].as_str().unwrap(), call.deposit))
+            .collect();
+        assert_eq!(
+            split,
+            vec![
+                ("alice.near", 12 * 10u128.pow(23)),
+                ("bob.near", 8 * 10u128.pow(23))
+            ]
+        );
+        assert!(calls.iter().all(|call| call.args["secret_hash"] == "hash"));
+
+        // Weights must sum to 100
+        args.to_addresses[1].weight = 30;
+        let err = validate_swap_inputs(&args).unwrap_err();
+        assert!(err.to_string().contains("must sum to 100, got 90"));
+
+        // Every recipient must be valid on the destination chain
+        args.to_addresses[1] = parse_weighted_recipient("0xabc:40").unwrap();
+        assert!(validate_swap_inputs(&args).is_err());
+
+        assert!(parse_weighted_recipient("alice.near:0").is_err());
+        assert!(parse_weighted_recipient("alice.near:abc").is_err());
+        assert_eq!(
+            parse_weighted_recipient("alice.near").unwrap(),
+            WeightedRecipient::sole("alice.near")
+        );
+    }
+
+    #[test]
+    fn test_safety_deposit_reaches_both_legs() {
+        // NEAR leg: FusionHTLC takes the deposit on top of the amount
+        let mut args = ethereum_to_near_args(&[]);
+        args.to_addresses = vec![WeightedRecipient::sole("alice.testnet")];
+        args.safety_deposit = 0.5;
+        args.safety_deposit_beneficiary = Some("bob.testnet".to_string());
+        assert!(validate_swap_inputs(&args).is_ok());
+
+        let call = near_escrow_calls(&args, "hash", 2 * 10u128.pow(24)).remove(0);
+        assert_eq!(call.contract, "fusion-htlc.testnet");
+        assert_eq!(call.deposit, 25 * 10u128.pow(23));
+        let params = &call.args["params"];
+        assert_eq!(params["safety_deposit"], "500000000000000000000000");
+        assert_eq!(params["safety_deposit_beneficiary"], "bob.testnet");
+        assert_eq!(params["amount"], "2000000000000000000000000");
+        // Destination stages: 900s exclusive, cancellable from 1800s
+        assert_eq!(params["finality_period"], 900);
+        assert_eq!(params["cancel_period"], 1800);
+
+        // EVM leg: the recorded deposit lands in the Immutables
+        let swap = StoredSwap {
+            safety_deposit: 0.5,
+            ..stored_near_to_ethereum_swap()
+        };
+        let immutables = leg_immutables(
+            &swap,
+            "dst",
+            Address::zero(),
+            leg_safety_deposit(&swap, None),
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            immutables.safety_deposit,
+            U256::from(500_000_000_000_000_000u128)
+        );
+        assert_eq!(leg_safety_deposit(&swap, Some(7)), U256::from(7));
+    }
+
+    #[test]
+    fn test_swap_without_safety_deposit_keeps_plain_htlc() {
+        let args = ethereum_to_near_args(&[]);
+        let call = near_escrow_calls(&args, "hash", 2 * 10u128.pow(24)).remove(0);
+        assert_eq!(call.contract, "htlc-v2.testnet");
+        assert_eq!(call.deposit, 2 * 10u128.pow(24));
+        assert!(call.args.get("params").is_none());
+
+        let mut args = ethereum_to_near_args(&[]);
+        args.safety_deposit_beneficiary = Some("bob.testnet".to_string());
+        assert!(validate_swap_inputs(&args).is_err());
+        args.safety_deposit = -1.0;
+        assert!(validate_swap_inputs(&args).is_err());
+    }
+
+    fn ethereum_to_near_args(fixed_rates: &[&str]) -> SwapArgs {
+        SwapArgs {
+            from_chain: "ethereum".to_string(),
+            to_chain: "near".to_string(),
+            from_token: "ETH".to_string(),
+            to_token: "NEAR".to_string(),
+            amount: 1.0,
+            amount_usd: None,
+            from_address: "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950".to_string(),
+            to_addresses: vec![WeightedRecipient::sole("alice.near")],
+            slippage: 0.0,
+            slippage_create: None,
+            slippage_claim: None,
+            timeout: 3600,
+            auto_claim: false,
+            verify_after: false,
+            auto_refund: false,
+            refund_notice: 300,
+            monitor_interval: 30,
+            dry_run: true,
+            chain_id: 84532,
+            limit_order_protocol: None,
+            evm_rpc: None,
+            near_network: "testnet".to_string(),
+            fixed_rates: fixed_rates.iter().map(|r| r.to_string()).collect(),
+            reference_rates: Vec::new(),
+            max_rate_deviation: 5.0,
+            price_source: "mock".to_string(),
+            price_feeds: Vec::new(),
+            max_price_age: DEFAULT_MAX_PRICE_AGE.as_secs(),
+            split: 1,
+            secret_index: None,
+            safety_deposit: 0.0,
+            safety_deposit_beneficiary: None,
+            output_unsigned: false,
+            escrow_factory: None,
+            escrow_recipient: None,
+            config: None,
+            amount_units: None,
+        }
+    }
+
+    #[test]
+    fn test_price_source_selection() {
+        let mut args = ethereum_to_near_args(&[]);
+        assert!(price_oracle(&args).is_ok());
+
+        args.price_source = "chainlink".to_string();
+        assert!(price_oracle(&args).is_err());
+        assert!(validate_swap_inputs(&args).is_err());
+
+        args.price_feeds = vec!["ETH=0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419".to_string()];
+        args.evm_rpc = Some("http://127.0.0.1:8545".to_string());
+        assert!(price_oracle(&args).is_ok());
+        assert!(validate_swap_inputs(&args).is_ok());
+        assert!(parse_price_feed("ETH").is_err());
+        assert!(parse_price_feed("ETH=0x1234").is_err());
+
+        args.price_source = "coingecko".to_string();
+        assert!(validate_swap_inputs(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_fixed_rate() {
+        assert_eq!(
+            parse_pair_rate("fixed rate", "ETH:NEAR=350.5").unwrap(),
+            ("ETH".to_string(), "NEAR".to_string(), 350.5)
+        );
+        assert!(parse_pair_rate("fixed rate", "ETH:NEAR").is_err());
+        assert!(parse_pair_rate("fixed rate", "ETH=1.0").is_err());
+        assert!(parse_pair_rate("fixed rate", ":NEAR=1.0").is_err());
+        assert!(parse_pair_rate("fixed rate", "ETH:NEAR=0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_taking_amount_uses_fixed_rate() {
+        // Oracle prices give 2000 / 5 = 400 NEAR per ETH
+        let from_oracle = calculate_taking_amount(&ethereum_to_near_args(&[]), 0)
+            .await
+            .unwrap();
+        assert!((convert_wei_to_amount(from_oracle, "NEAR") - 400.0).abs() < 1e-9);
+
+        let pinned = calculate_taking_amount(&ethereum_to_near_args(&["ETH:NEAR=250"]), 0)
+            .await
+            .unwrap();
+        assert!((convert_wei_to_amount(pinned, "NEAR") - 250.0).abs() < 1e-9);
+
+        // An override for another pair leaves ETH:NEAR on the oracle
+        let other_pair = calculate_taking_amount(&ethereum_to_near_args(&["NEAR:ETH=1"]), 0)
+            .await
+            .unwrap();
+        assert_eq!(other_pair, from_oracle);
+    }
+
+    #[tokio::test]
+    async fn test_amount_usd_converts_at_oracle_price() {
+        let mut oracle = MockPriceOracle::new();
+        oracle.set_price("ETH", 2500.0);
+        let mut args = ethereum_to_near_args(&[]);
+        args.amount = 0.0;
+        args.amount_usd = Some(100.0);
+        let converter = price_converter_with(&args, oracle).unwrap();
+
+        // $100 at $2500 per ETH
+        resolve_usd_amount(&mut args, &converter).await.unwrap();
+        assert!((args.amount - 0.04).abs() < 1e-12);
+        assert_eq!(
+            convert_amount_to_wei(args.amount, "ETH"),
+            40_000_000_000_000_000
+        );
+
+        let plan = create_swap_plan_with(&args, &converter).await.unwrap();
+        let conversion = plan.amount_conversion.unwrap();
+        assert_eq!(conversion.amount_usd, 100.0);
+        assert!((conversion.token_price_usd - 2500.0).abs() < 1e-9);
+        assert_eq!(conversion.token, "ETH");
+    }
+
+    #[tokio::test]
+    async fn test_amount_and_amount_usd_are_exclusive() {
+        let converter = PriceConverter::new(MockPriceOracle::new());
+        let mut args = ethereum_to_near_args(&[]);
+        args.amount_usd = Some(100.0);
+        let err = resolve_usd_amount(&mut args, &converter).await.unwrap_err();
+        assert!(err.to_string().contains("not both"));
+
+        args.amount = 0.0;
+        args.amount_usd = Some(-5.0);
+        assert!(resolve_usd_amount(&mut args, &converter).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reference_rate_rejects_bad_oracle_reading() {
+        // Oracle prices give 400 NEAR per ETH
+        let mut args = ethereum_to_near_args(&[]);
+        args.reference_rates = vec!["ETH:NEAR=390".to_string()];
+        assert!(calculate_taking_amount(&args, 0).await.is_ok());
+
+        // Even a dry run refuses to plan against a reading 20% off the reference
+        args.reference_rates = vec!["ETH:NEAR=500".to_string()];
+        let err = create_swap_plan(&args).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PriceError>(),
+            Some(PriceError::OutOfBounds { .. })
+        ));
+
+        args.max_rate_deviation = 25.0;
+        assert!(create_swap_plan(&args).await.is_ok());
+    }
+
+    struct FailingOracle;
+
+    #[async_trait::async_trait]
+    impl PriceOracle for FailingOracle {
+        async fn get_price(
+            &self,
+            _token_symbol: &str,
+        ) -> Result<fusion_core::price_oracle::PriceData> {
+            Err(anyhow!("oracle offline"))
+        }
+
+        async fn get_prices(
+            &self,
+            _token_symbols: &[&str],
+        ) -> Result<std::collections::HashMap<String, fusion_core::price_oracle::PriceData>>
+        {
+            Err(anyhow!("oracle offline"))
+        }
+
+        async fn supported_tokens(&self) -> Result<Vec<String>> {
+            Err(anyhow!("oracle offline"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_plan_tolerates_oracle_failure() {
+        let args = ethereum_to_near_args(&[]);
+        let converter = PriceConverter::new(FailingOracle);
+
+        let plan = create_swap_plan_with(&args, &converter).await.unwrap();
+        assert_eq!(plan.estimated_output, "unavailable");
+        assert_eq!(plan.steps.len(), 5);
+        assert!(plan
+            .validation_status
+            .warnings
+            .iter()
+            .any(|w| w.contains("Price oracle unavailable") && w.contains("oracle offline")));
+    }
+
+    #[tokio::test]
+    async fn test_execution_plan_requires_oracle() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.dry_run = false;
+        let converter = PriceConverter::new(FailingOracle);
+
+        let err = create_swap_plan_with(&args, &converter).await.unwrap_err();
+        assert!(err.to_string().contains("Price oracle unavailable"));
+
+        // A working oracle prices the output
+        let plan = create_swap_plan_with(&args, &price_converter(&args).unwrap())
+            .await
+            .unwrap();
+        assert_ne!(plan.estimated_output, "unavailable");
+        assert!(plan.estimated_output.ends_with(" NEAR"));
+    }
+
+    #[tokio::test]
+    async fn test_price_impact_matches_slippage() {
+        let mut oracle = MockPriceOracle::new();
+        oracle.set_price("ETH", 3000.0);
+        oracle.set_price("NEAR", 5.0);
+        let mut args = ethereum_to_near_args(&[]);
+        args.amount = 2.0;
+        args.slippage = 1.5;
+
+        let plan = create_swap_plan_with(&args, &PriceConverter::new(oracle))
+            .await
+            .unwrap();
+
+        assert_eq!(plan.oracle_rate, Some(600.0));
+        let effective = plan.effective_rate.unwrap();
+        assert!((effective - 591.0).abs() < 1e-6);
+        assert!((plan.price_impact_pct.unwrap() - 1.5).abs() < 1e-6);
+
+        // Without an oracle reading there is nothing to compare
+        let plan = create_swap_plan_with(&args, &PriceConverter::new(FailingOracle))
+            .await
+            .unwrap();
+        assert!(plan.effective_rate.is_none() && plan.price_impact_pct.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_claim_slippage_is_tighter_than_create_slippage() {
+        let mut oracle = MockPriceOracle::new();
+        oracle.set_price("ETH", 3000.0);
+        oracle.set_price("NEAR", 5.0);
+        let converter = PriceConverter::new(oracle);
+        let mut args = ethereum_to_near_args(&[]);
+        args.slippage = 5.0;
+        args.slippage_claim = Some(1.0);
+        assert!(validate_swap_inputs(&args).is_ok());
+        assert_eq!(args.create_slippage(), 5.0);
+
+        let create_min = taking_amount_with(&converter, &args, 500).await.unwrap();
+        let claim_min = taking_amount_with(&converter, &args, 100).await.unwrap();
+        assert!(create_min < claim_min);
+
+        // 590 NEAR is within 5% of the 600 NEAR quote but not within 1%
+        let mut target = leg_status("near", "pending", None);
+        target.amount = (590 * 10u128.pow(24)).to_string();
+        assert!((create_min..claim_min).contains(&target.amount.parse().unwrap()));
+
+        let outcome =
+            crate::htlc_monitor::claim_unless_below_minimum(&target, Some(claim_min), || async {
+                panic!("a short fill must not be claimed")
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            crate::htlc_monitor::SwapRunOutcome::BelowMinimum { .. }
+        ));
+
+        // The claim tolerance may only tighten
+        args.slippage_claim = Some(10.0);
+        assert!(validate_swap_inputs(&args).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_step_costs_sum_to_total_fees() {
+        let evm_to_near = ethereum_to_near_args(&[]);
+        let mut near_to_evm = ethereum_to_near_args(&[]);
+        near_to_evm.from_chain = "near".to_string();
+        near_to_evm.to_chain = "ethereum".to_string();
+        near_to_evm.from_token = "NEAR".to_string();
+        near_to_evm.to_token = "ETH".to_string();
 
-        // Round to nearest integer to handle floating point precision
-        scaled.round() as u128
+        for args in [evm_to_near, near_to_evm] {
+            let plan = create_swap_plan(&args).await.unwrap();
+            let costs: Vec<&StepCost> = plan
+                .steps
+                .iter()
+                .filter_map(|s| s.estimated_cost.as_ref())
+                .collect();
+            // Secret generation and monitoring are off-chain
+            assert_eq!(costs.len(), 3);
+
+            let step_total: f64 = costs.iter().map(|c| c.cost_usd.unwrap()).sum();
+            let total = plan.fees.total_usd.unwrap();
+            assert!((step_total - total).abs() < 1e-9);
+            assert_eq!(plan.fees.estimated_total, format!("~{:.4} USD", total));
+
+            // Only the order step carries the protocol fee
+            let protocol: Vec<f64> = costs.iter().filter_map(|c| c.protocol_fee_usd).collect();
+            let source_usd = if args.from_token == "ETH" {
+                2000.0
+            } else {
+                5.0
+            };
+            assert_eq!(protocol.len(), 1);
+            assert!((protocol[0] - args.amount * PROTOCOL_FEE_RATE * source_usd).abs() < 1e-9);
+        }
     }
-}
 
-/// Convert smallest unit (wei/yocto) to human-readable amount
-fn convert_wei_to_amount(wei: u128, token: &str) -> f64 {
-    let decimals = get_token_decimals(token);
-    let divisor = 10u128.pow(decimals as u32);
+    #[tokio::test]
+    async fn test_unpriced_steps_leave_total_unavailable() {
+        let args = ethereum_to_near_args(&[]);
+        let plan = create_swap_plan_with(&args, &PriceConverter::new(FailingOracle))
+            .await
+            .unwrap();
 
-    (wei as f64) / (divisor as f64)
-}
+        // Gas is still itemised in native units
+        let order = plan.steps[1].estimated_cost.as_ref().unwrap();
+        assert_eq!(order.gas, ESCROW_CREATION_GAS);
+        assert!((order.network_fee - 0.0003).abs() < 1e-12);
+        assert_eq!(plan.fees.total_usd, None);
+        assert_eq!(plan.fees.estimated_total, "unavailable");
+    }
 
-async fn calculate_taking_amount(
-    amount: f64,
-    from_token: &str,
-    to_token: &str,
-    slippage_bps: u16,
-) -> Result<u128> {
-    // Use price oracle to calculate expected output
-    let oracle = MockPriceOracle::new();
-    let converter = PriceConverter::new(oracle);
+    #[test]
+    fn test_tranche_amounts_sum_to_total() {
+        assert_eq!(tranche_amounts(10, 3), vec![3, 3, 4]);
+        assert_eq!(tranche_amounts(9, 3), vec![3, 3, 3]);
+        assert_eq!(tranche_amounts(5, 1), vec![5]);
+    }
 
-    let from_decimals = get_token_decimals(from_token);
-    let to_decimals = get_token_decimals(to_token);
+    #[tokio::test]
+    async fn test_split_tranches_escrow_exact_total() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.from_chain = "near".to_string();
+        args.to_chain = "ethereum".to_string();
+        args.from_token = "NEAR".to_string();
+        args.to_token = "ETH".to_string();
+        args.amount = 1.0;
+        args.split = 3;
 
-    let from_amount = convert_amount_to_wei(amount, from_token);
-    let expected_amount = converter
-        .convert_amount(
-            from_amount,
-            from_token,
-            from_decimals,
-            to_token,
-            to_decimals,
-        )
-        .await?;
+        // 1 NEAR does not split evenly, and f64 tranche amounts lose units
+        let mut escrowed = Vec::new();
+        for (_, tranche) in split_into_tranches(&args) {
+            let near_amount = near_leg_amount(&tranche).await.unwrap();
+            let deposits: u128 = near_escrow_calls(&tranche, "hash", near_amount)
+                .iter()
+                .map(|call| call.deposit)
+                .sum();
+            escrowed.push(deposits);
+        }
+        assert_eq!(
+            escrowed,
+            vec![
+                333_333_333_333_333_333_333_333,
+                333_333_333_333_333_333_333_333,
+                333_333_333_333_333_333_333_334,
+            ]
+        );
+        assert_eq!(escrowed.iter().sum::<u128>(), 10u128.pow(24));
+    }
 
-    // Apply slippage
-    let slippage_factor = 1.0 - (slippage_bps as f64 / 10000.0);
-    Ok((expected_amount as f64 * slippage_factor) as u128)
-}
+    #[tokio::test]
+    async fn test_split_swap_tracks_tranches_independently() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.amount = 1.0;
+        args.split = 3;
+        args.secret_index = Some(7);
 
-async fn monitor_and_claim(args: &SwapArgs, result: &SwapResult) -> Result<()> {
-    println!(
-        "{}",
-        json!({
-            "status": "Monitoring swap execution",
-            "swap_id": &result.swap_id,
-            "monitoring_interval": args.monitor_interval
+        let tranches = split_into_tranches(&args);
+        assert_eq!(tranches.len(), 3);
+        assert!(tranches.iter().all(|(_, t)| t.split == 1));
+        let indices: Vec<Option<u32>> = tranches.iter().map(|(_, t)| t.secret_index).collect();
+        assert_eq!(indices, vec![Some(7), Some(8), Some(9)]);
+        let total: u128 = tranches.iter().map(|(units, _)| units).sum();
+        assert_eq!(total, convert_amount_to_wei(1.0, "ETH"));
+        // The source escrows lock exactly the tranche units
+        let escrowed: u128 = tranches.iter().map(|(_, t)| t.source_amount_units()).sum();
+        assert_eq!(escrowed, total);
+
+        // The second tranche fails; the others keep their own swap IDs
+        let mut attempt = 0;
+        let result = run_tranches(&tranches, |_tranche| {
+            attempt += 1;
+            let current = attempt;
+            async move {
+                if current == 2 {
+                    return Err(anyhow!("escrow creation failed"));
+                }
+                let secret_hash = hash_secret(&generate_secret());
+                Ok(SwapResult {
+                    swap_id: format!("swap_{}", hex::encode(&secret_hash[..8])),
+                    status: "pending".to_string(),
+                    secret_hash: hex::encode(secret_hash),
+                    htlc_id: None,
+                    order_hash: None,
+                    min_received: None,
+                    transactions: vec![],
+                    next_steps: vec![],
+                })
+            }
         })
-    );
+        .await;
 
-    // Create HTLC monitor
-    let rpc_url = args
-        .evm_rpc
-        .as_ref()
-        .cloned()
-        .or_else(|| std::env::var("ETHEREUM_RPC_URL").ok())
-        .unwrap_or_else(|| "https://sepolia.base.org".to_string());
+        assert_eq!(result.status, "partially_completed");
+        let tracked: u128 = result
+            .tranches
+            .iter()
+            .map(|t| t.amount_units.parse::<u128>().unwrap())
+            .sum();
+        assert_eq!(tracked, total);
+        let statuses: Vec<&str> = result.tranches.iter().map(|t| t.status.as_str()).collect();
+        assert_eq!(statuses, vec!["pending", "failed", "pending"]);
+        assert_eq!(
+            result.tranches[1].error.as_deref(),
+            Some("escrow creation failed")
+        );
 
-    let monitor = crate::htlc_monitor::HTLCMonitor::new(rpc_url, args.near_network.clone());
+        let first = result.tranches[0].swap.as_ref().unwrap();
+        let third = result.tranches[2].swap.as_ref().unwrap();
+        assert_ne!(first.swap_id, third.swap_id);
+        assert_ne!(first.secret_hash, third.secret_hash);
+    }
 
-    // Determine monitoring parameters based on swap direction
-    let (source_chain, target_chain, source_htlc, target_htlc) =
-        match (args.from_chain.as_str(), args.to_chain.as_str()) {
-            ("ethereum", "near") => {
-                // For ETH->NEAR, we monitor Ethereum order and NEAR HTLC
-                (
-                    "ethereum",
-                    "near",
-                    result.order_hash.as_ref().unwrap_or(&result.swap_id),
-                    result.htlc_id.as_ref().unwrap_or(&result.swap_id),
-                )
-            }
-            ("near", "ethereum") => {
-                // For NEAR->ETH, we monitor NEAR HTLC and Ethereum order
-                (
-                    "near",
-                    "ethereum",
-                    result.htlc_id.as_ref().unwrap_or(&result.swap_id),
-                    result.order_hash.as_ref().unwrap_or(&result.swap_id),
-                )
-            }
-            _ => return Err(anyhow!("Unsupported swap direction")),
-        };
+    #[test]
+    fn test_limit_order_protocol_defaults_per_chain() {
+        let mut args = ethereum_to_near_args(&[]);
+        assert_eq!(
+            limit_order_protocol(&args).unwrap(),
+            "0x171C87724E720F2806fc29a010a62897B30fdb62"
+        );
 
-    // Get the secret for claiming (this would be securely stored in production)
-    let secret = result.secret_hash.clone(); // In production, this would be the actual secret
+        // Ethereum mainnet has no configured deployment, so the Base Sepolia one is not used
+        args.chain_id = 1;
+        let err = validate_swap_inputs(&args).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No limit order protocol configured for chain ID 1"));
 
-    // Execute bidirectional monitoring
-    match monitor
-        .execute_bidirectional_swap(
-            source_chain,
-            target_chain,
-            source_htlc,
-            target_htlc,
-            &secret,
-            args.monitor_interval,
-        )
-        .await
-    {
-        Ok(_) => {
-            println!(
-                "{}",
-                json!({
-                    "status": "Swap completed successfully",
-                    "swap_id": &result.swap_id,
-                    "source_chain": source_chain,
-                    "target_chain": target_chain
-                })
-            );
-        }
-        Err(e) => {
-            println!(
-                "{}",
-                json!({
-                    "status": "Swap monitoring failed",
-                    "error": e.to_string(),
-                    "manual_action_required": true,
-                    "instructions": result.next_steps
-                })
-            );
-            return Err(e);
-        }
+        args.limit_order_protocol = Some("0x111111125421cA6dc452d289314280a0f8842A65".to_string());
+        assert!(validate_swap_inputs(&args).is_ok());
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_l2_chains_are_accepted() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.from_chain = "arbitrum".to_string();
+        assert!(validate_swap_inputs(&args).is_ok());
+        args.from_chain = "optimism".to_string();
+        assert!(validate_swap_inputs(&args).is_ok());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        args.from_chain = "solana".to_string();
+        let err = validate_swap_inputs(&args).unwrap_err();
+        assert!(err.to_string().contains("Invalid from_chain"));
+    }
 
     #[test]
-    fn test_token_decimals() {
-        assert_eq!(get_token_decimals("NEAR"), 24);
-        assert_eq!(get_token_decimals("ETH"), 18);
-        assert_eq!(get_token_decimals("WETH"), 18);
-        assert_eq!(get_token_decimals("USDC"), 6);
-        assert_eq!(get_token_decimals("USDT"), 6);
-        assert_eq!(get_token_decimals("DAI"), 18);
-        assert_eq!(get_token_decimals("0x1234567890abcdef"), 18); // Unknown token
+    fn test_split_must_be_in_range() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.split = 0;
+        assert!(validate_swap_inputs(&args).is_err());
+        args.split = MAX_SPLIT_TRANCHES + 1;
+        assert!(validate_swap_inputs(&args).is_err());
+        args.split = 3;
+        assert!(validate_swap_inputs(&args).is_ok());
+    }
+
+    fn leg_status(chain: &str, status: &str, secret: Option<[u8; 32]>) -> HTLCStatus {
+        HTLCStatus {
+            htlc_id: format!("{}_htlc", chain),
+            chain: chain.to_string(),
+            status: status.to_string(),
+            secret: secret.map(hex::encode),
+            timeout: 3600,
+            recipient: "alice.testnet".to_string(),
+            amount: "1".to_string(),
+        }
     }
 
     #[test]
-    fn test_amount_conversion() {
-        // Test ETH conversion (18 decimals)
-        assert_eq!(convert_amount_to_wei(1.0, "ETH"), 1_000_000_000_000_000_000);
-        assert_eq!(convert_amount_to_wei(0.001, "ETH"), 1_000_000_000_000_000);
+    fn test_verify_both_claimed_passes() {
+        let secret = [5u8; 32];
+        let secret_hash = hex::encode(hash_secret(&secret));
 
-        // Test NEAR conversion (24 decimals)
-        assert_eq!(
-            convert_amount_to_wei(1.0, "NEAR"),
-            1_000_000_000_000_000_000_000_000
+        let report = verify_atomicity(
+            &secret_hash,
+            &leg_status("ethereum", "claimed", Some(secret)),
+            &leg_status("near", "claimed", Some(secret)),
+        );
+        assert_eq!(report.verdict, AtomicityVerdict::Pass);
+    }
+
+    #[test]
+    fn test_verify_both_refunded_passes() {
+        let report = verify_atomicity(
+            &hex::encode([0u8; 32]),
+            &leg_status("ethereum", "refunded", None),
+            &leg_status("near", "refunded", None),
+        );
+        assert_eq!(report.verdict, AtomicityVerdict::Pass);
+    }
+
+    #[test]
+    fn test_verify_mixed_claim_and_refund_fails() {
+        let report = verify_atomicity(
+            &hex::encode([0u8; 32]),
+            &leg_status("ethereum", "refunded", None),
+            &leg_status("near", "claimed", None),
         );
+        assert_eq!(report.verdict, AtomicityVerdict::Fail);
         assert_eq!(
-            convert_amount_to_wei(0.001, "NEAR"),
-            1_000_000_000_000_000_000_000
+            report.details,
+            vec!["near leg was claimed but ethereum leg was refunded".to_string()]
         );
+    }
 
-        // Test USDC conversion (6 decimals)
-        assert_eq!(convert_amount_to_wei(1.0, "USDC"), 1_000_000);
-        assert_eq!(convert_amount_to_wei(0.001, "USDC"), 1_000);
-        assert_eq!(convert_amount_to_wei(1000.0, "USDC"), 1_000_000_000);
+    #[test]
+    fn test_verify_rejects_wrong_secret_and_unsettled_legs() {
+        let secret_hash = hex::encode(hash_secret(&[5u8; 32]));
+
+        let report = verify_atomicity(
+            &secret_hash,
+            &leg_status("ethereum", "claimed", Some([6u8; 32])),
+            &leg_status("near", "claimed", Some([5u8; 32])),
+        );
+        assert_eq!(report.verdict, AtomicityVerdict::Fail);
+
+        let report = verify_atomicity(
+            &secret_hash,
+            &leg_status("ethereum", "pending", None),
+            &leg_status("near", "claimed", None),
+        );
+        assert_eq!(report.verdict, AtomicityVerdict::Incomplete);
     }
 
     #[test]
-    fn test_wei_to_amount_conversion() {
-        // Test ETH conversion
-        assert_eq!(convert_wei_to_amount(1_000_000_000_000_000_000, "ETH"), 1.0);
-        assert_eq!(convert_wei_to_amount(1_000_000_000_000_000, "ETH"), 0.001);
+    fn test_output_unsigned_requires_offline_details() {
+        let mut args = ethereum_to_near_args(&[]);
+        args.output_unsigned = true;
+        let err = validate_swap_inputs(&args).unwrap_err();
+        assert!(err.to_string().contains("--secret-index"));
 
-        // Test NEAR conversion
+        args.secret_index = Some(0);
+        let err = validate_swap_inputs(&args).unwrap_err();
+        assert!(err.to_string().contains("--evm-rpc"));
+
+        args.evm_rpc = Some("http://127.0.0.1:8545".to_string());
+        args.escrow_factory = Some("0x171C87724E720F2806fc29a010a62897B30fdb62".to_string());
+        args.escrow_recipient = Some("0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string());
+        assert!(validate_swap_inputs(&args).is_ok());
+
+        args.auto_claim = true;
+        assert!(validate_swap_inputs(&args).is_err());
+    }
+
+    /// Reports each leg of a swap in a fixed state
+    struct FixedLegs(Vec<HTLCStatus>);
+
+    #[async_trait::async_trait]
+    impl HtlcStatusSource for FixedLegs {
+        async fn check_htlc(&self, _htlc_id: &str, chain: &str) -> Result<HTLCStatus> {
+            self.0
+                .iter()
+                .find(|leg| leg.chain == chain)
+                .cloned()
+                .ok_or_else(|| anyhow!("No {} leg", chain))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_after_fails_on_mixed_outcome() {
+        let swap = stored_near_to_ethereum_swap();
+
+        // The NEAR leg was refunded while the Ethereum leg was claimed
+        let mixed = FixedLegs(vec![
+            leg_status("near", "refunded", None),
+            leg_status("ethereum", "claimed", None),
+        ]);
+        let err = check_swap_atomicity(&swap, &mixed).await.unwrap_err();
+        assert!(err.to_string().contains("is not atomic"));
+
+        let settled = FixedLegs(vec![
+            leg_status("near", "refunded", None),
+            leg_status("ethereum", "refunded", None),
+        ]);
+        assert!(check_swap_atomicity(&swap, &settled).await.is_ok());
+
+        let mut args = ethereum_to_near_args(&[]);
+        args.verify_after = true;
+        assert!(validate_swap_inputs(&args).is_err());
+        args.auto_claim = true;
+        assert!(validate_swap_inputs(&args).is_ok());
+    }
+
+    /// Reports the destination escrow of each part by its ID
+    struct PartLegs(std::collections::HashMap<String, &'static str>);
+
+    #[async_trait::async_trait]
+    impl HtlcStatusSource for PartLegs {
+        async fn check_htlc(&self, htlc_id: &str, chain: &str) -> Result<HTLCStatus> {
+            let status = self.0.get(htlc_id).copied().unwrap_or("pending");
+            Ok(leg_status(chain, status, None))
+        }
+    }
+
+    /// Records every batched read, answering each HTLC as pending
+    #[derive(Default)]
+    struct BatchLog(std::sync::Mutex<Vec<(String, Vec<String>)>>);
+
+    #[async_trait::async_trait]
+    impl HtlcStatusSource for BatchLog {
+        async fn check_htlc(&self, _htlc_id: &str, _chain: &str) -> Result<HTLCStatus> {
+            Err(anyhow!("status_all must read HTLCs in batches"))
+        }
+
+        async fn check_htlcs(&self, htlc_ids: &[String], chain: &str) -> Result<Vec<HTLCStatus>> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((chain.to_string(), htlc_ids.to_vec()));
+            Ok(htlc_ids
+                .iter()
+                .map(|id| HTLCStatus {
+                    htlc_id: id.clone(),
+                    ..leg_status(chain, "pending", None)
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_all_batches_one_query_per_chain() {
+        let swaps: Vec<StoredSwap> = (1..=3)
+            .map(|i| StoredSwap {
+                swap_id: format!("swap_{}", i),
+                order_hash: Some(format!("0x{:064x}", i)),
+                htlc_id: Some(format!("escrow_{}", i)),
+                ..stored_near_to_ethereum_swap()
+            })
+            .collect();
+
+        let source = BatchLog::default();
+        let statuses = status_all(&swaps, &source).await.unwrap();
+
+        let mut batches = source.0.lock().unwrap().clone();
+        batches.sort();
         assert_eq!(
-            convert_wei_to_amount(1_000_000_000_000_000_000_000_000, "NEAR"),
-            1.0
+            batches,
+            vec![
+                (
+                    "ethereum".to_string(),
+                    (1..=3).map(|i| format!("0x{:064x}", i)).collect()
+                ),
+                (
+                    "near".to_string(),
+                    (1..=3).map(|i| format!("escrow_{}", i)).collect()
+                ),
+            ]
         );
+        assert_eq!(statuses.len(), 3);
+        assert_eq!(statuses[1].swap_id, "swap_2");
+        assert_eq!(statuses[1].source.htlc_id, "escrow_2");
+        assert_eq!(statuses[1].target.htlc_id, format!("0x{:064x}", 2));
+    }
 
-        // Test USDC conversion
-        assert_eq!(convert_wei_to_amount(1_000_000, "USDC"), 1.0);
-        assert_eq!(convert_wei_to_amount(1_000, "USDC"), 0.001);
+    /// Answers prompts from a script and records whether a refund was offered
+    struct ScriptedPrompt {
+        answers: std::sync::Mutex<Vec<InterruptAction>>,
+        offered_refund: std::sync::Mutex<Vec<bool>>,
+    }
+
+    impl InterruptPrompt for ScriptedPrompt {
+        fn choose(&self, refund_eligible: bool) -> InterruptAction {
+            self.offered_refund.lock().unwrap().push(refund_eligible);
+            self.answers.lock().unwrap().remove(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interrupt_persists_swap_and_prompts() {
+        let path = std::env::temp_dir().join("fusion_cli_interrupt_swaps.json");
+        let _ = std::fs::remove_file(&path);
+        let store = SwapStore::new(&path);
+        let swap = stored_near_to_ethereum_swap();
+        store.save(swap.clone()).unwrap();
+
+        let prompt = ScriptedPrompt {
+            answers: std::sync::Mutex::new(vec![InterruptAction::Continue, InterruptAction::Exit]),
+            offered_refund: std::sync::Mutex::new(Vec::new()),
+        };
+        let runs = std::sync::atomic::AtomicUsize::new(0);
+
+        // The monitor never finishes and every run is interrupted straight away
+        let outcome = monitor_with_interrupts(
+            || {
+                runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                std::future::pending::<Result<SwapRunOutcome>>()
+            },
+            || async {},
+            &store,
+            &swap.swap_id,
+            &prompt,
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(outcome, MonitorOutcome::Exit));
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+        // The timelock of the stored swap passed long ago
+        assert_eq!(*prompt.offered_refund.lock().unwrap(), vec![true, true]);
+        assert_eq!(store.get(&swap.swap_id).unwrap(), swap);
+
+        // A monitor that finishes first never reaches the prompt
+        let outcome = monitor_with_interrupts(
+            || async { Ok(SwapRunOutcome::Completed) },
+            std::future::pending::<()>,
+            &store,
+            &swap.swap_id,
+            &prompt,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            outcome,
+            MonitorOutcome::Finished(Ok(SwapRunOutcome::Completed))
+        ));
+        assert_eq!(prompt.offered_refund.lock().unwrap().len(), 2);
+    }
+
+    fn split_part(index: u32) -> StoredSwap {
+        StoredSwap {
+            swap_id: format!("swap_part_{}", index),
+            order_hash: Some(format!("0x{:064x}", index)),
+            split: Some(SplitPart {
+                split_id: "split_resume".to_string(),
+                index,
+                parts: 4,
+            }),
+            ..stored_near_to_ethereum_swap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_fills_only_unfilled_parts() {
+        // Stored out of order, alongside a swap from another split
+        let mut other = split_part(0);
+        other.split.as_mut().unwrap().split_id = "split_other".to_string();
+        let stored = vec![
+            split_part(3),
+            split_part(1),
+            other,
+            split_part(0),
+            split_part(2),
+        ];
+        let parts = split_parts(stored, "split_resume").unwrap();
+        assert_eq!(parts.len(), 4);
+
+        // Parts 0 and 1 were filled on the destination chain before the interruption
+        let mut destination = PartLegs(
+            [0, 1]
+                .iter()
+                .map(|i| (format!("0x{:064x}", i), "claimed"))
+                .collect(),
+        );
+
+        let revealed = std::sync::Mutex::new(Vec::new());
+        let report = resume_split_parts(&parts, &destination, |part| {
+            revealed
+                .lock()
+                .unwrap()
+                .push(part.split.as_ref().unwrap().index);
+            async { Ok(()) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*revealed.lock().unwrap(), vec![2, 3]);
+        assert_eq!(report.already_filled, vec![0, 1]);
+        assert_eq!(report.filled, vec![2, 3]);
+        assert!(report.missing.is_empty() && report.failed.is_empty());
+        assert!((0..4).all(|i| report.is_completed(i)));
+
+        // Once every part is filled, resuming again reveals nothing
+        for i in [2, 3] {
+            destination.0.insert(format!("0x{:064x}", i), "claimed");
+        }
+        revealed.lock().unwrap().clear();
+        let report = resume_split_parts(&parts, &destination, |part| {
+            revealed
+                .lock()
+                .unwrap()
+                .push(part.split.as_ref().unwrap().index);
+            async { Ok(()) }
+        })
+        .await
+        .unwrap();
+        assert!(revealed.lock().unwrap().is_empty());
+        assert_eq!(report.already_filled, vec![0, 1, 2, 3]);
+        assert!(report.filled.is_empty());
+        assert!(split_parts(vec![split_part(0)], "split_missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resume_reports_missing_and_failed_parts() {
+        // Part 1 never got an escrow pair when the swap started
+        let parts = vec![split_part(0), split_part(2), split_part(3)];
+        let destination = PartLegs(
+            [(format!("0x{:064x}", 3), "refunded")]
+                .into_iter()
+                .collect(),
+        );
+
+        let report = resume_split_parts(&parts, &destination, |part| {
+            let index = part.split.as_ref().unwrap().index;
+            async move {
+                if index == 2 {
+                    return Err(anyhow!("claim reverted"));
+                }
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(report.filled, vec![0]);
+        assert_eq!(report.refunded, vec![3]);
+        assert_eq!(report.missing, vec![1]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].index, 2);
+        assert!(report.is_failed(2) && report.is_failed(3));
     }
 
     #[test]
-    fn test_precision_handling() {
-        // Test that we handle floating point precision correctly
-        let amount = 0.123456789;
-        let wei = convert_amount_to_wei(amount, "ETH");
-        let back = convert_wei_to_amount(wei, "ETH");
+    fn test_non_evm_leg_has_no_immutables() {
+        let swap = stored_near_to_ethereum_swap();
 
-        // Should be close within floating point precision
-        assert!((amount - back).abs() < 0.000000001);
+        let err = leg_immutables(&swap, "src", Address::zero(), U256::zero(), 0).unwrap_err();
+        assert!(err.to_string().contains("is on near"));
 
-        // Test with USDC (fewer decimals)
-        let usdc_amount = 1234.56;
-        let usdc_wei = convert_amount_to_wei(usdc_amount, "USDC");
-        assert_eq!(usdc_wei, 1_234_560_000); // 1234.56 * 10^6
+        let err = leg_immutables(&swap, "middle", Address::zero(), U256::zero(), 0).unwrap_err();
+        assert!(err.to_string().contains("must be src or dst"));
+    }
 
-        let usdc_back = convert_wei_to_amount(usdc_wei, "USDC");
-        assert!((usdc_amount - usdc_back).abs() < 0.01);
+    struct FixedBalances {
+        native: U256,
+    }
+
+    #[async_trait::async_trait]
+    impl BalanceSource for FixedBalances {
+        async fn native_balance(&self, _owner: Address) -> Result<U256, BalanceError> {
+            Ok(self.native)
+        }
+
+        async fn token_balance(
+            &self,
+            _token: Address,
+            _owner: Address,
+        ) -> Result<U256, BalanceError> {
+            Ok(U256::zero())
+        }
+
+        async fn gas_price(&self) -> Result<U256, BalanceError> {
+            Ok(U256::one())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_source_balance() {
+        let args = ethereum_to_near_args(&[]);
+        let one_eth = U256::from(convert_amount_to_wei(1.0, "ETH"));
+
+        let funded = FixedBalances { native: one_eth };
+        assert!(preflight_source_balance(&args, &funded).await.is_ok());
+
+        let short = FixedBalances {
+            native: one_eth - 1,
+        };
+        let err = preflight_source_balance(&args, &short).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BalanceError>(),
+            Some(&BalanceError::InsufficientBalance {
+                asset: SafetyDepositAsset::Native,
+                needed: one_eth,
+                available: one_eth - 1,
+            })
+        );
     }
 }
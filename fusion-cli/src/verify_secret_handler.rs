@@ -0,0 +1,149 @@
+use crate::exit_code::{cli_error, ExitCode};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use fusion_core::htlc::{secret_eq, HashAlgorithm, Secret, SecretHash};
+use serde_json::json;
+
+#[derive(Args)]
+pub struct VerifySecretArgs {
+    /// Secret as hex (32 bytes, optional 0x prefix)
+    #[arg(long)]
+    pub secret: String,
+
+    /// Hashlock to check against, as hex (32 bytes, optional 0x prefix)
+    #[arg(long)]
+    pub hash: String,
+
+    /// Hash function of the hashlock; auto tries sha256 (NEAR) then keccak256
+    #[arg(long, value_enum, default_value_t = HashAlgo::Auto)]
+    pub algo: HashAlgo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HashAlgo {
+    Sha256,
+    Keccak256,
+    Auto,
+}
+
+impl HashAlgo {
+    fn candidates(self) -> &'static [HashAlgorithm] {
+        match self {
+            HashAlgo::Sha256 => &[HashAlgorithm::Sha256],
+            HashAlgo::Keccak256 => &[HashAlgorithm::Keccak256],
+            HashAlgo::Auto => &[HashAlgorithm::Sha256, HashAlgorithm::Keccak256],
+        }
+    }
+}
+
+/// Hash function under which `secret` hashes to `hash`, if any of `algo`'s candidates do
+pub fn matching_algorithm(
+    secret: &Secret,
+    hash: &SecretHash,
+    algo: HashAlgo,
+) -> Option<HashAlgorithm> {
+    algo.candidates()
+        .iter()
+        .copied()
+        .find(|algorithm| secret_eq(&algorithm.hash(secret), hash))
+}
+
+fn algorithm_name(algorithm: HashAlgorithm) -> &'static str {
+    match algorithm {
+        HashAlgorithm::Sha256 => "sha256",
+        HashAlgorithm::Keccak256 => "keccak256",
+    }
+}
+
+/// Decode a 32-byte hex value, naming `field` in the error
+fn decode_bytes32(field: &str, value: &str) -> Result<[u8; 32]> {
+    let invalid = || {
+        cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": format!("Invalid {} format", field),
+                "message": format!("{} must be 32 bytes of hex", field)
+            }),
+        )
+    };
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|_| invalid())?;
+    bytes.try_into().map_err(|_| invalid())
+}
+
+/// Check a secret against a hashlock offline, without touching any chain
+pub async fn handle_verify_secret(args: VerifySecretArgs) -> Result<()> {
+    let secret = decode_bytes32("secret", &args.secret)?;
+    let hash = decode_bytes32("hash", &args.hash)?;
+
+    let Some(algorithm) = matching_algorithm(&secret, &hash, args.algo) else {
+        return Err(cli_error(
+            ExitCode::UserError,
+            json!({
+                "error": "Secret does not match hash",
+                "matches": false,
+                "hash": hex::encode(hash),
+            }),
+        ));
+    };
+
+    let output = json!({
+        "matches": true,
+        "algorithm": algorithm_name(algorithm),
+        "hash": hex::encode(hash),
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fusion_core::htlc::hash_secret_with;
+
+    const SECRET: Secret = [7u8; 32];
+
+    #[test]
+    fn test_sha256_hash_matches() {
+        let hash = hash_secret_with(&SECRET, HashAlgorithm::Sha256);
+        assert_eq!(
+            matching_algorithm(&SECRET, &hash, HashAlgo::Sha256),
+            Some(HashAlgorithm::Sha256)
+        );
+        assert_eq!(
+            matching_algorithm(&SECRET, &hash, HashAlgo::Auto),
+            Some(HashAlgorithm::Sha256)
+        );
+        assert_eq!(
+            matching_algorithm(&SECRET, &hash, HashAlgo::Keccak256),
+            None
+        );
+    }
+
+    #[test]
+    fn test_keccak256_hash_matches() {
+        let hash = hash_secret_with(&SECRET, HashAlgorithm::Keccak256);
+        assert_eq!(
+            matching_algorithm(&SECRET, &hash, HashAlgo::Keccak256),
+            Some(HashAlgorithm::Keccak256)
+        );
+        assert_eq!(
+            matching_algorithm(&SECRET, &hash, HashAlgo::Auto),
+            Some(HashAlgorithm::Keccak256)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_matching_secret_is_reported() {
+        let hash = hash_secret_with(&[8u8; 32], HashAlgorithm::Sha256);
+        assert_eq!(matching_algorithm(&SECRET, &hash, HashAlgo::Auto), None);
+
+        let err = handle_verify_secret(VerifySecretArgs {
+            secret: hex::encode(SECRET),
+            hash: format!("0x{}", hex::encode(hash)),
+            algo: HashAlgo::Auto,
+        })
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Secret does not match hash"));
+    }
+}
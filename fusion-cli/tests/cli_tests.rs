@@ -1,18 +1,42 @@
-// Note: These integration tests are currently ignored because the in-memory storage
-// doesn't persist between separate command executions. Each command runs as a separate
-// process with its own memory space. These tests will be re-enabled once persistent
-// storage (e.g., file-based) is implemented.
+// Each command runs as a separate process, so tests that span several commands
+// keep their HTLCs in a file given through FUSION_STORAGE_PATH.
 
 use assert_cmd::Command;
 use fusion_core::htlc::generate_secret;
 use predicates::prelude::*;
 use serde_json::Value;
+use std::path::PathBuf;
+
+/// Storage file private to one test, removed when dropped
+struct TempStorage(PathBuf);
+
+impl TempStorage {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("fusion_cli_{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Self(path)
+    }
+
+    fn cli(&self) -> Command {
+        let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+        cmd.env("FUSION_STORAGE_PATH", &self.0);
+        cmd
+    }
+}
+
+impl Drop for TempStorage {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
 
 #[test]
-#[ignore = "In-memory storage doesn't persist between separate command executions"]
 fn test_claim_with_valid_secret() {
+    let storage = TempStorage::new("claim_with_valid_secret");
+
     // First, create an HTLC
-    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    let mut cmd = storage.cli();
     let create_output = cmd
         .arg("create-htlc")
         .arg("--sender")
@@ -35,7 +59,7 @@ fn test_claim_with_valid_secret() {
     let secret = output_json["secret"].as_str().unwrap();
 
     // Now claim the HTLC
-    let mut claim_cmd = Command::cargo_bin("fusion-cli").unwrap();
+    let mut claim_cmd = storage.cli();
     claim_cmd
         .arg("claim")
         .arg("--htlc-id")
@@ -48,10 +72,11 @@ fn test_claim_with_valid_secret() {
 }
 
 #[test]
-#[ignore = "In-memory storage doesn't persist between separate command executions"]
 fn test_claim_with_invalid_secret() {
+    let storage = TempStorage::new("claim_with_invalid_secret");
+
     // First, create an HTLC
-    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    let mut cmd = storage.cli();
     let create_output = cmd
         .arg("create-htlc")
         .arg("--sender")
@@ -75,7 +100,7 @@ fn test_claim_with_invalid_secret() {
     // Try to claim with wrong secret
     let wrong_secret = hex::encode(generate_secret());
 
-    let mut claim_cmd = Command::cargo_bin("fusion-cli").unwrap();
+    let mut claim_cmd = storage.cli();
     claim_cmd
         .arg("claim")
         .arg("--htlc-id")
@@ -83,7 +108,7 @@ fn test_claim_with_invalid_secret() {
         .arg("--secret")
         .arg(&wrong_secret)
         .assert()
-        .success()
+        .code(1)
         .stdout(predicate::str::contains("Invalid secret"));
 }
 
@@ -96,15 +121,16 @@ fn test_claim_htlc_not_found() {
         .arg("--secret")
         .arg("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
         .assert()
-        .success()
+        .code(2)
         .stdout(predicate::str::contains("HTLC not found"));
 }
 
 #[test]
-#[ignore = "In-memory storage doesn't persist between separate command executions"]
 fn test_claim_already_claimed_htlc() {
+    let storage = TempStorage::new("claim_already_claimed_htlc");
+
     // First, create an HTLC
-    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    let mut cmd = storage.cli();
     let create_output = cmd
         .arg("create-htlc")
         .arg("--sender")
@@ -127,7 +153,7 @@ fn test_claim_already_claimed_htlc() {
     let secret = output_json["secret"].as_str().unwrap();
 
     // First claim should succeed
-    let mut claim_cmd = Command::cargo_bin("fusion-cli").unwrap();
+    let mut claim_cmd = storage.cli();
     claim_cmd
         .arg("claim")
         .arg("--htlc-id")
@@ -138,7 +164,7 @@ fn test_claim_already_claimed_htlc() {
         .success();
 
     // Second claim should fail
-    let mut claim_cmd2 = Command::cargo_bin("fusion-cli").unwrap();
+    let mut claim_cmd2 = storage.cli();
     claim_cmd2
         .arg("claim")
         .arg("--htlc-id")
@@ -146,6 +172,6 @@ fn test_claim_already_claimed_htlc() {
         .arg("--secret")
         .arg(secret)
         .assert()
-        .success()
+        .code(1)
         .stdout(predicate::str::contains("HTLC already claimed"));
 }
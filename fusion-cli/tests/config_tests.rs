@@ -0,0 +1,73 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn write_config(name: &str, content: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_config_validate_valid_file() {
+    let path = write_config(
+        "fusion_cli_valid_config.json",
+        r#"{
+            "chains": {
+                "base-sepolia": {
+                    "rpc_url": "https://sepolia.base.org",
+                    "chain_id": 84532,
+                    "limit_order_protocol": "0x171C87724E720F2806fc29a010a62897B30fdb62"
+                },
+                "near": {
+                    "rpc_url": "https://rpc.testnet.near.org",
+                    "escrow_factory": "htlc-v2.testnet"
+                }
+            }
+        }"#,
+    );
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("config").arg("validate").arg("--file").arg(&path);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"valid\": true"));
+}
+
+#[test]
+fn test_config_validate_missing_factory_address() {
+    let path = write_config(
+        "fusion_cli_missing_factory_config.json",
+        r#"{
+            "chains": {
+                "ethereum": {
+                    "rpc_url": "https://sepolia.infura.io/v3/key",
+                    "chain_id": 11155111
+                }
+            }
+        }"#,
+    );
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("config").arg("validate").arg("--file").arg(&path);
+
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("\"valid\": false"))
+        .stdout(predicate::str::contains(
+            "chains.ethereum needs an escrow_factory or limit_order_protocol address",
+        ));
+}
+
+#[test]
+fn test_config_validate_missing_file() {
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("config")
+        .arg("validate")
+        .arg("--file")
+        .arg("/nonexistent/fusion_config.json");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to read config file"));
+}
@@ -26,7 +26,8 @@ mod order_cli_tests {
             .arg("--chain-id")
             .arg("84532")
             .arg("--verifying-contract")
-            .arg("0x171C87724E720F2806fc29a010a62897B30fdb62");
+            .arg("0x171C87724E720F2806fc29a010a62897B30fdb62")
+            .arg("--no-token-check");
 
         cmd.assert()
             .success()
@@ -35,6 +36,46 @@ mod order_cli_tests {
             .stdout(predicate::str::contains("domain"));
     }
 
+    #[test]
+    fn test_order_create_with_salt_is_reproducible() {
+        let create = || {
+            let output = Command::cargo_bin("fusion-cli")
+                .unwrap()
+                .args([
+                    "order",
+                    "create",
+                    "--maker-asset",
+                    "0x4200000000000000000000000000000000000006",
+                    "--taker-asset",
+                    "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+                    "--maker",
+                    "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950",
+                    "--making-amount",
+                    "1000000000000000000",
+                    "--taking-amount",
+                    "3000000000",
+                    "--htlc-secret-hash",
+                    "1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                    "--htlc-timeout",
+                    "3600",
+                    "--chain-id",
+                    "84532",
+                    "--verifying-contract",
+                    "0x171C87724E720F2806fc29a010a62897B30fdb62",
+                    "--salt",
+                    "0x0123456789abcdef",
+                    "--no-token-check",
+                ])
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            let printed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            printed["eip712_hash"].as_str().unwrap().to_string()
+        };
+
+        assert_eq!(create(), create());
+    }
+
     #[test]
     fn test_order_create_with_invalid_address() {
         let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
@@ -62,4 +103,140 @@ mod order_cli_tests {
 
         cmd.assert().failure();
     }
+
+    #[test]
+    fn test_order_create_with_htlc_shares_one_secret() {
+        let store = std::env::temp_dir().join("fusion_cli_create_with_htlc.json");
+        std::fs::write(&store, "[]").unwrap();
+
+        let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+        cmd.env("FUSION_SWAP_STORE", &store)
+            .arg("order")
+            .arg("create-with-htlc")
+            .arg("--maker-asset")
+            .arg("0x4200000000000000000000000000000000000006")
+            .arg("--taker-asset")
+            .arg("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+            .arg("--maker")
+            .arg("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+            .arg("--making-amount")
+            .arg("1000000000000000000")
+            .arg("--taking-amount")
+            .arg("3000000000")
+            .arg("--chain-id")
+            .arg("84532")
+            .arg("--verifying-contract")
+            .arg("0x171C87724E720F2806fc29a010a62897B30fdb62")
+            .arg("--recipient-address")
+            .arg("alice.testnet")
+            .arg("--htlc-sender")
+            .arg("resolver.testnet")
+            .arg("--htlc-amount")
+            .arg("1000")
+            .arg("--no-token-check");
+
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        let secret: [u8; 32] = hex::decode(printed["secret"].as_str().unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let secret_hash = hex::encode(fusion_core::htlc::hash_secret(&secret));
+
+        assert_eq!(printed["htlc"]["secret_hash"], secret_hash);
+        assert_eq!(
+            printed["order"]["htlc_info"]["secret_hash"],
+            format!("0x{}", secret_hash)
+        );
+
+        // The link is persisted for later swap commands
+        let stored: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&store).unwrap()).unwrap();
+        assert_eq!(stored[0]["secret_hash"], secret_hash);
+        assert_eq!(stored[0]["order_hash"], printed["order"]["eip712_hash"]);
+        assert_eq!(stored[0]["htlc_id"], printed["htlc"]["htlc_id"]);
+    }
+
+    #[test]
+    fn test_order_typed_data_command() {
+        let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+
+        cmd.arg("order")
+            .arg("typed-data")
+            .arg("--maker-asset")
+            .arg("0x4200000000000000000000000000000000000006")
+            .arg("--taker-asset")
+            .arg("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+            .arg("--maker")
+            .arg("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+            .arg("--making-amount")
+            .arg("1000000000000000000")
+            .arg("--taking-amount")
+            .arg("3000000000")
+            .arg("--htlc-secret-hash")
+            .arg("1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+            .arg("--htlc-timeout")
+            .arg("3600")
+            .arg("--chain-id")
+            .arg("84532")
+            .arg("--verifying-contract")
+            .arg("0x171C87724E720F2806fc29a010a62897B30fdb62");
+
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert!(json["types"]["EIP712Domain"].is_array());
+        assert!(json["types"]["Order"].is_array());
+        assert_eq!(json["primaryType"], "Order");
+        assert_eq!(json["domain"]["chainId"], 84532);
+        assert_eq!(
+            json["message"]["maker"],
+            "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950"
+        );
+    }
+
+    #[test]
+    fn test_order_match_command_prints_profitable_matches() {
+        let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+        let orders_file = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/match_orders.json"
+        );
+
+        cmd.arg("order")
+            .arg("match")
+            .arg("--orders-file")
+            .arg(orders_file)
+            .arg("--min-profit-bps")
+            .arg("50");
+
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        // near-sell-2 only yields ~39 bps and eth-sell-1 is on the buyer's chain
+        assert_eq!(json["orders_loaded"], 4);
+        assert_eq!(json["total_matches"], 1);
+        let order_match = &json["matches"][0];
+        assert_eq!(order_match["buy_order_id"], "eth-buy-1");
+        assert_eq!(order_match["sell_order_id"], "near-sell-1");
+        assert_eq!(order_match["sell_maker"], "alice.testnet");
+        assert_eq!(order_match["match_amount"], "800");
+        let profit_bps = order_match["profit_bps"].as_u64().unwrap();
+        assert!((199..=200).contains(&profit_bps));
+    }
+
+    #[test]
+    fn test_order_match_rejects_missing_file() {
+        let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+
+        cmd.arg("order")
+            .arg("match")
+            .arg("--orders-file")
+            .arg("/nonexistent/orders.json");
+
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Failed to read orders file"));
+    }
 }
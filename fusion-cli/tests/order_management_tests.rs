@@ -17,6 +17,7 @@ fn test_order_status_command_returns_correct_status() {
         created_at: std::time::SystemTime::now(),
         chain: "ethereum".to_string(),
         order_hash: "0xabcdef1234567890".to_string(),
+        submission: None,
     };
     storage.store(order_id.to_string(), stored_order).unwrap();
 
@@ -56,6 +57,7 @@ fn test_order_cancel_command_cancels_active_order() {
         created_at: std::time::SystemTime::now(),
         chain: "ethereum".to_string(),
         order_hash: "0xabcdef1234567890".to_string(),
+        submission: None,
     };
     storage.store(order_id.to_string(), stored_order).unwrap();
 
@@ -83,6 +85,7 @@ fn test_order_cancel_command_fails_for_filled_order() {
         created_at: std::time::SystemTime::now(),
         chain: "ethereum".to_string(),
         order_hash: "0xabcdef1234567890".to_string(),
+        submission: None,
     };
     storage.store(order_id.to_string(), stored_order).unwrap();
 
@@ -112,6 +115,7 @@ fn test_orderbook_command_returns_orders_for_chain() {
             created_at: std::time::SystemTime::now(),
             chain: "ethereum".to_string(),
             order_hash: format!("0xabcdef{}", i),
+            submission: None,
         };
         storage.store(order.id.clone(), order).unwrap();
     }
@@ -129,6 +133,7 @@ fn test_orderbook_command_returns_orders_for_chain() {
             created_at: std::time::SystemTime::now(),
             chain: "near".to_string(),
             order_hash: format!("0xfedcba{}", i),
+            submission: None,
         };
         storage.store(order.id.clone(), order).unwrap();
     }
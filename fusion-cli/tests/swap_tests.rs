@@ -1,4 +1,6 @@
 use assert_cmd::Command;
+use ethers::types::{Address, H256, U256};
+use fusion_core::chains::ethereum::immutables::{address_of_escrow_dst, Immutables, Timelocks};
 use predicates::prelude::*;
 
 #[test]
@@ -65,6 +67,73 @@ fn test_swap_near_to_ethereum() {
         .stdout(predicate::str::contains("swap_plan"));
 }
 
+#[test]
+fn test_swap_dry_run_without_price_warns() {
+    // The oracle has no price for a raw token address, so the plan is
+    // still printed with the output marked unavailable
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("swap")
+        .arg("swap")
+        .arg("--from-chain")
+        .arg("near")
+        .arg("--to-chain")
+        .arg("ethereum")
+        .arg("--from-token")
+        .arg("NEAR")
+        .arg("--to-token")
+        .arg("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+        .arg("--amount")
+        .arg("10.0")
+        .arg("--from-address")
+        .arg("alice.near")
+        .arg("--to-address")
+        .arg("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+        .arg("--dry-run");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"estimated_output\": \"unavailable\"",
+        ))
+        .stdout(predicate::str::contains("Price oracle unavailable"));
+}
+
+#[test]
+fn test_swap_split_dry_run_lists_tranches() {
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("swap")
+        .arg("swap")
+        .arg("--from-chain")
+        .arg("ethereum")
+        .arg("--to-chain")
+        .arg("near")
+        .arg("--from-token")
+        .arg("USDC")
+        .arg("--to-token")
+        .arg("NEAR")
+        .arg("--amount")
+        .arg("10.0")
+        .arg("--from-address")
+        .arg("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+        .arg("--to-address")
+        .arg("alice.near")
+        .arg("--split")
+        .arg("3")
+        .arg("--dry-run");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    let tranches = printed["swap_plan"]["tranches"].as_array().unwrap();
+    assert_eq!(tranches.len(), 3);
+    let total: u128 = tranches
+        .iter()
+        .map(|t| t["amount_units"].as_str().unwrap().parse::<u128>().unwrap())
+        .sum();
+    // 10 USDC with 6 decimals
+    assert_eq!(total, 10_000_000);
+}
+
 #[test]
 fn test_swap_requires_from_chain() {
     // Test that from-chain is required
@@ -161,6 +230,34 @@ fn test_swap_invalid_chain() {
         .stderr(predicate::str::contains("Invalid from_chain"));
 }
 
+#[test]
+fn test_swap_rejects_malformed_fixed_rate() {
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("swap")
+        .arg("swap")
+        .arg("--from-chain")
+        .arg("ethereum")
+        .arg("--to-chain")
+        .arg("near")
+        .arg("--from-token")
+        .arg("WETH")
+        .arg("--to-token")
+        .arg("NEAR")
+        .arg("--amount")
+        .arg("1.0")
+        .arg("--from-address")
+        .arg("0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950")
+        .arg("--to-address")
+        .arg("alice.near")
+        .arg("--fixed-rate")
+        .arg("WETH:NEAR=abc")
+        .arg("--dry-run");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("rate is not a number"));
+}
+
 #[test]
 fn test_swap_batch_config() {
     // Test batch swap configuration
@@ -207,3 +304,311 @@ fn test_swap_batch_config() {
     // Clean up
     let _ = fs::remove_file(config_path);
 }
+
+#[test]
+fn test_swap_immutables_match_dst_escrow_creation() {
+    let store = std::env::temp_dir().join("fusion_cli_immutables_swaps.json");
+    std::fs::write(
+        &store,
+        r#"[{
+            "swap_id": "swap_aabbccddeeff0011",
+            "from_chain": "near",
+            "to_chain": "ethereum",
+            "from_token": "NEAR",
+            "to_token": "USDC",
+            "from_address": "alice.near",
+            "to_address": "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950",
+            "src_amount": 10000000000000000000000000,
+            "dst_amount": 49500000,
+            "secret_hash": "1111111111111111111111111111111111111111111111111111111111111111",
+            "order_hash": "0x2222222222222222222222222222222222222222222222222222222222222222",
+            "htlc_id": "escrow_1",
+            "timeout": 3600,
+            "status": "pending",
+            "created_at": 1700000000
+        }]"#,
+    )
+    .unwrap();
+
+    let factory = "0xa7bCb4EAc8964306F9e3764f67Db6A7af6DdF99A";
+    let implementation = "0x0000000000000000000000000000000000000dd5";
+    let taker = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.env("FUSION_SWAP_STORE", &store)
+        .arg("swap")
+        .arg("immutables")
+        .arg("--swap-id")
+        .arg("swap_aabbccddeeff0011")
+        .arg("--leg")
+        .arg("dst")
+        .arg("--taker")
+        .arg(taker)
+        .arg("--escrow-factory")
+        .arg(factory)
+        .arg("--escrow-implementation")
+        .arg(implementation)
+        .arg("--safety-deposit")
+        .arg("1000")
+        .arg("--safety-deposit-token")
+        .arg("0x4200000000000000000000000000000000000006")
+        .arg("--deployed-at")
+        .arg("1700000100");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    // The values a resolver would pass to createDstEscrow for this swap
+    let expected = Immutables {
+        order_hash: H256::repeat_byte(0x22),
+        hashlock: H256::repeat_byte(0x11),
+        maker: "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950"
+            .parse()
+            .unwrap(),
+        taker: taker.parse().unwrap(),
+        token: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+            .parse()
+            .unwrap(),
+        amount: U256::from(49_500_000u64),
+        safety_deposit: U256::from(1_000u64),
+        timelocks: Timelocks::from_timeout(3600).with_deployed_at(1_700_000_100),
+    };
+    let expected_address = address_of_escrow_dst(
+        factory.parse().unwrap(),
+        implementation.parse::<Address>().unwrap(),
+        &expected,
+    );
+
+    let immutables = &printed["immutables"];
+    assert_eq!(immutables["maker"], format!("{:?}", expected.maker));
+    assert_eq!(immutables["taker"], format!("{:?}", expected.taker));
+    assert_eq!(immutables["token"], format!("{:?}", expected.token));
+    assert_eq!(immutables["amount"], "49500000");
+    assert_eq!(immutables["safety_deposit"], "1000");
+    assert_eq!(
+        immutables["timelocks"],
+        format!("0x{:064x}", expected.timelocks.pack())
+    );
+    assert_eq!(printed["immutables_hash"], format!("{:?}", expected.hash()));
+    assert_eq!(
+        printed["predicted_escrow_address"],
+        format!("{:?}", expected_address)
+    );
+
+    // USDC amount and WETH safety deposit both need approvals, no ETH is attached
+    let funding = &printed["funding"];
+    assert_eq!(funding["value"], "0");
+    assert_eq!(funding["approvals"][0]["amount"], "49500000");
+    assert_eq!(funding["approvals"][1]["amount"], "1000");
+    assert_eq!(
+        funding["approvals"][1]["token"],
+        "0x4200000000000000000000000000000000000006"
+    );
+}
+
+#[test]
+fn test_swap_immutables_unknown_swap() {
+    let store = std::env::temp_dir().join("fusion_cli_immutables_missing.json");
+    std::fs::write(&store, "[]").unwrap();
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.env("FUSION_SWAP_STORE", &store)
+        .arg("swap")
+        .arg("immutables")
+        .arg("--swap-id")
+        .arg("swap_missing")
+        .arg("--leg")
+        .arg("src")
+        .arg("--taker")
+        .arg("0x70997970C51812dc3A010C7d01b50e0d17dc79C8")
+        .arg("--escrow-factory")
+        .arg("0xa7bCb4EAc8964306F9e3764f67Db6A7af6DdF99A")
+        .arg("--escrow-implementation")
+        .arg("0x0000000000000000000000000000000000000dd5");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Swap not found: swap_missing"));
+}
+
+#[test]
+fn test_swap_list_filters_by_state_in_created_order() {
+    let store = std::env::temp_dir().join("fusion_cli_list_swaps.json");
+    let swap = |id: &str, status: &str, created_at: u64| {
+        format!(
+            r#"{{"swap_id": "{id}", "from_chain": "ethereum", "to_chain": "near",
+                "from_token": "WETH", "to_token": "NEAR",
+                "from_address": "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950",
+                "to_address": "alice.near", "src_amount": 1000, "dst_amount": null,
+                "secret_hash": "00", "order_hash": null, "htlc_id": null, "timeout": 3600,
+                "status": "{status}", "created_at": {created_at}}}"#
+        )
+    };
+    std::fs::write(
+        &store,
+        format!(
+            "[{}, {}, {}]",
+            swap("swap_late", "pending", 1_700_000_300),
+            swap("swap_failed", "failed", 1_700_000_100),
+            swap("swap_early", "pending", 1_700_000_200)
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.env("FUSION_SWAP_STORE", &store)
+        .arg("swap")
+        .arg("list")
+        .arg("--state")
+        .arg("pending")
+        .arg("--sort")
+        .arg("created");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(printed["total"], 2);
+    assert_eq!(printed["swaps"][0]["swap_id"], "swap_early");
+    assert_eq!(printed["swaps"][1]["swap_id"], "swap_late");
+}
+
+#[test]
+fn test_swap_verify_unknown_swap() {
+    let store = std::env::temp_dir().join("fusion_cli_verify_missing.json");
+    std::fs::write(&store, "[]").unwrap();
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.env("FUSION_SWAP_STORE", &store)
+        .arg("swap")
+        .arg("verify")
+        .arg("--swap-id")
+        .arg("swap_missing");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Swap not found: swap_missing"));
+}
+
+#[test]
+fn test_swap_status_reports_completed_steps() {
+    let store = std::env::temp_dir().join("fusion_cli_status_swaps.json");
+    std::fs::write(
+        &store,
+        r#"[{
+            "swap_id": "swap_0011223344556677",
+            "from_chain": "ethereum",
+            "to_chain": "near",
+            "from_token": "WETH",
+            "to_token": "NEAR",
+            "from_address": "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950",
+            "to_address": "alice.near",
+            "src_amount": 1000,
+            "dst_amount": null,
+            "secret_hash": "00",
+            "order_hash": "0xorder",
+            "htlc_id": "fusion_7",
+            "timeout": 3600,
+            "status": "pending",
+            "created_at": 1700000000,
+            "transactions": [
+                {
+                    "chain": "ethereum",
+                    "tx_hash": "0xorder",
+                    "explorer_url": "https://sepolia.basescan.org/tx/0xorder",
+                    "description": "Limit order submitted to blockchain"
+                },
+                {
+                    "chain": "near",
+                    "tx_hash": "fusion_7",
+                    "explorer_url": "https://explorer.testnet.near.org/transactions/fusion_7",
+                    "description": "HTLC created"
+                }
+            ]
+        }]"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.env("FUSION_SWAP_STORE", &store)
+        .arg("swap")
+        .arg("status")
+        .arg("--swap-id")
+        .arg("swap_0011223344556677");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(printed["state"], "pending");
+    let steps: Vec<(&str, bool)> = printed["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| {
+            (
+                s["step"].as_str().unwrap(),
+                s["completed"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("order_created", true),
+            ("htlc_created", true),
+            ("secret_revealed", false),
+            ("funds_claimed", false),
+        ]
+    );
+    assert_eq!(printed["steps"][1]["tx_hash"], "fusion_7");
+    assert_eq!(printed["transactions"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_swap_status_unknown_swap() {
+    let store = std::env::temp_dir().join("fusion_cli_status_missing.json");
+    std::fs::write(&store, "[]").unwrap();
+
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.env("FUSION_SWAP_STORE", &store)
+        .arg("swap")
+        .arg("status")
+        .arg("--swap-id")
+        .arg("swap_missing");
+
+    let output = cmd.assert().code(2).get_output().stdout.clone();
+    let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(printed["error"], "swap not found");
+}
+
+#[test]
+fn test_swap_benchmark_reports_per_leg_statistics() {
+    let mut cmd = Command::cargo_bin("fusion-cli").unwrap();
+    cmd.arg("swap")
+        .arg("benchmark")
+        .arg("--from-chain")
+        .arg("ethereum")
+        .arg("--to-chain")
+        .arg("near")
+        .arg("--samples")
+        .arg("25");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let printed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(printed["samples"], 25);
+    assert_eq!(printed["gas_price_source"], "simulated");
+
+    let legs = printed["legs"].as_array().unwrap();
+    let names: Vec<&str> = legs.iter().map(|l| l["leg"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["source", "destination"]);
+    assert_eq!(legs[0]["chain"], "ethereum");
+    assert_eq!(legs[1]["cost_token"], "NEAR");
+    for leg in legs {
+        for stat in ["cost", "time_seconds"] {
+            let min = leg[stat]["min"].as_f64().unwrap();
+            let median = leg[stat]["median"].as_f64().unwrap();
+            let max = leg[stat]["max"].as_f64().unwrap();
+            assert!(0.0 < min && min <= median && median <= max);
+        }
+    }
+    assert!(printed["total_time_seconds"]["median"].as_f64().unwrap() > 0.0);
+}
@@ -48,6 +48,7 @@ fn demo_order_matching() -> anyhow::Result<()> {
             price: 5.5,
             amount: 2_000_000_000_000_000_000_000_000_000,
             timestamp: 1234567890,
+            expires_at: None,
         },
         PendingOrder {
             id: "near_sell_001".to_string(),
@@ -57,6 +58,7 @@ fn demo_order_matching() -> anyhow::Result<()> {
             price: 5.0,
             amount: 1_500_000_000_000_000_000_000_000_000,
             timestamp: 1234567891,
+            expires_at: None,
         },
         PendingOrder {
             id: "bsc_buy_001".to_string(),
@@ -66,6 +68,7 @@ fn demo_order_matching() -> anyhow::Result<()> {
             price: 5.3,
             amount: 1_000_000_000_000_000_000_000_000_000,
             timestamp: 1234567892,
+            expires_at: None,
         },
     ];
 
@@ -173,6 +176,7 @@ fn demo_path_optimization() -> anyhow::Result<()> {
         max_time: 3600,
         max_risk_score: 70,
         min_profit: 0.0,
+        max_hops: 2,
         priority: OptimizationPriority::MinimizeCost,
     };
 
@@ -215,6 +219,7 @@ async fn demo_automated_execution() -> anyhow::Result<()> {
         max_retries: 3,
         retry_delay: 60,
         exponential_backoff: true,
+        ..RetryConfig::default()
     };
 
     let mut executor = AutomatedExecutor::new(engine, 5, retry_config);
@@ -241,6 +246,8 @@ async fn demo_automated_execution() -> anyhow::Result<()> {
         created_at: 1234567900,
         updated_at: 1234567900,
         error_message: None,
+        retry_count: 0,
+        htlc: None,
     };
 
     executor.add_task(task)?;
@@ -2,18 +2,23 @@
 //!
 //! クロスチェーントランザクションを自動的に実行し、監視します。
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time::interval;
 
 use crate::cross_chain_executor::CrossChainExecutor;
-use crate::execution_path_optimizer::{ExecutionPath, StepType};
+use crate::execution_path_optimizer::{ExecutionPath, ExecutionStep, StepType};
+use crate::htlc::{Secret, SecretHash};
 use crate::order_matching_engine::OrderMatch;
 
 /// 自動実行タスク
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionTask {
     /// タスクID
     pub id: String,
@@ -29,10 +34,31 @@ pub struct ExecutionTask {
     pub updated_at: u64,
     /// エラーメッセージ
     pub error_message: Option<String>,
+    /// これまでのリトライ回数
+    pub retry_count: u8,
+    /// HTLC作成・クレームのステップに渡すパラメータ
+    #[serde(default)]
+    pub htlc: Option<HtlcParams>,
+}
+
+/// HTLCステップの実行に必要なパラメータ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HtlcParams {
+    /// シークレットハッシュ
+    pub secret_hash: SecretHash,
+    /// クレームに使うシークレット（ディスクには保存しない）
+    #[serde(skip)]
+    pub secret: Option<Secret>,
+    /// 受取人（Ethereumアドレスまたは NEAR アカウント）
+    pub recipient: String,
+    /// タイムアウト（秒）
+    pub timeout_seconds: u64,
+    /// クレーム対象のエスクロー（同じタスクで作成した場合は不要）
+    pub escrow_id: Option<String>,
 }
 
 /// タスクステータス
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TaskStatus {
     /// 待機中
     Pending,
@@ -43,14 +69,18 @@ pub enum TaskStatus {
     },
     /// 完了
     Completed { tx_hashes: Vec<String> },
-    /// 失敗
-    Failed { reason: String, retry_count: u8 },
+    /// 失敗（失敗したステップが分かる場合はその種類）
+    Failed {
+        reason: String,
+        retry_count: u8,
+        failed_step: Option<StepType>,
+    },
     /// キャンセル済み
     Cancelled,
 }
 
 /// ステップステータス
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StepStatus {
     /// 開始前
     NotStarted,
@@ -75,6 +105,46 @@ pub trait ExecutionEngine: Send + Sync {
     async fn cancel_task(&mut self, task_id: &str) -> Result<()>;
 }
 
+/// HTLCステップを実際のチェーンに送信する実行器
+#[async_trait]
+pub trait HtlcStepExecutor: Send + Sync {
+    /// `step.target_chain` にHTLCを作成し、エスクローIDを返す
+    async fn create_htlc(&self, step: &ExecutionStep, params: &HtlcParams) -> Result<String>;
+
+    /// `step.target_chain` のエスクローをクレームし、トランザクションハッシュを返す
+    async fn claim_htlc(
+        &self,
+        step: &ExecutionStep,
+        escrow_id: &str,
+        secret: Secret,
+    ) -> Result<String>;
+}
+
+#[async_trait]
+impl HtlcStepExecutor for CrossChainExecutor {
+    async fn create_htlc(&self, step: &ExecutionStep, params: &HtlcParams) -> Result<String> {
+        self.create_step_htlc(
+            &step.target_chain,
+            &step.token,
+            step.amount,
+            params.secret_hash,
+            params.timeout_seconds,
+            &params.recipient,
+        )
+        .await
+    }
+
+    async fn claim_htlc(
+        &self,
+        step: &ExecutionStep,
+        escrow_id: &str,
+        secret: Secret,
+    ) -> Result<String> {
+        self.claim_step_htlc(&step.target_chain, escrow_id, secret)
+            .await
+    }
+}
+
 /// 自動実行マネージャー
 pub struct AutomatedExecutor {
     /// 実行エンジン
@@ -83,76 +153,158 @@ pub struct AutomatedExecutor {
     task_queue: Arc<Mutex<Vec<ExecutionTask>>>,
     /// アクティブタスク
     active_tasks: Arc<Mutex<HashMap<String, ExecutionTask>>>,
+    /// リトライを使い切ったタスク（運用者が確認・手動で再実行する）
+    dead_letter: Arc<Mutex<Vec<ExecutionTask>>>,
     /// 最大同時実行数
     max_concurrent_tasks: usize,
     /// リトライ設定
     retry_config: RetryConfig,
+    /// ステータスが変わるたびに保存する先
+    persist_path: Option<PathBuf>,
+    /// リトライ遅延の揺らぎ（-1.0〜1.0）を返す関数
+    jitter_source: Box<dyn FnMut() -> f64 + Send>,
+}
+
+/// ファイルに保存するキューとアクティブタスク
+#[derive(Debug, Serialize, Deserialize)]
+struct ExecutorSnapshot {
+    task_queue: Vec<ExecutionTask>,
+    active_tasks: Vec<ExecutionTask>,
+    #[serde(default)]
+    dead_letter: Vec<ExecutionTask>,
 }
 
 /// リトライ設定
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
-    /// 最大リトライ回数
+    /// 最大リトライ回数（ステップ別の設定がない場合）
     pub max_retries: u8,
+    /// ステップタイプ別の最大リトライ回数
+    pub step_max_retries: HashMap<StepType, u8>,
     /// リトライ間隔（秒）
     pub retry_delay: u64,
     /// 指数バックオフ
     pub exponential_backoff: bool,
+    /// 遅延をランダムに揺らす割合（0.1なら±10%）
+    ///
+    /// RPC障害の後に多数のタスクが同時にリトライするのを防ぎます。
+    pub jitter_fraction: f64,
 }
 
 impl Default for RetryConfig {
     fn default() -> Self {
+        // クレームは冪等なので積極的に、作成は資金の二重ロックを避けるため慎重にリトライ
+        let step_max_retries = HashMap::from([(StepType::HTLCClaim, 5), (StepType::HTLCCreate, 1)]);
+
         Self {
             max_retries: 3,
+            step_max_retries,
             retry_delay: 60,
             exponential_backoff: true,
+            jitter_fraction: 0.1,
         }
     }
 }
 
+impl RetryConfig {
+    /// ステップタイプ別の最大リトライ回数を設定
+    pub fn with_step_max_retries(mut self, step_type: StepType, max_retries: u8) -> Self {
+        self.step_max_retries.insert(step_type, max_retries);
+        self
+    }
+
+    /// `retry_count` 回目のリトライまでの遅延
+    ///
+    /// `jitter` は-1.0〜1.0の値で、基準の遅延を `±jitter_fraction` の範囲で揺らします。
+    pub fn retry_delay_for(&self, retry_count: u8, jitter: f64) -> Duration {
+        let base = if self.exponential_backoff {
+            self.retry_delay * 2u64.pow(retry_count as u32)
+        } else {
+            self.retry_delay
+        };
+        let factor = 1.0 + self.jitter_fraction * jitter.clamp(-1.0, 1.0);
+        Duration::from_secs_f64((base as f64 * factor).max(0.0))
+    }
+
+    /// 失敗したステップに適用する最大リトライ回数
+    pub fn max_retries_for(&self, failed_step: Option<&StepType>) -> u8 {
+        failed_step
+            .and_then(|step_type| self.step_max_retries.get(step_type))
+            .copied()
+            .unwrap_or(self.max_retries)
+    }
+}
+
 /// 標準実行エンジン
-pub struct StandardExecutionEngine {
+pub struct StandardExecutionEngine<X: HtlcStepExecutor = CrossChainExecutor> {
     /// クロスチェーン実行器
-    #[allow(dead_code)]
-    cross_chain_executor: CrossChainExecutor,
-    /// 実行ログ
-    execution_log: Vec<ExecutionLog>,
+    cross_chain_executor: X,
+    /// 実行ログ（古いものから `log_capacity` 件を超えた分を破棄）
+    execution_log: VecDeque<ExecutionLog>,
+    /// 保持する実行ログの上限
+    log_capacity: usize,
 }
 
+/// 保持する実行ログの既定の上限
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
 /// 実行ログ
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-struct ExecutionLog {
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionLog {
     /// タイムスタンプ
-    timestamp: u64,
+    pub timestamp: u64,
     /// タスクID
-    task_id: String,
+    pub task_id: String,
     /// メッセージ
-    message: String,
+    pub message: String,
     /// ログレベル
-    level: LogLevel,
+    pub level: LogLevel,
 }
 
 /// ログレベル
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-enum LogLevel {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
     Info,
     Warning,
     Error,
 }
 
-impl StandardExecutionEngine {
-    pub fn new(cross_chain_executor: CrossChainExecutor) -> Self {
+impl<X: HtlcStepExecutor> StandardExecutionEngine<X> {
+    pub fn new(cross_chain_executor: X) -> Self {
         Self {
             cross_chain_executor,
-            execution_log: Vec::new(),
+            execution_log: VecDeque::new(),
+            log_capacity: DEFAULT_LOG_CAPACITY,
         }
     }
 
+    /// 保持する実行ログの上限を設定
+    pub fn with_log_capacity(mut self, log_capacity: usize) -> Self {
+        self.log_capacity = log_capacity;
+        while self.execution_log.len() > log_capacity {
+            self.execution_log.pop_front();
+        }
+        self
+    }
+
+    /// 実行ログを古い順に取得（タスクIDを指定するとそのタスクのログのみ）
+    pub fn get_logs(&self, task_id: Option<&str>) -> Vec<ExecutionLog> {
+        self.execution_log
+            .iter()
+            .filter(|log| task_id.is_none_or(|task_id| log.task_id == task_id))
+            .cloned()
+            .collect()
+    }
+
     /// ログを追加
     fn add_log(&mut self, task_id: String, message: String, level: LogLevel) {
-        self.execution_log.push(ExecutionLog {
+        if self.log_capacity == 0 {
+            return;
+        }
+        while self.execution_log.len() >= self.log_capacity {
+            self.execution_log.pop_front();
+        }
+        self.execution_log.push_back(ExecutionLog {
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
@@ -162,10 +314,40 @@ impl StandardExecutionEngine {
             level,
         });
     }
+
+    /// HTLCステップを実行し、トランザクションハッシュを返す
+    async fn execute_htlc_step(
+        &self,
+        task: &ExecutionTask,
+        step: &ExecutionStep,
+        created_escrow: &mut Option<String>,
+    ) -> Result<String> {
+        let params = task
+            .htlc
+            .as_ref()
+            .ok_or_else(|| anyhow!("Task {} has no HTLC parameters", task.id))?;
+
+        if step.step_type == StepType::HTLCCreate {
+            let escrow_id = self.cross_chain_executor.create_htlc(step, params).await?;
+            *created_escrow = Some(escrow_id.clone());
+            return Ok(escrow_id);
+        }
+
+        let escrow_id = created_escrow
+            .as_deref()
+            .or(params.escrow_id.as_deref())
+            .ok_or_else(|| anyhow!("No escrow to claim for task {}", task.id))?;
+        let secret = params
+            .secret
+            .ok_or_else(|| anyhow!("Task {} has no secret to claim with", task.id))?;
+        self.cross_chain_executor
+            .claim_htlc(step, escrow_id, secret)
+            .await
+    }
 }
 
 #[async_trait]
-impl ExecutionEngine for StandardExecutionEngine {
+impl<X: HtlcStepExecutor> ExecutionEngine for StandardExecutionEngine<X> {
     async fn execute_task(&mut self, task: &ExecutionTask) -> Result<TaskStatus> {
         self.add_log(
             task.id.clone(),
@@ -174,6 +356,7 @@ impl ExecutionEngine for StandardExecutionEngine {
         );
 
         let mut tx_hashes = Vec::new();
+        let mut created_escrow = None;
 
         // 各ステップを順番に実行
         for (i, step) in task.execution_path.steps.iter().enumerate() {
@@ -194,15 +377,26 @@ impl ExecutionEngine for StandardExecutionEngine {
                     let tx_hash = format!("swap_tx_{}", i);
                     tx_hashes.push(tx_hash);
                 }
-                StepType::HTLCCreate => {
-                    // HTLC作成（仮実装）
-                    let tx_hash = format!("htlc_create_tx_{}", i);
-                    tx_hashes.push(tx_hash);
-                }
-                StepType::HTLCClaim => {
-                    // HTLCクレーム（仮実装）
-                    let tx_hash = format!("htlc_claim_tx_{}", i);
-                    tx_hashes.push(tx_hash);
+                StepType::HTLCCreate | StepType::HTLCClaim => {
+                    // 失敗したら残りのステップは実行しない
+                    match self
+                        .execute_htlc_step(task, step, &mut created_escrow)
+                        .await
+                    {
+                        Ok(tx_hash) => tx_hashes.push(tx_hash),
+                        Err(e) => {
+                            self.add_log(
+                                task.id.clone(),
+                                format!("Step {} ({:?}) failed: {}", i, step.step_type, e),
+                                LogLevel::Error,
+                            );
+                            return Ok(TaskStatus::Failed {
+                                reason: e.to_string(),
+                                retry_count: task.retry_count,
+                                failed_step: Some(step.step_type.clone()),
+                            });
+                        }
+                    }
                 }
                 StepType::LimitOrderExecution => {
                     // リミットオーダー実行（仮実装）
@@ -252,8 +446,79 @@ impl AutomatedExecutor {
             engine,
             task_queue: Arc::new(Mutex::new(Vec::new())),
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
+            dead_letter: Arc::new(Mutex::new(Vec::new())),
             max_concurrent_tasks,
             retry_config,
+            persist_path: None,
+            jitter_source: Box::new(|| rand::thread_rng().gen_range(-1.0..=1.0)),
+        }
+    }
+
+    /// リトライ遅延の揺らぎを返す関数を差し替える（テストで固定値にする用途など）
+    pub fn with_jitter_source(mut self, source: impl FnMut() -> f64 + Send + 'static) -> Self {
+        self.jitter_source = Box::new(source);
+        self
+    }
+
+    /// 実行ループでステータスが変わるたびに `path` へ保存
+    pub fn with_auto_persist(mut self, path: impl Into<PathBuf>) -> Self {
+        self.persist_path = Some(path.into());
+        self
+    }
+
+    /// キューとアクティブタスクをJSONで保存
+    pub fn persist_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        let snapshot = ExecutorSnapshot {
+            task_queue: self.task_queue.lock().unwrap().clone(),
+            active_tasks: self
+                .active_tasks
+                .lock()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect(),
+            dead_letter: self.dead_letter.lock().unwrap().clone(),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&snapshot)?)?;
+        Ok(())
+    }
+
+    /// 保存したキューとアクティブタスクを読み込み、現在の内容を置き換える
+    ///
+    /// 実行途中で止まったタスクは、完了済みのステップを二重に実行しないよう
+    /// `Pending` としてキューに戻します。
+    pub fn load_from(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let snapshot: ExecutorSnapshot = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+
+        let mut queue = Vec::new();
+        let mut active = HashMap::new();
+        for mut task in snapshot.task_queue {
+            if matches!(task.status, TaskStatus::Executing { .. }) {
+                task.status = TaskStatus::Pending;
+            }
+            queue.push(task);
+        }
+        for mut task in snapshot.active_tasks {
+            if matches!(task.status, TaskStatus::Executing { .. }) {
+                task.status = TaskStatus::Pending;
+                queue.push(task);
+            } else {
+                active.insert(task.id.clone(), task);
+            }
+        }
+
+        *self.task_queue.lock().unwrap() = queue;
+        *self.active_tasks.lock().unwrap() = active;
+        *self.dead_letter.lock().unwrap() = snapshot.dead_letter;
+        Ok(())
+    }
+
+    /// 自動保存が有効なら現在の状態を保存
+    fn auto_persist(&self) {
+        if let Some(path) = &self.persist_path {
+            if let Err(e) = self.persist_to(path) {
+                eprintln!("Failed to persist task queue to {}: {}", path.display(), e);
+            }
         }
     }
 
@@ -264,6 +529,62 @@ impl AutomatedExecutor {
         Ok(())
     }
 
+    /// タスクをキャンセルし、キャンセルできたかを返す
+    ///
+    /// 開始前のタスクはキューから取り除き、アクティブなタスクはエンジンの
+    /// `cancel_task` に委ねてからリトライされないようアクティブから外します。
+    pub async fn cancel_queued_task(&mut self, task_id: &str) -> bool {
+        let removed = {
+            let mut queue = self.task_queue.lock().unwrap();
+            let before = queue.len();
+            queue.retain(|task| task.id != task_id);
+            queue.len() != before
+        };
+        if removed {
+            self.auto_persist();
+            return true;
+        }
+
+        if !self.active_tasks.lock().unwrap().contains_key(task_id) {
+            return false;
+        }
+        if let Err(e) = self.engine.cancel_task(task_id).await {
+            eprintln!("Failed to cancel task {}: {}", task_id, e);
+            return false;
+        }
+        self.active_tasks.lock().unwrap().remove(task_id);
+        self.auto_persist();
+        true
+    }
+
+    /// デッドレターキューのタスクをすべて取り出す
+    pub fn drain_dead_letter(&mut self) -> Vec<ExecutionTask> {
+        let drained = std::mem::take(&mut *self.dead_letter.lock().unwrap());
+        if !drained.is_empty() {
+            self.auto_persist();
+        }
+        drained
+    }
+
+    /// デッドレターキューのタスクをリトライ回数をリセットしてキューに戻す
+    pub fn requeue_dead_letter(&mut self, task_id: &str) -> Result<()> {
+        let mut task = {
+            let mut dead_letter = self.dead_letter.lock().unwrap();
+            let index = dead_letter
+                .iter()
+                .position(|t| t.id == task_id)
+                .ok_or_else(|| anyhow!("Task {} is not in the dead-letter queue", task_id))?;
+            dead_letter.remove(index)
+        };
+
+        task.status = TaskStatus::Pending;
+        task.retry_count = 0;
+        task.error_message = None;
+        self.add_task(task)?;
+        self.auto_persist();
+        Ok(())
+    }
+
     /// 実行ループを開始
     pub async fn start_execution_loop(&mut self) {
         let mut interval = interval(Duration::from_secs(5));
@@ -271,46 +592,61 @@ impl AutomatedExecutor {
         loop {
             interval.tick().await;
 
-            // アクティブタスク数をチェック
-            let active_count = self.active_tasks.lock().unwrap().len();
-            if active_count >= self.max_concurrent_tasks {
-                continue;
-            }
+            self.run_next_task().await;
 
-            // キューから次のタスクを取得
-            let next_task = {
-                let mut queue = self.task_queue.lock().unwrap();
-                queue.pop()
-            };
-
-            if let Some(mut task) = next_task {
-                // タスクを実行
-                match self.engine.execute_task(&task).await {
-                    Ok(status) => {
-                        task.status = status;
-                        task.updated_at = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs();
-                    }
-                    Err(e) => {
-                        task.status = TaskStatus::Failed {
-                            reason: e.to_string(),
-                            retry_count: 0,
-                        };
-                        task.error_message = Some(e.to_string());
+            // 完了/失敗タスクの処理
+            self.process_completed_tasks().await;
+        }
+    }
+
+    /// キューから次のタスクを取り出して実行
+    async fn run_next_task(&mut self) {
+        // アクティブタスク数をチェック
+        let active_count = self.active_tasks.lock().unwrap().len();
+        if active_count >= self.max_concurrent_tasks {
+            return;
+        }
+
+        // キューから次のタスクを取得
+        let next_task = {
+            let mut queue = self.task_queue.lock().unwrap();
+            queue.pop()
+        };
+
+        if let Some(mut task) = next_task {
+            // タスクを実行
+            match self.engine.execute_task(&task).await {
+                Ok(status) => {
+                    if let TaskStatus::Failed { reason, .. } = &status {
+                        task.error_message = Some(reason.clone());
                     }
+                    task.status = status;
+                    task.updated_at = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs();
                 }
+                Err(e) => {
+                    task.status = TaskStatus::Failed {
+                        reason: e.to_string(),
+                        retry_count: 0,
+                        failed_step: None,
+                    };
+                    task.error_message = Some(e.to_string());
+                }
+            }
 
-                // アクティブタスクに追加
-                self.active_tasks
-                    .lock()
-                    .unwrap()
-                    .insert(task.id.clone(), task);
+            // リトライ回数はタスク側で引き継ぐ
+            if let TaskStatus::Failed { retry_count, .. } = &mut task.status {
+                *retry_count = task.retry_count;
             }
 
-            // 完了/失敗タスクの処理
-            self.process_completed_tasks().await;
+            // アクティブタスクに追加
+            self.active_tasks
+                .lock()
+                .unwrap()
+                .insert(task.id.clone(), task);
+            self.auto_persist();
         }
     }
 
@@ -318,6 +654,7 @@ impl AutomatedExecutor {
     async fn process_completed_tasks(&mut self) {
         let mut tasks_to_retry = Vec::new();
         let mut completed_task_ids = Vec::new();
+        let mut exhausted_task_ids = Vec::new();
 
         {
             let active_tasks = self.active_tasks.lock().unwrap();
@@ -326,11 +663,16 @@ impl AutomatedExecutor {
                     TaskStatus::Completed { .. } => {
                         completed_task_ids.push(id.clone());
                     }
-                    TaskStatus::Failed { retry_count, .. } => {
-                        if *retry_count < self.retry_config.max_retries {
+                    TaskStatus::Failed {
+                        retry_count,
+                        failed_step,
+                        ..
+                    } => {
+                        let max_retries = self.retry_config.max_retries_for(failed_step.as_ref());
+                        if *retry_count < max_retries {
                             tasks_to_retry.push(task.clone());
                         } else {
-                            completed_task_ids.push(id.clone());
+                            exhausted_task_ids.push(id.clone());
                         }
                     }
                     _ => {}
@@ -338,12 +680,29 @@ impl AutomatedExecutor {
             }
         }
 
-        // 完了タスクを削除
+        if tasks_to_retry.is_empty()
+            && completed_task_ids.is_empty()
+            && exhausted_task_ids.is_empty()
+        {
+            return;
+        }
+
+        // 完了タスクとリトライするタスクをアクティブから外し、
+        // リトライを使い切ったタスクはデッドレターキューへ移す
         {
             let mut active_tasks = self.active_tasks.lock().unwrap();
             for id in completed_task_ids {
                 active_tasks.remove(&id);
             }
+            let mut dead_letter = self.dead_letter.lock().unwrap();
+            for id in exhausted_task_ids {
+                if let Some(task) = active_tasks.remove(&id) {
+                    dead_letter.push(task);
+                }
+            }
+            for task in &tasks_to_retry {
+                active_tasks.remove(&task.id);
+            }
         }
 
         // リトライタスクを再キュー
@@ -352,18 +711,17 @@ impl AutomatedExecutor {
                 let new_retry_count = retry_count + 1;
 
                 // リトライ遅延を適用
-                let delay = if self.retry_config.exponential_backoff {
-                    self.retry_config.retry_delay * 2u64.pow(new_retry_count as u32)
-                } else {
-                    self.retry_config.retry_delay
-                };
+                let jitter = (self.jitter_source)();
+                let delay = self.retry_config.retry_delay_for(new_retry_count, jitter);
 
-                tokio::time::sleep(Duration::from_secs(delay)).await;
+                tokio::time::sleep(delay).await;
 
                 task.status = TaskStatus::Pending;
+                task.retry_count = new_retry_count;
                 self.add_task(task).unwrap();
             }
         }
+        self.auto_persist();
     }
 
     /// タスクのステータスを取得
@@ -434,6 +792,8 @@ mod tests {
             created_at: 1234567890,
             updated_at: 1234567890,
             error_message: None,
+            retry_count: 0,
+            htlc: None,
         };
 
         assert_eq!(task.status, TaskStatus::Pending);
@@ -488,6 +848,8 @@ mod tests {
             created_at: 1234567890,
             updated_at: 1234567890,
             error_message: None,
+            retry_count: 0,
+            htlc: None,
         };
 
         let result = engine.execute_task(&task).await.unwrap();
@@ -542,20 +904,304 @@ mod tests {
         let status = TaskStatus::Failed {
             reason: "Test error".to_string(),
             retry_count: 2,
+            failed_step: Some(StepType::Bridge),
         };
 
         match status {
             TaskStatus::Failed {
                 reason,
                 retry_count,
+                failed_step,
             } => {
                 assert_eq!(reason, "Test error");
                 assert_eq!(retry_count, 2);
+                assert_eq!(failed_step, Some(StepType::Bridge));
             }
             _ => panic!("Expected failed status"),
         }
     }
 
+    /// 指定したステップで必ず失敗するエンジン
+    struct FailingEngine {
+        executions: Arc<Mutex<HashMap<String, usize>>>,
+    }
+
+    #[async_trait]
+    impl ExecutionEngine for FailingEngine {
+        async fn execute_task(&mut self, task: &ExecutionTask) -> Result<TaskStatus> {
+            *self
+                .executions
+                .lock()
+                .unwrap()
+                .entry(task.id.clone())
+                .or_insert(0) += 1;
+
+            Ok(TaskStatus::Failed {
+                reason: "step failed".to_string(),
+                retry_count: 0,
+                failed_step: task
+                    .execution_path
+                    .steps
+                    .first()
+                    .map(|s| s.step_type.clone()),
+            })
+        }
+
+        async fn get_task_progress(&self, _task_id: &str) -> Result<TaskStatus> {
+            Ok(TaskStatus::Pending)
+        }
+
+        async fn cancel_task(&mut self, _task_id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn single_step_task(id: &str, step_type: StepType) -> ExecutionTask {
+        ExecutionTask {
+            id: id.to_string(),
+            order_match: OrderMatch {
+                buy_order_id: "buy1".to_string(),
+                sell_order_id: "sell1".to_string(),
+                match_price: 5.0,
+                match_amount: 1000,
+                profit_bps: 100,
+            },
+            execution_path: ExecutionPath {
+                id: format!("path_{}", id),
+                steps: vec![ExecutionStep {
+                    step_type,
+                    source_chain: "ethereum".to_string(),
+                    target_chain: "near".to_string(),
+                    token: "USDC".to_string(),
+                    amount: 1000,
+                    estimated_cost: 10.0,
+                    estimated_time: 300,
+                }],
+                total_cost: 10.0,
+                total_time: 300,
+                risk_score: 20,
+                expected_profit: 5.0,
+            },
+            status: TaskStatus::Pending,
+            created_at: 1234567890,
+            updated_at: 1234567890,
+            error_message: None,
+            retry_count: 0,
+            htlc: None,
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_jitter_stays_within_band() {
+        let config = RetryConfig {
+            retry_delay: 60,
+            jitter_fraction: 0.2,
+            ..RetryConfig::default()
+        };
+        // 2回目のリトライの基準は 60 * 2^2 = 240秒
+        assert_eq!(config.retry_delay_for(2, 0.0), Duration::from_secs(240));
+        assert_eq!(config.retry_delay_for(2, 1.0), Duration::from_secs(288));
+        assert_eq!(config.retry_delay_for(2, -1.0), Duration::from_secs(192));
+
+        let mut rng = rand::thread_rng();
+        let delays: Vec<Duration> = (0..1000)
+            .map(|_| config.retry_delay_for(2, rng.gen_range(-1.0..=1.0)))
+            .collect();
+        assert!(delays
+            .iter()
+            .all(|d| (192.0..=288.0).contains(&d.as_secs_f64())));
+        // 揺らぎがあるので全タスクが同じ時刻にリトライしない
+        assert!(delays.iter().any(|d| *d != delays[0]));
+    }
+
+    #[test]
+    fn test_step_retry_budget_lookup() {
+        let config = RetryConfig::default().with_step_max_retries(StepType::Bridge, 0);
+
+        assert_eq!(config.max_retries_for(Some(&StepType::HTLCClaim)), 5);
+        assert_eq!(config.max_retries_for(Some(&StepType::HTLCCreate)), 1);
+        assert_eq!(config.max_retries_for(Some(&StepType::Bridge)), 0);
+        // 設定がないステップや不明なステップは共通の上限を使う
+        assert_eq!(config.max_retries_for(Some(&StepType::Swap)), 3);
+        assert_eq!(config.max_retries_for(None), 3);
+    }
+
+    #[tokio::test]
+    async fn test_claim_failures_retry_more_than_create_failures() {
+        let executions = Arc::new(Mutex::new(HashMap::new()));
+        let engine = Box::new(FailingEngine {
+            executions: executions.clone(),
+        });
+        let retry_config = RetryConfig {
+            retry_delay: 0,
+            exponential_backoff: false,
+            ..RetryConfig::default()
+        }
+        .with_step_max_retries(StepType::HTLCClaim, 4)
+        .with_step_max_retries(StepType::HTLCCreate, 1);
+
+        let mut executor =
+            AutomatedExecutor::new(engine, 5, retry_config).with_jitter_source(|| 0.0);
+        executor
+            .add_task(single_step_task("claim", StepType::HTLCClaim))
+            .unwrap();
+        executor
+            .add_task(single_step_task("create", StepType::HTLCCreate))
+            .unwrap();
+
+        for _ in 0..20 {
+            executor.run_next_task().await;
+            executor.process_completed_tasks().await;
+        }
+
+        let executions = executions.lock().unwrap();
+        // 初回実行 + リトライ回数
+        assert_eq!(executions["claim"], 5);
+        assert_eq!(executions["create"], 2);
+
+        // 予算を使い切ったタスクは再キューされない
+        assert!(executor.task_queue.lock().unwrap().is_empty());
+        assert!(executor.active_tasks.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_task() {
+        let executions = Arc::new(Mutex::new(HashMap::new()));
+        let engine = Box::new(FailingEngine {
+            executions: executions.clone(),
+        });
+        let mut executor = AutomatedExecutor::new(engine, 5, RetryConfig::default());
+        executor
+            .add_task(single_step_task("keep", StepType::Bridge))
+            .unwrap();
+        executor
+            .add_task(single_step_task("drop", StepType::Bridge))
+            .unwrap();
+
+        assert!(executor.cancel_queued_task("drop").await);
+        let queue = executor.task_queue.lock().unwrap().clone();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, "keep");
+        // 開始前のタスクなのでエンジンには渡らない
+        assert!(executions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_unknown_task_returns_false() {
+        let engine = Box::new(FailingEngine {
+            executions: Arc::new(Mutex::new(HashMap::new())),
+        });
+        let mut executor = AutomatedExecutor::new(engine, 5, RetryConfig::default());
+        executor
+            .add_task(single_step_task("keep", StepType::Bridge))
+            .unwrap();
+
+        assert!(!executor.cancel_queued_task("missing").await);
+        assert_eq!(executor.task_queue.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_tasks_land_in_dead_letter() {
+        let executions = Arc::new(Mutex::new(HashMap::new()));
+        let engine = Box::new(FailingEngine {
+            executions: executions.clone(),
+        });
+        let retry_config = RetryConfig {
+            max_retries: 1,
+            retry_delay: 0,
+            exponential_backoff: false,
+            ..RetryConfig::default()
+        };
+
+        let mut executor =
+            AutomatedExecutor::new(engine, 5, retry_config).with_jitter_source(|| 0.0);
+        executor
+            .add_task(single_step_task("bridge", StepType::Bridge))
+            .unwrap();
+
+        for _ in 0..5 {
+            executor.run_next_task().await;
+            executor.process_completed_tasks().await;
+        }
+        assert_eq!(executions.lock().unwrap()["bridge"], 2);
+
+        let dead = executor.drain_dead_letter();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].id, "bridge");
+        assert_eq!(dead[0].retry_count, 1);
+        assert_eq!(dead[0].error_message.as_deref(), Some("step failed"));
+        assert!(matches!(
+            &dead[0].status,
+            TaskStatus::Failed { reason, .. } if reason == "step failed"
+        ));
+        assert!(executor.drain_dead_letter().is_empty());
+
+        // 手動で再実行するとリトライ回数をリセットしてキューに戻る
+        executor.dead_letter.lock().unwrap().extend(dead);
+        assert!(executor.requeue_dead_letter("missing").is_err());
+        executor.requeue_dead_letter("bridge").unwrap();
+        let queue = executor.task_queue.lock().unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].status, TaskStatus::Pending);
+        assert_eq!(queue[0].retry_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_queue_round_trips() {
+        let path =
+            std::env::temp_dir().join(format!("fusion_executor_queue_{}.json", std::process::id()));
+        let new_executor = || {
+            let engine = Box::new(FailingEngine {
+                executions: Arc::new(Mutex::new(HashMap::new())),
+            });
+            AutomatedExecutor::new(engine, 5, RetryConfig::default())
+        };
+
+        let mut executor = new_executor().with_auto_persist(&path);
+        executor
+            .add_task(single_step_task("queued", StepType::Swap))
+            .unwrap();
+        // 実行ループが止まった時点で実行中だったタスク
+        let mut executing = single_step_task("executing", StepType::HTLCCreate);
+        executing.status = TaskStatus::Executing {
+            current_step: 0,
+            step_status: StepStatus::InProgress,
+        };
+        let mut completed = single_step_task("completed", StepType::HTLCClaim);
+        completed.status = TaskStatus::Completed {
+            tx_hashes: vec!["0xclaim".to_string()],
+        };
+        {
+            let mut active = executor.active_tasks.lock().unwrap();
+            active.insert(executing.id.clone(), executing.clone());
+            active.insert(completed.id.clone(), completed.clone());
+        }
+        executor.persist_to(&path).unwrap();
+
+        let mut restored = new_executor();
+        restored.load_from(&path).unwrap();
+
+        let queue = restored.task_queue.lock().unwrap().clone();
+        let queued: Vec<_> = queue.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(queued, vec!["queued", "executing"]);
+        assert_eq!(queue[1].status, TaskStatus::Pending);
+        assert_eq!(queue[1].execution_path, executing.execution_path);
+        assert_eq!(
+            restored.get_task_status("completed"),
+            Some(completed.status.clone())
+        );
+        assert_eq!(restored.get_task_status("executing"), None);
+
+        // ステータスが変わると自動保存される
+        executor.run_next_task().await;
+        let saved: ExecutorSnapshot =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(saved.task_queue.is_empty());
+        assert_eq!(saved.active_tasks.len(), 3);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_status_summary() {
         let cross_chain_executor = CrossChainExecutor::new(
@@ -576,4 +1222,155 @@ mod tests {
         assert_eq!(summary.get("completed").unwrap_or(&0), &0);
         assert_eq!(summary.get("failed").unwrap_or(&0), &0);
     }
+
+    /// 呼び出されたステップを記録し、指定したステップで失敗するモック
+    struct RecordingHtlcExecutor {
+        calls: Arc<Mutex<Vec<String>>>,
+        fail_claim: bool,
+    }
+
+    #[async_trait]
+    impl HtlcStepExecutor for RecordingHtlcExecutor {
+        async fn create_htlc(&self, step: &ExecutionStep, _params: &HtlcParams) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("create:{}", step.target_chain));
+            Ok(format!("escrow_on_{}", step.target_chain))
+        }
+
+        async fn claim_htlc(
+            &self,
+            step: &ExecutionStep,
+            escrow_id: &str,
+            _secret: Secret,
+        ) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("claim:{}", escrow_id));
+            if self.fail_claim {
+                return Err(anyhow!("claim reverted on {}", step.target_chain));
+            }
+            Ok("0xclaimtx".to_string())
+        }
+    }
+
+    fn htlc_task(steps: &[StepType]) -> ExecutionTask {
+        let mut task = single_step_task("htlc", StepType::HTLCCreate);
+        let template = task.execution_path.steps[0].clone();
+        task.execution_path.steps = steps
+            .iter()
+            .map(|step_type| ExecutionStep {
+                step_type: step_type.clone(),
+                ..template.clone()
+            })
+            .collect();
+        task.htlc = Some(HtlcParams {
+            secret_hash: [1u8; 32],
+            secret: Some([2u8; 32]),
+            recipient: "bob.near".to_string(),
+            timeout_seconds: 3600,
+            escrow_id: None,
+        });
+        task
+    }
+
+    #[tokio::test]
+    async fn test_htlc_steps_call_executor_and_abort_on_failure() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = StandardExecutionEngine::new(RecordingHtlcExecutor {
+            calls: calls.clone(),
+            fail_claim: false,
+        });
+
+        let task = htlc_task(&[StepType::HTLCCreate, StepType::HTLCClaim]);
+        let status = engine.execute_task(&task).await.unwrap();
+        assert_eq!(
+            status,
+            TaskStatus::Completed {
+                tx_hashes: vec!["escrow_on_near".to_string(), "0xclaimtx".to_string()]
+            }
+        );
+        // 同じタスクで作成したエスクローをクレームする
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "create:near".to_string(),
+                "claim:escrow_on_near".to_string()
+            ]
+        );
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = StandardExecutionEngine::new(RecordingHtlcExecutor {
+            calls: calls.clone(),
+            fail_claim: true,
+        });
+
+        let mut task = htlc_task(&[StepType::HTLCClaim, StepType::HTLCCreate]);
+        task.htlc.as_mut().unwrap().escrow_id = Some("existing".to_string());
+        let status = engine.execute_task(&task).await.unwrap();
+        assert_eq!(
+            status,
+            TaskStatus::Failed {
+                reason: "claim reverted on near".to_string(),
+                retry_count: 0,
+                failed_step: Some(StepType::HTLCClaim),
+            }
+        );
+        // 失敗後のステップは実行されない
+        assert_eq!(*calls.lock().unwrap(), vec!["claim:existing".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_execution_logs_are_filtered_by_task() {
+        let mut engine = StandardExecutionEngine::new(RecordingHtlcExecutor {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            fail_claim: false,
+        });
+
+        let task = htlc_task(&[]);
+        engine.execute_task(&task).await.unwrap();
+        engine.cancel_task("other").await.unwrap();
+
+        let logs = engine.get_logs(Some("htlc"));
+        assert_eq!(
+            logs.iter()
+                .map(|log| log.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Starting execution of task htlc",
+                "Task htlc completed successfully"
+            ]
+        );
+        assert!(logs.iter().all(|log| log.level == LogLevel::Info));
+
+        let other = engine.get_logs(Some("other"));
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].level, LogLevel::Warning);
+        assert_eq!(engine.get_logs(None).len(), 3);
+        assert!(engine.get_logs(Some("missing")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execution_log_is_capped() {
+        let mut engine = StandardExecutionEngine::new(RecordingHtlcExecutor {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            fail_claim: false,
+        })
+        .with_log_capacity(2);
+
+        for task_id in ["a", "b", "c"] {
+            engine.cancel_task(task_id).await.unwrap();
+        }
+
+        // 古いログから破棄される
+        let logs = engine.get_logs(None);
+        assert_eq!(
+            logs.iter()
+                .map(|log| log.task_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+    }
 }
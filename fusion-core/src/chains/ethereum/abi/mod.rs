@@ -49,6 +49,118 @@ pub mod factory {
                 "outputs": [{"internalType": "address", "name": "escrow", "type": "address"}],
                 "stateMutability": "payable",
                 "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "components": [
+                            {"internalType": "bytes32", "name": "orderHash", "type": "bytes32"},
+                            {"internalType": "bytes32", "name": "hashlock", "type": "bytes32"},
+                            {"internalType": "Address", "name": "maker", "type": "uint256"},
+                            {"internalType": "Address", "name": "taker", "type": "uint256"},
+                            {"internalType": "Address", "name": "token", "type": "uint256"},
+                            {"internalType": "uint256", "name": "amount", "type": "uint256"},
+                            {"internalType": "uint256", "name": "safetyDeposit", "type": "uint256"},
+                            {"internalType": "Timelocks", "name": "timelocks", "type": "uint256"}
+                        ],
+                        "internalType": "struct IBaseEscrow.Immutables",
+                        "name": "dstImmutables",
+                        "type": "tuple"
+                    },
+                    {"internalType": "uint256", "name": "srcCancellationTimestamp", "type": "uint256"}
+                ],
+                "name": "createDstEscrow",
+                "outputs": [],
+                "stateMutability": "payable",
+                "type": "function"
+            }
+        ]"#
+    );
+}
+
+#[allow(dead_code)]
+#[allow(clippy::all)]
+pub mod erc20 {
+    use ethers::prelude::*;
+
+    abigen!(
+        IERC20,
+        r#"[
+            {
+                "inputs": [
+                    {"internalType": "address", "name": "spender", "type": "address"},
+                    {"internalType": "uint256", "name": "amount", "type": "uint256"}
+                ],
+                "name": "approve",
+                "outputs": [{"internalType": "bool", "name": "", "type": "bool"}],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {"internalType": "address", "name": "owner", "type": "address"},
+                    {"internalType": "address", "name": "spender", "type": "address"}
+                ],
+                "name": "allowance",
+                "outputs": [{"internalType": "uint256", "name": "", "type": "uint256"}],
+                "stateMutability": "view",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {"internalType": "address", "name": "account", "type": "address"}
+                ],
+                "name": "balanceOf",
+                "outputs": [{"internalType": "uint256", "name": "", "type": "uint256"}],
+                "stateMutability": "view",
+                "type": "function"
+            },
+            {
+                "inputs": [],
+                "name": "decimals",
+                "outputs": [{"internalType": "uint8", "name": "", "type": "uint8"}],
+                "stateMutability": "view",
+                "type": "function"
+            },
+            {
+                "inputs": [],
+                "name": "symbol",
+                "outputs": [{"internalType": "string", "name": "", "type": "string"}],
+                "stateMutability": "view",
+                "type": "function"
+            }
+        ]"#
+    );
+}
+
+#[allow(dead_code)]
+#[allow(clippy::all)]
+pub mod aggregator {
+    // Chainlinkの価格フィード（AggregatorV3Interface）
+    use ethers::prelude::*;
+
+    abigen!(
+        AggregatorV3Interface,
+        r#"[
+            {
+                "inputs": [],
+                "name": "decimals",
+                "outputs": [{"internalType": "uint8", "name": "", "type": "uint8"}],
+                "stateMutability": "view",
+                "type": "function"
+            },
+            {
+                "inputs": [],
+                "name": "latestRoundData",
+                "outputs": [
+                    {"internalType": "uint80", "name": "roundId", "type": "uint80"},
+                    {"internalType": "int256", "name": "answer", "type": "int256"},
+                    {"internalType": "uint256", "name": "startedAt", "type": "uint256"},
+                    {"internalType": "uint256", "name": "updatedAt", "type": "uint256"},
+                    {"internalType": "uint80", "name": "answeredInRound", "type": "uint80"}
+                ],
+                "stateMutability": "view",
+                "type": "function"
             }
         ]"#
     );
@@ -0,0 +1,210 @@
+//! ソースエスクロー作成前の残高チェック
+//!
+//! エスクローに預ける本体とセーフティデポジット、ガス代をまかなえるかを
+//! 送信前に確認し、途中でリバートする代わりに不足額を明示して中断します。
+
+use super::abi;
+use super::immutables::{EscrowFunding, SafetyDepositAsset};
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{Address, U256};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// エスクロー作成トランザクションのガス上限の見積もり
+pub const ESCROW_CREATION_GAS: u64 = 300_000;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BalanceError {
+    #[error("Insufficient balance of {asset:?}: needed {needed}, available {available}")]
+    InsufficientBalance {
+        asset: SafetyDepositAsset,
+        needed: U256,
+        available: U256,
+    },
+    #[error("Failed to fetch balance from chain: {0}")]
+    Source(String),
+}
+
+/// 残高とガス価格を取得するソース
+#[async_trait]
+pub trait BalanceSource: Send + Sync {
+    async fn native_balance(&self, owner: Address) -> Result<U256, BalanceError>;
+    async fn token_balance(&self, token: Address, owner: Address) -> Result<U256, BalanceError>;
+    async fn gas_price(&self) -> Result<U256, BalanceError>;
+}
+
+#[async_trait]
+impl BalanceSource for Provider<Http> {
+    async fn native_balance(&self, owner: Address) -> Result<U256, BalanceError> {
+        self.get_balance(owner, None)
+            .await
+            .map_err(|e| BalanceError::Source(e.to_string()))
+    }
+
+    async fn token_balance(&self, token: Address, owner: Address) -> Result<U256, BalanceError> {
+        abi::erc20::IERC20::new(token, Arc::new(self.clone()))
+            .balance_of(owner)
+            .call()
+            .await
+            .map_err(|e| BalanceError::Source(e.to_string()))
+    }
+
+    async fn gas_price(&self) -> Result<U256, BalanceError> {
+        self.get_gas_price()
+            .await
+            .map_err(|e| BalanceError::Source(e.to_string()))
+    }
+}
+
+/// `funding` と `gas_limit` 分のガス代を `owner` が支払えるか確認
+///
+/// ネイティブトークンは送金額とガス代の合計、ERC20は承認額ごとに比較します。
+pub async fn check_funding<S: BalanceSource + ?Sized>(
+    source: &S,
+    owner: Address,
+    funding: &EscrowFunding,
+    gas_limit: U256,
+) -> Result<(), BalanceError> {
+    let gas_cost = if gas_limit.is_zero() {
+        U256::zero()
+    } else {
+        source.gas_price().await? * gas_limit
+    };
+
+    let native_needed = funding.value + gas_cost;
+    if !native_needed.is_zero() {
+        let available = source.native_balance(owner).await?;
+        if available < native_needed {
+            return Err(BalanceError::InsufficientBalance {
+                asset: SafetyDepositAsset::Native,
+                needed: native_needed,
+                available,
+            });
+        }
+    }
+
+    for (token, needed) in &funding.approvals {
+        let available = source.token_balance(*token, owner).await?;
+        if available < *needed {
+            return Err(BalanceError::InsufficientBalance {
+                asset: SafetyDepositAsset::Token(*token),
+                needed: *needed,
+                available,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MockProvider {
+        native: U256,
+        tokens: HashMap<Address, U256>,
+        gas_price: U256,
+    }
+
+    #[async_trait]
+    impl BalanceSource for MockProvider {
+        async fn native_balance(&self, _owner: Address) -> Result<U256, BalanceError> {
+            Ok(self.native)
+        }
+
+        async fn token_balance(
+            &self,
+            token: Address,
+            _owner: Address,
+        ) -> Result<U256, BalanceError> {
+            Ok(self.tokens.get(&token).copied().unwrap_or_default())
+        }
+
+        async fn gas_price(&self) -> Result<U256, BalanceError> {
+            Ok(self.gas_price)
+        }
+    }
+
+    fn native_funding(value: u64) -> EscrowFunding {
+        EscrowFunding {
+            value: value.into(),
+            approvals: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sufficient_balance_passes() {
+        let token = Address::repeat_byte(0x11);
+        let provider = MockProvider {
+            native: 1_300_000u64.into(),
+            tokens: HashMap::from([(token, U256::from(500u64))]),
+            gas_price: 1u64.into(),
+        };
+        let funding = EscrowFunding {
+            value: 1_000_000u64.into(),
+            approvals: vec![(token, 500u64.into())],
+        };
+
+        // 本体1_000_000 + ガス300_000 をちょうどまかなえる
+        let result = check_funding(
+            &provider,
+            Address::random(),
+            &funding,
+            ESCROW_CREATION_GAS.into(),
+        )
+        .await;
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_gas_cost_counts_toward_native_balance() {
+        let provider = MockProvider {
+            native: 1_000_000u64.into(),
+            tokens: HashMap::new(),
+            gas_price: 2u64.into(),
+        };
+
+        let result = check_funding(
+            &provider,
+            Address::random(),
+            &native_funding(1_000_000),
+            ESCROW_CREATION_GAS.into(),
+        )
+        .await;
+        assert_eq!(
+            result,
+            Err(BalanceError::InsufficientBalance {
+                asset: SafetyDepositAsset::Native,
+                needed: 1_600_000u64.into(),
+                available: 1_000_000u64.into(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_token_balance_is_reported() {
+        let token = Address::repeat_byte(0x22);
+        let provider = MockProvider {
+            native: U256::MAX,
+            tokens: HashMap::from([(token, U256::from(999u64))]),
+            gas_price: 1u64.into(),
+        };
+        let funding = EscrowFunding {
+            value: U256::zero(),
+            approvals: vec![(token, 1_000u64.into())],
+        };
+
+        let result = check_funding(&provider, Address::random(), &funding, U256::zero()).await;
+        assert_eq!(
+            result,
+            Err(BalanceError::InsufficientBalance {
+                asset: SafetyDepositAsset::Token(token),
+                needed: 1_000u64.into(),
+                available: 999u64.into(),
+            })
+        );
+    }
+}
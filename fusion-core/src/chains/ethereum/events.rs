@@ -1,10 +1,16 @@
+use crate::chains::ethereum::abi::escrow::ClaimCall;
 use crate::chains::ethereum::event_storage::{convert_to_stored_event, EventStorage};
+use crate::htlc::hash_secret;
+use crate::secret_manager::SecretManager;
+use async_trait::async_trait;
 use ethers::{
-    contract::EthEvent,
-    core::types::{Address, Filter, H256, U256},
-    providers::{Middleware, Provider, StreamExt, Ws},
+    abi::AbiDecode,
+    contract::{EthCall, EthEvent},
+    core::types::{Address, BlockNumber, Bytes, Filter, H256, U256},
+    providers::{Http, Middleware, Provider, StreamExt, Ws},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
@@ -200,3 +206,234 @@ impl LimitOrderEventMonitor {
         Ok(())
     }
 }
+
+/// エスクローの `claim(bytes32)` 呼び出し
+#[derive(Debug, Clone)]
+pub struct ClaimLog {
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub escrow: Address,
+    pub input: Bytes,
+}
+
+/// `claim` 呼び出しの取得元
+#[async_trait]
+pub trait ClaimSource: Send + Sync {
+    async fn latest_block(&self) -> Result<u64, Box<dyn std::error::Error>>;
+
+    /// `[from_block, to_block]` に含まれる `claim` 呼び出し
+    async fn claim_calls(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<ClaimLog>, Box<dyn std::error::Error>>;
+}
+
+#[async_trait]
+impl ClaimSource for Provider<Http> {
+    async fn latest_block(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(self.get_block_number().await?.as_u64())
+    }
+
+    async fn claim_calls(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<ClaimLog>, Box<dyn std::error::Error>> {
+        let mut calls = Vec::new();
+        for number in from_block..=to_block {
+            let Some(block) = self
+                .get_block_with_txs(BlockNumber::Number(number.into()))
+                .await?
+            else {
+                continue;
+            };
+            for tx in block.transactions {
+                let Some(escrow) = tx.to else {
+                    continue;
+                };
+                if tx.input.starts_with(&ClaimCall::selector()) {
+                    calls.push(ClaimLog {
+                        block_number: number,
+                        tx_hash: tx.hash,
+                        escrow,
+                        input: tx.input,
+                    });
+                }
+            }
+        }
+        Ok(calls)
+    }
+}
+
+/// オンチェーンで公開されたシークレット
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevealedSecret {
+    pub swap_id: String,
+    pub escrow: Address,
+    pub tx_hash: H256,
+    pub block_number: u64,
+}
+
+/// エスクローの `claim` 呼び出しからシークレットを取り出す監視
+///
+/// 前回の走査位置（`from_block`）から最新ブロックまでを走査し、
+/// reorgに備えて直近 `reorg_window` ブロックは毎回走査し直します。
+pub struct EthereumSecretWatcher<S: ClaimSource> {
+    source: S,
+    escrows: HashSet<Address>,
+    from_block: u64,
+    reorg_window: u64,
+}
+
+impl<S: ClaimSource> EthereumSecretWatcher<S> {
+    pub fn new(source: S, from_block: u64) -> Self {
+        Self {
+            source,
+            escrows: HashSet::new(),
+            from_block,
+            reorg_window: 12,
+        }
+    }
+
+    /// 監視対象のエスクローを限定（未指定なら全ての `claim` 呼び出しを対象）
+    pub fn with_escrows(mut self, escrows: impl IntoIterator<Item = Address>) -> Self {
+        self.escrows = escrows.into_iter().collect();
+        self
+    }
+
+    /// 再走査する確認待ちブロック数
+    pub fn with_reorg_window(mut self, reorg_window: u64) -> Self {
+        self.reorg_window = reorg_window;
+        self
+    }
+
+    /// 次回の走査を開始するブロック
+    pub fn from_block(&self) -> u64 {
+        self.from_block
+    }
+
+    /// 新しいブロックを走査し、今回初めて公開が確認されたスワップを返す
+    pub async fn poll(
+        &mut self,
+        secrets: &mut SecretManager,
+    ) -> Result<Vec<RevealedSecret>, Box<dyn std::error::Error>> {
+        let head = self.source.latest_block().await?;
+        let start = self.from_block.saturating_sub(self.reorg_window);
+        if start > head {
+            return Ok(Vec::new());
+        }
+
+        let mut revealed = Vec::new();
+        for call in self.source.claim_calls(start, head).await? {
+            if !self.escrows.is_empty() && !self.escrows.contains(&call.escrow) {
+                continue;
+            }
+            let Ok(claim) = ClaimCall::decode(&call.input) else {
+                continue;
+            };
+            let Some(swap_id) = secrets
+                .swap_for_hashlock(&hash_secret(&claim.secret))
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            // 再走査で同じ公開を二重に報告しない
+            if secrets.is_secret_revealed(&swap_id) {
+                continue;
+            }
+            secrets.mark_secret_revealed(&claim.secret);
+            revealed.push(RevealedSecret {
+                swap_id,
+                escrow: call.escrow,
+                tx_hash: call.tx_hash,
+                block_number: call.block_number,
+            });
+        }
+
+        self.from_block = head + 1;
+        Ok(revealed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::AbiEncode;
+    use std::sync::Mutex;
+
+    struct MockClaims {
+        head: Mutex<u64>,
+        calls: Vec<ClaimLog>,
+        scanned: Mutex<Vec<(u64, u64)>>,
+    }
+
+    #[async_trait]
+    impl ClaimSource for MockClaims {
+        async fn latest_block(&self) -> Result<u64, Box<dyn std::error::Error>> {
+            Ok(*self.head.lock().unwrap())
+        }
+
+        async fn claim_calls(
+            &self,
+            from_block: u64,
+            to_block: u64,
+        ) -> Result<Vec<ClaimLog>, Box<dyn std::error::Error>> {
+            self.scanned.lock().unwrap().push((from_block, to_block));
+            Ok(self
+                .calls
+                .iter()
+                .filter(|call| (from_block..=to_block).contains(&call.block_number))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn claim_log(block_number: u64, escrow: Address, secret: [u8; 32]) -> ClaimLog {
+        ClaimLog {
+            block_number,
+            tx_hash: H256::from_low_u64_be(block_number),
+            escrow,
+            input: ClaimCall { secret }.encode().into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claim_log_marks_swap_revealed() {
+        let mut secrets = SecretManager::new();
+        secrets.generate_secret("swap-1").unwrap();
+        let secret = secrets.swap_secret("swap-1").unwrap();
+
+        let escrow = Address::from_low_u64_be(0xe5c);
+        let other = Address::from_low_u64_be(0x0de);
+        let source = MockClaims {
+            head: Mutex::new(105),
+            calls: vec![
+                // 監視対象外のエスクローは無視
+                claim_log(101, other, [9u8; 32]),
+                claim_log(103, escrow, secret),
+            ],
+            scanned: Mutex::new(Vec::new()),
+        };
+        let mut watcher = EthereumSecretWatcher::new(source, 100)
+            .with_escrows([escrow])
+            .with_reorg_window(3);
+
+        let revealed = watcher.poll(&mut secrets).await.unwrap();
+        assert_eq!(revealed.len(), 1);
+        assert_eq!(revealed[0].swap_id, "swap-1");
+        assert_eq!(revealed[0].block_number, 103);
+        assert!(secrets.is_secret_revealed("swap-1"));
+        assert_eq!(watcher.from_block(), 106);
+
+        // 次の走査では確認待ちの範囲を再走査するが、二重には報告しない
+        *watcher.source.head.lock().unwrap() = 107;
+        let revealed = watcher.poll(&mut secrets).await.unwrap();
+        assert!(revealed.is_empty());
+        assert_eq!(
+            *watcher.source.scanned.lock().unwrap(),
+            vec![(97, 105), (103, 107)]
+        );
+        assert_eq!(watcher.from_block(), 108);
+    }
+}
@@ -0,0 +1,139 @@
+//! 操作ごとの実ガス使用量の記録
+//!
+//! 送信したトランザクションのレシートから `gas_used` を操作種別ごとに集計し、
+//! 固定の見積もりの代わりに観測した平均値をガス代の見積もりに使えるようにします。
+
+use crate::execution_path_optimizer::StepType;
+use ethers::types::{TransactionReceipt, U256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// ガス使用量を記録する操作の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GasOperation {
+    CreateEscrow,
+    ClaimEscrow,
+    RefundEscrow,
+}
+
+impl GasOperation {
+    /// 実行ステップに対応する操作（オンチェーン送信を伴わないステップはNone）
+    pub fn for_step(step_type: &StepType) -> Option<Self> {
+        match step_type {
+            StepType::HTLCCreate => Some(Self::CreateEscrow),
+            StepType::HTLCClaim => Some(Self::ClaimEscrow),
+            _ => None,
+        }
+    }
+}
+
+/// 操作ごとの累計
+#[derive(Debug, Clone, Copy, Default)]
+struct GasTotals {
+    count: u64,
+    total: U256,
+}
+
+/// 操作ごとの実ガス使用量の移動平均
+#[derive(Debug, Default)]
+pub struct GasUsageTracker {
+    totals: Mutex<HashMap<GasOperation, GasTotals>>,
+}
+
+impl GasUsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// レシートの `gas_used` を記録（`gas_used` のないレシートは無視）
+    pub fn record(&self, operation: GasOperation, receipt: &TransactionReceipt) {
+        let Some(gas_used) = receipt.gas_used else {
+            return;
+        };
+        let mut totals = self.totals.lock().unwrap();
+        let entry = totals.entry(operation).or_default();
+        entry.count += 1;
+        entry.total = entry.total.saturating_add(gas_used);
+    }
+
+    /// 記録した実ガス使用量の平均（未記録ならNone）
+    pub fn average(&self, operation: GasOperation) -> Option<U256> {
+        let totals = self.totals.lock().unwrap();
+        totals
+            .get(&operation)
+            .map(|entry| entry.total / U256::from(entry.count))
+    }
+
+    /// 記録した件数
+    pub fn sample_count(&self, operation: GasOperation) -> u64 {
+        let totals = self.totals.lock().unwrap();
+        totals.get(&operation).map_or(0, |entry| entry.count)
+    }
+
+    /// 観測した平均があればそれを、なければ `fallback` をガス量の見積もりとして返す
+    pub fn estimate_or(&self, operation: GasOperation, fallback: U256) -> U256 {
+        self.average(operation).unwrap_or(fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(gas_used: Option<u64>) -> TransactionReceipt {
+        TransactionReceipt {
+            gas_used: gas_used.map(U256::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_average_updates_with_each_receipt() {
+        let tracker = GasUsageTracker::new();
+        assert_eq!(tracker.average(GasOperation::CreateEscrow), None);
+        assert_eq!(
+            tracker.estimate_or(GasOperation::CreateEscrow, U256::from(300_000)),
+            U256::from(300_000)
+        );
+
+        tracker.record(GasOperation::CreateEscrow, &receipt(Some(180_000)));
+        assert_eq!(
+            tracker.average(GasOperation::CreateEscrow),
+            Some(U256::from(180_000))
+        );
+
+        tracker.record(GasOperation::CreateEscrow, &receipt(Some(220_000)));
+        // gas_used のないレシートは平均に影響しない
+        tracker.record(GasOperation::CreateEscrow, &receipt(None));
+        assert_eq!(
+            tracker.average(GasOperation::CreateEscrow),
+            Some(U256::from(200_000))
+        );
+        assert_eq!(tracker.sample_count(GasOperation::CreateEscrow), 2);
+        assert_eq!(
+            tracker.estimate_or(GasOperation::CreateEscrow, U256::from(300_000)),
+            U256::from(200_000)
+        );
+
+        // 操作ごとに独立して集計
+        tracker.record(GasOperation::ClaimEscrow, &receipt(Some(60_000)));
+        assert_eq!(
+            tracker.average(GasOperation::ClaimEscrow),
+            Some(U256::from(60_000))
+        );
+        assert_eq!(tracker.average(GasOperation::RefundEscrow), None);
+    }
+
+    #[test]
+    fn test_operation_for_step() {
+        assert_eq!(
+            GasOperation::for_step(&StepType::HTLCCreate),
+            Some(GasOperation::CreateEscrow)
+        );
+        assert_eq!(
+            GasOperation::for_step(&StepType::HTLCClaim),
+            Some(GasOperation::ClaimEscrow)
+        );
+        assert_eq!(GasOperation::for_step(&StepType::Bridge), None);
+    }
+}
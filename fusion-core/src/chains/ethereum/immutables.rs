@@ -0,0 +1,364 @@
+//! 1inch Cross-Chain Swap のエスクロー用Immutablesとタイムロック
+//!
+//! `IBaseEscrow.Immutables` と `TimelocksLib` のパッキングをRust側で再現し、
+//! エスクローファクトリーがCREATE2でデプロイするアドレスを事前に計算します。
+
+use ethers::abi::Token;
+use ethers::types::{Address, H256, U256};
+use ethers::utils::{get_create2_address_from_hash, keccak256};
+use serde::{Deserialize, Serialize};
+
+/// 各ステージの開始オフセット（デプロイ時刻からの秒数）
+///
+/// `TimelocksLib.Stage` と同じ順序でパックされます。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timelocks {
+    pub src_withdrawal: u32,
+    pub src_public_withdrawal: u32,
+    pub src_cancellation: u32,
+    pub src_public_cancellation: u32,
+    pub dst_withdrawal: u32,
+    pub dst_public_withdrawal: u32,
+    pub dst_cancellation: u32,
+    /// デプロイ時刻（ファクトリーがデプロイ時に設定）
+    pub deployed_at: u32,
+}
+
+impl Timelocks {
+    /// HTLCのタイムアウトからタイムロックを生成
+    ///
+    /// デスティネーション側のキャンセルはソース側より先に開始させ、
+    /// リゾルバーが両側で資金を失わないようにします。
+    pub fn from_timeout(timeout: u32) -> Self {
+        Self {
+            src_withdrawal: 0,
+            src_public_withdrawal: timeout / 2,
+            src_cancellation: timeout,
+            src_public_cancellation: timeout.saturating_add(timeout / 2),
+            dst_withdrawal: 0,
+            dst_public_withdrawal: timeout / 4,
+            dst_cancellation: timeout / 2,
+            deployed_at: 0,
+        }
+    }
+
+    pub fn with_deployed_at(mut self, deployed_at: u32) -> Self {
+        self.deployed_at = deployed_at;
+        self
+    }
+
+    /// `Timelocks` (uint256) へパック
+    pub fn pack(&self) -> U256 {
+        let stages = [
+            self.src_withdrawal,
+            self.src_public_withdrawal,
+            self.src_cancellation,
+            self.src_public_cancellation,
+            self.dst_withdrawal,
+            self.dst_public_withdrawal,
+            self.dst_cancellation,
+        ];
+
+        let packed = stages
+            .iter()
+            .enumerate()
+            .fold(U256::zero(), |acc, (i, offset)| {
+                acc | (U256::from(*offset) << (i * 32))
+            });
+        packed | (U256::from(self.deployed_at) << 224)
+    }
+
+    /// パック済みの値から復元
+    pub fn unpack(packed: U256) -> Self {
+        let stage = |i: usize| ((packed >> (i * 32)) & U256::from(u32::MAX)).as_u32();
+        Self {
+            src_withdrawal: stage(0),
+            src_public_withdrawal: stage(1),
+            src_cancellation: stage(2),
+            src_public_cancellation: stage(3),
+            dst_withdrawal: stage(4),
+            dst_public_withdrawal: stage(5),
+            dst_cancellation: stage(6),
+            deployed_at: stage(7),
+        }
+    }
+}
+
+/// `IBaseEscrow.Immutables`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Immutables {
+    pub order_hash: H256,
+    pub hashlock: H256,
+    pub maker: Address,
+    pub taker: Address,
+    /// ネイティブトークンの場合はゼロアドレス
+    pub token: Address,
+    pub amount: U256,
+    pub safety_deposit: U256,
+    pub timelocks: Timelocks,
+}
+
+impl Immutables {
+    /// `abi.encode(immutables)` と同じ順序のトークン列
+    pub fn to_tokens(&self) -> Vec<Token> {
+        vec![
+            Token::FixedBytes(self.order_hash.as_bytes().to_vec()),
+            Token::FixedBytes(self.hashlock.as_bytes().to_vec()),
+            Token::Address(self.maker),
+            Token::Address(self.taker),
+            Token::Address(self.token),
+            Token::Uint(self.amount),
+            Token::Uint(self.safety_deposit),
+            Token::Uint(self.timelocks.pack()),
+        ]
+    }
+
+    /// `ImmutablesLib.hash` と同じハッシュ（CREATE2のsalt）
+    pub fn hash(&self) -> H256 {
+        H256::from(keccak256(ethers::abi::encode(&self.to_tokens())))
+    }
+}
+
+/// セーフティデポジットとして預ける資産
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SafetyDepositAsset {
+    /// ETHなどのネイティブトークン（1inchファクトリーの標準）
+    #[default]
+    Native,
+    /// 任意のERC20トークン
+    Token(Address),
+}
+
+/// エスクロー作成時に必要な送金額と承認
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EscrowFunding {
+    /// トランザクションに付けるネイティブトークンの量
+    pub value: U256,
+    /// ファクトリーへの承認が必要なERC20と金額（同じトークンは合算）
+    pub approvals: Vec<(Address, U256)>,
+}
+
+impl EscrowFunding {
+    /// 本体の金額とセーフティデポジットの両方をまかなう資金計画を作成
+    pub fn for_immutables(immutables: &Immutables, deposit_asset: SafetyDepositAsset) -> Self {
+        let mut funding = Self::default();
+
+        let main_asset = if immutables.token == Address::zero() {
+            SafetyDepositAsset::Native
+        } else {
+            SafetyDepositAsset::Token(immutables.token)
+        };
+        funding.add(main_asset, immutables.amount);
+        funding.add(deposit_asset, immutables.safety_deposit);
+
+        funding
+    }
+
+    /// `token`（ゼロアドレスはネイティブ）を `amount` だけ送る資金計画を作成
+    pub fn for_transfer(token: Address, amount: U256) -> Self {
+        let asset = if token == Address::zero() {
+            SafetyDepositAsset::Native
+        } else {
+            SafetyDepositAsset::Token(token)
+        };
+        let mut funding = Self::default();
+        funding.add(asset, amount);
+        funding
+    }
+
+    fn add(&mut self, asset: SafetyDepositAsset, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        match asset {
+            SafetyDepositAsset::Native => self.value += amount,
+            SafetyDepositAsset::Token(token) => {
+                match self.approvals.iter_mut().find(|(t, _)| *t == token) {
+                    Some((_, approved)) => *approved += amount,
+                    None => self.approvals.push((token, amount)),
+                }
+            }
+        }
+    }
+}
+
+/// `Clones.cloneDeterministic` が使うプロキシのバイトコードハッシュ
+pub fn proxy_bytecode_hash(implementation: Address) -> H256 {
+    let mut bytecode = Vec::with_capacity(55);
+    bytecode.extend_from_slice(&[
+        0x3d, 0x60, 0x2d, 0x80, 0x60, 0x0a, 0x3d, 0x39, 0x81, 0xf3, 0x36, 0x3d, 0x3d, 0x37, 0x3d,
+        0x3d, 0x3d, 0x36, 0x3d, 0x73,
+    ]);
+    bytecode.extend_from_slice(implementation.as_bytes());
+    bytecode.extend_from_slice(&[
+        0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+    ]);
+    H256::from(keccak256(bytecode))
+}
+
+/// ファクトリーの `addressOfEscrowSrc` と同じ計算
+pub fn address_of_escrow_src(
+    factory: Address,
+    src_implementation: Address,
+    immutables: &Immutables,
+) -> Address {
+    get_create2_address_from_hash(
+        factory,
+        immutables.hash(),
+        proxy_bytecode_hash(src_implementation),
+    )
+}
+
+/// ファクトリーの `addressOfEscrowDst` と同じ計算
+pub fn address_of_escrow_dst(
+    factory: Address,
+    dst_implementation: Address,
+    immutables: &Immutables,
+) -> Address {
+    get_create2_address_from_hash(
+        factory,
+        immutables.hash(),
+        proxy_bytecode_hash(dst_implementation),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_immutables() -> Immutables {
+        Immutables {
+            order_hash: H256::repeat_byte(0x11),
+            hashlock: H256::repeat_byte(0x22),
+            maker: Address::repeat_byte(0x33),
+            taker: Address::repeat_byte(0x44),
+            token: Address::zero(),
+            amount: U256::from(1_000_000u64),
+            safety_deposit: U256::from(1_000u64),
+            timelocks: Timelocks::from_timeout(3600).with_deployed_at(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn test_timelocks_pack_layout() {
+        let timelocks = Timelocks {
+            src_withdrawal: 1,
+            dst_cancellation: 7,
+            deployed_at: 9,
+            ..Default::default()
+        };
+
+        let packed = timelocks.pack();
+        assert_eq!(packed & U256::from(u32::MAX), U256::from(1));
+        assert_eq!((packed >> 192) & U256::from(u32::MAX), U256::from(7));
+        assert_eq!(packed >> 224, U256::from(9));
+        assert_eq!(Timelocks::unpack(packed), timelocks);
+    }
+
+    #[test]
+    fn test_dst_cancellation_precedes_src_cancellation() {
+        let timelocks = Timelocks::from_timeout(3600);
+        assert!(timelocks.dst_cancellation < timelocks.src_cancellation);
+        assert!(timelocks.src_cancellation < timelocks.src_public_cancellation);
+    }
+
+    #[test]
+    fn test_hash_matches_abi_encoding() {
+        let immutables = sample_immutables();
+        let encoded = ethers::abi::encode(&immutables.to_tokens());
+
+        // 8ワードの静的エンコード
+        assert_eq!(encoded.len(), 8 * 32);
+        assert_eq!(immutables.hash(), H256::from(keccak256(encoded)));
+    }
+
+    #[test]
+    fn test_native_escrow_with_native_safety_deposit() {
+        let immutables = sample_immutables();
+        let funding = EscrowFunding::for_immutables(&immutables, SafetyDepositAsset::Native);
+
+        assert_eq!(funding.value, U256::from(1_001_000u64));
+        assert!(funding.approvals.is_empty());
+    }
+
+    #[test]
+    fn test_erc20_escrow_with_native_safety_deposit() {
+        let token = Address::repeat_byte(0x55);
+        let immutables = Immutables {
+            token,
+            ..sample_immutables()
+        };
+        let funding = EscrowFunding::for_immutables(&immutables, SafetyDepositAsset::Native);
+
+        assert_eq!(funding.value, U256::from(1_000u64));
+        assert_eq!(funding.approvals, vec![(token, U256::from(1_000_000u64))]);
+    }
+
+    #[test]
+    fn test_erc20_safety_deposit_approvals() {
+        let token = Address::repeat_byte(0x55);
+        let deposit_token = Address::repeat_byte(0x66);
+        let immutables = Immutables {
+            token,
+            ..sample_immutables()
+        };
+
+        // 同じトークンなら承認額を合算
+        let same = EscrowFunding::for_immutables(&immutables, SafetyDepositAsset::Token(token));
+        assert_eq!(same.value, U256::zero());
+        assert_eq!(same.approvals, vec![(token, U256::from(1_001_000u64))]);
+
+        // 別のトークンなら両方を承認
+        let different =
+            EscrowFunding::for_immutables(&immutables, SafetyDepositAsset::Token(deposit_token));
+        assert_eq!(different.value, U256::zero());
+        assert_eq!(
+            different.approvals,
+            vec![
+                (token, U256::from(1_000_000u64)),
+                (deposit_token, U256::from(1_000u64))
+            ]
+        );
+
+        // ネイティブの本体 + ERC20のデポジット
+        let native = EscrowFunding::for_immutables(
+            &sample_immutables(),
+            SafetyDepositAsset::Token(deposit_token),
+        );
+        assert_eq!(native.value, U256::from(1_000_000u64));
+        assert_eq!(
+            native.approvals,
+            vec![(deposit_token, U256::from(1_000u64))]
+        );
+    }
+
+    #[test]
+    fn test_zero_safety_deposit_needs_no_approval() {
+        let deposit_token = Address::repeat_byte(0x66);
+        let immutables = Immutables {
+            safety_deposit: U256::zero(),
+            ..sample_immutables()
+        };
+        let funding =
+            EscrowFunding::for_immutables(&immutables, SafetyDepositAsset::Token(deposit_token));
+
+        assert_eq!(funding.value, U256::from(1_000_000u64));
+        assert!(funding.approvals.is_empty());
+    }
+
+    #[test]
+    fn test_escrow_address_depends_on_immutables_and_implementation() {
+        let factory = Address::repeat_byte(0xfa);
+        let src_impl = Address::repeat_byte(0x01);
+        let dst_impl = Address::repeat_byte(0x02);
+        let immutables = sample_immutables();
+
+        let src = address_of_escrow_src(factory, src_impl, &immutables);
+        assert_eq!(src, address_of_escrow_src(factory, src_impl, &immutables));
+        assert_ne!(src, address_of_escrow_dst(factory, dst_impl, &immutables));
+
+        let mut later = immutables.clone();
+        later.timelocks.deployed_at += 1;
+        assert_ne!(src, address_of_escrow_src(factory, src_impl, &later));
+    }
+}
@@ -1,32 +1,199 @@
 use crate::htlc::SecretHash;
+use async_trait::async_trait;
+use balance::{check_funding, BalanceError, ESCROW_CREATION_GAS};
 use ethers::middleware::SignerMiddleware;
-use ethers::providers::{Http, Provider};
+use ethers::providers::{Http, Middleware, Provider};
 use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, TransactionReceipt, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockNumber, Bytes, NameOrAddress, TransactionReceipt, H256, U256};
+use gas_usage::{GasOperation, GasUsageTracker};
+use immutables::{EscrowFunding, Immutables, SafetyDepositAsset};
+use nonce_manager::NonceManager;
+use serde::Serialize;
 use std::sync::Arc;
 
 pub mod abi;
+pub mod balance;
 pub mod event_monitor;
 pub mod event_storage;
 pub mod events;
+pub mod gas_usage;
+pub mod immutables;
 pub mod limit_order_abi;
+pub mod nonce_manager;
 pub mod order_extractor;
+pub mod token_check;
+
+/// EIP-1559トランザクションのガス料金設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasConfig {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// オフライン署名用の未署名EIP-1559トランザクション
+///
+/// 署名ツールへそのまま取り込めるよう、数値は16進文字列で出力します。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction {
+    #[serde(rename = "type")]
+    pub tx_type: U256,
+    pub chain_id: U256,
+    pub nonce: U256,
+    pub from: Address,
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+    pub gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl UnsignedTransaction {
+    /// ガス料金まで設定済みのリクエストから作成
+    pub fn from_request(
+        tx: &TypedTransaction,
+        chain_id: u64,
+        nonce: U256,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let inner = tx
+            .as_eip1559_ref()
+            .ok_or("Only EIP-1559 transactions can be exported")?;
+        let to = match inner.to {
+            Some(NameOrAddress::Address(to)) => to,
+            _ => return Err("Transaction has no destination address".into()),
+        };
+
+        Ok(Self {
+            tx_type: U256::from(2),
+            chain_id: chain_id.into(),
+            nonce,
+            from: inner.from.ok_or("Transaction has no sender")?,
+            to,
+            data: inner.data.clone().unwrap_or_default(),
+            value: inner.value.unwrap_or_default(),
+            gas: inner.gas.ok_or("Transaction has no gas limit")?,
+            max_fee_per_gas: inner.max_fee_per_gas.ok_or("max_fee_per_gas is not set")?,
+            max_priority_fee_per_gas: inner
+                .max_priority_fee_per_gas
+                .ok_or("max_priority_fee_per_gas is not set")?,
+        })
+    }
+}
+
+/// `create_escrow_with_approval` で送信したトランザクション
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FundedEscrow {
+    /// 承認トランザクション。ネイティブETHや承認済みの場合はNone
+    pub approval_tx: Option<H256>,
+    pub create_tx: H256,
+    pub escrow: Address,
+}
+
+/// エスクロー作成とファクトリーへのERC20承認を送信する操作
+#[async_trait]
+pub trait EscrowTransactor: Send + Sync {
+    /// 署名者がファクトリーに承認しているトークン量
+    async fn factory_allowance(&self, token: Address) -> Result<U256, Box<dyn std::error::Error>>;
+
+    /// ファクトリーに `amount` を承認し、トランザクションハッシュを返す
+    async fn approve_factory(
+        &self,
+        token: Address,
+        amount: U256,
+    ) -> Result<H256, Box<dyn std::error::Error>>;
+
+    /// エスクローを作成し、(トランザクションハッシュ, エスクローアドレス) を返す
+    async fn send_create_escrow(
+        &self,
+        token: Address,
+        amount: U256,
+        secret_hash: SecretHash,
+        timeout: U256,
+        recipient: Address,
+    ) -> Result<(H256, Address), Box<dyn std::error::Error>>;
+}
+
+/// 承認が足りなければ承認してからエスクローを作成
+///
+/// ネイティブETH（ゼロアドレス）は承認不要なので作成だけを送ります。
+pub async fn fund_and_create_escrow<T: EscrowTransactor + ?Sized>(
+    transactor: &T,
+    token: Address,
+    amount: U256,
+    secret_hash: SecretHash,
+    timeout: U256,
+    recipient: Address,
+) -> Result<FundedEscrow, Box<dyn std::error::Error>> {
+    let approval_tx =
+        if token == Address::zero() || transactor.factory_allowance(token).await? >= amount {
+            None
+        } else {
+            Some(transactor.approve_factory(token, amount).await?)
+        };
+
+    let (create_tx, escrow) = transactor
+        .send_create_escrow(token, amount, secret_hash, timeout, recipient)
+        .await?;
+    Ok(FundedEscrow {
+        approval_tx,
+        create_tx,
+        escrow,
+    })
+}
 
 pub struct EthereumConnector {
     provider: Arc<Provider<Http>>,
     factory_address: Address,
     signer: Option<LocalWallet>,
+    nonce_manager: Arc<NonceManager>,
+    gas_config: Option<GasConfig>,
+    gas_usage: Arc<GasUsageTracker>,
+    /// 署名に使うチェーンID。未設定ならプロバイダーから取得してキャッシュ
+    chain_id: tokio::sync::OnceCell<u64>,
 }
 
 impl EthereumConnector {
     pub fn new(rpc_url: &str, factory_address: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let provider = Provider::<Http>::try_from(rpc_url)?;
+        Self::new_with_headers(rpc_url, factory_address, std::iter::empty::<(&str, &str)>())
+    }
+
+    /// 全リクエストにHTTPヘッダーを付与するプロバイダーで作成
+    ///
+    /// APIキーをURLではなく `Authorization` 等のヘッダーで渡すRPC向けです。
+    /// ヘッダー値はsensitive扱いとなり、デバッグ出力に表示されません。
+    pub fn new_with_headers<K, V>(
+        rpc_url: &str,
+        factory_address: &str,
+        headers: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut header_map = reqwest::header::HeaderMap::new();
+        for (name, value) in headers {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_ref().as_bytes())?;
+            let mut value = reqwest::header::HeaderValue::from_str(value.as_ref())?;
+            value.set_sensitive(true);
+            header_map.insert(name, value);
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(header_map)
+            .build()?;
+        let provider = Provider::new(Http::new_with_client(reqwest::Url::parse(rpc_url)?, client));
         let factory_address = factory_address.parse::<Address>()?;
 
         Ok(Self {
             provider: Arc::new(provider),
             factory_address,
             signer: None,
+            nonce_manager: Arc::new(NonceManager::new()),
+            gas_config: None,
+            gas_usage: Arc::new(GasUsageTracker::new()),
+            chain_id: tokio::sync::OnceCell::new(),
         })
     }
 
@@ -36,6 +203,120 @@ impl EthereumConnector {
         Ok(self)
     }
 
+    /// 署名するトランザクションのチェーンIDを固定
+    ///
+    /// 未設定の場合はプロバイダーの `eth_chainId` で取得します。
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = tokio::sync::OnceCell::new_with(Some(chain_id));
+        self
+    }
+
+    /// 署名に使うチェーンID
+    pub async fn chain_id(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let chain_id = self
+            .chain_id
+            .get_or_try_init(|| async {
+                self.provider
+                    .get_chainid()
+                    .await
+                    .map(|chain_id| chain_id.as_u64())
+            })
+            .await?;
+        Ok(*chain_id)
+    }
+
+    /// チェーンIDを設定した署名者
+    async fn signing_wallet(&self) -> Result<LocalWallet, Box<dyn std::error::Error>> {
+        let signer = self.signer.as_ref().ok_or("Signer not configured")?;
+        Ok(signer.clone().with_chain_id(self.chain_id().await?))
+    }
+
+    /// 同じアドレスで送信する他のコネクタとnonceマネージャーを共有
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<NonceManager>) -> Self {
+        self.nonce_manager = nonce_manager;
+        self
+    }
+
+    pub fn nonce_manager(&self) -> Arc<NonceManager> {
+        self.nonce_manager.clone()
+    }
+
+    /// 他のコネクタと実ガス使用量の記録を共有
+    pub fn with_gas_usage(mut self, gas_usage: Arc<GasUsageTracker>) -> Self {
+        self.gas_usage = gas_usage;
+        self
+    }
+
+    /// 送信したエスクロー操作のレシートから集計した実ガス使用量
+    pub fn gas_usage(&self) -> Arc<GasUsageTracker> {
+        self.gas_usage.clone()
+    }
+
+    /// 送信するトランザクションのEIP-1559ガス料金を固定
+    ///
+    /// 未設定の場合はプロバイダーの `estimate_eip1559_fees` で見積もります。
+    pub fn with_gas_config(mut self, gas_config: GasConfig) -> Self {
+        self.gas_config = Some(gas_config);
+        self
+    }
+
+    /// 設定済みまたは見積もったガス料金をトランザクションに設定
+    async fn apply_gas_fees(
+        &self,
+        tx: &mut TypedTransaction,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let gas = match self.gas_config {
+            Some(gas) => gas,
+            None => {
+                let (max_fee_per_gas, max_priority_fee_per_gas) =
+                    self.provider.estimate_eip1559_fees(None).await?;
+                GasConfig {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                }
+            }
+        };
+
+        match tx.as_eip1559_mut() {
+            Some(inner) => {
+                inner.max_fee_per_gas = Some(gas.max_fee_per_gas);
+                inner.max_priority_fee_per_gas = Some(gas.max_priority_fee_per_gas);
+            }
+            // レガシー形式では上限額をガス価格として使う
+            None => {
+                tx.set_gas_price(gas.max_fee_per_gas);
+            }
+        }
+        Ok(())
+    }
+
+    /// 送信失敗で欠番が出ないようチェーンと再同期する
+    async fn recover_nonce(&self, address: Address) {
+        if self
+            .nonce_manager
+            .resync(address, self.provider.as_ref())
+            .await
+            .is_err()
+        {
+            // 再同期できなければ次回の割り当て時にチェーンから初期化
+            self.nonce_manager.reset(address).await;
+        }
+    }
+
+    /// 送信前に資金とエスクロー作成のガス代が足りるか確認
+    ///
+    /// ガス量はエスクロー作成の実測平均があればそれを、なければ固定の見積もりを使います。
+    pub async fn preflight_funding(
+        &self,
+        owner: Address,
+        funding: &EscrowFunding,
+    ) -> Result<(), BalanceError> {
+        let gas_limit = self
+            .gas_usage
+            .estimate_or(GasOperation::CreateEscrow, ESCROW_CREATION_GAS.into());
+        check_funding(self.provider.as_ref(), owner, funding, gas_limit).await
+    }
+
     pub async fn create_escrow(
         &self,
         token: Address,
@@ -44,10 +325,41 @@ impl EthereumConnector {
         timeout: U256,
         recipient: Address,
     ) -> Result<Address, Box<dyn std::error::Error>> {
+        let (_, escrow) = self
+            .send_create_escrow(token, amount, secret_hash, timeout, recipient)
+            .await?;
+        Ok(escrow)
+    }
+
+    /// ERC20の承認が足りなければ先に承認し、エスクローを作成
+    ///
+    /// 承認と作成の両方のトランザクションハッシュを返します。
+    pub async fn create_escrow_with_approval(
+        &self,
+        token: Address,
+        amount: U256,
+        secret_hash: SecretHash,
+        timeout: U256,
+        recipient: Address,
+    ) -> Result<FundedEscrow, Box<dyn std::error::Error>> {
+        fund_and_create_escrow(self, token, amount, secret_hash, timeout, recipient).await
+    }
+
+    async fn create_escrow_tx(
+        &self,
+        token: Address,
+        amount: U256,
+        secret_hash: SecretHash,
+        timeout: U256,
+        recipient: Address,
+    ) -> Result<(H256, Address), Box<dyn std::error::Error>> {
         let signer = self.signer.as_ref().ok_or("Signer not configured")?;
 
-        let client =
-            SignerMiddleware::new(self.provider.clone(), signer.clone().with_chain_id(1u64));
+        // 途中でリバートする前に残高不足で中断
+        let funding = EscrowFunding::for_transfer(token, amount);
+        self.preflight_funding(signer.address(), &funding).await?;
+
+        let client = SignerMiddleware::new(self.provider.clone(), self.signing_wallet().await?);
 
         // Foundryで生成されたABIバインディングを使用
         let factory = abi::factory::IEscrowFactory::new(self.factory_address, Arc::new(client));
@@ -65,8 +377,22 @@ impl EthereumConnector {
             tx
         };
 
-        let pending_tx = tx.send().await?;
+        let nonce = self
+            .nonce_manager
+            .next_nonce(signer.address(), self.provider.as_ref())
+            .await?;
+        let mut tx = tx.nonce(nonce);
+        self.apply_gas_fees(&mut tx.tx).await?;
+        let pending_tx = match tx.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.recover_nonce(signer.address()).await;
+                return Err(e.into());
+            }
+        };
         let receipt = pending_tx.await?.ok_or("Transaction failed")?;
+        self.gas_usage.record(GasOperation::CreateEscrow, &receipt);
+        let tx_hash = receipt.transaction_hash;
 
         // イベントからescrowアドレスを取得
         // EscrowCreatedイベントの2番目のトピックがescrowアドレス
@@ -76,13 +402,170 @@ impl EthereumConnector {
                 // topic[1]: escrowId (indexed)
                 // topic[2]: escrow address (indexed)
                 let escrow_address = Address::from(log.topics[2]);
-                return Ok(escrow_address);
+                return Ok((tx_hash, escrow_address));
             }
         }
 
         Err("Escrow address not found in logs".into())
     }
 
+    /// `create_escrow` の呼び出し（宛先・calldata・value・ガス上限）を組み立てる
+    pub fn create_escrow_request(
+        &self,
+        from: Address,
+        token: Address,
+        amount: U256,
+        secret_hash: SecretHash,
+        timeout: U256,
+        recipient: Address,
+    ) -> TypedTransaction {
+        let factory =
+            abi::factory::IEscrowFactory::new(self.factory_address, self.provider.clone());
+        let call = factory
+            .create_escrow(token, amount, secret_hash, timeout, recipient)
+            .from(from)
+            .gas(ESCROW_CREATION_GAS);
+
+        // ETHを送る場合はvalueを設定
+        let call = if token == Address::zero() {
+            call.value(amount)
+        } else {
+            call
+        };
+        call.tx
+    }
+
+    /// 送信せずに `create_escrow` の未署名トランザクションを作成
+    ///
+    /// nonce・チェーンID・ガス料金はチェーンから取得します。nonceは予約しないため、
+    /// 署名するまで同じアドレスから他のトランザクションを送らないでください。
+    pub async fn unsigned_create_escrow(
+        &self,
+        from: Address,
+        token: Address,
+        amount: U256,
+        secret_hash: SecretHash,
+        timeout: U256,
+        recipient: Address,
+    ) -> Result<UnsignedTransaction, Box<dyn std::error::Error>> {
+        let mut tx =
+            self.create_escrow_request(from, token, amount, secret_hash, timeout, recipient);
+        self.apply_gas_fees(&mut tx).await?;
+
+        let nonce = self
+            .provider
+            .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+            .await?;
+        UnsignedTransaction::from_request(&tx, self.chain_id().await?, nonce)
+    }
+
+    /// 1inchファクトリーでデスティネーションエスクローを作成
+    ///
+    /// セーフティデポジットはネイティブトークンかERC20で預けられます。
+    /// ERC20で必要な分はファクトリーへの承認を先に送ります。
+    /// `deployed_at` はファクトリーがブロック時刻で上書きします。
+    pub async fn create_dst_escrow(
+        &self,
+        immutables: &Immutables,
+        deposit_asset: SafetyDepositAsset,
+        src_cancellation_timestamp: U256,
+    ) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
+        let signer = self.signer.as_ref().ok_or("Signer not configured")?;
+
+        let client = Arc::new(SignerMiddleware::new(
+            self.provider.clone(),
+            self.signing_wallet().await?,
+        ));
+
+        let funding = EscrowFunding::for_immutables(immutables, deposit_asset);
+        self.preflight_funding(signer.address(), &funding).await?;
+        for (token, amount) in &funding.approvals {
+            self.ensure_allowance(client.clone(), *token, *amount)
+                .await?;
+        }
+
+        let factory = abi::factory::IEscrowFactory::new(self.factory_address, client);
+
+        let dst_immutables = abi::factory::Immutables {
+            order_hash: immutables.order_hash.into(),
+            hashlock: immutables.hashlock.into(),
+            maker: address_to_uint(immutables.maker),
+            taker: address_to_uint(immutables.taker),
+            token: address_to_uint(immutables.token),
+            amount: immutables.amount,
+            safety_deposit: immutables.safety_deposit,
+            timelocks: immutables.timelocks.pack(),
+        };
+
+        let tx = factory
+            .create_dst_escrow(dst_immutables, src_cancellation_timestamp)
+            .value(funding.value);
+
+        let nonce = self
+            .nonce_manager
+            .next_nonce(signer.address(), self.provider.as_ref())
+            .await?;
+        let mut tx = tx.nonce(nonce);
+        self.apply_gas_fees(&mut tx.tx).await?;
+        let pending_tx = match tx.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.recover_nonce(signer.address()).await;
+                return Err(e.into());
+            }
+        };
+        let receipt = pending_tx.await?.ok_or("Transaction failed")?;
+
+        Ok(receipt)
+    }
+
+    /// ファクトリーへの承認が足りなければ追加で承認
+    async fn ensure_allowance(
+        &self,
+        client: Arc<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let owner = client.address();
+        let erc20 = abi::erc20::IERC20::new(token, client.clone());
+
+        let allowance = erc20.allowance(owner, self.factory_address).call().await?;
+        if allowance >= amount {
+            return Ok(());
+        }
+
+        self.send_approval(client, token, amount).await?;
+        Ok(())
+    }
+
+    /// ファクトリーに `amount` を承認するトランザクションを送信
+    async fn send_approval(
+        &self,
+        client: Arc<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>,
+        token: Address,
+        amount: U256,
+    ) -> Result<H256, Box<dyn std::error::Error>> {
+        let owner = client.address();
+        let erc20 = abi::erc20::IERC20::new(token, client);
+
+        let nonce = self
+            .nonce_manager
+            .next_nonce(owner, self.provider.as_ref())
+            .await?;
+        let mut tx = erc20.approve(self.factory_address, amount).nonce(nonce);
+        self.apply_gas_fees(&mut tx.tx).await?;
+        let pending_tx = match tx.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.recover_nonce(owner).await;
+                return Err(e.into());
+            }
+        };
+        let receipt = pending_tx.await?.ok_or("Approval failed")?;
+
+        Ok(receipt.transaction_hash)
+    }
+
     pub async fn claim_escrow(
         &self,
         escrow_address: Address,
@@ -90,16 +573,27 @@ impl EthereumConnector {
     ) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
         let signer = self.signer.as_ref().ok_or("Signer not configured")?;
 
-        let client =
-            SignerMiddleware::new(self.provider.clone(), signer.clone().with_chain_id(1u64));
+        let client = SignerMiddleware::new(self.provider.clone(), self.signing_wallet().await?);
 
         // Escrowコントラクトに接続
         let escrow = abi::escrow::IEscrow::new(escrow_address, Arc::new(client));
 
         // claimを実行
-        let tx = escrow.claim(secret);
-        let pending_tx = tx.send().await?;
+        let nonce = self
+            .nonce_manager
+            .next_nonce(signer.address(), self.provider.as_ref())
+            .await?;
+        let mut tx = escrow.claim(secret).nonce(nonce);
+        self.apply_gas_fees(&mut tx.tx).await?;
+        let pending_tx = match tx.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.recover_nonce(signer.address()).await;
+                return Err(e.into());
+            }
+        };
         let receipt = pending_tx.await?.ok_or("Transaction failed")?;
+        self.gas_usage.record(GasOperation::ClaimEscrow, &receipt);
 
         Ok(receipt)
     }
@@ -110,21 +604,73 @@ impl EthereumConnector {
     ) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
         let signer = self.signer.as_ref().ok_or("Signer not configured")?;
 
-        let client =
-            SignerMiddleware::new(self.provider.clone(), signer.clone().with_chain_id(1u64));
+        let client = SignerMiddleware::new(self.provider.clone(), self.signing_wallet().await?);
 
         // Escrowコントラクトに接続
         let escrow = abi::escrow::IEscrow::new(escrow_address, Arc::new(client));
 
         // refundを実行
-        let tx = escrow.refund();
-        let pending_tx = tx.send().await?;
+        let nonce = self
+            .nonce_manager
+            .next_nonce(signer.address(), self.provider.as_ref())
+            .await?;
+        let mut tx = escrow.refund().nonce(nonce);
+        self.apply_gas_fees(&mut tx.tx).await?;
+        let pending_tx = match tx.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.recover_nonce(signer.address()).await;
+                return Err(e.into());
+            }
+        };
         let receipt = pending_tx.await?.ok_or("Transaction failed")?;
+        self.gas_usage.record(GasOperation::RefundEscrow, &receipt);
 
         Ok(receipt)
     }
 }
 
+#[async_trait]
+impl EscrowTransactor for EthereumConnector {
+    async fn factory_allowance(&self, token: Address) -> Result<U256, Box<dyn std::error::Error>> {
+        let signer = self.signer.as_ref().ok_or("Signer not configured")?;
+        let erc20 = abi::erc20::IERC20::new(token, self.provider.clone());
+        Ok(erc20
+            .allowance(signer.address(), self.factory_address)
+            .call()
+            .await?)
+    }
+
+    async fn approve_factory(
+        &self,
+        token: Address,
+        amount: U256,
+    ) -> Result<H256, Box<dyn std::error::Error>> {
+        let client = Arc::new(SignerMiddleware::new(
+            self.provider.clone(),
+            self.signing_wallet().await?,
+        ));
+        self.send_approval(client, token, amount).await
+    }
+
+    async fn send_create_escrow(
+        &self,
+        token: Address,
+        amount: U256,
+        secret_hash: SecretHash,
+        timeout: U256,
+        recipient: Address,
+    ) -> Result<(H256, Address), Box<dyn std::error::Error>> {
+        self.create_escrow_tx(token, amount, secret_hash, timeout, recipient)
+            .await
+    }
+}
+
+/// 1inchの `Address` 型（uint256）へ変換
+fn address_to_uint(address: Address) -> U256 {
+    U256::from_big_endian(address.as_bytes())
+}
+
 // For testing on Sepolia
 pub const SEPOLIA_RPC: &str = "https://sepolia.infura.io/v3/YOUR_INFURA_KEY";
 pub const ESCROW_FACTORY_SEPOLIA: &str = "0x0000000000000000000000000000000000000000"; // TODO: Get actual address after deployment
@@ -155,4 +701,290 @@ mod tests {
         let result = connector.with_signer(private_key);
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_signer_uses_configured_chain_id() {
+        // RPCに届かないURLでも、明示したチェーンIDは問い合わせずに使う
+        let connector = EthereumConnector::new(
+            "http://127.0.0.1:1",
+            "0x0000000000000000000000000000000000000000",
+        )
+        .unwrap()
+        .with_signer("0x0123456789012345678901234567890123456789012345678901234567890123")
+        .unwrap()
+        .with_chain_id(84532);
+
+        assert_eq!(connector.chain_id().await.unwrap(), 84532);
+        assert_eq!(connector.signing_wallet().await.unwrap().chain_id(), 84532);
+    }
+
+    #[test]
+    fn test_shared_nonce_manager() {
+        let nonce_manager = Arc::new(NonceManager::new());
+
+        let first = EthereumConnector::new(
+            "https://sepolia.infura.io/v3/test",
+            "0x0000000000000000000000000000000000000000",
+        )
+        .unwrap()
+        .with_nonce_manager(nonce_manager.clone());
+        let second = EthereumConnector::new(
+            "https://sepolia.infura.io/v3/test",
+            "0x0000000000000000000000000000000000000000",
+        )
+        .unwrap()
+        .with_nonce_manager(nonce_manager.clone());
+
+        assert!(Arc::ptr_eq(&first.nonce_manager(), &second.nonce_manager()));
+    }
+
+    #[tokio::test]
+    async fn test_gas_config_sets_eip1559_fields() {
+        use ethers::types::Eip1559TransactionRequest;
+
+        let gas = GasConfig {
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(2_000_000_000u64),
+        };
+        // 固定設定があればRPCに問い合わせない
+        let connector = EthereumConnector::new(
+            "http://127.0.0.1:1",
+            "0x0000000000000000000000000000000000000000",
+        )
+        .unwrap()
+        .with_gas_config(gas);
+
+        let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::new());
+        connector.apply_gas_fees(&mut tx).await.unwrap();
+
+        let inner = tx.as_eip1559_ref().unwrap();
+        assert_eq!(inner.max_fee_per_gas, Some(gas.max_fee_per_gas));
+        assert_eq!(
+            inner.max_priority_fee_per_gas,
+            Some(gas.max_priority_fee_per_gas)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_create_escrow_targets_factory() {
+        use abi::factory::CreateEscrowCall;
+        use ethers::abi::AbiDecode;
+        use ethers::contract::EthCall;
+
+        let factory: Address = "0x171C87724E720F2806fc29a010a62897B30fdb62"
+            .parse()
+            .unwrap();
+        let connector = EthereumConnector::new("http://127.0.0.1:1", &format!("{:?}", factory))
+            .unwrap()
+            .with_gas_config(GasConfig {
+                max_fee_per_gas: U256::from(30_000_000_000u64),
+                max_priority_fee_per_gas: U256::from(2_000_000_000u64),
+            });
+
+        let from = Address::repeat_byte(0x11);
+        let amount = U256::exp10(18);
+        let mut tx = connector.create_escrow_request(
+            from,
+            Address::zero(),
+            amount,
+            [7u8; 32],
+            U256::from(3600),
+            Address::repeat_byte(0x22),
+        );
+        connector.apply_gas_fees(&mut tx).await.unwrap();
+        let unsigned = UnsignedTransaction::from_request(&tx, 84532, U256::from(5)).unwrap();
+
+        let json = serde_json::to_value(&unsigned).unwrap();
+        assert_eq!(json["to"], format!("{:?}", factory));
+        assert_eq!(json["from"], format!("{:?}", from));
+        assert_eq!(json["nonce"], "0x5");
+        assert_eq!(json["chainId"], "0x14a34");
+        assert_eq!(json["value"], "0xde0b6b3a7640000");
+        assert_eq!(json["gas"], format!("{:#x}", ESCROW_CREATION_GAS));
+        assert_eq!(json["maxFeePerGas"], "0x6fc23ac00");
+
+        // createEscrow(address,uint256,bytes32,uint256,address)
+        assert_eq!(hex::encode(CreateEscrowCall::selector()), "b30f23ef");
+        assert!(json["data"].as_str().unwrap().starts_with("0xb30f23ef"));
+        let call = CreateEscrowCall::decode(&unsigned.data).unwrap();
+        assert_eq!(call.secret_hash, [7u8; 32]);
+        assert_eq!(call.recipient, Address::repeat_byte(0x22));
+    }
+
+    /// 1リクエストだけ受け付け、受信したヘッダーを返すJSON-RPCサーバー
+    async fn capture_request_headers() -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // ヘッダーとボディを読み切るまで受信
+            let (head, body) = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break (head.to_string(), body.to_string());
+                    }
+                }
+            };
+
+            let id = serde_json::from_str::<serde_json::Value>(&body).unwrap()["id"].clone();
+            let response_body =
+                serde_json::json!({"jsonrpc": "2.0", "id": id, "result": "0x1"}).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response_body.len(),
+                response_body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            head
+        });
+
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_are_sent_to_rpc() {
+        use ethers::providers::Middleware;
+
+        let (url, server) = capture_request_headers().await;
+        let connector = EthereumConnector::new_with_headers(
+            &url,
+            "0x0000000000000000000000000000000000000000",
+            [
+                ("Authorization", "Bearer test-key"),
+                ("X-Api-Key", "abc123"),
+            ],
+        )
+        .unwrap();
+
+        let chain_id = connector.provider.get_chainid().await.unwrap();
+        assert_eq!(chain_id, U256::one());
+
+        let head = server.await.unwrap().to_lowercase();
+        assert!(head.contains("authorization: bearer test-key"));
+        assert!(head.contains("x-api-key: abc123"));
+    }
+
+    /// 許可量を固定で返し、送信したトランザクションを記録するモック
+    struct RecordingTransactor {
+        allowance: U256,
+        sent: std::sync::Mutex<Vec<&'static str>>,
+    }
+
+    #[async_trait]
+    impl EscrowTransactor for RecordingTransactor {
+        async fn factory_allowance(
+            &self,
+            _token: Address,
+        ) -> Result<U256, Box<dyn std::error::Error>> {
+            Ok(self.allowance)
+        }
+
+        async fn approve_factory(
+            &self,
+            _token: Address,
+            _amount: U256,
+        ) -> Result<H256, Box<dyn std::error::Error>> {
+            self.sent.lock().unwrap().push("approve");
+            Ok(H256::repeat_byte(0xaa))
+        }
+
+        async fn send_create_escrow(
+            &self,
+            _token: Address,
+            _amount: U256,
+            _secret_hash: SecretHash,
+            _timeout: U256,
+            _recipient: Address,
+        ) -> Result<(H256, Address), Box<dyn std::error::Error>> {
+            self.sent.lock().unwrap().push("create");
+            Ok((H256::repeat_byte(0xcc), Address::repeat_byte(0xee)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approval_sent_only_when_allowance_is_short() {
+        let token = Address::repeat_byte(0x33);
+        let amount = U256::from(1_000_000u64);
+        let create = |transactor: RecordingTransactor, token: Address| async move {
+            let funded = fund_and_create_escrow(
+                &transactor,
+                token,
+                amount,
+                [7u8; 32],
+                U256::from(3600),
+                Address::repeat_byte(0x22),
+            )
+            .await
+            .unwrap();
+            (funded, transactor.sent.into_inner().unwrap())
+        };
+
+        // 許可量不足: 承認してから作成
+        let (funded, sent) = create(
+            RecordingTransactor {
+                allowance: amount - 1,
+                sent: Default::default(),
+            },
+            token,
+        )
+        .await;
+        assert_eq!(sent, vec!["approve", "create"]);
+        assert_eq!(
+            funded,
+            FundedEscrow {
+                approval_tx: Some(H256::repeat_byte(0xaa)),
+                create_tx: H256::repeat_byte(0xcc),
+                escrow: Address::repeat_byte(0xee),
+            }
+        );
+
+        // 承認済み: 作成のみ
+        let (funded, sent) = create(
+            RecordingTransactor {
+                allowance: amount,
+                sent: Default::default(),
+            },
+            token,
+        )
+        .await;
+        assert_eq!(sent, vec!["create"]);
+        assert_eq!(funded.approval_tx, None);
+
+        // ネイティブETHは承認しない
+        let (_, sent) = create(
+            RecordingTransactor {
+                allowance: U256::zero(),
+                sent: Default::default(),
+            },
+            Address::zero(),
+        )
+        .await;
+        assert_eq!(sent, vec!["create"]);
+    }
+
+    #[test]
+    fn test_invalid_header_is_rejected() {
+        let result = EthereumConnector::new_with_headers(
+            "https://sepolia.infura.io/v3/test",
+            "0x0000000000000000000000000000000000000000",
+            [("Bad Header", "value")],
+        );
+        assert!(result.is_err());
+    }
 }
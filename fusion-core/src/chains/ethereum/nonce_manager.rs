@@ -0,0 +1,200 @@
+//! アドレスごとのnonce管理
+//!
+//! 並行して送信されるトランザクションに重複しない連番のnonceを割り当てます。
+//! 初回はチェーンのpending nonceから初期化し、送信失敗で欠番が出た場合は
+//! チェーンから再同期します。
+
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{Address, BlockNumber, U256};
+use std::collections::HashMap;
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Error)]
+pub enum NonceError {
+    #[error("Failed to fetch nonce from chain: {0}")]
+    Source(String),
+}
+
+/// チェーン上のpending nonceを取得するソース
+#[async_trait]
+pub trait NonceSource: Send + Sync {
+    async fn pending_nonce(&self, address: Address) -> Result<U256, NonceError>;
+}
+
+#[async_trait]
+impl NonceSource for Provider<Http> {
+    async fn pending_nonce(&self, address: Address) -> Result<U256, NonceError> {
+        self.get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| NonceError::Source(e.to_string()))
+    }
+}
+
+/// ローカルでnonceを割り当てるマネージャー
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    /// アドレスごとの次に割り当てるnonce
+    next_nonces: Mutex<HashMap<Address, U256>>,
+}
+
+impl NonceManager {
+    /// 新しいマネージャーを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 次のnonceを割り当て
+    ///
+    /// 未初期化のアドレスはチェーンのpending nonceから開始します。
+    /// ロックは割り当てが終わるまで保持するため、並行呼び出しでも重複しません。
+    pub async fn next_nonce<S: NonceSource + ?Sized>(
+        &self,
+        address: Address,
+        source: &S,
+    ) -> Result<U256, NonceError> {
+        let mut next_nonces = self.next_nonces.lock().await;
+
+        let nonce = match next_nonces.get(&address) {
+            Some(nonce) => *nonce,
+            None => source.pending_nonce(address).await?,
+        };
+        next_nonces.insert(address, nonce + 1);
+
+        Ok(nonce)
+    }
+
+    /// チェーンのpending nonceに合わせて再同期
+    ///
+    /// 送信に失敗して欠番が出た場合に呼び出し、次の割り当てで欠番を埋めます。
+    pub async fn resync<S: NonceSource + ?Sized>(
+        &self,
+        address: Address,
+        source: &S,
+    ) -> Result<U256, NonceError> {
+        let mut next_nonces = self.next_nonces.lock().await;
+        let nonce = source.pending_nonce(address).await?;
+        next_nonces.insert(address, nonce);
+        Ok(nonce)
+    }
+
+    /// キャッシュを破棄し、次回の割り当て時にチェーンから初期化
+    pub async fn reset(&self, address: Address) {
+        self.next_nonces.lock().await.remove(&address);
+    }
+
+    /// 次に割り当てるnonceを参照（未初期化ならNone）
+    pub async fn peek(&self, address: Address) -> Option<U256> {
+        self.next_nonces.lock().await.get(&address).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct MockSource {
+        pending: Mutex<U256>,
+        calls: AtomicUsize,
+    }
+
+    impl MockSource {
+        fn new(pending: u64) -> Self {
+            Self {
+                pending: Mutex::new(U256::from(pending)),
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl NonceSource for MockSource {
+        async fn pending_nonce(&self, _address: Address) -> Result<U256, NonceError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            // 並行時の競合を起こしやすくするため一度yieldする
+            tokio::task::yield_now().await;
+            Ok(*self.pending.lock().await)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sends_get_distinct_sequential_nonces() {
+        let manager = Arc::new(NonceManager::new());
+        let source = Arc::new(MockSource::new(42));
+        let address = Address::random();
+
+        let handles: Vec<_> = (0..20)
+            .map(|_| {
+                let manager = manager.clone();
+                let source = source.clone();
+                tokio::spawn(async move { manager.next_nonce(address, source.as_ref()).await })
+            })
+            .collect();
+
+        let mut nonces = Vec::new();
+        for handle in handles {
+            nonces.push(handle.await.unwrap().unwrap().as_u64());
+        }
+        nonces.sort();
+
+        assert_eq!(nonces, (42..62).collect::<Vec<u64>>());
+        // チェーンへの問い合わせは初期化の1回のみ
+        assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_addresses_are_tracked_independently() {
+        let manager = NonceManager::new();
+        let source = MockSource::new(5);
+        let first = Address::random();
+        let second = Address::random();
+
+        assert_eq!(manager.next_nonce(first, &source).await.unwrap(), 5.into());
+        assert_eq!(manager.next_nonce(first, &source).await.unwrap(), 6.into());
+        assert_eq!(manager.next_nonce(second, &source).await.unwrap(), 5.into());
+    }
+
+    #[tokio::test]
+    async fn test_resync_fills_gap_after_failed_send() {
+        let manager = NonceManager::new();
+        let source = MockSource::new(10);
+        let address = Address::random();
+
+        assert_eq!(
+            manager.next_nonce(address, &source).await.unwrap(),
+            10.into()
+        );
+        // nonce 11 の送信が失敗し、チェーン上は 11 が次のまま
+        assert_eq!(
+            manager.next_nonce(address, &source).await.unwrap(),
+            11.into()
+        );
+        *source.pending.lock().await = U256::from(11);
+
+        manager.resync(address, &source).await.unwrap();
+        assert_eq!(
+            manager.next_nonce(address, &source).await.unwrap(),
+            11.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reset_reseeds_from_chain() {
+        let manager = NonceManager::new();
+        let source = MockSource::new(3);
+        let address = Address::random();
+
+        manager.next_nonce(address, &source).await.unwrap();
+        assert_eq!(manager.peek(address).await, Some(4.into()));
+
+        manager.reset(address).await;
+        assert_eq!(manager.peek(address).await, None);
+        assert_eq!(
+            manager.next_nonce(address, &source).await.unwrap(),
+            3.into()
+        );
+    }
+}
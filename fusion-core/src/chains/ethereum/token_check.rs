@@ -0,0 +1,214 @@
+//! オーダー作成前のトークンアドレス検証
+//!
+//! 存在しないアドレスやERC20ではないコントラクトを資産に指定すると、
+//! 決してフィルされないオーダーができてしまうため、作成前にチェーン上で
+//! コントラクトコードと `decimals()` / `symbol()` の応答を確認します。
+
+use super::abi;
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TokenCheckError {
+    #[error("Invalid token address: {0}")]
+    InvalidAddress(String),
+    #[error("Token address {given} fails the EIP-55 checksum, expected {expected}")]
+    BadChecksum { given: String, expected: String },
+    #[error("No contract deployed at token address {0:?}")]
+    NotAContract(Address),
+    #[error("Contract at {address:?} is not an ERC20 token: {reason}")]
+    NotErc20 { address: Address, reason: String },
+    #[error("Failed to query token from chain: {0}")]
+    Source(String),
+}
+
+/// 確認できたトークンの情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// トークンのコードとメタデータを取得するソース
+#[async_trait]
+pub trait TokenSource: Send + Sync {
+    async fn has_code(&self, address: Address) -> Result<bool, TokenCheckError>;
+    /// コントラクトが応答しない場合は `NotErc20` を返す
+    async fn decimals(&self, address: Address) -> Result<u8, TokenCheckError>;
+    async fn symbol(&self, address: Address) -> Result<String, TokenCheckError>;
+}
+
+#[async_trait]
+impl TokenSource for Provider<Http> {
+    async fn has_code(&self, address: Address) -> Result<bool, TokenCheckError> {
+        self.get_code(address, None)
+            .await
+            .map(|code| !code.is_empty())
+            .map_err(|e| TokenCheckError::Source(e.to_string()))
+    }
+
+    async fn decimals(&self, address: Address) -> Result<u8, TokenCheckError> {
+        abi::erc20::IERC20::new(address, Arc::new(self.clone()))
+            .decimals()
+            .call()
+            .await
+            .map_err(|e| TokenCheckError::NotErc20 {
+                address,
+                reason: format!("decimals() failed: {}", e),
+            })
+    }
+
+    async fn symbol(&self, address: Address) -> Result<String, TokenCheckError> {
+        abi::erc20::IERC20::new(address, Arc::new(self.clone()))
+            .symbol()
+            .call()
+            .await
+            .map_err(|e| TokenCheckError::NotErc20 {
+                address,
+                reason: format!("symbol() failed: {}", e),
+            })
+    }
+}
+
+/// アドレスを解析し、大文字小文字が混在していればEIP-55チェックサムも検証
+///
+/// すべて小文字またはすべて大文字のアドレスはチェックサムなしとして受け付けます。
+pub fn parse_checksummed_address(address: &str) -> Result<Address, TokenCheckError> {
+    let hex = address
+        .strip_prefix("0x")
+        .ok_or_else(|| TokenCheckError::InvalidAddress(address.to_string()))?;
+    let parsed: Address = address
+        .parse()
+        .map_err(|_| TokenCheckError::InvalidAddress(address.to_string()))?;
+
+    let mixed_case =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    let expected = to_checksum(&parsed, None);
+    if mixed_case && expected != address {
+        return Err(TokenCheckError::BadChecksum {
+            given: address.to_string(),
+            expected,
+        });
+    }
+    Ok(parsed)
+}
+
+/// `address` がERC20トークンとして使えるか確認
+pub async fn check_token<S: TokenSource + ?Sized>(
+    source: &S,
+    address: &str,
+) -> Result<TokenInfo, TokenCheckError> {
+    let address = parse_checksummed_address(address)?;
+
+    if !source.has_code(address).await? {
+        return Err(TokenCheckError::NotAContract(address));
+    }
+
+    let decimals = source.decimals(address).await?;
+    let symbol = source.symbol(address).await?;
+    Ok(TokenInfo {
+        address,
+        symbol,
+        decimals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// アドレスごとにコードとメタデータを持つモック
+    #[derive(Default)]
+    struct MockProvider {
+        code: HashMap<Address, bool>,
+        tokens: HashMap<Address, (u8, String)>,
+    }
+
+    #[async_trait]
+    impl TokenSource for MockProvider {
+        async fn has_code(&self, address: Address) -> Result<bool, TokenCheckError> {
+            Ok(self.code.get(&address).copied().unwrap_or(false))
+        }
+
+        async fn decimals(&self, address: Address) -> Result<u8, TokenCheckError> {
+            self.tokens
+                .get(&address)
+                .map(|(decimals, _)| *decimals)
+                .ok_or(TokenCheckError::NotErc20 {
+                    address,
+                    reason: "decimals() reverted".to_string(),
+                })
+        }
+
+        async fn symbol(&self, address: Address) -> Result<String, TokenCheckError> {
+            self.tokens
+                .get(&address)
+                .map(|(_, symbol)| symbol.clone())
+                .ok_or(TokenCheckError::NotErc20 {
+                    address,
+                    reason: "symbol() reverted".to_string(),
+                })
+        }
+    }
+
+    const USDC: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+
+    #[tokio::test]
+    async fn test_valid_erc20_passes() {
+        let usdc: Address = USDC.parse().unwrap();
+        let provider = MockProvider {
+            code: HashMap::from([(usdc, true)]),
+            tokens: HashMap::from([(usdc, (6, "USDC".to_string()))]),
+        };
+
+        let info = check_token(&provider, USDC).await.unwrap();
+        assert_eq!(
+            info,
+            TokenInfo {
+                address: usdc,
+                symbol: "USDC".to_string(),
+                decimals: 6,
+            }
+        );
+        // チェックサムなしの小文字アドレスも受け付ける
+        assert!(check_token(&provider, &USDC.to_lowercase()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_non_contract_address_is_rejected() {
+        let provider = MockProvider::default();
+
+        let result = check_token(&provider, USDC).await;
+        assert_eq!(
+            result,
+            Err(TokenCheckError::NotAContract(USDC.parse().unwrap()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_erc20_contract_is_rejected() {
+        let contract: Address = USDC.parse().unwrap();
+        let provider = MockProvider {
+            code: HashMap::from([(contract, true)]),
+            tokens: HashMap::new(),
+        };
+
+        let result = check_token(&provider, USDC).await;
+        assert!(matches!(result, Err(TokenCheckError::NotErc20 { .. })));
+    }
+
+    #[test]
+    fn test_bad_checksum_is_rejected() {
+        let broken = USDC.replace("fCD6", "fcD6");
+        assert!(matches!(
+            parse_checksummed_address(&broken),
+            Err(TokenCheckError::BadChecksum { .. })
+        ));
+    }
+}
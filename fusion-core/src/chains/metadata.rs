@@ -0,0 +1,73 @@
+//! チェーンごとの既定RPC・エクスプローラーURL
+//!
+//! 設定ファイルや環境変数で上書きされない場合の既定値です。
+
+use super::Chain;
+
+pub const ETHEREUM_RPC_URL: &str = "https://ethereum-rpc.publicnode.com";
+pub const ETHEREUM_EXPLORER_URL: &str = "https://etherscan.io";
+
+pub const NEAR_RPC_URL: &str = "https://rpc.mainnet.near.org";
+pub const NEAR_EXPLORER_URL: &str = "https://nearblocks.io";
+
+pub const POLYGON_RPC_URL: &str = "https://polygon-rpc.com";
+pub const POLYGON_EXPLORER_URL: &str = "https://polygonscan.com";
+
+pub const BASE_SEPOLIA_RPC_URL: &str = "https://sepolia.base.org";
+pub const BASE_SEPOLIA_EXPLORER_URL: &str = "https://sepolia.basescan.org";
+
+pub const ARBITRUM_RPC_URL: &str = "https://arb1.arbitrum.io/rpc";
+pub const ARBITRUM_EXPLORER_URL: &str = "https://arbiscan.io";
+
+pub const OPTIMISM_RPC_URL: &str = "https://mainnet.optimism.io";
+pub const OPTIMISM_EXPLORER_URL: &str = "https://optimistic.etherscan.io";
+
+impl Chain {
+    /// 既定のRPC URL
+    pub fn default_rpc_url(&self) -> &'static str {
+        match self {
+            Chain::Ethereum => ETHEREUM_RPC_URL,
+            Chain::NEAR => NEAR_RPC_URL,
+            Chain::Polygon => POLYGON_RPC_URL,
+            Chain::BaseSepolia => BASE_SEPOLIA_RPC_URL,
+            Chain::Arbitrum => ARBITRUM_RPC_URL,
+            Chain::Optimism => OPTIMISM_RPC_URL,
+        }
+    }
+
+    /// 既定のブロックエクスプローラーURL
+    pub fn explorer_url(&self) -> &'static str {
+        match self {
+            Chain::Ethereum => ETHEREUM_EXPLORER_URL,
+            Chain::NEAR => NEAR_EXPLORER_URL,
+            Chain::Polygon => POLYGON_EXPLORER_URL,
+            Chain::BaseSepolia => BASE_SEPOLIA_EXPLORER_URL,
+            Chain::Arbitrum => ARBITRUM_EXPLORER_URL,
+            Chain::Optimism => OPTIMISM_EXPLORER_URL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l2_chains_resolve_id_and_explorer() {
+        let arbitrum: Chain = "arbitrum".parse().unwrap();
+        assert_eq!(arbitrum, Chain::Arbitrum);
+        assert_eq!(arbitrum.name(), "Arbitrum");
+        assert_eq!(arbitrum.chain_id(), Some(42161));
+        assert_eq!(arbitrum.explorer_url(), "https://arbiscan.io");
+        assert_eq!(arbitrum.default_rpc_url(), ARBITRUM_RPC_URL);
+
+        let optimism: Chain = "Optimism".parse().unwrap();
+        assert_eq!(optimism, Chain::Optimism);
+        assert_eq!(optimism.name(), "Optimism");
+        assert_eq!(optimism.chain_id(), Some(10));
+        assert_eq!(optimism.explorer_url(), "https://optimistic.etherscan.io");
+        assert_eq!(optimism.default_rpc_url(), OPTIMISM_RPC_URL);
+
+        assert!(arbitrum.is_evm() && optimism.is_evm());
+    }
+}
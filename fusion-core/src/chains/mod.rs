@@ -1,5 +1,13 @@
+use crate::htlc::HashAlgorithm;
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+
 pub mod ethereum;
+pub mod metadata;
 pub mod near;
+/// NEARコントラクトと共有するNEP-297イベントスキーマ
+#[path = "../../../contracts/near-htlc/src/event_schema.rs"]
+pub mod near_event_schema;
 pub mod near_events;
 pub mod near_monitor;
 
@@ -9,6 +17,8 @@ pub enum Chain {
     NEAR,
     Polygon,
     BaseSepolia,
+    Arbitrum,
+    Optimism,
     // Add more chains as needed
 }
 
@@ -19,6 +29,136 @@ impl Chain {
             Chain::NEAR => "NEAR",
             Chain::Polygon => "Polygon",
             Chain::BaseSepolia => "Base Sepolia",
+            Chain::Arbitrum => "Arbitrum",
+            Chain::Optimism => "Optimism",
+        }
+    }
+
+    /// EVM互換チェーンかどうか
+    pub fn is_evm(&self) -> bool {
+        !matches!(self, Chain::NEAR)
+    }
+
+    /// EVMチェーンIDの正規値（EVM以外はNone）
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            Chain::Ethereum => Some(1),
+            Chain::NEAR => None,
+            Chain::Polygon => Some(137),
+            Chain::BaseSepolia => Some(84532),
+            Chain::Arbitrum => Some(42161),
+            Chain::Optimism => Some(10),
+        }
+    }
+
+    /// エスクローがハッシュロックの検証に使えるハッシュ関数
+    ///
+    /// NEARコントラクトはSHA256のみ、EVMはSHA256プリコンパイルとkeccak256の両方に対応します。
+    pub fn supported_hash_algorithms(&self) -> &'static [HashAlgorithm] {
+        match self {
+            Chain::NEAR => &[HashAlgorithm::Sha256],
+            _ => &[HashAlgorithm::Sha256, HashAlgorithm::Keccak256],
+        }
+    }
+}
+
+/// CLIで使うチェーン名（大文字小文字を区別しない）をパース
+impl FromStr for Chain {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ethereum" => Ok(Chain::Ethereum),
+            "near" => Ok(Chain::NEAR),
+            "polygon" => Ok(Chain::Polygon),
+            "base-sepolia" => Ok(Chain::BaseSepolia),
+            "arbitrum" => Ok(Chain::Arbitrum),
+            "optimism" => Ok(Chain::Optimism),
+            _ => Err(anyhow!(
+                "Unsupported chain: {} (expected one of: ethereum, near, polygon, base-sepolia, arbitrum, optimism)",
+                name
+            )),
+        }
+    }
+}
+
+/// スワップの方向（ソースチェーン → デスティネーションチェーン）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapDirection {
+    pub from: Chain,
+    pub to: Chain,
+}
+
+impl SwapDirection {
+    pub fn new(from: Chain, to: Chain) -> Result<Self> {
+        if from == to {
+            return Err(anyhow!("from_chain and to_chain cannot be the same"));
+        }
+        Ok(Self { from, to })
+    }
+
+    /// `--from-chain` / `--to-chain` の値から作成
+    pub fn parse(from: &str, to: &str) -> Result<Self> {
+        Self::new(from.parse()?, to.parse()?)
+    }
+
+    /// EVMチェーンからNEARへのスワップ
+    pub fn is_evm_to_near(&self) -> bool {
+        self.from.is_evm() && self.to == Chain::NEAR
+    }
+
+    /// NEARからEVMチェーンへのスワップ
+    pub fn is_near_to_evm(&self) -> bool {
+        self.from == Chain::NEAR && self.to.is_evm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_from_str_and_chain_id() {
+        let cases = [
+            ("ethereum", Chain::Ethereum, Some(1)),
+            ("NEAR", Chain::NEAR, None),
+            ("Polygon", Chain::Polygon, Some(137)),
+            ("base-sepolia", Chain::BaseSepolia, Some(84532)),
+        ];
+        for (name, chain, chain_id) in cases {
+            assert_eq!(name.parse::<Chain>().unwrap(), chain);
+            assert_eq!(chain.chain_id(), chain_id);
         }
+
+        let err = "solana".parse::<Chain>().unwrap_err().to_string();
+        assert!(err.contains("Unsupported chain: solana"));
+        assert!(err.contains("base-sepolia"));
+    }
+
+    #[test]
+    fn test_swap_direction_classification() {
+        let direction = SwapDirection::parse("ethereum", "near").unwrap();
+        assert_eq!(direction.from, Chain::Ethereum);
+        assert_eq!(direction.to, Chain::NEAR);
+        assert!(direction.is_evm_to_near());
+        assert!(!direction.is_near_to_evm());
+
+        let direction = SwapDirection::parse("near", "base-sepolia").unwrap();
+        assert!(direction.is_near_to_evm());
+        assert!(!direction.is_evm_to_near());
+
+        // EVM同士はどちらにも当てはまらない
+        let direction = SwapDirection::parse("ethereum", "polygon").unwrap();
+        assert!(!direction.is_evm_to_near());
+        assert!(!direction.is_near_to_evm());
+    }
+
+    #[test]
+    fn test_swap_direction_rejects_invalid_pairs() {
+        assert!(SwapDirection::parse("near", "near").is_err());
+        assert!(SwapDirection::parse("solana", "near")
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported chain: solana"));
     }
 }
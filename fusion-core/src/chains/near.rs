@@ -2,7 +2,9 @@ use crate::htlc::SecretHash;
 
 pub mod event_monitor;
 pub mod htlc_connector;
+pub mod multi_contract_monitor;
 pub use htlc_connector::NearHtlcConnector;
+pub use multi_contract_monitor::{ContractEscrow, EscrowViewSource, MultiContractMonitor};
 
 pub struct NEARConnector {
     _rpc_url: String,
@@ -3,7 +3,7 @@ use anyhow::{anyhow, Result};
 use near_crypto::{InMemorySigner, KeyType};
 use near_jsonrpc_client::{methods, JsonRpcClient};
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
-use near_primitives::types::{AccountId, BlockReference};
+use near_primitives::types::{AccountId, BlockReference, Finality, FunctionArgs};
 use near_primitives::views::QueryRequest;
 use serde_json::json;
 use std::str::FromStr;
@@ -12,6 +12,7 @@ pub struct NearHtlcConnector {
     rpc_client: JsonRpcClient,
     contract_id: Option<AccountId>,
     signer: Option<InMemorySigner>,
+    finality: Finality,
 }
 
 impl NearHtlcConnector {
@@ -21,9 +22,20 @@ impl NearHtlcConnector {
             rpc_client,
             contract_id: None,
             signer: None,
+            finality: Finality::Final,
         }
     }
 
+    /// HTLCの状態を読むときのファイナリティ（既定は `Final`）
+    ///
+    /// `Finality::None`（optimistic）は遅延が短くなりますが、観測したクレームが
+    /// 巻き戻る可能性があるため、公開されたシークレットで相手側をクレームする前の
+    /// 確認には自己責任で使ってください。
+    pub fn with_finality(mut self, finality: Finality) -> Self {
+        self.finality = finality;
+        self
+    }
+
     pub fn with_contract(mut self, contract_id: &str) -> Self {
         self.contract_id = Some(AccountId::from_str(contract_id).unwrap());
         self
@@ -136,6 +148,69 @@ impl NearHtlcConnector {
         Ok(tx_hash)
     }
 
+    /// `get_escrow` のビュー呼び出しを設定したファイナリティで組み立てる
+    fn escrow_query(&self, htlc_id: &str) -> Result<methods::query::RpcQueryRequest> {
+        let contract_id = self
+            .contract_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("Contract ID not set"))?;
+
+        Ok(methods::query::RpcQueryRequest {
+            block_reference: BlockReference::Finality(self.finality.clone()),
+            request: QueryRequest::CallFunction {
+                account_id: contract_id.clone(),
+                method_name: "get_escrow".to_string(),
+                args: FunctionArgs::from(json!({ "escrow_id": htlc_id }).to_string().into_bytes()),
+            },
+        })
+    }
+
+    /// コントラクトから生のエスクロー情報を取得（存在しなければ `None`）
+    ///
+    /// クレーム検知に使うため、既定では `final` のブロックを参照します。
+    pub async fn get_escrow(&self, htlc_id: &str) -> Result<Option<serde_json::Value>> {
+        let response = self
+            .rpc_client
+            .call(self.escrow_query(htlc_id)?)
+            .await
+            .map_err(|e| anyhow!("Failed to query HTLC {}: {}", htlc_id, e))?;
+
+        let QueryResponseKind::CallResult(result) = response.kind else {
+            return Err(anyhow!("Unexpected response type"));
+        };
+        let escrow: serde_json::Value = serde_json::from_slice(&result.result)?;
+        Ok((!escrow.is_null()).then_some(escrow))
+    }
+
+    /// 任意のコントラクトのビューメソッドを設定したファイナリティで呼び出す
+    pub async fn view_contract(
+        &self,
+        contract_id: &str,
+        method_name: &str,
+        args: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let account_id = AccountId::from_str(contract_id)
+            .map_err(|e| anyhow!("Invalid contract ID {}: {}", contract_id, e))?;
+        let request = methods::query::RpcQueryRequest {
+            block_reference: BlockReference::Finality(self.finality.clone()),
+            request: QueryRequest::CallFunction {
+                account_id,
+                method_name: method_name.to_string(),
+                args: FunctionArgs::from(args.to_string().into_bytes()),
+            },
+        };
+        let response = self
+            .rpc_client
+            .call(request)
+            .await
+            .map_err(|e| anyhow!("Failed to call {} on {}: {}", method_name, contract_id, e))?;
+
+        let QueryResponseKind::CallResult(result) = response.kind else {
+            return Err(anyhow!("Unexpected response type"));
+        };
+        Ok(serde_json::from_slice(&result.result)?)
+    }
+
     pub async fn get_htlc_status(&self, _htlc_id: &str) -> Result<String> {
         let _contract_id = self
             .contract_id
@@ -147,3 +222,30 @@ impl NearHtlcConnector {
         Ok("active".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_query_uses_final_finality_by_default() {
+        let connector =
+            NearHtlcConnector::new("http://127.0.0.1:1").with_contract("fusion-htlc.testnet");
+
+        let query = connector.escrow_query("escrow_1").unwrap();
+        assert_eq!(
+            query.block_reference,
+            BlockReference::Finality(Finality::Final)
+        );
+
+        // 低遅延を選んだ場合のみoptimisticで読む
+        let query = connector
+            .with_finality(Finality::None)
+            .escrow_query("escrow_1")
+            .unwrap();
+        assert_eq!(
+            query.block_reference,
+            BlockReference::Finality(Finality::None)
+        );
+    }
+}
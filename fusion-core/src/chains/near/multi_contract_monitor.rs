@@ -0,0 +1,289 @@
+//! 複数のFusionHTLCコントラクトをまとめて監視
+//!
+//! デプロイメントごとに別のコントラクトアカウントへ `get_active_escrows` /
+//! `get_claimable_escrows` を並行して問い合わせ、どのコントラクトの
+//! エスクローかを付けて結果をまとめます。同時に投げる問い合わせ数は
+//! RPCのレート制限に合わせて上限を設定できます。
+
+use super::NearHtlcConnector;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// コントラクトごとのエスクロー一覧を返すビュー呼び出し
+#[async_trait]
+pub trait EscrowViewSource: Send + Sync {
+    /// `get_active_escrows(from_index, limit)` の結果
+    async fn active_escrows(
+        &self,
+        contract_id: &str,
+        from_index: u64,
+        limit: u64,
+    ) -> Result<Vec<(String, Value)>>;
+
+    /// `get_claimable_escrows(beneficiary)` の結果
+    async fn claimable_escrows(
+        &self,
+        contract_id: &str,
+        beneficiary: &str,
+    ) -> Result<Vec<(String, Value)>>;
+}
+
+#[async_trait]
+impl EscrowViewSource for NearHtlcConnector {
+    async fn active_escrows(
+        &self,
+        contract_id: &str,
+        from_index: u64,
+        limit: u64,
+    ) -> Result<Vec<(String, Value)>> {
+        let result = self
+            .view_contract(
+                contract_id,
+                "get_active_escrows",
+                json!({ "from_index": from_index, "limit": limit }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    async fn claimable_escrows(
+        &self,
+        contract_id: &str,
+        beneficiary: &str,
+    ) -> Result<Vec<(String, Value)>> {
+        let result = self
+            .view_contract(
+                contract_id,
+                "get_claimable_escrows",
+                json!({ "beneficiary": beneficiary }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+/// 取得元のコントラクトを付けたエスクロー
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractEscrow {
+    pub contract_id: String,
+    pub escrow_id: String,
+    pub escrow: Value,
+}
+
+/// 複数コントラクトへの問い合わせを並行して行うモニター
+pub struct MultiContractMonitor {
+    source: Arc<dyn EscrowViewSource>,
+    contracts: Vec<String>,
+    /// 同時に実行する問い合わせ数の上限
+    limiter: Arc<Semaphore>,
+}
+
+impl MultiContractMonitor {
+    /// `max_concurrency` 件までの問い合わせを同時に実行するモニターを作成（0は1として扱う）
+    pub fn new(
+        source: Arc<dyn EscrowViewSource>,
+        contracts: Vec<String>,
+        max_concurrency: usize,
+    ) -> Self {
+        Self {
+            source,
+            contracts,
+            limiter: Arc::new(Semaphore::new(max_concurrency.max(1))),
+        }
+    }
+
+    pub fn contracts(&self) -> &[String] {
+        &self.contracts
+    }
+
+    /// 全コントラクトのアクティブなエスクロー（設定したコントラクト順）
+    pub async fn active_escrows(&self, from_index: u64, limit: u64) -> Result<Vec<ContractEscrow>> {
+        self.fan_out(move |source, contract_id| async move {
+            source.active_escrows(&contract_id, from_index, limit).await
+        })
+        .await
+    }
+
+    /// 全コントラクトで `beneficiary` がクレームできるエスクロー（設定したコントラクト順）
+    pub async fn claimable_escrows(&self, beneficiary: &str) -> Result<Vec<ContractEscrow>> {
+        let beneficiary = beneficiary.to_string();
+        self.fan_out(move |source, contract_id| {
+            let beneficiary = beneficiary.clone();
+            async move { source.claimable_escrows(&contract_id, &beneficiary).await }
+        })
+        .await
+    }
+
+    /// コントラクトごとに `query` を並行実行し、結果をコントラクト順にまとめる
+    ///
+    /// いずれかのコントラクトで失敗した場合はそのエラーを返します。
+    async fn fan_out<F, Fut>(&self, query: F) -> Result<Vec<ContractEscrow>>
+    where
+        F: Fn(Arc<dyn EscrowViewSource>, String) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<(String, Value)>>> + Send + 'static,
+    {
+        let mut tasks = JoinSet::new();
+        for (index, contract_id) in self.contracts.iter().enumerate() {
+            let limiter = self.limiter.clone();
+            let request = query(self.source.clone(), contract_id.clone());
+            tasks.spawn(async move {
+                let _permit = limiter.acquire_owned().await?;
+                Ok::<_, anyhow::Error>((index, request.await?))
+            });
+        }
+
+        let mut per_contract = vec![Vec::new(); self.contracts.len()];
+        while let Some(joined) = tasks.join_next().await {
+            let (index, escrows) =
+                joined.map_err(|e| anyhow!("Escrow query panicked: {}", e))??;
+            per_contract[index] = escrows;
+        }
+
+        Ok(self
+            .contracts
+            .iter()
+            .zip(per_contract)
+            .flat_map(|(contract_id, escrows)| {
+                escrows
+                    .into_iter()
+                    .map(move |(escrow_id, escrow)| ContractEscrow {
+                        contract_id: contract_id.clone(),
+                        escrow_id,
+                        escrow,
+                    })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// コントラクトごとに固定のエスクローを返し、同時実行数の最大値を記録するモック
+    #[derive(Default)]
+    struct MockContracts {
+        escrows: HashMap<String, Vec<(String, Value)>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl MockContracts {
+        async fn respond(&self, contract_id: &str) -> Result<Vec<(String, Value)>> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            self.escrows
+                .get(contract_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Unknown contract {}", contract_id))
+        }
+    }
+
+    #[async_trait]
+    impl EscrowViewSource for MockContracts {
+        async fn active_escrows(
+            &self,
+            contract_id: &str,
+            _from_index: u64,
+            _limit: u64,
+        ) -> Result<Vec<(String, Value)>> {
+            self.respond(contract_id).await
+        }
+
+        async fn claimable_escrows(
+            &self,
+            contract_id: &str,
+            beneficiary: &str,
+        ) -> Result<Vec<(String, Value)>> {
+            let escrows = self.respond(contract_id).await?;
+            Ok(escrows
+                .into_iter()
+                .filter(|(_, escrow)| escrow["beneficiary"] == beneficiary)
+                .collect())
+        }
+    }
+
+    fn two_contracts() -> Arc<MockContracts> {
+        let escrow = |beneficiary: &str| json!({ "beneficiary": beneficiary });
+        Arc::new(MockContracts {
+            escrows: HashMap::from([
+                (
+                    "htlc-a.testnet".to_string(),
+                    vec![
+                        ("escrow_1".to_string(), escrow("alice.testnet")),
+                        ("escrow_2".to_string(), escrow("bob.testnet")),
+                    ],
+                ),
+                (
+                    "htlc-b.testnet".to_string(),
+                    vec![("escrow_1".to_string(), escrow("alice.testnet"))],
+                ),
+            ]),
+            ..Default::default()
+        })
+    }
+
+    fn tags(escrows: &[ContractEscrow]) -> Vec<(&str, &str)> {
+        escrows
+            .iter()
+            .map(|e| (e.contract_id.as_str(), e.escrow_id.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_results_are_merged_and_tagged_by_contract() {
+        let contracts = two_contracts();
+        let monitor = MultiContractMonitor::new(
+            contracts.clone(),
+            vec!["htlc-a.testnet".to_string(), "htlc-b.testnet".to_string()],
+            2,
+        );
+
+        let active = monitor.active_escrows(0, 100).await.unwrap();
+        assert_eq!(
+            tags(&active),
+            vec![
+                ("htlc-a.testnet", "escrow_1"),
+                ("htlc-a.testnet", "escrow_2"),
+                ("htlc-b.testnet", "escrow_1"),
+            ]
+        );
+        assert_eq!(contracts.max_in_flight.load(Ordering::SeqCst), 2);
+
+        let claimable = monitor.claimable_escrows("alice.testnet").await.unwrap();
+        assert_eq!(
+            tags(&claimable),
+            vec![
+                ("htlc-a.testnet", "escrow_1"),
+                ("htlc-b.testnet", "escrow_1")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_is_respected() {
+        let contracts = two_contracts();
+        let monitor = MultiContractMonitor::new(
+            contracts.clone(),
+            vec!["htlc-a.testnet".to_string(), "htlc-b.testnet".to_string()],
+            1,
+        );
+
+        assert_eq!(monitor.active_escrows(0, 100).await.unwrap().len(), 3);
+        assert_eq!(contracts.max_in_flight.load(Ordering::SeqCst), 1);
+
+        // 未知のコントラクトのエラーはそのまま返す
+        let monitor = MultiContractMonitor::new(contracts, vec!["missing.testnet".to_string()], 1);
+        assert!(monitor.active_escrows(0, 100).await.is_err());
+    }
+}
@@ -13,32 +13,13 @@ pub enum ParseError {
 }
 
 /// NEAR HTLCイベント構造体
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct NearHtlcCreateEvent {
-    pub escrow_id: String,
-    pub resolver: String,
-    pub beneficiary: String,
-    pub amount: u128,
-    pub secret_hash: String,
-    pub finality_time: u64,
-    pub cancel_time: u64,
-    pub public_cancel_time: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct NearHtlcClaimEvent {
-    pub escrow_id: String,
-    pub claimer: String,
-    pub secret: String,
-    pub timestamp: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct NearHtlcCancelEvent {
-    pub escrow_id: String,
-    pub canceller: String,
-    pub timestamp: u64,
-}
+///
+/// フィールドはコントラクトと共有するスキーマで一度だけ定義します。
+pub use super::near_event_schema::{
+    EscrowCancelled as NearHtlcCancelEvent, EscrowClaimed as NearHtlcClaimEvent,
+    EscrowCreated as NearHtlcCreateEvent,
+};
+use super::near_event_schema::{HtlcEvent, HtlcEventLog};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum NearHtlcEvent {
@@ -51,6 +32,37 @@ pub enum NearHtlcEvent {
 pub struct NearEventParser;
 
 impl NearEventParser {
+    /// NEP-297 (`EVENT_JSON:`) ログのパース
+    ///
+    /// 1つのログに複数のイベントが含まれる場合があるため、すべて返します。
+    pub fn parse_event_log(log: &str) -> Result<Vec<NearHtlcEvent>, ParseError> {
+        let event_log = HtlcEventLog::from_log_str(log)
+            .ok_or_else(|| ParseError::InvalidFormat("Not an EVENT_JSON log".to_string()))?
+            .map_err(|e| ParseError::InvalidFormat(e.to_string()))?;
+
+        if event_log.standard != super::near_event_schema::EVENT_STANDARD {
+            return Err(ParseError::InvalidValue(format!(
+                "Unknown event standard: {}",
+                event_log.standard
+            )));
+        }
+        if !event_log.is_compatible() {
+            return Err(ParseError::InvalidValue(format!(
+                "Unsupported event version: {}",
+                event_log.version
+            )));
+        }
+
+        let events = match event_log.event {
+            HtlcEvent::EscrowCreated(data) => data.into_iter().map(NearHtlcEvent::Create).collect(),
+            HtlcEvent::EscrowClaimed(data) => data.into_iter().map(NearHtlcEvent::Claim).collect(),
+            HtlcEvent::EscrowCancelled(data) => {
+                data.into_iter().map(NearHtlcEvent::Cancel).collect()
+            }
+        };
+        Ok(events)
+    }
+
     /// Createイベントのパース
     pub fn parse_create_event(log: &str) -> Result<NearHtlcCreateEvent, ParseError> {
         // Example log format:
@@ -189,4 +201,58 @@ mod tests {
 
         assert_eq!(event.secret, "deadbeef1234567890abcdef");
     }
+
+    #[test]
+    fn should_round_trip_contract_claim_event() {
+        // FusionHTLCのclaimが出力するログと同じ形式
+        let contract_log = HtlcEventLog::new(HtlcEvent::EscrowClaimed(vec![NearHtlcClaimEvent {
+            escrow_id: "fusion_0".to_string(),
+            claimer: "bob.near".to_string(),
+            secret: "deadbeef".to_string(),
+            timestamp: 1_700_000_000_000_000_000,
+        }]))
+        .to_log_string();
+        assert_eq!(
+            contract_log,
+            r#"EVENT_JSON:{"standard":"fusion-htlc","version":"1.0.0","event":"escrow_claimed","data":[{"escrow_id":"fusion_0","claimer":"bob.near","secret":"deadbeef","timestamp":1700000000000000000}]}"#
+        );
+
+        let events = NearEventParser::parse_event_log(&contract_log).unwrap();
+        assert_eq!(
+            events,
+            vec![NearHtlcEvent::Claim(NearHtlcClaimEvent {
+                escrow_id: "fusion_0".to_string(),
+                claimer: "bob.near".to_string(),
+                secret: "deadbeef".to_string(),
+                timestamp: 1_700_000_000_000_000_000,
+            })]
+        );
+    }
+
+    #[test]
+    fn should_read_create_event_amount_as_string() {
+        let log = r#"EVENT_JSON:{"standard":"fusion-htlc","version":"1.2.0","event":"escrow_created","data":[{"escrow_id":"fusion_1","resolver":"alice.near","beneficiary":"bob.near","amount":"1000000000000000000000000","secret_hash":"hash","finality_time":1,"cancel_time":2,"public_cancel_time":3}]}"#;
+
+        match NearEventParser::parse_event_log(log).unwrap().as_slice() {
+            [NearHtlcEvent::Create(event)] => {
+                assert_eq!(event.amount, 1_000_000_000_000_000_000_000_000);
+                assert_eq!(event.public_cancel_time, 3);
+            }
+            other => panic!("Expected one create event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_reject_incompatible_event_version() {
+        let log = r#"EVENT_JSON:{"standard":"fusion-htlc","version":"2.0.0","event":"escrow_cancelled","data":[]}"#;
+
+        assert!(matches!(
+            NearEventParser::parse_event_log(log),
+            Err(ParseError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            NearEventParser::parse_event_log("Secret revealed: deadbeef"),
+            Err(ParseError::InvalidFormat(_))
+        ));
+    }
 }
@@ -1,4 +1,5 @@
 use crate::chains::near_events::NearHtlcEvent;
+use near_primitives::types::Finality;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -30,6 +31,7 @@ pub struct NearBlock {
 pub struct NearRpcConnector {
     client: Client,
     rpc_url: String,
+    finality: Finality,
 }
 
 impl NearRpcConnector {
@@ -42,18 +44,31 @@ impl NearRpcConnector {
         Ok(Self {
             client,
             rpc_url: rpc_url.to_string(),
+            finality: Finality::Final,
         })
     }
 
-    pub async fn get_latest_block(&self) -> Result<NearBlock, NearError> {
-        let request = serde_json::json!({
+    /// 最新ブロックを読むときのファイナリティ（既定は `Final`）
+    ///
+    /// optimisticにすると遅延は短くなりますが、検知したクレームが巻き戻る可能性があります。
+    pub fn with_finality(mut self, finality: Finality) -> Self {
+        self.finality = finality;
+        self
+    }
+
+    fn latest_block_request(&self) -> Value {
+        serde_json::json!({
             "jsonrpc": "2.0",
             "id": "dontcare",
             "method": "block",
             "params": {
-                "finality": "final"
+                "finality": self.finality
             }
-        });
+        })
+    }
+
+    pub async fn get_latest_block(&self) -> Result<NearBlock, NearError> {
+        let request = self.latest_block_request();
 
         let response = self
             .client
@@ -155,6 +170,12 @@ impl NearHtlcMonitor {
         })
     }
 
+    /// クレーム検知に使うファイナリティを変更（既定は `Final`）
+    pub fn with_finality(mut self, finality: Finality) -> Self {
+        self.rpc_connector = self.rpc_connector.with_finality(finality);
+        self
+    }
+
     pub async fn start_monitoring(
         &self,
         sender: mpsc::Sender<NearHtlcEvent>,
@@ -227,6 +248,21 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn should_request_final_blocks_by_default() {
+        let connector = NearRpcConnector::new("http://127.0.0.1:1").await.unwrap();
+        assert_eq!(
+            connector.latest_block_request()["params"]["finality"],
+            "final"
+        );
+
+        let connector = connector.with_finality(Finality::None);
+        assert_eq!(
+            connector.latest_block_request()["params"]["finality"],
+            "optimistic"
+        );
+    }
+
     #[tokio::test]
     async fn should_monitor_contract_logs() {
         let monitor =
@@ -2,6 +2,7 @@ use crate::chains::ethereum::EthereumConnector;
 use crate::chains::near::NearHtlcConnector;
 use crate::cross_chain_secret_manager::CrossChainSecretManager;
 use crate::htlc::SecretHash;
+use crate::swap_telemetry::now_millis;
 use anyhow::{anyhow, Result};
 use ethers::types::Address;
 use std::sync::Arc;
@@ -69,6 +70,8 @@ impl ClaimExecutor {
             .await
             .map_err(|e| anyhow!("Failed to claim escrow: {}", e))?;
 
+        self.record_claim(secret_hash, "ethereum").await;
+
         Ok(format!("0x{:x}", receipt.transaction_hash))
     }
 
@@ -87,9 +90,20 @@ impl ClaimExecutor {
         // Claim the HTLC
         let tx_hash = near_connector.claim_htlc(htlc_id, secret).await?;
 
+        self.record_claim(secret_hash, "near").await;
+
         Ok(tx_hash)
     }
 
+    /// Record the claim when telemetry is enabled on the secret manager
+    async fn record_claim(&self, secret_hash: &SecretHash, chain: &str) {
+        if let Some(telemetry) = self.secret_manager.telemetry() {
+            telemetry
+                .record_claimed(secret_hash, chain, now_millis())
+                .await;
+        }
+    }
+
     /// Wait for a secret to be revealed on either chain
     async fn wait_for_secret_revelation(
         &self,
@@ -1,4 +1,5 @@
 use crate::chains::Chain;
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -6,6 +7,8 @@ pub struct ChainConfig {
     pub rpc_url: String,
     pub chain_id: u64,
     pub escrow_factory: Option<String>,
+    /// オーダーの署名・送信先となるLimit Order Protocolのアドレス
+    pub limit_order_protocol: Option<String>,
     pub explorer_url: String,
 }
 
@@ -25,6 +28,7 @@ impl Default for Config {
                 rpc_url: "https://sepolia.infura.io/v3/YOUR_INFURA_KEY".to_string(),
                 chain_id: 11155111,
                 escrow_factory: None, // Will be set after deployment
+                limit_order_protocol: None,
                 explorer_url: "https://sepolia.etherscan.io".to_string(),
             },
         );
@@ -36,6 +40,7 @@ impl Default for Config {
                 rpc_url: "https://rpc.testnet.near.org".to_string(),
                 chain_id: 0,          // NEAR doesn't use chain IDs
                 escrow_factory: None, // Contract account ID will be set after deployment
+                limit_order_protocol: None,
                 explorer_url: "https://explorer.testnet.near.org".to_string(),
             },
         );
@@ -47,6 +52,9 @@ impl Default for Config {
                 rpc_url: "https://sepolia.base.org".to_string(),
                 chain_id: 84532,
                 escrow_factory: None, // Will be set after deployment
+                limit_order_protocol: Some(
+                    "0x171C87724E720F2806fc29a010a62897B30fdb62".to_string(),
+                ),
                 explorer_url: "https://sepolia.basescan.org".to_string(),
             },
         );
@@ -72,6 +80,12 @@ impl Config {
             }
         }
 
+        if let Ok(eth_protocol) = std::env::var("ETH_LIMIT_ORDER_PROTOCOL_ADDRESS") {
+            if let Some(eth_config) = config.chains.get_mut(&Chain::Ethereum) {
+                eth_config.limit_order_protocol = Some(eth_protocol);
+            }
+        }
+
         if let Ok(near_rpc) = std::env::var("NEAR_TESTNET_RPC_URL") {
             if let Some(near_config) = config.chains.get_mut(&Chain::NEAR) {
                 near_config.rpc_url = near_rpc;
@@ -97,10 +111,63 @@ impl Config {
             }
         }
 
+        if let Ok(base_protocol) = std::env::var("LIMIT_ORDER_PROTOCOL_ADDRESS") {
+            if let Some(base_config) = config.chains.get_mut(&Chain::BaseSepolia) {
+                base_config.limit_order_protocol = Some(base_protocol);
+            }
+        }
+
         config
     }
 
     pub fn get_chain_config(&self, chain: Chain) -> Option<&ChainConfig> {
         self.chains.get(&chain)
     }
+
+    /// チェーンIDに対応するLimit Order Protocolのアドレス
+    ///
+    /// 別チェーンのアドレスで署名しないよう、未設定のチェーンはエラーにします。
+    pub fn limit_order_protocol(&self, chain_id: u64) -> Result<&str> {
+        self.chains
+            .values()
+            .filter(|chain| chain.chain_id == chain_id)
+            .find_map(|chain| chain.limit_order_protocol.as_deref())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No limit order protocol configured for chain ID {}",
+                    chain_id
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_order_protocol_follows_chain_id() {
+        let mut config = Config::default();
+        config
+            .chains
+            .get_mut(&Chain::Ethereum)
+            .unwrap()
+            .limit_order_protocol = Some("0x111111125421cA6dc452d289314280a0f8842A65".to_string());
+
+        let base = config.limit_order_protocol(84532).unwrap();
+        let ethereum = config.limit_order_protocol(11155111).unwrap();
+        assert_eq!(base, "0x171C87724E720F2806fc29a010a62897B30fdb62");
+        assert_ne!(base, ethereum);
+    }
+
+    #[test]
+    fn test_unconfigured_chain_has_no_limit_order_protocol() {
+        let config = Config::default();
+
+        // Ethereumメインネットは未設定、Sepoliaはアドレス未設定
+        for chain_id in [1, 11155111] {
+            let err = config.limit_order_protocol(chain_id).unwrap_err();
+            assert!(err.to_string().contains(&chain_id.to_string()));
+        }
+    }
 }
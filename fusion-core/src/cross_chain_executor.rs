@@ -1,14 +1,23 @@
 use crate::chains::ethereum::EthereumConnector;
 use crate::chains::near::NEARConnector;
-use crate::htlc::{Secret, SecretHash};
+use crate::chains::Chain;
+use crate::htlc::{secret_eq, HashAlgorithm, Secret, SecretHash};
 use crate::limit_order_htlc::OrderHTLCExt;
 use crate::order::Order;
+use crate::swap_telemetry::{now_millis, SwapTelemetry};
 use anyhow::{anyhow, Result};
+use ethers::types::{Address, U256};
+use std::sync::Arc;
 
 /// クロスチェーン実行フローを管理する構造体
 pub struct CrossChainExecutor {
     ethereum_connector: EthereumConnector,
     near_connector: NEARConnector,
+    /// レイテンシ計測（明示的に設定した場合のみ）
+    telemetry: Option<Arc<SwapTelemetry>>,
+    /// ソース（Ethereum）とデスティネーション（NEAR）のハッシュロックに使うハッシュ関数
+    source_hash_algorithm: HashAlgorithm,
+    destination_hash_algorithm: HashAlgorithm,
 }
 
 /// 実行フローの状態
@@ -34,6 +43,75 @@ pub enum ExecutionState {
     Failed { reason: String },
 }
 
+/// スワップの片側レッグのハッシュロック設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashlockLeg {
+    pub chain: Chain,
+    pub algorithm: HashAlgorithm,
+    pub hashlock: SecretHash,
+}
+
+/// ソースで公開されるシークレットがデスティネーションのハッシュロックの原像にもなるか確認する
+///
+/// シークレットが分かっている場合は両レッグのハッシュロックをそれぞれのハッシュ関数で検証し、
+/// 分からない場合（ハッシュロックを使い回す場合）は同じハッシュ関数・同じハッシュロックを要求します。
+/// 資金を動かす前に呼び出してください。
+pub fn ensure_hashlock_compatibility(
+    source: &HashlockLeg,
+    destination: &HashlockLeg,
+    secret: Option<&Secret>,
+) -> Result<()> {
+    let incompatible = |reason: &str| {
+        anyhow!(
+            "Incompatible hashlocks for {} ({:?}) -> {} ({:?}): {}",
+            source.chain.name(),
+            source.algorithm,
+            destination.chain.name(),
+            destination.algorithm,
+            reason
+        )
+    };
+
+    for leg in [source, destination] {
+        if !leg
+            .chain
+            .supported_hash_algorithms()
+            .contains(&leg.algorithm)
+        {
+            return Err(incompatible(&format!(
+                "{} escrows cannot verify {:?} hashlocks",
+                leg.chain.name(),
+                leg.algorithm
+            )));
+        }
+    }
+
+    match secret {
+        Some(secret) => {
+            if !secret_eq(&source.algorithm.hash(secret), &source.hashlock) {
+                return Err(incompatible(
+                    "secret is not a preimage of the source hashlock",
+                ));
+            }
+            if !secret_eq(&destination.algorithm.hash(secret), &destination.hashlock) {
+                return Err(incompatible(
+                    "secret is not a preimage of the destination hashlock",
+                ));
+            }
+        }
+        None => {
+            if source.algorithm != destination.algorithm
+                || !secret_eq(&source.hashlock, &destination.hashlock)
+            {
+                return Err(incompatible(
+                    "a shared hashlock requires the same algorithm on both chains",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// クロスチェーン実行のパラメータ
 pub struct ExecutionParams {
     /// 実行するLimit Order
@@ -53,6 +131,9 @@ impl CrossChainExecutor {
         Ok(Self {
             ethereum_connector,
             near_connector,
+            telemetry: None,
+            source_hash_algorithm: HashAlgorithm::default(),
+            destination_hash_algorithm: HashAlgorithm::default(),
         })
     }
 
@@ -71,6 +152,23 @@ impl CrossChainExecutor {
         self
     }
 
+    /// レイテンシ計測を有効化
+    pub fn with_telemetry(mut self, telemetry: Arc<SwapTelemetry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// ハッシュロックに使うハッシュ関数を設定（ソース: Ethereum、デスティネーション: NEAR）
+    pub fn with_hash_algorithms(
+        mut self,
+        source: HashAlgorithm,
+        destination: HashAlgorithm,
+    ) -> Self {
+        self.source_hash_algorithm = source;
+        self.destination_hash_algorithm = destination;
+        self
+    }
+
     /// オーダーのフィル状態を監視
     pub async fn monitor_order_fill(
         &self,
@@ -127,6 +225,76 @@ impl CrossChainExecutor {
         Ok(tx_id)
     }
 
+    /// `chain` にHTLCを作成し、エスクローID（Ethereumではエスクローアドレス）を返す
+    ///
+    /// Ethereumでは `token` に "ETH" かERC20アドレスを指定します。
+    pub async fn create_step_htlc(
+        &self,
+        chain: &str,
+        token: &str,
+        amount: u128,
+        secret_hash: SecretHash,
+        timeout_seconds: u64,
+        recipient: &str,
+    ) -> Result<String> {
+        match chain.to_lowercase().as_str() {
+            "near" => self
+                .near_connector
+                .create_escrow(amount, secret_hash, timeout_seconds, recipient.to_string())
+                .await
+                .map_err(|e| anyhow!("Failed to create NEAR escrow: {}", e)),
+            "ethereum" => {
+                let token = if token.eq_ignore_ascii_case("ETH") {
+                    Address::zero()
+                } else {
+                    token
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid token address: {}", token))?
+                };
+                let recipient: Address = recipient
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid recipient address: {}", recipient))?;
+                let funded = self
+                    .ethereum_connector
+                    .create_escrow_with_approval(
+                        token,
+                        U256::from(amount),
+                        secret_hash,
+                        U256::from(timeout_seconds),
+                        recipient,
+                    )
+                    .await
+                    .map_err(|e| anyhow!("Failed to create Ethereum escrow: {}", e))?;
+                Ok(format!("{:?}", funded.escrow))
+            }
+            other => Err(anyhow!("Unsupported chain for HTLC: {}", other)),
+        }
+    }
+
+    /// `chain` のエスクローをクレームし、トランザクションハッシュを返す
+    pub async fn claim_step_htlc(
+        &self,
+        chain: &str,
+        escrow_id: &str,
+        secret: Secret,
+    ) -> Result<String> {
+        match chain.to_lowercase().as_str() {
+            "near" => self.claim_near_htlc(escrow_id, secret).await,
+            "ethereum" => {
+                let escrow: Address = escrow_id
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid escrow address: {}", escrow_id))?;
+                let receipt = self
+                    .ethereum_connector
+                    .claim_escrow(escrow, secret)
+                    .await
+                    .map_err(|e| anyhow!("Failed to claim Ethereum escrow: {}", e))?;
+                Ok(format!("{:?}", receipt.transaction_hash))
+            }
+            other => Err(anyhow!("Unsupported chain for HTLC: {}", other)),
+        }
+    }
+
     /// クロスチェーン実行フローを実行
     pub async fn execute_cross_chain_swap(
         &mut self,
@@ -138,11 +306,33 @@ impl CrossChainExecutor {
             .extract_htlc_data()
             .map_err(|e| anyhow!("Failed to extract HTLC data: {}", e))?;
 
+        // 0. 資金を動かす前にハッシュロックの互換性を確認
+        // NEARのHTLCはオーダーのハッシュロックをそのまま使う
+        ensure_hashlock_compatibility(
+            &HashlockLeg {
+                chain: Chain::Ethereum,
+                algorithm: self.source_hash_algorithm,
+                hashlock: htlc_data.secret_hash,
+            },
+            &HashlockLeg {
+                chain: Chain::NEAR,
+                algorithm: self.destination_hash_algorithm,
+                hashlock: htlc_data.secret_hash,
+            },
+            None,
+        )?;
+
         // 1. Ethereumでオーダーのフィルを監視
         let (tx_hash, block_number) = self
             .monitor_order_fill(&params.order, &params.limit_order_protocol)
             .await?;
 
+        if let Some(telemetry) = &self.telemetry {
+            telemetry
+                .record_leg_created(&htlc_data.secret_hash, "ethereum", now_millis())
+                .await;
+        }
+
         let _state = ExecutionState::OrderFilled {
             tx_hash: tx_hash.clone(),
             block_number,
@@ -153,6 +343,12 @@ impl CrossChainExecutor {
             .create_near_htlc(&params.order, htlc_data.secret_hash)
             .await?;
 
+        if let Some(telemetry) = &self.telemetry {
+            telemetry
+                .record_leg_created(&htlc_data.secret_hash, "near", now_millis())
+                .await;
+        }
+
         let _state = ExecutionState::HTLCCreated {
             escrow_id: escrow_id.clone(),
             secret_hash: htlc_data.secret_hash,
@@ -164,6 +360,12 @@ impl CrossChainExecutor {
         let escrow_address = "0x0000000000000000000000000000000000000000"; // 仮のアドレス
         let secret = self.monitor_secret_reveal(escrow_address).await?;
 
+        if let Some(telemetry) = &self.telemetry {
+            telemetry
+                .record_secret_revealed(&htlc_data.secret_hash, now_millis())
+                .await;
+        }
+
         let _state = ExecutionState::SecretRevealed {
             secret,
             ethereum_claim_tx: tx_hash,
@@ -172,6 +374,12 @@ impl CrossChainExecutor {
         // 4. NEARでクレーム実行
         let near_claim_tx = self.claim_near_htlc(&escrow_id, secret).await?;
 
+        if let Some(telemetry) = &self.telemetry {
+            telemetry
+                .record_claimed(&htlc_data.secret_hash, "near", now_millis())
+                .await;
+        }
+
         Ok(ExecutionState::Completed { near_claim_tx })
     }
 }
@@ -221,7 +429,7 @@ pub mod price_conversion {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::htlc::{generate_secret, hash_secret};
+    use crate::htlc::{generate_secret, hash_secret, hash_secret_with};
     use crate::limit_order_htlc::create_near_to_ethereum_order;
 
     #[test]
@@ -295,6 +503,63 @@ mod tests {
         assert_eq!(taking_amount, 49_500_000);
     }
 
+    #[test]
+    fn test_compatible_hashlocks_for_known_secret() {
+        let secret = generate_secret();
+        let source = HashlockLeg {
+            chain: Chain::Ethereum,
+            algorithm: HashAlgorithm::Keccak256,
+            hashlock: hash_secret_with(&secret, HashAlgorithm::Keccak256),
+        };
+        let destination = HashlockLeg {
+            chain: Chain::NEAR,
+            algorithm: HashAlgorithm::Sha256,
+            hashlock: hash_secret(&secret),
+        };
+
+        // 同じシークレットが両方のハッシュロックの原像になる
+        assert!(ensure_hashlock_compatibility(&source, &destination, Some(&secret)).is_ok());
+
+        // 同じハッシュロックを使い回すなら同じハッシュ関数が必要
+        let shared = HashlockLeg {
+            chain: Chain::Ethereum,
+            algorithm: HashAlgorithm::Sha256,
+            hashlock: destination.hashlock,
+        };
+        assert!(ensure_hashlock_compatibility(&shared, &destination, None).is_ok());
+    }
+
+    #[test]
+    fn test_incompatible_hashlock_misconfiguration() {
+        let secret = generate_secret();
+        let keccak_hashlock = hash_secret_with(&secret, HashAlgorithm::Keccak256);
+        let source = HashlockLeg {
+            chain: Chain::Ethereum,
+            algorithm: HashAlgorithm::Keccak256,
+            hashlock: keccak_hashlock,
+        };
+
+        // NEARのエスクローはkeccak256を検証できない
+        let keccak_on_near = HashlockLeg {
+            chain: Chain::NEAR,
+            algorithm: HashAlgorithm::Keccak256,
+            hashlock: keccak_hashlock,
+        };
+        let err = ensure_hashlock_compatibility(&source, &keccak_on_near, Some(&secret))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("NEAR escrows cannot verify Keccak256"));
+
+        // keccak256のハッシュロックをSHA256として使い回すと、公開されたシークレットで開けない
+        let reused = HashlockLeg {
+            chain: Chain::NEAR,
+            algorithm: HashAlgorithm::Sha256,
+            hashlock: keccak_hashlock,
+        };
+        assert!(ensure_hashlock_compatibility(&source, &reused, Some(&secret)).is_err());
+        assert!(ensure_hashlock_compatibility(&source, &reused, None).is_err());
+    }
+
     #[test]
     fn test_extract_htlc_from_order() {
         let secret = generate_secret();
@@ -1,7 +1,8 @@
 use crate::chains::ethereum::event_monitor::EthereumEventMonitor;
 use crate::chains::near::event_monitor::NearEventMonitor;
 use crate::htlc::SecretHash;
-use anyhow::Result;
+use crate::swap_telemetry::{now_millis, SwapTelemetry};
+use anyhow::{anyhow, Result};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -21,6 +22,7 @@ pub struct CrossChainSecretManager {
     secret_mappings: Arc<RwLock<HashMap<SecretHash, SecretMapping>>>,
     eth_monitor: Option<Arc<EthereumEventMonitor>>,
     near_monitor: Option<Arc<NearEventMonitor>>,
+    telemetry: Option<Arc<SwapTelemetry>>,
 }
 
 impl Default for CrossChainSecretManager {
@@ -35,6 +37,7 @@ impl CrossChainSecretManager {
             secret_mappings: Arc::new(RwLock::new(HashMap::new())),
             eth_monitor: None,
             near_monitor: None,
+            telemetry: None,
         }
     }
 
@@ -48,6 +51,16 @@ impl CrossChainSecretManager {
         self
     }
 
+    /// Record swap latencies (opt-in, kept in memory)
+    pub fn with_telemetry(mut self, telemetry: Arc<SwapTelemetry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    pub fn telemetry(&self) -> Option<Arc<SwapTelemetry>> {
+        self.telemetry.clone()
+    }
+
     pub async fn register_cross_chain_swap(
         &self,
         secret: [u8; 32],
@@ -72,6 +85,21 @@ impl CrossChainSecretManager {
         };
 
         let mut mappings = self.secret_mappings.write().await;
+
+        // Revealing the secret for one swap would unlock any other swap sharing its hashlock
+        if let Some(existing) = mappings.get(&secret_hash) {
+            let same_swap = existing.evm_order_hash == mapping.evm_order_hash
+                && existing.near_htlc_id == mapping.near_htlc_id;
+            if existing.revealed_on.is_none() && !same_swap {
+                return Err(anyhow!(
+                    "Hashlock 0x{} is already used by in-flight swap (order {}, HTLC {})",
+                    hex::encode(secret_hash),
+                    existing.evm_order_hash,
+                    existing.near_htlc_id
+                ));
+            }
+        }
+
         mappings.insert(secret_hash, mapping);
 
         Ok(secret_hash)
@@ -84,6 +112,7 @@ impl CrossChainSecretManager {
         if let Some(eth_monitor) = &self.eth_monitor {
             let (eth_tx, mut eth_rx) = mpsc::channel::<Vec<u8>>(100);
             let mappings_eth = mappings.clone();
+            let telemetry_eth = self.telemetry.clone();
             let eth_monitor = eth_monitor.clone();
 
             tokio::spawn(async move {
@@ -117,6 +146,13 @@ impl CrossChainSecretManager {
                                     .as_secs(),
                             );
                         }
+                        drop(mappings);
+
+                        if let Some(telemetry) = &telemetry_eth {
+                            telemetry
+                                .record_secret_revealed(&secret_hash, now_millis())
+                                .await;
+                        }
                     }
                 }
             });
@@ -126,6 +162,7 @@ impl CrossChainSecretManager {
         if let Some(near_monitor) = &self.near_monitor {
             let (near_tx, mut near_rx) = mpsc::channel::<Vec<u8>>(100);
             let mappings_near = mappings.clone();
+            let telemetry_near = self.telemetry.clone();
             let near_monitor = near_monitor.clone();
 
             tokio::spawn(async move {
@@ -159,6 +196,13 @@ impl CrossChainSecretManager {
                                     .as_secs(),
                             );
                         }
+                        drop(mappings);
+
+                        if let Some(telemetry) = &telemetry_near {
+                            telemetry
+                                .record_secret_revealed(&secret_hash, now_millis())
+                                .await;
+                        }
                     }
                 }
             });
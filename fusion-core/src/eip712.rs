@@ -55,6 +55,71 @@ impl TypedData {
     }
 }
 
+/// EIP712Domain 型のフィールド（名前, Solidity型）
+const EIP712_DOMAIN_FIELDS: [(&str, &str); 4] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+];
+
+/// Order 型のフィールド。並びは `hash_struct` の型文字列と一致させる
+const ORDER_FIELDS: [(&str, &str); 10] = [
+    ("salt", "uint256"),
+    ("makerAsset", "address"),
+    ("takerAsset", "address"),
+    ("maker", "address"),
+    ("receiver", "address"),
+    ("allowedSender", "address"),
+    ("makingAmount", "uint256"),
+    ("takingAmount", "uint256"),
+    ("offsets", "uint256"),
+    ("interactions", "bytes"),
+];
+
+/// 外部ウォレットの `eth_signTypedData_v4` にそのまま渡せる型付きデータ
+///
+/// 署名対象のハッシュだけでなく `types`・`domain`・`primaryType`・`message`
+/// をすべて含めるので、MetaMask などで内容を確認してから署名できます。
+pub fn to_typed_data_json(order: &Order, domain: &EIP712Domain) -> serde_json::Value {
+    let fields = |fields: &[(&str, &str)]| {
+        fields
+            .iter()
+            .map(|(name, ty)| serde_json::json!({ "name": name, "type": ty }))
+            .collect::<Vec<_>>()
+    };
+
+    serde_json::json!({
+        "types": {
+            "EIP712Domain": fields(&EIP712_DOMAIN_FIELDS),
+            "Order": fields(&ORDER_FIELDS),
+        },
+        "domain": {
+            "name": domain.name,
+            "version": domain.version,
+            "chainId": domain.chain_id,
+            "verifyingContract": domain.verifying_contract,
+        },
+        "primaryType": "Order",
+        "message": order_message(order),
+    })
+}
+
+fn order_message(order: &Order) -> serde_json::Value {
+    serde_json::json!({
+        "salt": format!("0x{}", hex::encode(order.salt)),
+        "makerAsset": order.maker_asset,
+        "takerAsset": order.taker_asset,
+        "maker": order.maker,
+        "receiver": order.receiver,
+        "allowedSender": order.allowed_sender,
+        "makingAmount": order.making_amount.to_string(),
+        "takingAmount": order.taking_amount.to_string(),
+        "offsets": order.offsets.to_string(),
+        "interactions": order.interactions,
+    })
+}
+
 pub trait OrderEIP712 {
     fn to_eip712(&self, chain_id: u64, verifying_contract: &str) -> TypedData;
 }
@@ -68,23 +133,10 @@ impl OrderEIP712 for Order {
             verifying_contract: verifying_contract.to_string(),
         };
 
-        let message = serde_json::json!({
-            "salt": format!("0x{}", hex::encode(self.salt)),
-            "makerAsset": self.maker_asset,
-            "takerAsset": self.taker_asset,
-            "maker": self.maker,
-            "receiver": self.receiver,
-            "allowedSender": self.allowed_sender,
-            "makingAmount": self.making_amount.to_string(),
-            "takingAmount": self.taking_amount.to_string(),
-            "offsets": self.offsets.to_string(),
-            "interactions": self.interactions,
-        });
-
         TypedData {
             domain,
             primary_type: "Order".to_string(),
-            message,
+            message: order_message(self),
         }
     }
 }
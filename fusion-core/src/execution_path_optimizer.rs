@@ -3,10 +3,14 @@
 //! クロスチェーン取引の最適な実行パスを計算し、コストとリスクを最小化します。
 
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// 混雑度だけが設定された未知のチェーンのブロック時間（秒）
+const DEFAULT_BLOCK_TIME: u64 = 12;
+
 /// 実行パス
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExecutionPath {
     /// パスID
     pub id: String,
@@ -23,7 +27,7 @@ pub struct ExecutionPath {
 }
 
 /// 実行ステップ
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExecutionStep {
     /// ステップタイプ
     pub step_type: StepType,
@@ -42,7 +46,7 @@ pub struct ExecutionStep {
 }
 
 /// ステップタイプ
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum StepType {
     /// ブリッジ転送
     Bridge,
@@ -84,6 +88,8 @@ pub struct OptimizationParams {
     pub max_risk_score: u8,
     /// 最小要求利益（USD）
     pub min_profit: f64,
+    /// 最大ホップ数（パスに含めるブリッジの数）
+    pub max_hops: usize,
     /// 優先度（コスト、時間、リスク）
     pub priority: OptimizationPriority,
 }
@@ -99,6 +105,35 @@ pub enum OptimizationPriority {
     MinimizeRisk,
     /// 利益最大化
     MaximizeProfit,
+    /// 各指標の重み付き和（重みは合計で正規化される）
+    Weighted {
+        cost: f64,
+        time: f64,
+        risk: f64,
+        profit: f64,
+    },
+}
+
+impl OptimizationPriority {
+    /// 重みが有限の非負値で、合計が正であることを確認
+    pub fn validate(&self) -> Result<()> {
+        if let OptimizationPriority::Weighted {
+            cost,
+            time,
+            risk,
+            profit,
+        } = self
+        {
+            let weights = [cost, time, risk, profit];
+            if weights.iter().any(|w| !w.is_finite() || **w < 0.0) {
+                return Err(anyhow!("Priority weights must be finite and non-negative"));
+            }
+            if weights.into_iter().sum::<f64>() <= 0.0 {
+                return Err(anyhow!("Priority weights must sum to a positive number"));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// 実行パスオプティマイザー
@@ -170,6 +205,72 @@ impl ExecutionPathOptimizer {
         info
     }
 
+    /// チェーンの平均ブロック時間（秒）を取得
+    pub fn block_time(&self, chain: &str) -> Option<u64> {
+        self.chain_info.get(chain).map(|info| info.block_time)
+    }
+
+    /// チェーンの平均ブロック時間（秒）を設定ファイル等の値で上書き
+    pub fn set_block_time(&mut self, chain: &str, block_time: u64) {
+        if let Some(info) = self.chain_info.get_mut(chain) {
+            info.block_time = block_time;
+        } else {
+            self.chain_info.insert(
+                chain.to_string(),
+                ChainInfo {
+                    gas_price: 0.0,
+                    block_time,
+                    congestion: 0.0,
+                },
+            );
+        }
+    }
+
+    /// チェーンの混雑度をガスオラクル等の値で更新
+    ///
+    /// `0.0..=1.0` の範囲外の値は範囲内に丸めます。
+    pub fn set_congestion(&mut self, chain: &str, congestion: f64) {
+        let congestion = if congestion.is_nan() {
+            0.0
+        } else {
+            congestion.clamp(0.0, 1.0)
+        };
+        if let Some(info) = self.chain_info.get_mut(chain) {
+            info.congestion = congestion;
+        } else {
+            self.chain_info.insert(
+                chain.to_string(),
+                ChainInfo {
+                    gas_price: 0.0,
+                    block_time: DEFAULT_BLOCK_TIME,
+                    congestion,
+                },
+            );
+        }
+    }
+
+    /// 混雑度に応じてコストと時間に掛ける係数（混雑度が分からないチェーンは1.0）
+    fn congestion_factor(&self, chain: &str) -> f64 {
+        1.0 + self
+            .chain_info
+            .get(chain)
+            .map_or(0.0, |info| info.congestion)
+    }
+
+    /// ルートのコストと時間を送信元チェーンの混雑度で補正したステップ
+    fn route_step(&self, route: &Route, token: &str, amount: u128) -> ExecutionStep {
+        let factor = self.congestion_factor(&route.source_chain);
+        ExecutionStep {
+            step_type: StepType::Bridge,
+            source_chain: route.source_chain.clone(),
+            target_chain: route.target_chain.clone(),
+            token: token.to_string(),
+            amount,
+            estimated_cost: route.base_cost * factor,
+            estimated_time: (route.base_time as f64 * factor).round() as u64,
+        }
+    }
+
     /// ルートを追加
     pub fn add_route(&mut self, route: Route) {
         self.routes.push(route);
@@ -184,6 +285,7 @@ impl ExecutionPathOptimizer {
         amount: u128,
         params: &OptimizationParams,
     ) -> Result<Vec<ExecutionPath>> {
+        params.priority.validate()?;
         let mut paths = Vec::new();
 
         // 直接パスを探索
@@ -195,7 +297,8 @@ impl ExecutionPathOptimizer {
         }
 
         // 中継パスを探索
-        let relay_paths = self.find_relay_paths(source_chain, target_chain, token, amount);
+        let relay_paths =
+            self.find_relay_paths(source_chain, target_chain, token, amount, params.max_hops);
         for path in relay_paths {
             if self.is_valid_path(&path, params) {
                 paths.push(path);
@@ -235,113 +338,124 @@ impl ExecutionPathOptimizer {
             return None;
         }
 
-        let steps = vec![ExecutionStep {
-            step_type: StepType::Bridge,
-            source_chain: source_chain.to_string(),
-            target_chain: target_chain.to_string(),
-            token: token.to_string(),
-            amount,
-            estimated_cost: route.base_cost,
-            estimated_time: route.base_time,
-        }];
+        let step = self.route_step(route, token, amount);
 
         Some(ExecutionPath {
             id: format!("direct_{}_to_{}", source_chain, target_chain),
-            steps,
-            total_cost: route.base_cost,
-            total_time: route.base_time,
+            total_cost: step.estimated_cost,
+            total_time: step.estimated_time,
+            steps: vec![step],
             risk_score: self.calculate_risk_score(source_chain, target_chain, 1),
             expected_profit: 15.0, // 簡易的な利益計算（実装すべき）
         })
     }
 
     /// 中継パスを探索
+    ///
+    /// ルートを深さ優先で辿り、同じチェーンを2度通るパスは除外します。
+    /// ルートに循環があっても `max_hops` 以内で打ち切られます。
     fn find_relay_paths(
         &self,
         source_chain: &str,
         target_chain: &str,
         token: &str,
         amount: u128,
+        max_hops: usize,
     ) -> Vec<ExecutionPath> {
-        let mut paths = Vec::new();
-        let intermediate_chains = self.get_intermediate_chains();
+        let amount_usd = self.estimate_amount_usd(amount, token);
+        let mut visited = HashSet::from([source_chain.to_string()]);
+        let mut hops = Vec::new();
+        let mut found = Vec::new();
+        self.expand_routes(
+            source_chain,
+            target_chain,
+            amount_usd,
+            max_hops,
+            &mut visited,
+            &mut hops,
+            &mut found,
+        );
 
-        for intermediate in intermediate_chains {
-            // ソース → 中継
-            let route1 = self
-                .routes
-                .iter()
-                .find(|r| r.source_chain == source_chain && r.target_chain == intermediate);
+        found
+            .into_iter()
+            .map(|routes| self.build_relay_path(&routes, token, amount))
+            .collect()
+    }
 
-            // 中継 → ターゲット
-            let route2 = self
-                .routes
-                .iter()
-                .find(|r| r.source_chain == intermediate && r.target_chain == target_chain);
+    /// `current` から出るルートを辿り、`target_chain` に着いた2ホップ以上の経路を集める
+    #[allow(clippy::too_many_arguments)]
+    fn expand_routes<'a>(
+        &'a self,
+        current: &str,
+        target_chain: &str,
+        amount_usd: f64,
+        max_hops: usize,
+        visited: &mut HashSet<String>,
+        hops: &mut Vec<&'a Route>,
+        found: &mut Vec<Vec<&'a Route>>,
+    ) {
+        if hops.len() >= max_hops {
+            return;
+        }
 
-            if let (Some(r1), Some(r2)) = (route1, route2) {
-                let amount_usd = self.estimate_amount_usd(amount, token);
+        for route in self.routes.iter().filter(|r| r.source_chain == current) {
+            // 流動性チェック
+            if route.liquidity < amount_usd || visited.contains(&route.target_chain) {
+                continue;
+            }
 
-                // 流動性チェック
-                if r1.liquidity < amount_usd || r2.liquidity < amount_usd {
-                    continue;
+            hops.push(route);
+            if route.target_chain == target_chain {
+                // 直接パスは find_direct_path で扱う
+                if hops.len() >= 2 {
+                    found.push(hops.clone());
                 }
-
-                let steps = vec![
-                    ExecutionStep {
-                        step_type: StepType::Bridge,
-                        source_chain: source_chain.to_string(),
-                        target_chain: intermediate.clone(),
-                        token: token.to_string(),
-                        amount,
-                        estimated_cost: r1.base_cost,
-                        estimated_time: r1.base_time,
-                    },
-                    ExecutionStep {
-                        step_type: StepType::Bridge,
-                        source_chain: intermediate.clone(),
-                        target_chain: target_chain.to_string(),
-                        token: token.to_string(),
-                        amount,
-                        estimated_cost: r2.base_cost,
-                        estimated_time: r2.base_time,
-                    },
-                ];
-
-                let path = ExecutionPath {
-                    id: format!(
-                        "relay_{}_{}_to_{}",
-                        source_chain, intermediate, target_chain
-                    ),
-                    steps,
-                    total_cost: r1.base_cost + r2.base_cost,
-                    total_time: r1.base_time + r2.base_time,
-                    risk_score: self.calculate_risk_score(source_chain, target_chain, 2),
-                    expected_profit: 12.0, // 簡易的な利益計算（中継パス）
-                };
-
-                paths.push(path);
+            } else {
+                visited.insert(route.target_chain.clone());
+                self.expand_routes(
+                    &route.target_chain,
+                    target_chain,
+                    amount_usd,
+                    max_hops,
+                    visited,
+                    hops,
+                    found,
+                );
+                visited.remove(&route.target_chain);
             }
+            hops.pop();
         }
-
-        paths
     }
 
-    /// 中継チェーンのリストを取得
-    fn get_intermediate_chains(&self) -> Vec<String> {
-        let mut chains = HashSet::new();
-
-        for route in &self.routes {
-            chains.insert(route.source_chain.clone());
-            chains.insert(route.target_chain.clone());
+    /// ルートの列から中継パスを組み立てる
+    fn build_relay_path(&self, routes: &[&Route], token: &str, amount: u128) -> ExecutionPath {
+        let steps: Vec<ExecutionStep> = routes
+            .iter()
+            .map(|route| self.route_step(route, token, amount))
+            .collect();
+
+        let source_chain = &routes[0].source_chain;
+        let target_chain = &routes[routes.len() - 1].target_chain;
+        let chains: Vec<&str> = routes.iter().map(|r| r.source_chain.as_str()).collect();
+
+        ExecutionPath {
+            id: format!("relay_{}_to_{}", chains.join("_"), target_chain),
+            total_cost: steps.iter().map(|s| s.estimated_cost).sum(),
+            total_time: steps.iter().map(|s| s.estimated_time).sum(),
+            risk_score: self.calculate_risk_score(
+                source_chain,
+                target_chain,
+                u8::try_from(routes.len()).unwrap_or(u8::MAX),
+            ),
+            expected_profit: 12.0, // 簡易的な利益計算（中継パス）
+            steps,
         }
-
-        chains.into_iter().collect()
     }
 
     /// パスの妥当性をチェック
     fn is_valid_path(&self, path: &ExecutionPath, params: &OptimizationParams) -> bool {
-        path.total_cost <= params.max_cost
+        path.steps.len() <= params.max_hops
+            && path.total_cost <= params.max_cost
             && path.total_time <= params.max_time
             && path.risk_score <= params.max_risk_score
             && path.expected_profit >= params.min_profit
@@ -362,6 +476,20 @@ impl ExecutionPathOptimizer {
             OptimizationPriority::MaximizeProfit => {
                 paths.sort_by(|a, b| b.expected_profit.partial_cmp(&a.expected_profit).unwrap());
             }
+            OptimizationPriority::Weighted {
+                cost,
+                time,
+                risk,
+                profit,
+            } => {
+                let scores = weighted_scores(paths, [*cost, *time, *risk, *profit]);
+                let mut ranked: Vec<(f64, ExecutionPath)> =
+                    scores.into_iter().zip(paths.iter().cloned()).collect();
+                ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+                for (slot, (_, path)) in paths.iter_mut().zip(ranked) {
+                    *slot = path;
+                }
+            }
         }
     }
 
@@ -370,14 +498,14 @@ impl ExecutionPathOptimizer {
         let mut score = 0u8;
 
         // ホップ数によるリスク
-        score += hop_count * 10;
+        score = score.saturating_add(hop_count.saturating_mul(10));
 
         // チェーンの混雑度によるリスク
         if let Some(source_info) = self.chain_info.get(source_chain) {
-            score += (source_info.congestion * 20.0) as u8;
+            score = score.saturating_add((source_info.congestion * 20.0) as u8);
         }
         if let Some(target_info) = self.chain_info.get(target_chain) {
-            score += (target_info.congestion * 20.0) as u8;
+            score = score.saturating_add((target_info.congestion * 20.0) as u8);
         }
 
         score.min(100)
@@ -395,6 +523,56 @@ impl ExecutionPathOptimizer {
     }
 }
 
+/// 各パスの重み付きスコア（0.0が最良、1.0が最悪）
+///
+/// コスト・時間・リスク・利益を候補の中で0〜1に正規化し、重みの合計で割った
+/// 加重和を返します。利益は大きいほど良いので向きを反転させます。
+fn weighted_scores(paths: &[ExecutionPath], weights: [f64; 4]) -> Vec<f64> {
+    let metrics: Vec<[f64; 4]> = paths
+        .iter()
+        .map(|p| {
+            [
+                p.total_cost,
+                p.total_time as f64,
+                f64::from(p.risk_score),
+                -p.expected_profit,
+            ]
+        })
+        .collect();
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight <= 0.0 {
+        return vec![0.0; paths.len()];
+    }
+
+    let bounds: Vec<(f64, f64)> = (0..4)
+        .map(|i| {
+            metrics
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), m| {
+                    (lo.min(m[i]), hi.max(m[i]))
+                })
+        })
+        .collect();
+
+    metrics
+        .iter()
+        .map(|m| {
+            (0..4)
+                .map(|i| {
+                    let (lo, hi) = bounds[i];
+                    let normalized = if hi > lo {
+                        (m[i] - lo) / (hi - lo)
+                    } else {
+                        0.0
+                    };
+                    weights[i] * normalized
+                })
+                .sum::<f64>()
+                / total_weight
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,6 +585,19 @@ mod tests {
         assert!(optimizer.chain_info.contains_key("near"));
     }
 
+    #[test]
+    fn test_block_time_override() {
+        let mut optimizer = ExecutionPathOptimizer::new();
+        assert_eq!(optimizer.block_time("ethereum"), Some(12));
+        assert_eq!(optimizer.block_time("near"), Some(1));
+        assert_eq!(optimizer.block_time("polygon"), None);
+
+        optimizer.set_block_time("ethereum", 2);
+        optimizer.set_block_time("polygon", 2);
+        assert_eq!(optimizer.block_time("ethereum"), Some(2));
+        assert_eq!(optimizer.block_time("polygon"), Some(2));
+    }
+
     #[test]
     fn test_add_route() {
         let mut optimizer = ExecutionPathOptimizer::new();
@@ -442,6 +633,7 @@ mod tests {
             max_time: 3600,
             max_risk_score: 80,
             min_profit: 0.0,
+            max_hops: 2,
             priority: OptimizationPriority::MinimizeCost,
         };
 
@@ -457,7 +649,8 @@ mod tests {
 
         assert_eq!(paths.len(), 1);
         assert_eq!(paths[0].steps.len(), 1);
-        assert_eq!(paths[0].total_cost, 10.0);
+        // Ethereumの混雑度0.5の分だけ割高
+        assert_eq!(paths[0].total_cost, 15.0);
     }
 
     #[test]
@@ -489,6 +682,7 @@ mod tests {
             max_time: 3600,
             max_risk_score: 80,
             min_profit: 0.0,
+            max_hops: 2,
             priority: OptimizationPriority::MinimizeCost,
         };
 
@@ -503,8 +697,9 @@ mod tests {
         assert!(relay_path.is_some());
 
         if let Some(path) = relay_path {
-            assert_eq!(path.total_cost, 8.0); // 5.0 + 3.0
-            assert_eq!(path.total_time, 300); // 180 + 120
+            // 5.0 × 1.5 + 3.0 × 1.3（送信元チェーンの混雑度で補正）
+            assert!((path.total_cost - 11.4).abs() < 1e-9);
+            assert_eq!(path.total_time, 426); // 180 × 1.5 + 120 × 1.3
         }
     }
 
@@ -526,6 +721,7 @@ mod tests {
             max_time: 3600,
             max_risk_score: 80,
             min_profit: 5.0,
+            max_hops: 2,
             priority: OptimizationPriority::MinimizeCost,
         };
 
@@ -587,4 +783,184 @@ mod tests {
         assert_eq!(paths[1].id, "path1");
         assert_eq!(paths[2].id, "path2");
     }
+
+    fn cyclic_optimizer() -> ExecutionPathOptimizer {
+        let mut optimizer = ExecutionPathOptimizer::new();
+        // 全チェーン間に双方向のルートがあり、どこからでも循環できる
+        for (source, target) in [
+            ("ethereum", "near"),
+            ("near", "ethereum"),
+            ("ethereum", "bsc"),
+            ("bsc", "ethereum"),
+            ("bsc", "near"),
+            ("near", "bsc"),
+        ] {
+            optimizer.add_route(Route {
+                source_chain: source.to_string(),
+                target_chain: target.to_string(),
+                protocol: "bridge".to_string(),
+                base_cost: 5.0,
+                base_time: 100,
+                liquidity: 1000000.0,
+            });
+        }
+        optimizer
+    }
+
+    #[test]
+    fn test_cyclic_routes_yield_only_acyclic_paths() {
+        let optimizer = cyclic_optimizer();
+        let params = OptimizationParams {
+            max_cost: 1000.0,
+            max_time: 36000,
+            max_risk_score: 100,
+            min_profit: 0.0,
+            max_hops: 10,
+            priority: OptimizationPriority::MinimizeCost,
+        };
+
+        let paths = optimizer
+            .find_optimal_path("ethereum", "near", "USDC", 1_000_000_000, &params)
+            .unwrap();
+
+        // 直接パスと bsc 経由のパスだけで、チェーンを再訪するパスはない
+        let ids: Vec<&str> = paths.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["direct_ethereum_to_near", "relay_ethereum_bsc_to_near"]
+        );
+        for path in &paths {
+            let mut chains = HashSet::from([path.steps[0].source_chain.as_str()]);
+            for step in &path.steps {
+                assert!(chains.insert(step.target_chain.as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_hops_limits_relay_paths() {
+        let optimizer = cyclic_optimizer();
+        let params = OptimizationParams {
+            max_cost: 1000.0,
+            max_time: 36000,
+            max_risk_score: 100,
+            min_profit: 0.0,
+            max_hops: 1,
+            priority: OptimizationPriority::MinimizeCost,
+        };
+
+        let paths = optimizer
+            .find_optimal_path("ethereum", "near", "USDC", 1_000_000_000, &params)
+            .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].steps.len(), 1);
+
+        // 直接パスも許さなければパスは見つからない
+        let no_hops = OptimizationParams {
+            max_hops: 0,
+            ..params
+        };
+        assert!(optimizer
+            .find_optimal_path("ethereum", "near", "USDC", 1_000_000_000, &no_hops)
+            .is_err());
+    }
+
+    #[test]
+    fn test_congestion_raises_route_cost() {
+        let mut optimizer = ExecutionPathOptimizer::new();
+        optimizer.add_route(Route {
+            source_chain: "ethereum".to_string(),
+            target_chain: "near".to_string(),
+            protocol: "rainbow_bridge".to_string(),
+            base_cost: 10.0,
+            base_time: 300,
+            liquidity: 1000000.0,
+        });
+        let params = OptimizationParams {
+            max_cost: 100.0,
+            max_time: 3600,
+            max_risk_score: 100,
+            min_profit: 0.0,
+            max_hops: 1,
+            priority: OptimizationPriority::MinimizeCost,
+        };
+        let direct_path = |optimizer: &ExecutionPathOptimizer| {
+            optimizer
+                .find_optimal_path("ethereum", "near", "USDC", 1_000_000_000, &params)
+                .unwrap()
+                .remove(0)
+        };
+
+        optimizer.set_congestion("ethereum", 0.1);
+        let quiet = direct_path(&optimizer);
+        // 範囲外の値は1.0に丸められる
+        optimizer.set_congestion("ethereum", 3.0);
+        let busy = direct_path(&optimizer);
+
+        assert_eq!(quiet.total_cost, 11.0);
+        assert_eq!(busy.total_cost, 20.0);
+        assert!(busy.total_cost > quiet.total_cost);
+        assert_eq!((quiet.total_time, busy.total_time), (330, 600));
+    }
+
+    #[test]
+    fn test_weighting_time_selects_faster_costlier_path() {
+        let mut optimizer = ExecutionPathOptimizer::new();
+        // 直接ルートは高いが速く、bsc経由は安いが遅い
+        for (source, target, base_cost, base_time) in [
+            ("ethereum", "near", 40.0, 100),
+            ("ethereum", "bsc", 5.0, 600),
+            ("bsc", "near", 5.0, 600),
+        ] {
+            optimizer.add_route(Route {
+                source_chain: source.to_string(),
+                target_chain: target.to_string(),
+                protocol: "bridge".to_string(),
+                base_cost,
+                base_time,
+                liquidity: 1000000.0,
+            });
+        }
+        let best_path = |cost: f64, time: f64| {
+            let params = OptimizationParams {
+                max_cost: 1000.0,
+                max_time: 36000,
+                max_risk_score: 100,
+                min_profit: 0.0,
+                max_hops: 2,
+                priority: OptimizationPriority::Weighted {
+                    cost,
+                    time,
+                    risk: 0.0,
+                    profit: 0.0,
+                },
+            };
+            optimizer
+                .find_optimal_path("ethereum", "near", "USDC", 1_000_000_000, &params)
+                .unwrap()
+                .remove(0)
+                .id
+        };
+
+        assert_eq!(best_path(7.0, 3.0), "relay_ethereum_bsc_to_near");
+        assert_eq!(best_path(3.0, 7.0), "direct_ethereum_to_near");
+        // 重みは合計で正規化されるので倍率は結果に影響しない
+        assert_eq!(best_path(0.3, 0.7), "direct_ethereum_to_near");
+    }
+
+    #[test]
+    fn test_invalid_weights_are_rejected() {
+        let weighted = |cost, time| OptimizationPriority::Weighted {
+            cost,
+            time,
+            risk: 0.0,
+            profit: 0.0,
+        };
+
+        assert!(weighted(0.7, 0.3).validate().is_ok());
+        assert!(weighted(0.0, 0.0).validate().is_err());
+        assert!(weighted(-1.0, 2.0).validate().is_err());
+        assert!(weighted(f64::NAN, 1.0).validate().is_err());
+        assert!(OptimizationPriority::MinimizeCost.validate().is_ok());
+    }
 }
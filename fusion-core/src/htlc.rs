@@ -1,6 +1,7 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 use std::time::{Duration, SystemTime};
 use subtle::ConstantTimeEq;
 use thiserror::Error;
@@ -19,11 +20,43 @@ pub fn generate_secret() -> Secret {
     secret
 }
 
+/// ハッシュロックに使うハッシュ関数
+///
+/// NEARコントラクトはSHA256を使いますが、keccak256でハッシュロックを
+/// 組むEVMデプロイメントもあるためチェーンごとに選べるようにしています。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Keccak256,
+}
+
+impl HashAlgorithm {
+    /// シークレットのハッシュを計算する
+    pub fn hash(&self, secret: &Secret) -> SecretHash {
+        match self {
+            Self::Sha256 => Sha256::digest(secret).into(),
+            Self::Keccak256 => Keccak256::digest(secret).into(),
+        }
+    }
+}
+
 /// シークレットのSHA256ハッシュを計算する
 pub fn hash_secret(secret: &Secret) -> SecretHash {
-    let mut hasher = Sha256::new();
-    hasher.update(secret);
-    hasher.finalize().into()
+    hash_secret_with(secret, HashAlgorithm::Sha256)
+}
+
+/// 指定したハッシュ関数でシークレットのハッシュを計算する
+pub fn hash_secret_with(secret: &Secret, algorithm: HashAlgorithm) -> SecretHash {
+    algorithm.hash(secret)
+}
+
+/// シークレットやハッシュを定数時間で比較する
+///
+/// 比較にかかる時間から一致したバイト数が漏れないよう、
+/// シークレット・ハッシュロックの比較は `==` ではなくこれを使います。
+pub fn secret_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.ct_eq(b).into()
 }
 
 /// HTLCのエラー型
@@ -57,13 +90,14 @@ pub struct Htlc {
     recipient: String,
     amount: u64,
     secret_hash: SecretHash,
+    hash_algorithm: HashAlgorithm,
     timeout: Duration,
     created_at: SystemTime,
     state: HtlcState,
 }
 
 impl Htlc {
-    /// 新しいHTLCを作成
+    /// 新しいHTLCを作成（ハッシュロックはSHA256）
     pub fn new(
         sender: String,
         recipient: String,
@@ -87,12 +121,19 @@ impl Htlc {
             recipient,
             amount,
             secret_hash,
+            hash_algorithm: HashAlgorithm::default(),
             timeout,
             created_at: SystemTime::now(),
             state: HtlcState::Pending,
         })
     }
 
+    /// ハッシュロックのハッシュ関数を設定
+    pub fn with_hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
     /// 現在の状態を取得
     pub fn state(&self) -> &HtlcState {
         &self.state
@@ -118,6 +159,11 @@ impl Htlc {
         &self.secret_hash
     }
 
+    /// ハッシュロックのハッシュ関数を取得
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
     /// タイムアウトしているかチェック
     pub fn is_timed_out(&self) -> bool {
         match SystemTime::now().duration_since(self.created_at) {
@@ -134,8 +180,8 @@ impl Htlc {
         }
 
         // シークレットの検証（定数時間比較を使用）
-        let provided_hash = hash_secret(secret);
-        if provided_hash.ct_eq(&self.secret_hash).unwrap_u8() != 1 {
+        let provided_hash = self.hash_algorithm.hash(secret);
+        if !secret_eq(&provided_hash, &self.secret_hash) {
             return Err(HtlcError::InvalidSecret);
         }
 
@@ -7,13 +7,18 @@ pub mod eip712;
 pub mod event_order_linker;
 pub mod htlc;
 pub mod limit_order_htlc;
+#[cfg(feature = "metrics_prometheus")]
+pub mod metrics_prometheus;
 pub mod near_limit_order;
 pub mod order;
 pub mod price_oracle;
 pub mod secret_manager;
+#[cfg(feature = "server")]
+pub mod server;
 
 // 新しいモジュール
 pub mod automated_executor;
 pub mod enhanced_price_oracle;
 pub mod execution_path_optimizer;
 pub mod order_matching_engine;
+pub mod swap_telemetry;
@@ -0,0 +1,171 @@
+//! スワップのメトリクスをPrometheusのテキスト形式で公開
+//!
+//! `metrics_prometheus` フィーチャーが有効な場合のみビルドされます。
+//!
+//! - `GET /metrics` `SwapTelemetry` のスナップショットをexposition形式で返す
+
+use crate::swap_telemetry::{MetricsSnapshot, SwapTelemetry};
+use anyhow::Result;
+use axum::extract::State;
+use axum::http::header::{HeaderName, CONTENT_TYPE};
+use axum::routing::get;
+use axum::Router;
+use std::fmt::Write;
+use std::future::Future;
+use std::net::TcpListener;
+use std::sync::Arc;
+
+/// Prometheusのテキスト形式のContent-Type
+pub const CONTENT_TYPE_TEXT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// スナップショットをPrometheusのexposition形式に変換
+///
+/// 完了したスワップがなくレイテンシが未計測の場合、レイテンシのサンプルは出力しません。
+pub fn render(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(&str, u64)]| {
+        writeln!(out, "# HELP {} {}", name, help).unwrap();
+        writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+        for (labels, value) in samples {
+            writeln!(out, "{}{} {}", name, labels, value).unwrap();
+        }
+    };
+
+    metric(
+        "fusion_swaps_tracked",
+        "gauge",
+        "Swaps with at least one recorded leg",
+        &[("", snapshot.swaps_tracked as u64)],
+    );
+    metric(
+        "fusion_claims_recorded_total",
+        "counter",
+        "HTLC claims recorded across all legs",
+        &[("", snapshot.claims_recorded as u64)],
+    );
+    metric(
+        "fusion_completed_swaps_total",
+        "counter",
+        "Swaps whose destination leg was claimed",
+        &[("", snapshot.completed_swaps as u64)],
+    );
+
+    let latencies: Vec<(&str, u64)> = [
+        ("{stat=\"avg\"}", snapshot.avg_end_to_end_latency_ms),
+        ("{stat=\"min\"}", snapshot.min_end_to_end_latency_ms),
+        ("{stat=\"max\"}", snapshot.max_end_to_end_latency_ms),
+    ]
+    .into_iter()
+    .filter_map(|(labels, value)| value.map(|value| (labels, value)))
+    .collect();
+    metric(
+        "fusion_end_to_end_latency_ms",
+        "gauge",
+        "Milliseconds from source leg creation to destination claim",
+        &latencies,
+    );
+    out
+}
+
+/// エンドポイントを登録したルーターを作成
+pub fn router(telemetry: Arc<SwapTelemetry>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(telemetry)
+}
+
+/// `listener` でスクレイプを受け付け、`shutdown` が完了したら停止
+///
+/// ポートは呼び出し側が `TcpListener::bind` で選びます。
+pub async fn serve<F>(
+    listener: TcpListener,
+    telemetry: Arc<SwapTelemetry>,
+    shutdown: F,
+) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    listener.set_nonblocking(true)?;
+    axum::Server::from_tcp(listener)?
+        .serve(router(telemetry).into_make_service())
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+async fn metrics(
+    State(telemetry): State<Arc<SwapTelemetry>>,
+) -> ([(HeaderName, &'static str); 1], String) {
+    (
+        [(CONTENT_TYPE, CONTENT_TYPE_TEXT)],
+        render(&telemetry.snapshot().await),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rendered_text_has_metric_names_and_types() {
+        let text = render(&MetricsSnapshot {
+            swaps_tracked: 3,
+            claims_recorded: 4,
+            completed_swaps: 2,
+            avg_end_to_end_latency_ms: Some(1500),
+            min_end_to_end_latency_ms: Some(1000),
+            max_end_to_end_latency_ms: Some(2000),
+        });
+
+        for line in [
+            "# TYPE fusion_swaps_tracked gauge",
+            "fusion_swaps_tracked 3",
+            "# TYPE fusion_claims_recorded_total counter",
+            "fusion_claims_recorded_total 4",
+            "# TYPE fusion_completed_swaps_total counter",
+            "fusion_completed_swaps_total 2",
+            "# TYPE fusion_end_to_end_latency_ms gauge",
+            "fusion_end_to_end_latency_ms{stat=\"avg\"} 1500",
+            "fusion_end_to_end_latency_ms{stat=\"min\"} 1000",
+            "fusion_end_to_end_latency_ms{stat=\"max\"} 2000",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {:?} in\n{}",
+                line,
+                text
+            );
+        }
+
+        // 未計測のレイテンシはサンプルを出さない
+        let empty = render(&MetricsSnapshot::default());
+        assert!(empty.contains("# TYPE fusion_end_to_end_latency_ms gauge"));
+        assert!(!empty.contains("fusion_end_to_end_latency_ms{"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_snapshot() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/metrics", listener.local_addr().unwrap());
+        let telemetry = Arc::new(SwapTelemetry::new());
+        telemetry
+            .record_leg_created(&[1u8; 32], "ethereum", 1_000)
+            .await;
+
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, telemetry, async {
+            let _ = shutdown_rx.await;
+        }));
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_TYPE],
+            CONTENT_TYPE_TEXT
+        );
+        let body = response.text().await.unwrap();
+        assert!(body.contains("fusion_swaps_tracked 1"));
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -3,18 +3,23 @@
 //! 異なるチェーン間のオーダーをマッチングし、最適な実行パスを決定します。
 
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 /// マッチング可能なオーダーのペア
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderMatch {
     /// 買い注文ID
     pub buy_order_id: String,
     /// 売り注文ID
     pub sell_order_id: String,
-    /// マッチング価格
+    /// マッチング価格（小数点桁数を正規化した価格）
     pub match_price: f64,
-    /// マッチング数量
+    /// マッチング数量（18桁基準に正規化）
     pub match_amount: u128,
     /// 想定利益（basis points）
     pub profit_bps: u16,
@@ -23,14 +28,21 @@ pub struct OrderMatch {
 /// オーダーブック
 #[derive(Debug, Default)]
 pub struct OrderBook {
-    /// 買い注文（価格降順）
-    buy_orders: Vec<PendingOrder>,
-    /// 売り注文（価格昇順）
-    sell_orders: Vec<PendingOrder>,
+    /// 買い注文（価格降順、同値は受付順）
+    buy_orders: Vec<RestingOrder>,
+    /// 売り注文（価格昇順、同値は受付順）
+    sell_orders: Vec<RestingOrder>,
 }
 
-/// 保留中のオーダー
+/// オーダーブックに載っているオーダーと受付シーケンス番号
 #[derive(Debug, Clone)]
+struct RestingOrder {
+    order: PendingOrder,
+    sequence: u64,
+}
+
+/// 保留中のオーダー
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingOrder {
     /// オーダーID
     pub id: String,
@@ -40,27 +52,51 @@ pub struct PendingOrder {
     pub token_pair: String,
     /// オーダータイプ
     pub order_type: OrderType,
-    /// 価格
+    /// 価格（クォートの最小単位 / ベースの最小単位）
     pub price: f64,
-    /// 数量
+    /// 数量（ベーストークンの最小単位）
     pub amount: u128,
     /// タイムスタンプ
     pub timestamp: u64,
+    /// 有効期限（UNIX秒、この時刻以降はマッチングされない）。Noneは無期限
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+impl PendingOrder {
+    /// `now` 時点で期限切れか
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// 約定計画: マッチと、各オーダーの約定後の残り数量（正規化済み、ブックと同じ並び）
+struct FillPlan {
+    matches: Vec<OrderMatch>,
+    buy_remaining: Vec<u128>,
+    sell_remaining: Vec<u128>,
 }
 
 /// オーダータイプ
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderType {
     Buy,
     Sell,
 }
 
+/// 比較時に数量を揃える共通の小数点桁数
+pub const NORMALIZED_DECIMALS: u8 = 18;
+
 /// オーダーマッチングエンジン
 pub struct OrderMatchingEngine {
     /// チェーンごとのオーダーブック
     order_books: HashMap<String, OrderBook>,
     /// 最小利益閾値（basis points）
     min_profit_threshold: u16,
+    /// (チェーンID, トークンシンボル) ごとの小数点桁数
+    token_decimals: HashMap<(String, String), u8>,
+    /// 次に割り当てる受付シーケンス番号
+    next_sequence: u64,
 }
 
 impl OrderMatchingEngine {
@@ -69,74 +105,237 @@ impl OrderMatchingEngine {
         Self {
             order_books: HashMap::new(),
             min_profit_threshold,
+            token_decimals: HashMap::new(),
+            next_sequence: 0,
+        }
+    }
+
+    /// チェーン上のトークンの小数点桁数を登録
+    ///
+    /// 未登録のトークンは18桁として扱います。
+    pub fn set_token_decimals(&mut self, chain_id: &str, token: &str, decimals: u8) {
+        self.token_decimals
+            .insert((chain_id.to_string(), token.to_string()), decimals);
+
+        // 登録済みオーダーの並び順を正規化価格で更新
+        for order_book in self.order_books.values_mut() {
+            sort_order_book(order_book, &self.token_decimals);
         }
     }
 
     /// オーダーを追加
+    ///
+    /// 追加順にシーケンス番号を割り当て、同じ価格・タイムスタンプの
+    /// オーダーは先に追加された方を優先します。
     pub fn add_order(&mut self, order: PendingOrder) -> Result<()> {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
         let order_book = self
             .order_books
             .entry(order.token_pair.clone())
             .or_default();
 
-        match order.order_type {
-            OrderType::Buy => {
-                order_book.buy_orders.push(order);
-                // 価格降順でソート
-                order_book
-                    .buy_orders
-                    .sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap());
-            }
-            OrderType::Sell => {
-                order_book.sell_orders.push(order);
-                // 価格昇順でソート
-                order_book
-                    .sell_orders
-                    .sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap());
-            }
+        let order_type = order.order_type.clone();
+        let resting = RestingOrder { order, sequence };
+        match order_type {
+            OrderType::Buy => order_book.buy_orders.push(resting),
+            OrderType::Sell => order_book.sell_orders.push(resting),
         }
+        sort_order_book(order_book, &self.token_decimals);
 
         Ok(())
     }
 
+    /// 次のオーダーに割り当てられるシーケンス番号
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// オーダーに割り当てられたシーケンス番号
+    pub fn sequence_of(&self, token_pair: &str, order_id: &str) -> Option<u64> {
+        let order_book = self.order_books.get(token_pair)?;
+        order_book
+            .buy_orders
+            .iter()
+            .chain(&order_book.sell_orders)
+            .find(|r| r.order.id == order_id)
+            .map(|r| r.sequence)
+    }
+
+    /// 小数点桁数を考慮した価格（ベース1単位あたりのクォート数量）
+    pub fn normalized_price(&self, order: &PendingOrder) -> f64 {
+        normalized_price(order, &self.token_decimals)
+    }
+
+    /// 数量を共通の小数点桁数に正規化
+    pub fn normalized_amount(&self, order: &PendingOrder) -> u128 {
+        let (base, _) = split_pair(&order.token_pair);
+        let base_decimals = lookup_decimals(&self.token_decimals, &order.chain_id, base);
+
+        if base_decimals <= NORMALIZED_DECIMALS {
+            order
+                .amount
+                .saturating_mul(10u128.pow((NORMALIZED_DECIMALS - base_decimals) as u32))
+        } else {
+            order.amount / 10u128.pow((base_decimals - NORMALIZED_DECIMALS) as u32)
+        }
+    }
+
+    /// 正規化した数量をオーダーのベーストークンの最小単位に戻す（端数切り捨て）
+    fn denormalized_amount(&self, order: &PendingOrder, amount: u128) -> u128 {
+        let (base, _) = split_pair(&order.token_pair);
+        let base_decimals = lookup_decimals(&self.token_decimals, &order.chain_id, base);
+
+        if base_decimals <= NORMALIZED_DECIMALS {
+            amount / 10u128.pow((NORMALIZED_DECIMALS - base_decimals) as u32)
+        } else {
+            amount.saturating_mul(10u128.pow((base_decimals - NORMALIZED_DECIMALS) as u32))
+        }
+    }
+
     /// マッチング可能なオーダーを検索
+    ///
+    /// 価格・時間優先の順に部分約定を含めて返します。数量の大きい側の残りは
+    /// 同じ優先順位のまま次のオーダーと突き合わせます。
+    /// 期限切れのオーダーは削除前でもマッチングしません。
     pub fn find_matches(&self, token_pair: &str) -> Vec<OrderMatch> {
-        let mut matches = Vec::new();
+        self.order_books
+            .get(token_pair)
+            .map(|order_book| self.plan_fills(order_book, unix_now()).matches)
+            .unwrap_or_default()
+    }
 
-        if let Some(order_book) = self.order_books.get(token_pair) {
-            for buy_order in &order_book.buy_orders {
-                for sell_order in &order_book.sell_orders {
-                    if let Some(order_match) = self.try_match(buy_order, sell_order) {
-                        matches.push(order_match);
-                    }
+    /// マッチングを実行してオーダーブックに反映
+    ///
+    /// 全量約定したオーダーは削除し、部分約定したオーダーは残り数量で
+    /// 元の優先順位のまま残します。
+    pub fn match_orders(&mut self, token_pair: &str) -> Vec<OrderMatch> {
+        let Some(order_book) = self.order_books.get(token_pair) else {
+            return Vec::new();
+        };
+        let plan = self.plan_fills(order_book, unix_now());
+
+        let buy_orders = self.apply_fills(&order_book.buy_orders, &plan.buy_remaining);
+        let sell_orders = self.apply_fills(&order_book.sell_orders, &plan.sell_remaining);
+        let order_book = self.order_books.get_mut(token_pair).unwrap();
+        order_book.buy_orders = buy_orders;
+        order_book.sell_orders = sell_orders;
+
+        plan.matches
+    }
+
+    /// オーダーブックを優先順に突き合わせ、約定と各オーダーの残り数量を計算
+    fn plan_fills(&self, order_book: &OrderBook, now: u64) -> FillPlan {
+        let remaining = |orders: &[RestingOrder]| -> Vec<u128> {
+            orders
+                .iter()
+                .map(|r| self.normalized_amount(&r.order))
+                .collect()
+        };
+        let mut plan = FillPlan {
+            matches: Vec::new(),
+            buy_remaining: remaining(&order_book.buy_orders),
+            sell_remaining: remaining(&order_book.sell_orders),
+        };
+
+        for (i, buy) in order_book.buy_orders.iter().enumerate() {
+            if buy.order.is_expired(now) {
+                continue;
+            }
+            for (j, sell) in order_book.sell_orders.iter().enumerate() {
+                if plan.buy_remaining[i] == 0 {
+                    break;
+                }
+                if plan.sell_remaining[j] == 0 || sell.order.is_expired(now) {
+                    continue;
+                }
+
+                // 数量の小さい側に合わせて約定し、大きい側の残りを次に回す
+                let quantity = plan.buy_remaining[i].min(plan.sell_remaining[j]);
+                if let Some(order_match) = self.try_match(&buy.order, &sell.order, quantity) {
+                    plan.buy_remaining[i] -= quantity;
+                    plan.sell_remaining[j] -= quantity;
+                    plan.matches.push(order_match);
                 }
             }
         }
 
-        matches
+        plan
     }
 
-    /// 2つのオーダーのマッチングを試行
-    fn try_match(&self, buy_order: &PendingOrder, sell_order: &PendingOrder) -> Option<OrderMatch> {
+    /// 残り数量を反映し、全量約定したオーダーを除いたリストを返す
+    fn apply_fills(&self, orders: &[RestingOrder], remaining: &[u128]) -> Vec<RestingOrder> {
+        orders
+            .iter()
+            .zip(remaining)
+            .filter(|(_, &remaining)| remaining > 0)
+            .map(|(resting, &remaining)| {
+                let mut resting = resting.clone();
+                if remaining < self.normalized_amount(&resting.order) {
+                    resting.order.amount = self.denormalized_amount(&resting.order, remaining);
+                }
+                resting
+            })
+            .collect()
+    }
+
+    /// すべてのトークンペアのマッチングを検索（トークンペア順）
+    pub fn find_all_matches(&self) -> Vec<OrderMatch> {
+        let mut token_pairs: Vec<&String> = self.order_books.keys().collect();
+        token_pairs.sort();
+        token_pairs
+            .into_iter()
+            .flat_map(|token_pair| self.find_matches(token_pair))
+            .collect()
+    }
+
+    /// 指定チェーンのオーダーを (買い注文, 売り注文) で取得
+    pub fn orders_on_chain(&self, chain_id: &str) -> (Vec<PendingOrder>, Vec<PendingOrder>) {
+        let mut token_pairs: Vec<&String> = self.order_books.keys().collect();
+        token_pairs.sort();
+
+        let mut buy_orders = Vec::new();
+        let mut sell_orders = Vec::new();
+        for token_pair in token_pairs {
+            let order_book = &self.order_books[token_pair];
+            let on_chain = |r: &&RestingOrder| r.order.chain_id == chain_id;
+            let order = |r: &RestingOrder| r.order.clone();
+            buy_orders.extend(order_book.buy_orders.iter().filter(on_chain).map(order));
+            sell_orders.extend(order_book.sell_orders.iter().filter(on_chain).map(order));
+        }
+        (buy_orders, sell_orders)
+    }
+
+    /// 2つのオーダーを `match_amount`（正規化済み）で約定できるか試行
+    fn try_match(
+        &self,
+        buy_order: &PendingOrder,
+        sell_order: &PendingOrder,
+        match_amount: u128,
+    ) -> Option<OrderMatch> {
         // 異なるチェーンのオーダーのみマッチング
         if buy_order.chain_id == sell_order.chain_id {
             return None;
         }
 
+        // チェーンごとに小数点桁数が異なるため正規化してから比較
+        let buy_price = self.normalized_price(buy_order);
+        let sell_price = self.normalized_price(sell_order);
+
         // 価格条件チェック（買値 >= 売値）
-        if buy_order.price < sell_order.price {
+        if buy_price < sell_price {
             return None;
         }
 
         // マッチング価格は中間値
-        let match_price = (buy_order.price + sell_order.price) / 2.0;
-
-        // マッチング数量は小さい方
-        let match_amount = buy_order.amount.min(sell_order.amount);
+        let match_price = (buy_price + sell_price) / 2.0;
 
-        // 利益計算（スプレッドから）
-        let spread = buy_order.price - sell_order.price;
-        let profit_bps = ((spread / sell_order.price) * 10000.0) as u16;
+        // 利益計算（約定数量ぶんの買い代金と売り代金の差から）
+        let quantity = match_amount as f64;
+        let buy_value = buy_price * quantity;
+        let sell_value = sell_price * quantity;
+        let profit_bps = ((buy_value - sell_value) / sell_value * 10000.0) as u16;
 
         // 最小利益閾値チェック
         if profit_bps < self.min_profit_threshold {
@@ -152,6 +351,18 @@ impl OrderMatchingEngine {
         })
     }
 
+    /// 期限切れのオーダーを削除し、削除した件数を返す
+    pub fn prune_expired(&mut self, now: u64) -> usize {
+        let mut removed = 0;
+        for order_book in self.order_books.values_mut() {
+            let before = order_book.buy_orders.len() + order_book.sell_orders.len();
+            order_book.buy_orders.retain(|r| !r.order.is_expired(now));
+            order_book.sell_orders.retain(|r| !r.order.is_expired(now));
+            removed += before - order_book.buy_orders.len() - order_book.sell_orders.len();
+        }
+        removed
+    }
+
     /// オーダーを削除
     pub fn remove_order(&mut self, token_pair: &str, order_id: &str) -> Result<()> {
         let order_book = self
@@ -160,9 +371,9 @@ impl OrderMatchingEngine {
             .ok_or_else(|| anyhow!("Order book not found for {}", token_pair))?;
 
         // 買い注文から削除を試行
-        order_book.buy_orders.retain(|o| o.id != order_id);
+        order_book.buy_orders.retain(|r| r.order.id != order_id);
         // 売り注文から削除を試行
-        order_book.sell_orders.retain(|o| o.id != order_id);
+        order_book.sell_orders.retain(|r| r.order.id != order_id);
 
         Ok(())
     }
@@ -177,6 +388,69 @@ impl OrderMatchingEngine {
     }
 }
 
+/// 一定間隔で `prune_expired` を呼び出すバックグラウンドタスクを起動
+///
+/// 返されたハンドルを `abort` すると停止します。
+pub fn spawn_expiry_pruner(
+    engine: Arc<RwLock<OrderMatchingEngine>>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            engine.write().await.prune_expired(unix_now());
+        }
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// トークンペアをベースとクォートに分割
+fn split_pair(token_pair: &str) -> (&str, &str) {
+    token_pair.split_once('/').unwrap_or((token_pair, ""))
+}
+
+fn lookup_decimals(decimals: &HashMap<(String, String), u8>, chain_id: &str, token: &str) -> u8 {
+    decimals
+        .get(&(chain_id.to_string(), token.to_string()))
+        .copied()
+        .unwrap_or(NORMALIZED_DECIMALS)
+}
+
+fn normalized_price(order: &PendingOrder, decimals: &HashMap<(String, String), u8>) -> f64 {
+    let (base, quote) = split_pair(&order.token_pair);
+    let base_decimals = lookup_decimals(decimals, &order.chain_id, base) as i32;
+    let quote_decimals = lookup_decimals(decimals, &order.chain_id, quote) as i32;
+
+    order.price * 10f64.powi(base_decimals - quote_decimals)
+}
+
+/// 価格・時間優先で並べ替え: (価格, タイムスタンプ, シーケンス番号)
+fn sort_order_book(order_book: &mut OrderBook, decimals: &HashMap<(String, String), u8>) {
+    let time_priority = |a: &RestingOrder, b: &RestingOrder| {
+        (a.order.timestamp, a.sequence).cmp(&(b.order.timestamp, b.sequence))
+    };
+
+    // 買い注文は価格降順
+    order_book.buy_orders.sort_by(|a, b| {
+        normalized_price(&b.order, decimals)
+            .total_cmp(&normalized_price(&a.order, decimals))
+            .then_with(|| time_priority(a, b))
+    });
+    // 売り注文は価格昇順
+    order_book.sell_orders.sort_by(|a, b| {
+        normalized_price(&a.order, decimals)
+            .total_cmp(&normalized_price(&b.order, decimals))
+            .then_with(|| time_priority(a, b))
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,6 +474,7 @@ mod tests {
             price: 5.0,
             amount: 1000,
             timestamp: 1234567890,
+            expires_at: None,
         };
 
         engine.add_order(order).unwrap();
@@ -222,6 +497,7 @@ mod tests {
             price: 5.1,
             amount: 1000,
             timestamp: 1234567890,
+            expires_at: None,
         };
 
         // 売り注文（低い価格）
@@ -233,6 +509,7 @@ mod tests {
             price: 5.0,
             amount: 800,
             timestamp: 1234567891,
+            expires_at: None,
         };
 
         engine.add_order(buy_order).unwrap();
@@ -263,6 +540,7 @@ mod tests {
             price: 5.1,
             amount: 1000,
             timestamp: 1234567890,
+            expires_at: None,
         };
 
         let sell_order = PendingOrder {
@@ -273,6 +551,7 @@ mod tests {
             price: 5.0,
             amount: 800,
             timestamp: 1234567891,
+            expires_at: None,
         };
 
         engine.add_order(buy_order).unwrap();
@@ -295,6 +574,7 @@ mod tests {
             price: 4.9,
             amount: 1000,
             timestamp: 1234567890,
+            expires_at: None,
         };
 
         let sell_order = PendingOrder {
@@ -305,6 +585,7 @@ mod tests {
             price: 5.0,
             amount: 800,
             timestamp: 1234567891,
+            expires_at: None,
         };
 
         engine.add_order(buy_order).unwrap();
@@ -327,6 +608,7 @@ mod tests {
             price: 5.02,
             amount: 1000,
             timestamp: 1234567890,
+            expires_at: None,
         };
 
         let sell_order = PendingOrder {
@@ -337,6 +619,7 @@ mod tests {
             price: 5.0,
             amount: 800,
             timestamp: 1234567891,
+            expires_at: None,
         };
 
         engine.add_order(buy_order).unwrap();
@@ -358,6 +641,7 @@ mod tests {
             price: 5.0,
             amount: 1000,
             timestamp: 1234567890,
+            expires_at: None,
         };
 
         engine.add_order(order).unwrap();
@@ -366,4 +650,288 @@ mod tests {
         engine.remove_order("NEAR/USDC", "order1").unwrap();
         assert_eq!(engine.get_order_count("NEAR/USDC").0, 0);
     }
+
+    #[test]
+    fn test_decimals_aware_price_crossing() {
+        let mut engine = OrderMatchingEngine::new(10);
+        // Ethereum上のUSDCは6桁、NEAR側のブリッジUSDCは18桁
+        engine.set_token_decimals("ethereum", "WETH", 18);
+        engine.set_token_decimals("ethereum", "USDC", 6);
+        engine.set_token_decimals("near", "WETH", 18);
+        engine.set_token_decimals("near", "USDC", 18);
+
+        // 2000 USDC/WETH の最小単位価格: 2000 * 10^6 / 10^18
+        let buy_order = PendingOrder {
+            id: "buy1".to_string(),
+            chain_id: "ethereum".to_string(),
+            token_pair: "WETH/USDC".to_string(),
+            order_type: OrderType::Buy,
+            price: 2.0e-9,
+            amount: 1_000_000_000_000_000_000,
+            timestamp: 1234567890,
+            expires_at: None,
+        };
+
+        // 1990 USDC/WETH（両方18桁）
+        let sell_order = PendingOrder {
+            id: "sell1".to_string(),
+            chain_id: "near".to_string(),
+            token_pair: "WETH/USDC".to_string(),
+            order_type: OrderType::Sell,
+            price: 1990.0,
+            amount: 500_000_000_000_000_000,
+            timestamp: 1234567891,
+            expires_at: None,
+        };
+
+        engine.add_order(buy_order).unwrap();
+        engine.add_order(sell_order).unwrap();
+
+        // 生の価格では 2e-9 < 1990 だが、正規化後は 2000 >= 1990 でクロスする
+        let matches = engine.find_matches("WETH/USDC");
+        assert_eq!(matches.len(), 1);
+        assert!((matches[0].match_price - 1995.0).abs() < 1e-6);
+        assert_eq!(matches[0].match_amount, 500_000_000_000_000_000);
+        assert!(matches[0].profit_bps >= 49 && matches[0].profit_bps <= 50);
+    }
+
+    #[test]
+    fn test_decimals_aware_no_false_crossing() {
+        let mut engine = OrderMatchingEngine::new(10);
+        engine.set_token_decimals("ethereum", "USDC", 6);
+
+        // 正規化後 1980 USDC/WETH
+        let buy_order = PendingOrder {
+            id: "buy1".to_string(),
+            chain_id: "near".to_string(),
+            token_pair: "WETH/USDC".to_string(),
+            order_type: OrderType::Buy,
+            price: 1980.0,
+            amount: 1_000_000_000_000_000_000,
+            timestamp: 1234567890,
+            expires_at: None,
+        };
+
+        // 正規化後 1990 USDC/WETH（生の価格は 1.99e-9）
+        let sell_order = PendingOrder {
+            id: "sell1".to_string(),
+            chain_id: "ethereum".to_string(),
+            token_pair: "WETH/USDC".to_string(),
+            order_type: OrderType::Sell,
+            price: 1.99e-9,
+            amount: 1_000_000_000_000_000_000,
+            timestamp: 1234567891,
+            expires_at: None,
+        };
+
+        engine.add_order(buy_order).unwrap();
+        engine.add_order(sell_order).unwrap();
+
+        // 生の価格比較ではクロスしてしまうが、正規化後は買値 < 売値
+        assert!(engine.find_matches("WETH/USDC").is_empty());
+    }
+
+    fn crossing_orders(sell_expires_at: Option<u64>) -> [PendingOrder; 2] {
+        [
+            PendingOrder {
+                id: "buy1".to_string(),
+                chain_id: "ethereum".to_string(),
+                token_pair: "NEAR/USDC".to_string(),
+                order_type: OrderType::Buy,
+                price: 5.1,
+                amount: 1000,
+                timestamp: 1234567890,
+                expires_at: None,
+            },
+            PendingOrder {
+                id: "sell1".to_string(),
+                chain_id: "near".to_string(),
+                token_pair: "NEAR/USDC".to_string(),
+                order_type: OrderType::Sell,
+                price: 5.0,
+                amount: 800,
+                timestamp: 1234567891,
+                expires_at: sell_expires_at,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_expired_order_never_matches_and_is_pruned() {
+        let mut engine = OrderMatchingEngine::new(50);
+        for order in crossing_orders(Some(1_000)) {
+            engine.add_order(order).unwrap();
+        }
+
+        assert!(engine.find_matches("NEAR/USDC").is_empty());
+        assert_eq!(engine.find_all_matches().len(), 0);
+
+        // 期限前の時刻では残り、期限以降で削除される
+        assert_eq!(engine.prune_expired(999), 0);
+        assert_eq!(engine.prune_expired(1_000), 1);
+        assert_eq!(engine.get_order_count("NEAR/USDC"), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_expiry_pruner_removes_expired_orders() {
+        let engine = Arc::new(RwLock::new(OrderMatchingEngine::new(50)));
+        {
+            let mut engine = engine.write().await;
+            for order in crossing_orders(Some(1)) {
+                engine.add_order(order).unwrap();
+            }
+            let [_, mut fresh] = crossing_orders(None);
+            fresh.id = "sell2".to_string();
+            engine.add_order(fresh).unwrap();
+        }
+
+        let pruner = spawn_expiry_pruner(engine.clone(), Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        pruner.abort();
+
+        let engine = engine.read().await;
+        assert_eq!(engine.get_order_count("NEAR/USDC"), (1, 1));
+        assert_eq!(engine.find_matches("NEAR/USDC")[0].sell_order_id, "sell2");
+    }
+
+    #[test]
+    fn test_price_time_priority_for_same_price_orders() {
+        let mut engine = OrderMatchingEngine::new(50);
+        engine
+            .add_order(PendingOrder {
+                id: "buy1".to_string(),
+                chain_id: "ethereum".to_string(),
+                token_pair: "NEAR/USDC".to_string(),
+                order_type: OrderType::Buy,
+                price: 5.1,
+                amount: 1000,
+                timestamp: 1234567890,
+                expires_at: None,
+            })
+            .unwrap();
+
+        // 同じ価格の売り注文: sell_late は後のタイムスタンプ、
+        // sell_first と sell_second は同じタイムスタンプで追加順のみ異なる
+        for (id, timestamp) in [
+            ("sell_late", 1234567895),
+            ("sell_first", 1234567891),
+            ("sell_second", 1234567891),
+        ] {
+            engine
+                .add_order(PendingOrder {
+                    id: id.to_string(),
+                    chain_id: "near".to_string(),
+                    token_pair: "NEAR/USDC".to_string(),
+                    order_type: OrderType::Sell,
+                    price: 5.0,
+                    amount: 300,
+                    timestamp,
+                    expires_at: None,
+                })
+                .unwrap();
+        }
+
+        assert_eq!(engine.next_sequence(), 4);
+        assert_eq!(engine.sequence_of("NEAR/USDC", "sell_first"), Some(2));
+        assert_eq!(engine.sequence_of("NEAR/USDC", "sell_second"), Some(3));
+
+        let fill_order: Vec<String> = engine
+            .find_matches("NEAR/USDC")
+            .into_iter()
+            .map(|m| m.sell_order_id)
+            .collect();
+        assert_eq!(fill_order, vec!["sell_first", "sell_second", "sell_late"]);
+    }
+
+    fn sized_orders(buy_amount: u128, sell_amount: u128) -> [PendingOrder; 2] {
+        let [mut buy, mut sell] = crossing_orders(None);
+        buy.amount = buy_amount;
+        sell.amount = sell_amount;
+        [buy, sell]
+    }
+
+    #[test]
+    fn test_partial_fill_leaves_buy_residual() {
+        let mut engine = OrderMatchingEngine::new(50);
+        for order in sized_orders(1000, 800) {
+            engine.add_order(order).unwrap();
+        }
+
+        let matches = engine.match_orders("NEAR/USDC");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].match_amount, 800);
+        assert!(matches[0].profit_bps >= 199 && matches[0].profit_bps <= 200);
+
+        // 買い注文の残り200が元の優先順位のまま残る
+        assert_eq!(engine.get_order_count("NEAR/USDC"), (1, 0));
+        let (buy_orders, _) = engine.orders_on_chain("ethereum");
+        assert_eq!(buy_orders[0].amount, 200);
+        assert_eq!(engine.sequence_of("NEAR/USDC", "buy1"), Some(0));
+
+        // 残りは後から来た売り注文と約定する
+        let [_, mut sell] = sized_orders(0, 500);
+        sell.id = "sell2".to_string();
+        engine.add_order(sell).unwrap();
+        let matches = engine.match_orders("NEAR/USDC");
+        assert_eq!(matches[0].sell_order_id, "sell2");
+        assert_eq!(matches[0].match_amount, 200);
+        assert_eq!(engine.get_order_count("NEAR/USDC"), (0, 1));
+    }
+
+    #[test]
+    fn test_partial_fill_leaves_sell_residual() {
+        let mut engine = OrderMatchingEngine::new(50);
+        for order in sized_orders(300, 800) {
+            engine.add_order(order).unwrap();
+        }
+        let [mut buy, _] = sized_orders(400, 0);
+        buy.id = "buy2".to_string();
+        buy.timestamp += 10;
+        engine.add_order(buy).unwrap();
+
+        // 売り注文の残りが次の買い注文と約定する
+        let matches = engine.match_orders("NEAR/USDC");
+        let fills: Vec<(&str, u128)> = matches
+            .iter()
+            .map(|m| (m.buy_order_id.as_str(), m.match_amount))
+            .collect();
+        assert_eq!(fills, vec![("buy1", 300), ("buy2", 400)]);
+
+        assert_eq!(engine.get_order_count("NEAR/USDC"), (0, 1));
+        let (_, sell_orders) = engine.orders_on_chain("near");
+        assert_eq!(sell_orders[0].amount, 100);
+    }
+
+    #[test]
+    fn test_exact_fill_removes_both_orders() {
+        let mut engine = OrderMatchingEngine::new(50);
+        for order in sized_orders(800, 800) {
+            engine.add_order(order).unwrap();
+        }
+
+        let matches = engine.match_orders("NEAR/USDC");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].match_amount, 800);
+        assert_eq!(engine.get_order_count("NEAR/USDC"), (0, 0));
+        assert!(engine.match_orders("NEAR/USDC").is_empty());
+    }
+
+    #[test]
+    fn test_normalized_amount() {
+        let mut engine = OrderMatchingEngine::new(10);
+        engine.set_token_decimals("ethereum", "USDC", 6);
+
+        let order = PendingOrder {
+            id: "sell1".to_string(),
+            chain_id: "ethereum".to_string(),
+            token_pair: "USDC/WETH".to_string(),
+            order_type: OrderType::Sell,
+            price: 0.0005,
+            amount: 1_500_000, // 1.5 USDC
+            timestamp: 1234567890,
+            expires_at: None,
+        };
+
+        assert_eq!(engine.normalized_amount(&order), 1_500_000_000_000_000_000);
+    }
 }
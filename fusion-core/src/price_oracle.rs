@@ -1,7 +1,53 @@
+use crate::chains::ethereum::abi::aggregator::AggregatorV3Interface;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use ethers::providers::{Http, Provider};
+use ethers::types::Address;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// 価格を有効とみなす既定の最大経過時間
+pub const DEFAULT_MAX_PRICE_AGE: Duration = Duration::from_secs(3600);
+
+/// 価格変換のエラー
+#[derive(Debug, Error, PartialEq)]
+pub enum PriceError {
+    #[error(
+        "Conversion rate {rate} for {from_token}->{to_token} deviates more than {max_deviation_bps} bps from the reference {reference_rate}"
+    )]
+    OutOfBounds {
+        from_token: String,
+        to_token: String,
+        rate: f64,
+        reference_rate: f64,
+        max_deviation_bps: u32,
+    },
+    #[error(
+        "Price feed for {token} was last updated at {updated_at}, more than {max_age_secs}s ago"
+    )]
+    StalePrice {
+        token: String,
+        updated_at: u64,
+        max_age_secs: u64,
+    },
+}
+
+/// オラクルのレートが収まるべき範囲（基準レートからの許容乖離）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateBounds {
+    pub reference_rate: f64,
+    pub max_deviation_bps: u32,
+}
+
+impl RateBounds {
+    pub fn contains(&self, rate: f64) -> bool {
+        let deviation = (rate / self.reference_rate - 1.0).abs();
+        rate.is_finite() && deviation * 10_000.0 <= self.max_deviation_bps as f64
+    }
+}
 
 /// 価格データ
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +74,8 @@ pub trait PriceOracle: Send + Sync {
 }
 
 /// モック価格オラクル（テスト用）
+///
+/// 返す価格のタイムスタンプは常に現在時刻で、古い価格として拒否されることはありません。
 pub struct MockPriceOracle {
     prices: HashMap<String, PriceData>,
 }
@@ -86,20 +134,21 @@ impl MockPriceOracle {
     pub fn set_price(&mut self, token: &str, price: f64) {
         if let Some(data) = self.prices.get_mut(token) {
             data.price = price;
-            data.timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
         }
     }
+
+    fn fresh(&self, token_symbol: &str) -> Option<PriceData> {
+        self.prices.get(token_symbol).map(|data| PriceData {
+            timestamp: unix_now(),
+            ..data.clone()
+        })
+    }
 }
 
 #[async_trait]
 impl PriceOracle for MockPriceOracle {
     async fn get_price(&self, token_symbol: &str) -> Result<PriceData> {
-        self.prices
-            .get(token_symbol)
-            .cloned()
+        self.fresh(token_symbol)
             .ok_or_else(|| anyhow!("Token {} not supported", token_symbol))
     }
 
@@ -107,8 +156,8 @@ impl PriceOracle for MockPriceOracle {
         let mut result = HashMap::new();
 
         for symbol in token_symbols {
-            if let Some(price) = self.prices.get(*symbol) {
-                result.insert(symbol.to_string(), price.clone());
+            if let Some(price) = self.fresh(symbol) {
+                result.insert(symbol.to_string(), price);
             }
         }
 
@@ -120,49 +169,245 @@ impl PriceOracle for MockPriceOracle {
     }
 }
 
-/// Chainlink価格オラクル（将来の実装用）
-pub struct ChainlinkOracle {
-    // TODO: Chainlinkのコントラクトアドレスなどを保持
+#[async_trait]
+impl PriceOracle for Box<dyn PriceOracle> {
+    async fn get_price(&self, token_symbol: &str) -> Result<PriceData> {
+        self.as_ref().get_price(token_symbol).await
+    }
+
+    async fn get_prices(&self, token_symbols: &[&str]) -> Result<HashMap<String, PriceData>> {
+        self.as_ref().get_prices(token_symbols).await
+    }
+
+    async fn supported_tokens(&self) -> Result<Vec<String>> {
+        self.as_ref().supported_tokens().await
+    }
+}
+
+/// Chainlinkフィードの最新ラウンド
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeedRound {
+    pub answer: i128,
+    pub decimals: u8,
+    /// ラウンドが更新されたUnix時間（秒）
+    pub updated_at: u64,
+}
+
+/// 価格フィードコントラクトの読み取り元
+#[async_trait]
+pub trait PriceFeedSource: Send + Sync {
+    async fn latest_round(&self, feed: Address) -> Result<FeedRound>;
+}
+
+#[async_trait]
+impl PriceFeedSource for Provider<Http> {
+    async fn latest_round(&self, feed: Address) -> Result<FeedRound> {
+        let aggregator = AggregatorV3Interface::new(feed, Arc::new(self.clone()));
+        let decimals = aggregator.decimals().call().await?;
+        let (_, answer, _, updated_at, _) = aggregator.latest_round_data().call().await?;
+        Ok(FeedRound {
+            answer: i128::try_from(answer)
+                .map_err(|_| anyhow!("Feed {:?} answer {} is out of range", feed, answer))?,
+            decimals,
+            updated_at: updated_at.try_into().unwrap_or(u64::MAX),
+        })
+    }
+}
+
+/// ChainlinkのUSD建て価格フィードを読む価格オラクル
+///
+/// `updatedAt` が `max_age` より古いラウンドは古い価格として拒否します。
+pub struct ChainlinkPriceOracle<S: PriceFeedSource = Provider<Http>> {
+    source: S,
+    /// トークンシンボル -> USD建てフィードのアドレス
+    feeds: HashMap<String, Address>,
+    max_age: Duration,
 }
 
-impl ChainlinkOracle {
-    pub fn new(_network: &str) -> Self {
-        Self {}
+impl<S: PriceFeedSource> ChainlinkPriceOracle<S> {
+    pub fn new(source: S, feeds: HashMap<String, Address>) -> Self {
+        Self {
+            source,
+            feeds,
+            max_age: DEFAULT_MAX_PRICE_AGE,
+        }
+    }
+
+    /// 許容するラウンドの最大経過時間を変更
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
     }
 }
 
 #[async_trait]
-impl PriceOracle for ChainlinkOracle {
-    async fn get_price(&self, _token_symbol: &str) -> Result<PriceData> {
-        // TODO: Chainlinkから実際の価格を取得
-        Err(anyhow!("Chainlink oracle not implemented yet"))
+impl<S: PriceFeedSource> PriceOracle for ChainlinkPriceOracle<S> {
+    async fn get_price(&self, token_symbol: &str) -> Result<PriceData> {
+        let feed = self
+            .feeds
+            .get(token_symbol)
+            .ok_or_else(|| anyhow!("Token {} not supported", token_symbol))?;
+        let round = self.source.latest_round(*feed).await?;
+
+        if unix_now().saturating_sub(round.updated_at) > self.max_age.as_secs() {
+            return Err(PriceError::StalePrice {
+                token: token_symbol.to_string(),
+                updated_at: round.updated_at,
+                max_age_secs: self.max_age.as_secs(),
+            }
+            .into());
+        }
+        if round.answer <= 0 {
+            return Err(anyhow!(
+                "Price feed for {} returned a non-positive answer {}",
+                token_symbol,
+                round.answer
+            ));
+        }
+
+        Ok(PriceData {
+            price: round.answer as f64 / 10f64.powi(round.decimals as i32),
+            timestamp: round.updated_at,
+            confidence: 1.0,
+        })
     }
 
-    async fn get_prices(&self, _token_symbols: &[&str]) -> Result<HashMap<String, PriceData>> {
-        Err(anyhow!("Chainlink oracle not implemented yet"))
+    async fn get_prices(&self, token_symbols: &[&str]) -> Result<HashMap<String, PriceData>> {
+        let mut result = HashMap::new();
+
+        for symbol in token_symbols {
+            if self.feeds.contains_key(*symbol) {
+                result.insert(symbol.to_string(), self.get_price(symbol).await?);
+            }
+        }
+
+        Ok(result)
     }
 
     async fn supported_tokens(&self) -> Result<Vec<String>> {
-        Err(anyhow!("Chainlink oracle not implemented yet"))
+        Ok(self.feeds.keys().cloned().collect())
     }
 }
 
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 /// 価格変換ユーティリティ
 pub struct PriceConverter<O: PriceOracle> {
     oracle: O,
+    /// オラクルの価格を使える最大経過時間
+    max_age: Duration,
+    /// (from, to) -> 固定レート。オラクルより優先される
+    fixed_rates: HashMap<(String, String), f64>,
+    /// (from, to) -> オラクルのレートに課す範囲
+    rate_bounds: HashMap<(String, String), RateBounds>,
 }
 
 impl<O: PriceOracle> PriceConverter<O> {
     pub fn new(oracle: O) -> Self {
-        Self { oracle }
+        Self {
+            oracle,
+            max_age: DEFAULT_MAX_PRICE_AGE,
+            fixed_rates: HashMap::new(),
+            rate_bounds: HashMap::new(),
+        }
+    }
+
+    /// オラクルの価格を使える最大経過時間を変更
+    ///
+    /// タイムスタンプが `max_age` より古い価格は `PriceError::StalePrice` で拒否されます。
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// オラクルから価格を取得し、古すぎないことを確認
+    async fn fresh_price(&self, token: &str) -> Result<PriceData> {
+        let price = self.oracle.get_price(token).await?;
+        if unix_now().saturating_sub(price.timestamp) > self.max_age.as_secs() {
+            return Err(PriceError::StalePrice {
+                token: token.to_string(),
+                updated_at: price.timestamp,
+                max_age_secs: self.max_age.as_secs(),
+            }
+            .into());
+        }
+        Ok(price)
+    }
+
+    /// 特定ペアの変換レートを固定（テストや決定的なスワップ用）
+    ///
+    /// 固定されていないペアは従来どおりオラクルに問い合わせます。
+    pub fn with_fixed_rate(mut self, from_token: &str, to_token: &str, rate: f64) -> Self {
+        self.fixed_rates
+            .insert((from_token.to_string(), to_token.to_string()), rate);
+        self
+    }
+
+    /// オラクルのレートを基準レートから `max_deviation_bps` 以内に制限
+    ///
+    /// オラクルの異常値で極端な金額のスワップが作られるのを防ぎます。
+    /// 範囲外のレートは `PriceError::OutOfBounds` で拒否されます。
+    /// 固定レートはユーザーの指定なので対象外です。
+    pub fn with_rate_bounds(
+        mut self,
+        from_token: &str,
+        to_token: &str,
+        reference_rate: f64,
+        max_deviation_bps: u32,
+    ) -> Self {
+        self.rate_bounds.insert(
+            (from_token.to_string(), to_token.to_string()),
+            RateBounds {
+                reference_rate,
+                max_deviation_bps,
+            },
+        );
+        self
     }
 
     /// トークンAからトークンBへの変換レートを計算
     pub async fn get_conversion_rate(&self, from_token: &str, to_token: &str) -> Result<f64> {
-        let from_price = self.oracle.get_price(from_token).await?;
-        let to_price = self.oracle.get_price(to_token).await?;
+        let pair = (from_token.to_string(), to_token.to_string());
+        if let Some(rate) = self.fixed_rates.get(&pair) {
+            return Ok(*rate);
+        }
 
-        Ok(from_price.price / to_price.price)
+        let from_price = self.fresh_price(from_token).await?;
+        let to_price = self.fresh_price(to_token).await?;
+        let rate = from_price.price / to_price.price;
+
+        if let Some(bounds) = self.rate_bounds.get(&pair) {
+            if !bounds.contains(rate) {
+                return Err(PriceError::OutOfBounds {
+                    from_token: from_token.to_string(),
+                    to_token: to_token.to_string(),
+                    rate,
+                    reference_rate: bounds.reference_rate,
+                    max_deviation_bps: bounds.max_deviation_bps,
+                }
+                .into());
+            }
+        }
+
+        Ok(rate)
+    }
+
+    /// トークン1単位のUSD価格
+    ///
+    /// `with_fixed_rate(token, "USD", ..)` で固定されていればオラクルより優先します。
+    pub async fn usd_price(&self, token: &str) -> Result<f64> {
+        if let Some(rate) = self
+            .fixed_rates
+            .get(&(token.to_string(), "USD".to_string()))
+        {
+            return Ok(*rate);
+        }
+        Ok(self.fresh_price(token).await?.price)
     }
 
     /// 金額を変換
@@ -189,6 +434,81 @@ impl<O: PriceOracle> PriceConverter<O> {
 mod tests {
     use super::*;
 
+    /// フィードごとに固定のラウンドを返すモック
+    struct MockFeeds(HashMap<Address, FeedRound>);
+
+    #[async_trait]
+    impl PriceFeedSource for MockFeeds {
+        async fn latest_round(&self, feed: Address) -> Result<FeedRound> {
+            self.0
+                .get(&feed)
+                .copied()
+                .ok_or_else(|| anyhow!("feed reverted"))
+        }
+    }
+
+    fn chainlink_oracle(eth_updated_at: u64) -> ChainlinkPriceOracle<MockFeeds> {
+        let eth_feed = Address::from_low_u64_be(1);
+        let near_feed = Address::from_low_u64_be(2);
+        let rounds = MockFeeds(HashMap::from([
+            (
+                eth_feed,
+                FeedRound {
+                    answer: 300_012_345_678,
+                    decimals: 8,
+                    updated_at: eth_updated_at,
+                },
+            ),
+            (
+                near_feed,
+                FeedRound {
+                    answer: 500_000_000,
+                    decimals: 8,
+                    updated_at: unix_now(),
+                },
+            ),
+        ]));
+        ChainlinkPriceOracle::new(
+            rounds,
+            HashMap::from([
+                ("ETH".to_string(), eth_feed),
+                ("NEAR".to_string(), near_feed),
+            ]),
+        )
+        .with_max_age(Duration::from_secs(600))
+    }
+
+    #[tokio::test]
+    async fn test_chainlink_price_from_feed() {
+        let oracle = chainlink_oracle(unix_now() - 60);
+
+        let price = oracle.get_price("ETH").await.unwrap();
+        assert!((price.price - 3000.12345678).abs() < 1e-9);
+
+        let converter = PriceConverter::new(oracle);
+        let rate = converter.get_conversion_rate("NEAR", "ETH").await.unwrap();
+        assert!((rate - 5.0 / 3000.12345678).abs() < 1e-12);
+        assert!(converter.usd_price("USDC").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_chainlink_rejects_stale_round() {
+        let updated_at = unix_now() - 3600;
+        let oracle = chainlink_oracle(updated_at);
+
+        let err = oracle.get_price("ETH").await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PriceError>(),
+            Some(&PriceError::StalePrice {
+                token: "ETH".to_string(),
+                updated_at,
+                max_age_secs: 600,
+            })
+        );
+        // A fresh feed still answers
+        assert!(oracle.get_price("NEAR").await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_mock_oracle() {
         let oracle = MockPriceOracle::new();
@@ -224,6 +544,176 @@ mod tests {
         assert_eq!(eth_amount, 2_500_000_000_000_000);
     }
 
+    /// 固定のタイムスタンプで価格を返すオラクル
+    struct StaleOracle {
+        inner: MockPriceOracle,
+        timestamp: u64,
+    }
+
+    #[async_trait]
+    impl PriceOracle for StaleOracle {
+        async fn get_price(&self, token_symbol: &str) -> Result<PriceData> {
+            let mut price = self.inner.get_price(token_symbol).await?;
+            price.timestamp = self.timestamp;
+            Ok(price)
+        }
+
+        async fn get_prices(&self, token_symbols: &[&str]) -> Result<HashMap<String, PriceData>> {
+            self.inner.get_prices(token_symbols).await
+        }
+
+        async fn supported_tokens(&self) -> Result<Vec<String>> {
+            self.inner.supported_tokens().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_converter_rejects_stale_price() {
+        let updated_at = unix_now() - 7200;
+        let converter = PriceConverter::new(StaleOracle {
+            inner: MockPriceOracle::new(),
+            timestamp: updated_at,
+        });
+
+        let err = converter
+            .convert_amount(1_000_000_000_000_000_000, "ETH", 18, "NEAR", 24)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PriceError>(),
+            Some(&PriceError::StalePrice {
+                token: "ETH".to_string(),
+                updated_at,
+                max_age_secs: DEFAULT_MAX_PRICE_AGE.as_secs(),
+            })
+        );
+        assert!(converter.usd_price("ETH").await.is_err());
+
+        // 許容時間を延ばせば同じ価格を使える
+        let converter = converter.with_max_age(Duration::from_secs(3 * 3600));
+        assert_eq!(converter.usd_price("ETH").await.unwrap(), 2000.0);
+
+        // モックの価格は常に最新
+        let converter =
+            PriceConverter::new(MockPriceOracle::new()).with_max_age(Duration::from_secs(1));
+        assert!(converter.get_conversion_rate("NEAR", "ETH").await.is_ok());
+    }
+
+    /// 問い合わせ回数を数えるオラクル
+    struct CountingOracle {
+        inner: MockPriceOracle,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PriceOracle for CountingOracle {
+        async fn get_price(&self, token_symbol: &str) -> Result<PriceData> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.get_price(token_symbol).await
+        }
+
+        async fn get_prices(&self, token_symbols: &[&str]) -> Result<HashMap<String, PriceData>> {
+            self.inner.get_prices(token_symbols).await
+        }
+
+        async fn supported_tokens(&self) -> Result<Vec<String>> {
+            self.inner.supported_tokens().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixed_rate_overrides_oracle() {
+        let oracle = CountingOracle {
+            inner: MockPriceOracle::new(),
+            calls: Default::default(),
+        };
+        let converter = PriceConverter::new(oracle).with_fixed_rate("NEAR", "ETH", 0.01);
+
+        let rate = converter.get_conversion_rate("NEAR", "ETH").await.unwrap();
+        assert_eq!(rate, 0.01);
+        assert_eq!(
+            converter
+                .oracle
+                .calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            0
+        );
+
+        // 1 NEAR -> 0.01 ETH
+        let eth_amount = converter
+            .convert_amount(1_000_000_000_000_000_000_000_000, "NEAR", 24, "ETH", 18)
+            .await
+            .unwrap();
+        assert_eq!(eth_amount, 10_000_000_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_non_fixed_pair_falls_through_to_oracle() {
+        let oracle = CountingOracle {
+            inner: MockPriceOracle::new(),
+            calls: Default::default(),
+        };
+        let converter = PriceConverter::new(oracle).with_fixed_rate("NEAR", "ETH", 0.01);
+
+        // 逆方向は固定されていない
+        let rate = converter.get_conversion_rate("ETH", "NEAR").await.unwrap();
+        assert_eq!(rate, 2000.0 / 5.0);
+        assert_eq!(
+            converter
+                .oracle
+                .calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_usd_price_prefers_fixed_rate() {
+        let converter = PriceConverter::new(MockPriceOracle::new());
+        assert_eq!(converter.usd_price("ETH").await.unwrap(), 2000.0);
+
+        let converter = converter.with_fixed_rate("ETH", "USD", 2500.0);
+        assert_eq!(converter.usd_price("ETH").await.unwrap(), 2500.0);
+        assert_eq!(converter.usd_price("NEAR").await.unwrap(), 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_within_bounds_is_used() {
+        let mut oracle = MockPriceOracle::new();
+        // ETH/NEAR = 418、基準400から4.5%
+        oracle.set_price("ETH", 2090.0);
+        let converter = PriceConverter::new(oracle).with_rate_bounds("ETH", "NEAR", 400.0, 500);
+
+        let rate = converter.get_conversion_rate("ETH", "NEAR").await.unwrap();
+        assert!((rate - 418.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_rate_out_of_bounds_is_rejected() {
+        let mut oracle = MockPriceOracle::new();
+        // 異常値: ETH/NEAR = 4000
+        oracle.set_price("ETH", 20_000.0);
+        let converter = PriceConverter::new(oracle).with_rate_bounds("ETH", "NEAR", 400.0, 500);
+
+        let err = converter
+            .convert_amount(1_000_000_000_000_000_000, "ETH", 18, "NEAR", 24)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PriceError>(),
+            Some(&PriceError::OutOfBounds {
+                from_token: "ETH".to_string(),
+                to_token: "NEAR".to_string(),
+                rate: 4000.0,
+                reference_rate: 400.0,
+                max_deviation_bps: 500,
+            })
+        );
+
+        // 逆方向のペアは制限されない
+        assert!(converter.get_conversion_rate("NEAR", "ETH").await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_batch_price_fetch() {
         let oracle = MockPriceOracle::new();
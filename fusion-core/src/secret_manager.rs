@@ -1,21 +1,80 @@
 use crate::chains::near_events::NearHtlcClaimEvent;
-use std::collections::HashMap;
+use crate::htlc::{generate_secret, hash_secret, Secret, SecretHash};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::XChaCha20Poly1305;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Sha512};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
+/// HKDFのsalt/infoに使うドメイン分離用ラベル
+const SECRET_DERIVATION_LABEL: &[u8] = b"fusion-swap-secret";
+
+/// 暗号化して保存したファイルの先頭に付けるマジックバイト（形式のバージョンを含む）
+const ENCRYPTED_STORE_MAGIC: &[u8; 4] = b"FSS2";
+const ENCRYPTED_STORE_NONCE_LEN: usize = 24;
+const ENCRYPTED_STORE_TAG_LEN: usize = 16;
+
+/// スワップのシークレットを保持する既定の期間
+pub const DEFAULT_SECRET_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// シークレットを保持できる最長の期間
+pub const MAX_SECRET_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
 #[derive(Error, Debug)]
 pub enum SecretError {
     #[error("Secret not found")]
     SecretNotFound,
     #[error("Invalid secret format")]
     InvalidSecretFormat,
-    #[error("Secret already exists")]
-    SecretAlreadyExists,
+    #[error("Secret already exists: {0}")]
+    SecretAlreadyExists(String),
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("Hashlock already used by active swap {0}")]
+    HashlockInUse(String),
+    #[error("Invalid secret TTL: {0:?} (must be non-zero and at most {max:?})", max = MAX_SECRET_TTL)]
+    InvalidTtl(Duration),
+    #[error("Secret storage error: {0}")]
+    Storage(String),
+    #[error("Failed to decrypt secret storage (wrong key or tampered file)")]
+    DecryptionFailed,
+}
+
+/// 暗号化して保存するスワップのシークレット
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretData {
+    pub swap_id: String,
+    pub secret: Secret,
+    pub expires_at: Option<u64>,
+    pub revealed: bool,
 }
 
 /// シークレット管理
-#[derive(Default)]
 pub struct SecretManager {
-    secrets: HashMap<String, String>, // escrow_id -> secret
+    secrets: HashMap<String, String>,             // escrow_id -> secret
+    swap_secrets: HashMap<String, Secret>,        // swap_id -> secret
+    secrets_by_hash: HashMap<SecretHash, String>, // hashlock -> swap_id
+    revealed_swaps: HashSet<String>,              // オンチェーンでシークレットが公開されたswap_id
+    expires_at: HashMap<String, u64>,             // swap_id -> 破棄するUNIX時刻（秒）
+    default_ttl: Duration,
+}
+
+impl Default for SecretManager {
+    fn default() -> Self {
+        Self {
+            secrets: HashMap::new(),
+            swap_secrets: HashMap::new(),
+            secrets_by_hash: HashMap::new(),
+            revealed_swaps: HashSet::new(),
+            expires_at: HashMap::new(),
+            default_ttl: DEFAULT_SECRET_TTL,
+        }
+    }
 }
 
 impl SecretManager {
@@ -35,7 +94,7 @@ impl SecretManager {
 
         // 既存のシークレットがある場合はエラー（重複防止）
         if self.secrets.contains_key(&event.escrow_id) {
-            return Err(SecretError::SecretAlreadyExists);
+            return Err(SecretError::SecretAlreadyExists(event.escrow_id.clone()));
         }
 
         self.secrets
@@ -51,13 +110,351 @@ impl SecretManager {
             .ok_or(SecretError::SecretNotFound)
     }
 
+    /// スワップ用の新しいシークレットを生成して登録（既定の保持期間を使用）
+    pub fn generate_secret(&mut self, swap_id: &str) -> Result<SecretHash, SecretError> {
+        self.import_secret(swap_id, generate_secret())
+    }
+
+    /// 複数のスワップ用のシークレットをまとめて生成して登録
+    ///
+    /// 1つでも登録済み（または重複した）スワップIDがあれば何も登録せず、
+    /// そのIDを含む `SecretAlreadyExists` を返します。
+    pub fn generate_secrets(&mut self, swap_ids: &[&str]) -> Result<Vec<SecretHash>, SecretError> {
+        let mut seen = HashSet::with_capacity(swap_ids.len());
+        for swap_id in swap_ids {
+            if self.swap_secrets.contains_key(*swap_id) || !seen.insert(*swap_id) {
+                return Err(SecretError::SecretAlreadyExists(swap_id.to_string()));
+            }
+        }
+
+        let secrets: Vec<Secret> = swap_ids.iter().map(|_| generate_secret()).collect();
+        let mut hashes = HashSet::with_capacity(secrets.len());
+        for (swap_id, secret) in swap_ids.iter().zip(&secrets) {
+            let secret_hash = hash_secret(secret);
+            if let Some(owner) = self.secrets_by_hash.get(&secret_hash) {
+                return Err(SecretError::HashlockInUse(owner.clone()));
+            }
+            if !hashes.insert(secret_hash) {
+                return Err(SecretError::HashlockInUse(swap_id.to_string()));
+            }
+        }
+
+        // 検証が済んでから登録するので途中で失敗することはない
+        swap_ids
+            .iter()
+            .zip(secrets)
+            .map(|(swap_id, secret)| self.insert_secret(swap_id, secret, self.default_ttl))
+            .collect()
+    }
+
+    /// 保持期間を指定してスワップ用の新しいシークレットを生成して登録
+    ///
+    /// タイムアウトの短いスワップは早めに、長いスワップは長めに破棄したい場合に使います。
+    pub fn generate_secret_with_ttl(
+        &mut self,
+        swap_id: &str,
+        ttl: Duration,
+    ) -> Result<SecretHash, SecretError> {
+        if ttl.is_zero() || ttl > MAX_SECRET_TTL {
+            return Err(SecretError::InvalidTtl(ttl));
+        }
+        self.insert_secret(swap_id, generate_secret(), ttl)
+    }
+
+    /// 外部で用意したシークレットをスワップに登録
+    ///
+    /// 同じハッシュロックを別のスワップと共有すると、片方で公開した
+    /// シークレットでもう片方もクレームされてしまうため拒否します。
+    pub fn import_secret(
+        &mut self,
+        swap_id: &str,
+        secret: Secret,
+    ) -> Result<SecretHash, SecretError> {
+        self.insert_secret(swap_id, secret, self.default_ttl)
+    }
+
+    fn insert_secret(
+        &mut self,
+        swap_id: &str,
+        secret: Secret,
+        ttl: Duration,
+    ) -> Result<SecretHash, SecretError> {
+        let secret_hash = hash_secret(&secret);
+        if let Some(owner) = self.secrets_by_hash.get(&secret_hash) {
+            if owner != swap_id {
+                return Err(SecretError::HashlockInUse(owner.clone()));
+            }
+        }
+        if self.swap_secrets.contains_key(swap_id) {
+            return Err(SecretError::SecretAlreadyExists(swap_id.to_string()));
+        }
+
+        self.swap_secrets.insert(swap_id.to_string(), secret);
+        self.secrets_by_hash
+            .insert(secret_hash, swap_id.to_string());
+        self.expires_at.insert(
+            swap_id.to_string(),
+            unix_now().saturating_add(ttl.as_secs()),
+        );
+        Ok(secret_hash)
+    }
+
+    /// スワップのシークレットを破棄するUNIX時刻（秒）
+    pub fn expires_at(&self, swap_id: &str) -> Option<u64> {
+        self.expires_at.get(swap_id).copied()
+    }
+
+    /// 保持期間を過ぎたシークレットを破棄し、破棄したスワップIDを返す
+    pub fn prune_expired(&mut self, now: u64) -> Vec<String> {
+        let mut expired: Vec<String> = self
+            .expires_at
+            .iter()
+            .filter(|(_, expires_at)| **expires_at <= now)
+            .map(|(swap_id, _)| swap_id.clone())
+            .collect();
+        expired.sort();
+        for swap_id in &expired {
+            self.release_swap(swap_id);
+        }
+        expired
+    }
+
+    /// スワップIDからシークレットを取得
+    pub fn swap_secret(&self, swap_id: &str) -> Result<Secret, SecretError> {
+        self.swap_secrets
+            .get(swap_id)
+            .copied()
+            .ok_or(SecretError::SecretNotFound)
+    }
+
+    /// ハッシュロックを使っている進行中のスワップ
+    pub fn swap_for_hashlock(&self, secret_hash: &SecretHash) -> Option<&str> {
+        self.secrets_by_hash.get(secret_hash).map(String::as_str)
+    }
+
+    /// オンチェーンで公開されたシークレットを記録し、該当するスワップIDを返す
+    ///
+    /// 管理しているハッシュロックに一致しなければNoneを返します。
+    pub fn mark_secret_revealed(&mut self, secret: &Secret) -> Option<String> {
+        let swap_id = self.secrets_by_hash.get(&hash_secret(secret))?.clone();
+        self.revealed_swaps.insert(swap_id.clone());
+        Some(swap_id)
+    }
+
+    /// スワップのシークレットがオンチェーンで公開済みか
+    pub fn is_secret_revealed(&self, swap_id: &str) -> bool {
+        self.revealed_swaps.contains(swap_id)
+    }
+
+    /// スワップが終了したらシークレットを解放
+    pub fn release_swap(&mut self, swap_id: &str) -> Option<Secret> {
+        self.revealed_swaps.remove(swap_id);
+        self.expires_at.remove(swap_id);
+        let secret = self.swap_secrets.remove(swap_id)?;
+        self.secrets_by_hash.remove(&hash_secret(&secret));
+        Some(secret)
+    }
+
+    /// ハッシュロックの索引がスワップのシークレットと一致しているか確認
+    pub fn verify_index(&self) -> bool {
+        self.secrets_by_hash.len() == self.swap_secrets.len()
+            && self.swap_secrets.iter().all(|(swap_id, secret)| {
+                self.secrets_by_hash.get(&hash_secret(secret)) == Some(swap_id)
+            })
+    }
+
+    /// スワップのシークレットからハッシュを計算し直して索引を作り直す
+    ///
+    /// 万一同じハッシュロックのスワップが複数あれば、IDが小さい方を索引に残します。
+    pub fn rebuild_index(&mut self) {
+        let mut swap_ids: Vec<&String> = self.swap_secrets.keys().collect();
+        swap_ids.sort();
+
+        let mut index = HashMap::with_capacity(swap_ids.len());
+        for swap_id in swap_ids {
+            index
+                .entry(hash_secret(&self.swap_secrets[swap_id]))
+                .or_insert_with(|| swap_id.clone());
+        }
+        self.secrets_by_hash = index;
+    }
+
+    /// BIP39ニーモニックから64バイトのシードを計算
+    ///
+    /// PBKDF2-HMAC-SHA512（2048回）で計算します。単語リストとチェックサムは
+    /// 検証しないため、ウォレットで生成したニーモニックを使ってください。
+    pub fn seed_from_mnemonic(mnemonic: &str, passphrase: &str) -> Result<[u8; 64], SecretError> {
+        let words: Vec<&str> = mnemonic.split_whitespace().collect();
+        if ![12, 15, 18, 21, 24].contains(&words.len()) {
+            return Err(SecretError::InvalidMnemonic(format!(
+                "expected 12, 15, 18, 21 or 24 words, got {}",
+                words.len()
+            )));
+        }
+        // NFKD正規化を省略するためASCIIのみ受け付ける
+        if !mnemonic.is_ascii() || !passphrase.is_ascii() {
+            return Err(SecretError::InvalidMnemonic(
+                "only ASCII mnemonics and passphrases are supported".to_string(),
+            ));
+        }
+
+        let normalized = words.join(" ");
+        let salt = format!("mnemonic{}", passphrase);
+        let mut seed = [0u8; 64];
+        pbkdf2::pbkdf2_hmac::<Sha512>(normalized.as_bytes(), salt.as_bytes(), 2048, &mut seed);
+        Ok(seed)
+    }
+
+    /// シードとスワップ番号からシークレットを決定的に導出（オプトイン）
+    ///
+    /// HKDF-SHA256でシードから番号ごとに独立したシークレットを作るため、
+    /// ストレージを失ってもシードだけで復元できます。その代わりシードが
+    /// 漏れると全スワップのシークレットが漏れる点に注意してください。
+    pub fn derive_secret(seed: &[u8], index: u32) -> Secret {
+        // Extract
+        let mut extract = <Hmac<Sha256> as Mac>::new_from_slice(SECRET_DERIVATION_LABEL)
+            .expect("HMAC accepts keys of any length");
+        extract.update(seed);
+        let prk = extract.finalize().into_bytes();
+
+        // Expand（出力は32バイトなので1ブロックで足りる）
+        let mut expand =
+            <Hmac<Sha256> as Mac>::new_from_slice(&prk).expect("HMAC accepts keys of any length");
+        expand.update(SECRET_DERIVATION_LABEL);
+        expand.update(&index.to_be_bytes());
+        expand.update(&[1u8]);
+        expand.finalize().into_bytes().into()
+    }
+
+    /// スワップのシークレットを呼び出し側の32バイト鍵で暗号化して保存
+    ///
+    /// XChaCha20-Poly1305で暗号化し、所有者のみ読み書きできる一時ファイルに書いてから
+    /// リネームするため、書き込み途中で失敗しても既存のファイルは壊れません。
+    pub fn persist_encrypted(
+        &self,
+        path: impl AsRef<Path>,
+        key: &[u8; 32],
+    ) -> Result<(), SecretError> {
+        let mut swap_ids: Vec<&String> = self.swap_secrets.keys().collect();
+        swap_ids.sort();
+        let entries: Vec<SecretData> = swap_ids
+            .into_iter()
+            .map(|swap_id| SecretData {
+                swap_id: swap_id.clone(),
+                secret: self.swap_secrets[swap_id],
+                expires_at: self.expires_at.get(swap_id).copied(),
+                revealed: self.revealed_swaps.contains(swap_id),
+            })
+            .collect();
+        let plaintext =
+            serde_json::to_vec(&entries).map_err(|e| SecretError::Storage(e.to_string()))?;
+        write_private_atomically(path.as_ref(), &seal(key, &plaintext)?)
+            .map_err(|e| SecretError::Storage(e.to_string()))
+    }
+
+    /// `persist_encrypted` で保存したシークレットを読み込み、スワップのシークレットを置き換える
+    ///
+    /// 鍵が違う・ファイルが改ざんされている場合は認証タグの検証で失敗します。
+    pub fn load_encrypted(
+        &mut self,
+        path: impl AsRef<Path>,
+        key: &[u8; 32],
+    ) -> Result<(), SecretError> {
+        let data = std::fs::read(path).map_err(|e| SecretError::Storage(e.to_string()))?;
+        let plaintext = open(key, &data)?;
+        let entries: Vec<SecretData> =
+            serde_json::from_slice(&plaintext).map_err(|e| SecretError::Storage(e.to_string()))?;
+
+        self.swap_secrets.clear();
+        self.expires_at.clear();
+        self.revealed_swaps.clear();
+        for entry in entries {
+            if let Some(expires_at) = entry.expires_at {
+                self.expires_at.insert(entry.swap_id.clone(), expires_at);
+            }
+            if entry.revealed {
+                self.revealed_swaps.insert(entry.swap_id.clone());
+            }
+            self.swap_secrets.insert(entry.swap_id, entry.secret);
+        }
+        self.rebuild_index();
+        Ok(())
+    }
+
     /// すべてのシークレットをクリア（テスト用）
     #[cfg(test)]
     pub fn clear(&mut self) {
         self.secrets.clear();
+        self.swap_secrets.clear();
+        self.secrets_by_hash.clear();
+        self.revealed_swaps.clear();
+        self.expires_at.clear();
     }
 }
 
+/// 同じディレクトリの一時ファイル（Unixでは0600）に書き込み、リネームで置き換える
+fn write_private_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(data)?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// magic || nonce || ciphertext || tag（magicは追加認証データとして認証される）
+fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, SecretError> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: ENCRYPTED_STORE_MAGIC,
+            },
+        )
+        .map_err(|_| SecretError::Storage("failed to encrypt secret store".to_string()))?;
+
+    let mut sealed =
+        Vec::with_capacity(ENCRYPTED_STORE_MAGIC.len() + nonce.len() + ciphertext.len());
+    sealed.extend_from_slice(ENCRYPTED_STORE_MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, SecretError> {
+    let header_len = ENCRYPTED_STORE_MAGIC.len() + ENCRYPTED_STORE_NONCE_LEN;
+    if sealed.len() < header_len + ENCRYPTED_STORE_TAG_LEN
+        || &sealed[..ENCRYPTED_STORE_MAGIC.len()] != ENCRYPTED_STORE_MAGIC
+    {
+        return Err(SecretError::Storage(
+            "not an encrypted secret store".to_string(),
+        ));
+    }
+    let nonce = &sealed[ENCRYPTED_STORE_MAGIC.len()..header_len];
+    // 鍵違い・改ざんはいずれもPoly1305タグの検証で失敗する
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            nonce.into(),
+            Payload {
+                msg: &sealed[header_len..],
+                aad: ENCRYPTED_STORE_MAGIC,
+            },
+        )
+        .map_err(|_| SecretError::DecryptionFailed)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 /// クロスチェーン実行リクエスト
 #[derive(Debug, Clone)]
 pub struct CrossChainClaimRequest {
@@ -162,7 +559,7 @@ mod tests {
         assert!(result.is_err());
 
         match result {
-            Err(SecretError::SecretAlreadyExists) => {}
+            Err(SecretError::SecretAlreadyExists(escrow_id)) => assert_eq!(escrow_id, "fusion_0"),
             _ => panic!("Expected SecretAlreadyExists error"),
         }
     }
@@ -180,6 +577,243 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_reject_hashlock_reused_by_another_swap() {
+        let mut secret_manager = SecretManager::new();
+        let secret = [7u8; 32];
+
+        let secret_hash = secret_manager.import_secret("swap_a", secret).unwrap();
+        assert_eq!(
+            secret_manager.swap_for_hashlock(&secret_hash),
+            Some("swap_a")
+        );
+
+        match secret_manager.import_secret("swap_b", secret) {
+            Err(SecretError::HashlockInUse(owner)) => assert_eq!(owner, "swap_a"),
+            other => panic!("Expected HashlockInUse error, got {:?}", other),
+        }
+
+        // 最初のスワップが終われば再登録できる
+        assert_eq!(secret_manager.release_swap("swap_a"), Some(secret));
+        assert!(secret_manager.import_secret("swap_b", secret).is_ok());
+    }
+
+    #[test]
+    fn should_rebuild_corrupted_hash_index() {
+        let mut secret_manager = SecretManager::new();
+        let hash_a = secret_manager.import_secret("swap_a", [1u8; 32]).unwrap();
+        let hash_b = secret_manager.import_secret("swap_b", [2u8; 32]).unwrap();
+        assert!(secret_manager.verify_index());
+
+        // 片方の索引が消え、もう片方は別のスワップを指している
+        secret_manager.secrets_by_hash.remove(&hash_a);
+        secret_manager
+            .secrets_by_hash
+            .insert(hash_b, "swap_stale".to_string());
+        assert!(!secret_manager.verify_index());
+        assert_eq!(secret_manager.swap_for_hashlock(&hash_a), None);
+
+        secret_manager.rebuild_index();
+        assert!(secret_manager.verify_index());
+        assert_eq!(secret_manager.swap_for_hashlock(&hash_a), Some("swap_a"));
+        assert_eq!(secret_manager.swap_for_hashlock(&hash_b), Some("swap_b"));
+    }
+
+    #[test]
+    fn should_generate_secrets_in_batch() {
+        let mut secret_manager = SecretManager::new();
+        let hashes = secret_manager
+            .generate_secrets(&["leg_a", "leg_b", "leg_c"])
+            .unwrap();
+        assert_eq!(hashes.len(), 3);
+        for (swap_id, hash) in ["leg_a", "leg_b", "leg_c"].iter().zip(&hashes) {
+            assert_eq!(secret_manager.swap_for_hashlock(hash), Some(*swap_id));
+            assert_eq!(
+                hash_secret(&secret_manager.swap_secret(swap_id).unwrap()),
+                *hash
+            );
+        }
+        assert!(secret_manager.verify_index());
+    }
+
+    #[test]
+    fn should_roll_back_batch_on_conflict() {
+        let mut secret_manager = SecretManager::new();
+        let existing = secret_manager.generate_secret("leg_b").unwrap();
+
+        match secret_manager.generate_secrets(&["leg_a", "leg_b", "leg_c"]) {
+            Err(SecretError::SecretAlreadyExists(swap_id)) => assert_eq!(swap_id, "leg_b"),
+            other => panic!("Expected SecretAlreadyExists, got {:?}", other),
+        }
+        // 同じバッチ内の重複も拒否
+        assert!(matches!(
+            secret_manager.generate_secrets(&["leg_d", "leg_d"]),
+            Err(SecretError::SecretAlreadyExists(swap_id)) if swap_id == "leg_d"
+        ));
+
+        // 何も登録されず、既存のスワップはそのまま
+        for swap_id in ["leg_a", "leg_c", "leg_d"] {
+            assert!(secret_manager.swap_secret(swap_id).is_err());
+            assert!(secret_manager.expires_at(swap_id).is_none());
+        }
+        assert_eq!(secret_manager.swap_for_hashlock(&existing), Some("leg_b"));
+        assert!(secret_manager.verify_index());
+    }
+
+    #[test]
+    fn should_expire_secrets_on_their_own_ttl() {
+        let mut secret_manager = SecretManager::new();
+        secret_manager
+            .generate_secret_with_ttl("swap_short", Duration::from_secs(60))
+            .unwrap();
+        secret_manager
+            .generate_secret_with_ttl("swap_long", Duration::from_secs(3600))
+            .unwrap();
+        secret_manager.generate_secret("swap_default").unwrap();
+
+        let short = secret_manager.expires_at("swap_short").unwrap();
+        let long = secret_manager.expires_at("swap_long").unwrap();
+        let default = secret_manager.expires_at("swap_default").unwrap();
+        // 生成の間に秒が切り替わることがあるため1秒の誤差を許容
+        assert!((long - short).abs_diff(3600 - 60) <= 1);
+        assert!(default >= long + DEFAULT_SECRET_TTL.as_secs() - 3600);
+
+        // 短い方だけが先に破棄される
+        assert!(secret_manager.prune_expired(short - 1).is_empty());
+        assert_eq!(secret_manager.prune_expired(short), vec!["swap_short"]);
+        assert!(secret_manager.swap_secret("swap_short").is_err());
+        assert!(secret_manager.swap_secret("swap_long").is_ok());
+
+        assert_eq!(secret_manager.prune_expired(long), vec!["swap_long"]);
+        assert!(secret_manager.swap_secret("swap_default").is_ok());
+        assert!(secret_manager.verify_index());
+    }
+
+    #[test]
+    fn should_reject_invalid_ttl() {
+        let mut secret_manager = SecretManager::new();
+        for ttl in [Duration::ZERO, MAX_SECRET_TTL + Duration::from_secs(1)] {
+            assert!(matches!(
+                secret_manager.generate_secret_with_ttl("swap", ttl),
+                Err(SecretError::InvalidTtl(_))
+            ));
+        }
+        assert!(secret_manager
+            .generate_secret_with_ttl("swap", MAX_SECRET_TTL)
+            .is_ok());
+    }
+
+    fn temp_store_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "fusion_secret_store_{}_{}.bin",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn should_round_trip_encrypted_secrets() {
+        let path = temp_store_path("round_trip");
+        let key = [0x42u8; 32];
+
+        let mut secret_manager = SecretManager::new();
+        let hash_a = secret_manager.import_secret("swap_a", [1u8; 32]).unwrap();
+        secret_manager
+            .generate_secret_with_ttl("swap_b", Duration::from_secs(600))
+            .unwrap();
+        secret_manager.mark_secret_revealed(&[1u8; 32]);
+        secret_manager.persist_encrypted(&path, &key).unwrap();
+
+        // 平文のシークレットはファイルに現れない
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(32).any(|w| w == [1u8; 32]));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let mut restored = SecretManager::new();
+        restored.load_encrypted(&path, &key).unwrap();
+        assert_eq!(restored.swap_secret("swap_a").unwrap(), [1u8; 32]);
+        assert_eq!(
+            restored.swap_secret("swap_b").unwrap(),
+            secret_manager.swap_secret("swap_b").unwrap()
+        );
+        assert_eq!(restored.swap_for_hashlock(&hash_a), Some("swap_a"));
+        assert_eq!(
+            restored.expires_at("swap_b"),
+            secret_manager.expires_at("swap_b")
+        );
+        assert!(restored.is_secret_revealed("swap_a"));
+        assert!(!restored.is_secret_revealed("swap_b"));
+        assert!(restored.verify_index());
+
+        // 鍵が違えば認証タグの検証で失敗する
+        assert!(matches!(
+            SecretManager::new().load_encrypted(&path, &[0x43u8; 32]),
+            Err(SecretError::DecryptionFailed)
+        ));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn should_reject_tampered_ciphertext() {
+        let path = temp_store_path("tampered");
+        let key = [7u8; 32];
+
+        let mut secret_manager = SecretManager::new();
+        secret_manager.import_secret("swap", [9u8; 32]).unwrap();
+        secret_manager.persist_encrypted(&path, &key).unwrap();
+
+        let mut data = std::fs::read(&path).unwrap();
+        let middle = data.len() / 2;
+        data[middle] ^= 0x01;
+        std::fs::write(&path, &data).unwrap();
+
+        let mut restored = SecretManager::new();
+        assert!(matches!(
+            restored.load_encrypted(&path, &key),
+            Err(SecretError::DecryptionFailed)
+        ));
+        assert!(restored.swap_secret("swap").is_err());
+        std::fs::remove_file(&path).ok();
+    }
+
+    const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn should_compute_bip39_seed() {
+        // BIP39公式テストベクター
+        let seed = SecretManager::seed_from_mnemonic(TEST_MNEMONIC, "TREZOR").unwrap();
+        assert_eq!(
+            hex::encode(seed),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+
+        assert!(matches!(
+            SecretManager::seed_from_mnemonic("abandon about", ""),
+            Err(SecretError::InvalidMnemonic(_))
+        ));
+    }
+
+    #[test]
+    fn should_derive_reproducible_secrets_per_index() {
+        let seed = SecretManager::seed_from_mnemonic(TEST_MNEMONIC, "").unwrap();
+
+        let first = SecretManager::derive_secret(&seed, 0);
+        assert_eq!(first, SecretManager::derive_secret(&seed, 0));
+
+        // 番号が違えば別のシークレット
+        let second = SecretManager::derive_secret(&seed, 1);
+        assert_ne!(first, second);
+
+        // シードが違っても別のシークレット
+        let other_seed = SecretManager::seed_from_mnemonic(TEST_MNEMONIC, "other").unwrap();
+        assert_ne!(first, SecretManager::derive_secret(&other_seed, 0));
+    }
+
     // Mockコネクター（テスト用）
     struct MockEthereumConnector;
 
@@ -0,0 +1,140 @@
+//! オーダーマッチングエンジンのHTTPサーバー
+//!
+//! `server` フィーチャーが有効な場合のみビルドされます。
+//!
+//! - `POST /orders` オーダーを登録
+//! - `GET /orderbook/{chain}` 指定チェーンのオーダーブック
+//! - `GET /matches` 現在マッチング可能なオーダーのペア
+
+use crate::order_matching_engine::{OrderMatch, OrderMatchingEngine, PendingOrder};
+use anyhow::Result;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::net::TcpListener;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// ハンドラー間で共有するエンジン
+pub type SharedEngine = Arc<RwLock<OrderMatchingEngine>>;
+
+/// エンドポイントを登録したルーターを作成
+pub fn router(engine: SharedEngine) -> Router {
+    Router::new()
+        .route("/orders", post(submit_order))
+        .route("/orderbook/:chain", get(order_book))
+        .route("/matches", get(matches))
+        .with_state(engine)
+}
+
+/// `listener` でリクエストを受け付け、`shutdown` が完了したら処理中のリクエストを終えて停止
+pub async fn serve<F>(listener: TcpListener, engine: SharedEngine, shutdown: F) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    listener.set_nonblocking(true)?;
+    axum::Server::from_tcp(listener)?
+        .serve(router(engine).into_make_service())
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+async fn submit_order(
+    State(engine): State<SharedEngine>,
+    Json(order): Json<PendingOrder>,
+) -> (StatusCode, Json<Value>) {
+    match engine.write().await.add_order(order.clone()) {
+        Ok(()) => (StatusCode::CREATED, Json(json!(order))),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+async fn order_book(State(engine): State<SharedEngine>, Path(chain): Path<String>) -> Json<Value> {
+    let (buy_orders, sell_orders) = engine.read().await.orders_on_chain(&chain);
+    Json(json!({
+        "chain": chain,
+        "buy_orders": buy_orders,
+        "sell_orders": sell_orders,
+    }))
+}
+
+async fn matches(State(engine): State<SharedEngine>) -> Json<Vec<OrderMatch>> {
+    Json(engine.read().await.find_all_matches())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_matching_engine::OrderType;
+
+    fn order(id: &str, chain_id: &str, order_type: OrderType, price: f64) -> PendingOrder {
+        PendingOrder {
+            id: id.to_string(),
+            chain_id: chain_id.to_string(),
+            token_pair: "NEAR/USDC".to_string(),
+            order_type,
+            price,
+            amount: 1000,
+            timestamp: 1234567890,
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crossing_orders_submitted_over_http_are_matched() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let engine = Arc::new(RwLock::new(OrderMatchingEngine::new(50)));
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, engine, async {
+            let _ = shutdown_rx.await;
+        }));
+
+        let client = reqwest::Client::new();
+        for order in [
+            order("buy1", "ethereum", OrderType::Buy, 5.1),
+            order("sell1", "near", OrderType::Sell, 5.0),
+        ] {
+            let response = client
+                .post(format!("{}/orders", base_url))
+                .json(&order)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+        }
+
+        let book: Value = client
+            .get(format!("{}/orderbook/near", base_url))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(book["buy_orders"].as_array().unwrap().len(), 0);
+        assert_eq!(book["sell_orders"][0]["id"], "sell1");
+
+        let matches: Vec<OrderMatch> = client
+            .get(format!("{}/matches", base_url))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].buy_order_id, "buy1");
+        assert_eq!(matches[0].sell_order_id, "sell1");
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+//! スワップのレイテンシ計測
+//!
+//! 各レッグの作成・シークレット公開・クレームの時刻を記録し、
+//! タイムロック調整のためのレイテンシを算出します。
+//! 記録はメモリ内のみで、外部への送信は行いません。
+//! コネクタやエグゼキュータに明示的に渡した場合のみ有効になります。
+
+use crate::htlc::SecretHash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+/// 現在時刻（UNIXエポックからのミリ秒）
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// スワップのレッグ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwapLeg {
+    Source,
+    Destination,
+}
+
+/// 1レッグ分のタイムライン（ミリ秒）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LegTimeline {
+    pub chain: String,
+    pub created_at: Option<u64>,
+    pub secret_revealed_at: Option<u64>,
+    pub claimed_at: Option<u64>,
+}
+
+impl LegTimeline {
+    fn new(chain: &str) -> Self {
+        Self {
+            chain: chain.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 作成からクレームまでの時間
+    pub fn claim_latency_ms(&self) -> Option<u64> {
+        Some(self.claimed_at?.saturating_sub(self.created_at?))
+    }
+
+    /// シークレット公開からクレームまでの時間
+    pub fn reveal_to_claim_ms(&self) -> Option<u64> {
+        Some(self.claimed_at?.saturating_sub(self.secret_revealed_at?))
+    }
+}
+
+/// スワップ全体のタイムライン
+///
+/// 先に作成されたレッグをソース、後のレッグをデスティネーションとして扱います。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SwapTimeline {
+    pub secret_hash: String,
+    pub source: Option<LegTimeline>,
+    pub destination: Option<LegTimeline>,
+}
+
+impl SwapTimeline {
+    /// ソースレッグ作成からデスティネーションのクレームまでの時間
+    pub fn end_to_end_latency_ms(&self) -> Option<u64> {
+        let created_at = self.source.as_ref()?.created_at?;
+        let claimed_at = self.destination.as_ref()?.claimed_at?;
+        Some(claimed_at.saturating_sub(created_at))
+    }
+
+    fn leg_mut(&mut self, chain: &str) -> Option<(SwapLeg, &mut LegTimeline)> {
+        if let Some(leg) = self.source.as_mut().filter(|l| l.chain == chain) {
+            return Some((SwapLeg::Source, leg));
+        }
+        self.destination
+            .as_mut()
+            .filter(|l| l.chain == chain)
+            .map(|leg| (SwapLeg::Destination, leg))
+    }
+}
+
+/// クレームごとに発行されるテレメトリイベント
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaimTelemetryEvent {
+    pub secret_hash: String,
+    pub leg: SwapLeg,
+    pub chain: String,
+    pub claimed_at: u64,
+    /// 同じレッグの作成からの時間
+    pub claim_latency_ms: Option<u64>,
+    /// シークレット公開からの時間
+    pub reveal_to_claim_ms: Option<u64>,
+    /// ソースレッグ作成からの時間
+    pub since_source_created_ms: Option<u64>,
+}
+
+/// 計測結果のスナップショット
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub swaps_tracked: usize,
+    pub claims_recorded: usize,
+    pub completed_swaps: usize,
+    pub avg_end_to_end_latency_ms: Option<u64>,
+    pub min_end_to_end_latency_ms: Option<u64>,
+    pub max_end_to_end_latency_ms: Option<u64>,
+}
+
+/// スワップのレイテンシを記録するレコーダー
+#[derive(Debug, Default)]
+pub struct SwapTelemetry {
+    timelines: RwLock<HashMap<SecretHash, SwapTimeline>>,
+    events: RwLock<Vec<ClaimTelemetryEvent>>,
+}
+
+impl SwapTelemetry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// レッグの作成を記録
+    ///
+    /// 最初に記録されたチェーンがソース、次のチェーンがデスティネーションになります。
+    pub async fn record_leg_created(&self, secret_hash: &SecretHash, chain: &str, at_ms: u64) {
+        let mut timelines = self.timelines.write().await;
+        let timeline = timelines
+            .entry(*secret_hash)
+            .or_insert_with(|| SwapTimeline {
+                secret_hash: hex::encode(secret_hash),
+                ..Default::default()
+            });
+
+        if let Some((_, leg)) = timeline.leg_mut(chain) {
+            leg.created_at.get_or_insert(at_ms);
+            return;
+        }
+
+        let mut leg = LegTimeline::new(chain);
+        leg.created_at = Some(at_ms);
+        if timeline.source.is_none() {
+            timeline.source = Some(leg);
+        } else if timeline.destination.is_none() {
+            timeline.destination = Some(leg);
+        }
+    }
+
+    /// シークレットの公開を記録
+    ///
+    /// 公開後は両レッグともクレーム可能になるため、両方に同じ時刻を記録します。
+    pub async fn record_secret_revealed(&self, secret_hash: &SecretHash, at_ms: u64) {
+        let mut timelines = self.timelines.write().await;
+        if let Some(timeline) = timelines.get_mut(secret_hash) {
+            for leg in [timeline.source.as_mut(), timeline.destination.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                leg.secret_revealed_at.get_or_insert(at_ms);
+            }
+        }
+    }
+
+    /// クレームを記録し、テレメトリイベントを返す
+    ///
+    /// 作成が記録されていないチェーンのクレームは無視されます。
+    pub async fn record_claimed(
+        &self,
+        secret_hash: &SecretHash,
+        chain: &str,
+        at_ms: u64,
+    ) -> Option<ClaimTelemetryEvent> {
+        let mut timelines = self.timelines.write().await;
+        let timeline = timelines.get_mut(secret_hash)?;
+        let source_created_at = timeline.source.as_ref().and_then(|l| l.created_at);
+
+        let (leg_kind, leg) = timeline.leg_mut(chain)?;
+        leg.claimed_at = Some(at_ms);
+
+        let event = ClaimTelemetryEvent {
+            secret_hash: hex::encode(secret_hash),
+            leg: leg_kind,
+            chain: chain.to_string(),
+            claimed_at: at_ms,
+            claim_latency_ms: leg.claim_latency_ms(),
+            reveal_to_claim_ms: leg.reveal_to_claim_ms(),
+            since_source_created_ms: source_created_at.map(|c| at_ms.saturating_sub(c)),
+        };
+        drop(timelines);
+
+        self.events.write().await.push(event.clone());
+        Some(event)
+    }
+
+    /// 記録済みのクレームイベント
+    pub async fn events(&self) -> Vec<ClaimTelemetryEvent> {
+        self.events.read().await.clone()
+    }
+
+    /// 指定スワップのタイムラインをエクスポート
+    pub async fn export_swap(&self, secret_hash: &SecretHash) -> Option<SwapTimeline> {
+        self.timelines.read().await.get(secret_hash).cloned()
+    }
+
+    /// すべてのタイムラインをエクスポート（シークレットハッシュ順）
+    pub async fn export_swaps(&self) -> Vec<SwapTimeline> {
+        let mut swaps: Vec<SwapTimeline> = self.timelines.read().await.values().cloned().collect();
+        swaps.sort_by(|a, b| a.secret_hash.cmp(&b.secret_hash));
+        swaps
+    }
+
+    /// 現在のメトリクスを集計
+    pub async fn snapshot(&self) -> MetricsSnapshot {
+        let timelines = self.timelines.read().await;
+        let latencies: Vec<u64> = timelines
+            .values()
+            .filter_map(SwapTimeline::end_to_end_latency_ms)
+            .collect();
+
+        MetricsSnapshot {
+            swaps_tracked: timelines.len(),
+            claims_recorded: self.events.read().await.len(),
+            completed_swaps: latencies.len(),
+            avg_end_to_end_latency_ms: (!latencies.is_empty())
+                .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+            min_end_to_end_latency_ms: latencies.iter().min().copied(),
+            max_end_to_end_latency_ms: latencies.iter().max().copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_latencies_follow_simulated_timeline() {
+        let telemetry = SwapTelemetry::new();
+        let secret_hash = [7u8; 32];
+
+        // t=0 ソース作成, t=30s デスティネーション作成, t=90s 公開,
+        // t=95s ソースのクレーム, t=120s デスティネーションのクレーム
+        telemetry
+            .record_leg_created(&secret_hash, "ethereum", 1_000)
+            .await;
+        telemetry
+            .record_leg_created(&secret_hash, "near", 31_000)
+            .await;
+        telemetry.record_secret_revealed(&secret_hash, 91_000).await;
+
+        let source_claim = telemetry
+            .record_claimed(&secret_hash, "ethereum", 96_000)
+            .await
+            .unwrap();
+        assert_eq!(source_claim.leg, SwapLeg::Source);
+        assert_eq!(source_claim.claim_latency_ms, Some(95_000));
+        assert_eq!(source_claim.reveal_to_claim_ms, Some(5_000));
+
+        let destination_claim = telemetry
+            .record_claimed(&secret_hash, "near", 121_000)
+            .await
+            .unwrap();
+        assert_eq!(destination_claim.leg, SwapLeg::Destination);
+        assert_eq!(destination_claim.claim_latency_ms, Some(90_000));
+        assert_eq!(destination_claim.reveal_to_claim_ms, Some(30_000));
+        assert_eq!(destination_claim.since_source_created_ms, Some(120_000));
+
+        let timeline = telemetry.export_swap(&secret_hash).await.unwrap();
+        assert_eq!(timeline.secret_hash, hex::encode(secret_hash));
+        assert_eq!(timeline.end_to_end_latency_ms(), Some(120_000));
+        let destination = timeline.destination.unwrap();
+        assert_eq!(destination.chain, "near");
+        assert_eq!(destination.created_at, Some(31_000));
+        assert_eq!(destination.secret_revealed_at, Some(91_000));
+        assert_eq!(destination.claimed_at, Some(121_000));
+
+        let snapshot = telemetry.snapshot().await;
+        assert_eq!(snapshot.swaps_tracked, 1);
+        assert_eq!(snapshot.claims_recorded, 2);
+        assert_eq!(snapshot.completed_swaps, 1);
+        assert_eq!(snapshot.avg_end_to_end_latency_ms, Some(120_000));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_aggregates_completed_swaps() {
+        let telemetry = SwapTelemetry::new();
+
+        for (i, latency) in [60_000u64, 180_000].iter().enumerate() {
+            let secret_hash = [i as u8; 32];
+            telemetry.record_leg_created(&secret_hash, "near", 0).await;
+            telemetry
+                .record_leg_created(&secret_hash, "ethereum", 10)
+                .await;
+            telemetry
+                .record_claimed(&secret_hash, "ethereum", *latency)
+                .await;
+        }
+
+        // クレーム前のスワップは完了数に含めない
+        telemetry.record_leg_created(&[9u8; 32], "near", 0).await;
+
+        let snapshot = telemetry.snapshot().await;
+        assert_eq!(snapshot.swaps_tracked, 3);
+        assert_eq!(snapshot.completed_swaps, 2);
+        assert_eq!(snapshot.avg_end_to_end_latency_ms, Some(120_000));
+        assert_eq!(snapshot.min_end_to_end_latency_ms, Some(60_000));
+        assert_eq!(snapshot.max_end_to_end_latency_ms, Some(180_000));
+        assert_eq!(telemetry.export_swaps().await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_claim_on_unknown_chain_is_ignored() {
+        let telemetry = SwapTelemetry::new();
+        let secret_hash = [1u8; 32];
+
+        assert!(telemetry
+            .record_claimed(&secret_hash, "near", 100)
+            .await
+            .is_none());
+
+        telemetry
+            .record_leg_created(&secret_hash, "ethereum", 0)
+            .await;
+        assert!(telemetry
+            .record_claimed(&secret_hash, "near", 100)
+            .await
+            .is_none());
+        assert!(telemetry.events().await.is_empty());
+    }
+}
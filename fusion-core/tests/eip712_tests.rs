@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod eip712_tests {
-    use fusion_core::eip712::{EIP712Domain, OrderEIP712};
+    use fusion_core::eip712::{to_typed_data_json, EIP712Domain, OrderEIP712};
     use fusion_core::order::Order;
 
     #[test]
@@ -36,4 +36,56 @@ mod eip712_tests {
 
         assert_eq!(hash.len(), 32);
     }
+
+    #[test]
+    fn test_typed_data_json_for_external_signers() {
+        let order = Order {
+            salt: [1u8; 32],
+            maker_asset: "0x4200000000000000000000000000000000000006".to_string(),
+            taker_asset: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
+            maker: "0x7aD8317e9aB4837AEF734e23d1C62F4938a6D950".to_string(),
+            receiver: "0x0000000000000000000000000000000000000000".to_string(),
+            allowed_sender: "0x0000000000000000000000000000000000000000".to_string(),
+            making_amount: 1000000000000000000u128,
+            taking_amount: 3000000000u128,
+            offsets: 0,
+            interactions: "0x".to_string(),
+        };
+        let domain = EIP712Domain {
+            name: "1inch Limit Order Protocol".to_string(),
+            version: "3".to_string(),
+            chain_id: 84532,
+            verifying_contract: "0x171C87724E720F2806fc29a010a62897B30fdb62".to_string(),
+        };
+
+        let json = to_typed_data_json(&order, &domain);
+
+        let types = json["types"].as_object().unwrap();
+        for type_name in ["EIP712Domain", "Order"] {
+            for field in types[type_name].as_array().unwrap() {
+                assert!(field["name"].is_string());
+                assert!(field["type"].is_string());
+            }
+        }
+        assert_eq!(types["EIP712Domain"].as_array().unwrap().len(), 4);
+        assert_eq!(types["Order"].as_array().unwrap().len(), 10);
+
+        assert_eq!(json["primaryType"], "Order");
+        assert_eq!(json["domain"]["chainId"], 84532);
+        assert_eq!(
+            json["domain"]["verifyingContract"],
+            "0x171C87724E720F2806fc29a010a62897B30fdb62"
+        );
+
+        // Every declared Order field is present in the message
+        let message = json["message"].as_object().unwrap();
+        for field in types["Order"].as_array().unwrap() {
+            assert!(message.contains_key(field["name"].as_str().unwrap()));
+        }
+        assert_eq!(message["makingAmount"], "1000000000000000000");
+
+        // The message is the one that gets hashed for signing
+        let typed_data = order.to_eip712(84532, "0x171C87724E720F2806fc29a010a62897B30fdb62");
+        assert_eq!(json["message"], typed_data.message);
+    }
 }
@@ -1,4 +1,7 @@
-use fusion_core::htlc::{generate_secret, hash_secret, Htlc, HtlcError, HtlcState};
+use fusion_core::htlc::{
+    generate_secret, hash_secret, hash_secret_with, secret_eq, HashAlgorithm, Htlc, HtlcError,
+    HtlcState,
+};
 use std::time::Duration;
 
 #[test]
@@ -117,6 +120,38 @@ fn test_htlc_claim_with_wrong_secret() {
     assert_eq!(htlc.state(), &HtlcState::Pending);
 }
 
+#[test]
+fn test_htlc_claim_with_keccak_hashlock() {
+    let secret = generate_secret();
+    let keccak_hash = hash_secret_with(&secret, HashAlgorithm::Keccak256);
+    assert_ne!(keccak_hash, hash_secret(&secret));
+
+    let new_htlc = || {
+        Htlc::new(
+            "Alice".to_string(),
+            "Bob".to_string(),
+            1000,
+            keccak_hash,
+            Duration::from_secs(3600),
+        )
+        .expect("Failed to create HTLC")
+    };
+
+    // デフォルトのSHA256モードではkeccak256のハッシュロックと一致しない
+    let mut sha256_htlc = new_htlc();
+    assert_eq!(sha256_htlc.hash_algorithm(), HashAlgorithm::Sha256);
+    assert!(matches!(
+        sha256_htlc.claim(&secret),
+        Err(HtlcError::InvalidSecret)
+    ));
+    assert_eq!(sha256_htlc.state(), &HtlcState::Pending);
+
+    // keccak256モードならクレームできる
+    let mut keccak_htlc = new_htlc().with_hash_algorithm(HashAlgorithm::Keccak256);
+    assert!(keccak_htlc.claim(&secret).is_ok());
+    assert_eq!(keccak_htlc.state(), &HtlcState::Claimed);
+}
+
 #[test]
 fn test_htlc_refund_after_timeout() {
     let secret = generate_secret();
@@ -262,3 +297,19 @@ fn test_htlc_refund_after_claim() {
         _ => panic!("Expected InvalidState error"),
     }
 }
+
+#[test]
+fn test_secret_eq() {
+    let secret = [7u8; 32];
+    assert!(secret_eq(&secret, &[7u8; 32]));
+    assert!(secret_eq(&hash_secret(&secret), &hash_secret(&[7u8; 32])));
+
+    // 先頭・末尾の1バイトだけ異なる場合も不一致
+    let mut first = secret;
+    first[0] ^= 1;
+    let mut last = secret;
+    last[31] ^= 0x80;
+    assert!(!secret_eq(&secret, &first));
+    assert!(!secret_eq(&secret, &last));
+    assert!(!secret_eq(&hash_secret(&secret), &hash_secret(&first)));
+}
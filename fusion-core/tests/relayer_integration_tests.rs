@@ -27,6 +27,7 @@ async fn test_end_to_end_order_matching_and_execution() {
         price: 5.2,
         amount: 1_000_000_000_000_000_000_000_000_000, // 1000 NEAR
         timestamp: 1234567890,
+        expires_at: None,
     };
 
     // 売り注文を追加（NEAR上）
@@ -38,6 +39,7 @@ async fn test_end_to_end_order_matching_and_execution() {
         price: 5.0,
         amount: 1_000_000_000_000_000_000_000_000_000, // 1000 NEAR
         timestamp: 1234567891,
+        expires_at: None,
     };
 
     matching_engine.add_order(buy_order).unwrap();
@@ -76,6 +78,7 @@ async fn test_end_to_end_order_matching_and_execution() {
         max_time: 3600,
         max_risk_score: 50,
         min_profit: 10.0,
+        max_hops: 2,
         priority: OptimizationPriority::MinimizeCost,
     };
 
@@ -118,6 +121,8 @@ async fn test_end_to_end_order_matching_and_execution() {
         created_at: 1234567900,
         updated_at: 1234567900,
         error_message: None,
+        retry_count: 0,
+        htlc: None,
     };
 
     // タスクを追加
@@ -163,6 +168,7 @@ fn test_order_matching_with_multiple_orders() {
             price: 5.0 + (i as f64 * 0.1),
             amount: 500_000_000_000_000_000_000_000_000,
             timestamp: 1234567890 + i,
+            expires_at: None,
         };
         engine.add_order(order).unwrap();
     }
@@ -177,6 +183,7 @@ fn test_order_matching_with_multiple_orders() {
             price: 4.8 + (i as f64 * 0.1),
             amount: 500_000_000_000_000_000_000_000_000,
             timestamp: 1234567900 + i,
+            expires_at: None,
         };
         engine.add_order(order).unwrap();
     }
@@ -231,6 +238,7 @@ fn test_execution_path_with_constraints() {
         max_time: 3600,
         max_risk_score: 100,
         min_profit: 0.0,
+        max_hops: 2,
         priority: OptimizationPriority::MinimizeCost,
     };
 
@@ -247,6 +255,7 @@ fn test_execution_path_with_constraints() {
         max_time: 3600,
         max_risk_score: 100,
         min_profit: 0.0,
+        max_hops: 2,
         priority: OptimizationPriority::MinimizeTime,
     };
 
@@ -254,8 +263,8 @@ fn test_execution_path_with_constraints() {
         .find_optimal_path("ethereum", "near", "USDC", 1_000_000_000, &time_params)
         .unwrap();
 
-    // 最も時間が短いパスが最初に来ることを確認
-    assert!(time_optimal_paths[0].total_time <= 360);
+    // 最も時間が短いパスが最初に来ることを確認（Ethereumの混雑度0.5で300秒 × 1.5）
+    assert!(time_optimal_paths[0].total_time <= 450);
 }
 
 #[tokio::test]
@@ -265,6 +274,9 @@ async fn test_automated_executor_retry_logic() {
         max_retries: 2,
         retry_delay: 1, // テスト用に短く設定
         exponential_backoff: false,
+        // ステップ別の予算は使わず共通の上限で検証
+        step_max_retries: Default::default(),
+        jitter_fraction: 0.0,
     };
 
     let cross_chain_executor = CrossChainExecutor::new(
@@ -300,6 +312,8 @@ async fn test_automated_executor_retry_logic() {
         created_at: 1234567890,
         updated_at: 1234567890,
         error_message: None,
+        retry_count: 0,
+        htlc: None,
     };
 
     automated_executor.add_task(task).unwrap();